@@ -0,0 +1,35 @@
+-- The projects, along with their groups, users and path rules (in the order
+-- that they were defined in). Timestamps are RFC 3339 strings.
+CREATE TABLE projects (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    visibility TEXT NOT NULL,
+    valid_from TEXT,
+    valid_until TEXT
+);
+
+CREATE TABLE project_groups (
+    project_id TEXT NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    group_name TEXT NOT NULL,
+    valid_from TEXT,
+    valid_until TEXT,
+    PRIMARY KEY (project_id, position)
+);
+
+CREATE TABLE project_users (
+    project_id TEXT NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    email TEXT NOT NULL,
+    PRIMARY KEY (project_id, position)
+);
+
+-- The `paths` (globs) and `groups` of a path rule are JSON arrays of strings.
+CREATE TABLE project_path_rules (
+    project_id TEXT NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    paths TEXT NOT NULL,
+    groups TEXT NOT NULL,
+    PRIMARY KEY (project_id, position)
+);
@@ -0,0 +1,296 @@
+use std::path::Component;
+use std::sync::Arc;
+
+use super::super::domain::AccessExplanation;
+use super::super::domain::AccessExplanationRequest;
+use super::super::domain::authorization::AccessExplanationTypeResource;
+use super::error::AccessExplanationServiceError;
+use crate::authorization::domain::action::Action;
+use crate::authorization::ports::authorization_engine::AuthorizationEngine;
+use crate::project::ports::project_repository::ProjectRepository;
+use crate::project_asset::domain::authorization::ProjectAssetResource;
+use crate::user::domain::User;
+
+/// A service for explaining the access decisions of the authorization engine.
+#[derive(Debug, Clone)]
+pub struct AccessExplanationService {
+    /// Used to read the project regardless of who is asking, as the
+    /// explanation is about someone else.
+    project_repository: Arc<dyn ProjectRepository>,
+
+    /// Used to explain the decisions, and to determine if a user is authorized
+    /// to have them explained.
+    authorization_engine: Arc<dyn AuthorizationEngine>,
+}
+
+impl AccessExplanationService {
+    pub fn new(
+        project_repository: Arc<dyn ProjectRepository>,
+        authorization_engine: Arc<dyn AuthorizationEngine>,
+    ) -> Self {
+        Self {
+            project_repository,
+            authorization_engine,
+        }
+    }
+
+    /// Explains whether the user of the `request` can read its project (and
+    /// asset). `requester` is used for authorization, only admins are allowed
+    /// to have decisions explained.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `requester` is not authorized, the request
+    /// is invalid or the project can't be found.
+    pub async fn explain(
+        &self,
+        requester: &Option<User>,
+        request: &AccessExplanationRequest,
+    ) -> Result<AccessExplanation, AccessExplanationServiceError> {
+        self.authorization_engine.can_on_type(
+            requester,
+            &Action::Read,
+            &AccessExplanationTypeResource,
+        )?;
+
+        self.explain_locally(request).await
+    }
+
+    /// Explains whether the user of the `request` can read its project (and
+    /// asset), without authorizing whoever is asking. Only to be used locally
+    /// (e.g. by the CLI), where the data directory can be read anyway.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request is invalid or the project can't be
+    /// found.
+    pub async fn explain_locally(
+        &self,
+        request: &AccessExplanationRequest,
+    ) -> Result<AccessExplanation, AccessExplanationServiceError> {
+        // Only allow relative paths that stay within the project directory.
+        if request.asset_path.as_ref().is_some_and(|asset_path| {
+            !asset_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        }) {
+            return Err(AccessExplanationServiceError::InvalidRequest {
+                message: "the asset path must be relative to the project directory".to_owned(),
+            });
+        }
+
+        let project = self.project_repository.read(&request.project_id).await?;
+        let user = Some(request.user());
+
+        let project_explanation = self
+            .authorization_engine
+            .explain(&user, &Action::Read, &project);
+
+        let project_asset_explanation = request.asset_path.as_ref().map(|asset_path| {
+            self.authorization_engine.explain(
+                &user,
+                &Action::Read,
+                &ProjectAssetResource {
+                    associated_project: &project,
+                    asset_path,
+                },
+            )
+        });
+
+        Ok(AccessExplanation {
+            allowed: project_explanation.allowed
+                && project_asset_explanation
+                    .as_ref()
+                    .is_none_or(|explanation| explanation.allowed),
+            project: project_explanation,
+            project_asset: project_asset_explanation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod access_explanation_service_tests {
+    use std::path::PathBuf;
+
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+    use crate::authorization::domain::error::AuthorizationEngineError;
+    use crate::authorization::domain::explanation::Check;
+    use crate::authorization::domain::explanation::CheckKind;
+    use crate::authorization::domain::explanation::Explanation;
+    use crate::authorization::ports::authorization_engine::MockAuthorizationEngine;
+    use crate::project::domain::Project;
+    use crate::project::ports::project_repository::MockProjectRepository;
+    use crate::project::ports::project_repository::ProjectRepositoryError;
+
+    fn request(asset_path: Option<&str>) -> AccessExplanationRequest {
+        AccessExplanationRequest {
+            asset_path: asset_path.map(PathBuf::from),
+            ..Faker.fake()
+        }
+    }
+
+    /// An explanation of a decision on a `Faker` project.
+    fn explanation(allowed: bool) -> Explanation {
+        let result = match allowed {
+            true => Ok(()),
+            false => Err(AuthorizationEngineError::NotAuthenticated),
+        };
+
+        Explanation::new(
+            &result,
+            &Action::Read,
+            &Faker.fake::<Project>(),
+            vec![Check::new(CheckKind::Default, allowed, "default").decisive(true)],
+        )
+    }
+
+    mod explain {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_requester_not_authorized() {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine.expect_can_on_type().return_const(Err(
+                AuthorizationEngineError::NotAuthorized {
+                    user: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: None,
+                    resource_type: Faker.fake(),
+                },
+            ));
+
+            let access_explanation_service = AccessExplanationService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = access_explanation_service
+                .explain(&Faker.fake(), &request(None))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AccessExplanationServiceError::NotAuthorized { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_explain_the_project_if_requester_authorized() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_read()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .return_const(Ok(()));
+            authorization_engine
+                .expect_explain()
+                .return_const(explanation(true));
+
+            let access_explanation_service = AccessExplanationService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let access_explanation = access_explanation_service
+                .explain(&Some(User::dummy_admin()), &request(None))
+                .await
+                .unwrap();
+
+            // Assert
+            assert!(access_explanation.allowed);
+            assert_eq!(access_explanation.project_asset, None);
+        }
+    }
+
+    mod explain_locally {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_only_be_allowed_if_the_project_and_asset_are_allowed() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_read()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            let mut explanations = vec![explanation(true), explanation(false)].into_iter();
+            authorization_engine
+                .expect_explain()
+                .times(2)
+                .returning(move |_, _, _| explanations.next().unwrap());
+
+            let access_explanation_service = AccessExplanationService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let access_explanation = access_explanation_service
+                .explain_locally(&request(Some("data/raw/scan.csv")))
+                .await
+                .unwrap();
+
+            // Assert
+            assert!(!access_explanation.allowed);
+            assert!(access_explanation.project.allowed);
+            assert!(!access_explanation.project_asset.unwrap().allowed);
+        }
+
+        #[test_case::test_case("../project_2/data.csv"; "parent directory")]
+        #[test_case::test_case("/etc/passwd"; "absolute path")]
+        #[tokio::test]
+        async fn should_return_err_if_asset_path_outside_of_project(asset_path: &str) {
+            // Arrange
+            let access_explanation_service = AccessExplanationService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(MockAuthorizationEngine::new()),
+            );
+
+            // Act
+            let res = access_explanation_service
+                .explain_locally(&request(Some(asset_path)))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AccessExplanationServiceError::InvalidRequest { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_project_not_found() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository.expect_read().return_const(Err(
+                ProjectRepositoryError::ResourceNotFound { id: Faker.fake() },
+            ));
+
+            let access_explanation_service = AccessExplanationService::new(
+                Arc::new(project_repository),
+                Arc::new(MockAuthorizationEngine::new()),
+            );
+
+            // Act
+            let res = access_explanation_service
+                .explain_locally(&request(None))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AccessExplanationServiceError::ProjectNotFound { .. })
+            ));
+        }
+    }
+}
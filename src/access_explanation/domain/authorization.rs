@@ -0,0 +1,17 @@
+//! AuthZ trait implementations for access explanations.
+
+use crate::authorization::domain::resource::Resource;
+use crate::authorization::domain::resource::ResourceType;
+use crate::common::domain::resource_type;
+
+/// Represents an access explanation type for type-level (rather than
+/// instance-level) authZ. E.g. whether a user is allowed to have access
+/// decisions explained.
+#[derive(Debug)]
+pub struct AccessExplanationTypeResource;
+
+impl Resource for AccessExplanationTypeResource {
+    fn resource_type(&self) -> ResourceType {
+        ResourceType::new(resource_type::ACCESS_EXPLANATION.to_owned())
+    }
+}
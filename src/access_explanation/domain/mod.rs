@@ -0,0 +1,62 @@
+pub mod authorization;
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::authorization::domain::explanation::Explanation;
+use crate::common::domain::Group;
+use crate::project::domain::ProjectId;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
+use crate::user::domain::UserId;
+use crate::user::domain::UserName;
+
+/// Who, and what, an access decision should be explained for.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
+pub struct AccessExplanationRequest {
+    /// The email address of the user.
+    pub email: EmailAddress,
+
+    /// The groups that the user is a member of (as known to the application,
+    /// i.e. after any group mapping or prefixing).
+    pub groups: Vec<Group>,
+
+    /// The project that the user is trying to read.
+    pub project_id: ProjectId,
+
+    /// If populated, the asset of the project (relative to the project
+    /// directory) that the user is trying to read.
+    pub asset_path: Option<PathBuf>,
+}
+
+impl AccessExplanationRequest {
+    /// The user that the decision is explained for. Their id and name are not
+    /// known, so the email address is used instead. The email address is
+    /// assumed to be verified.
+    pub fn user(&self) -> User {
+        User {
+            id: UserId::new(self.email.to_string()),
+            name: UserName::new(self.email.to_string()),
+            email: self.email.clone(),
+            email_verified: true,
+            groups: self.groups.clone(),
+            provider: None,
+        }
+    }
+}
+
+/// Explains whether a user can read a project (and one of its assets).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccessExplanation {
+    /// Whether the user can read the project (and the asset, if requested).
+    pub allowed: bool,
+
+    /// The decision on the project, which is also required to read its assets.
+    pub project: Explanation,
+
+    /// The decision on the asset, if one was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_asset: Option<Explanation>,
+}
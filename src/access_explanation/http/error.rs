@@ -0,0 +1,50 @@
+use super::super::application::error::AccessExplanationServiceError;
+use crate::common::utils::http::api_error::ApiError;
+
+impl From<AccessExplanationServiceError> for ApiError {
+    fn from(value: AccessExplanationServiceError) -> Self {
+        match value {
+            AccessExplanationServiceError::ProjectNotFound { id } => Self::ResourceNotFound {
+                resource_name: format!("project: {id}"),
+            },
+            AccessExplanationServiceError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AccessExplanationServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AccessExplanationServiceError::NotAuthenticated => Self::NotAuthenticated,
+            AccessExplanationServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+            AccessExplanationServiceError::InvalidRequest { message } => {
+                Self::InvalidRequest { message }
+            }
+            AccessExplanationServiceError::Infrastucture { message } => {
+                Self::Infrastucture { message }
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod error;
+mod extractors;
+mod route_handlers;
+mod router;
+mod state;
+
+pub use router::ACCESS_EXPLANATIONS;
+pub use router::build_router;
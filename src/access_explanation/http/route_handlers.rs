@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use axum::Json;
+
+use super::super::application::service::AccessExplanationService;
+use super::super::domain::AccessExplanation;
+use super::super::domain::AccessExplanationRequest;
+use crate::common::domain::Group;
+use crate::common::utils::http::api_error::ApiError;
+use crate::project::domain::ProjectId;
+use crate::user::domain::EmailAddress;
+use crate::user::http::extractors::UserExtractor;
+
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct ExplainAccessRequest {
+    /// The email address of the user whose access should be explained.
+    pub email: EmailAddress,
+
+    /// The groups that the user is a member of.
+    #[serde(default)]
+    pub groups: Vec<Group>,
+
+    /// The project that the user is trying to read.
+    pub project_id: ProjectId,
+
+    /// If populated, the asset of the project that the user is trying to read.
+    pub asset_path: Option<PathBuf>,
+}
+
+/// Explains whether a user can read a project (and one of its assets).
+#[tracing::instrument(name = "`access explanation route handlers`: explaining access", err)]
+pub(crate) async fn explain_access(
+    UserExtractor(user): UserExtractor,
+    access_explanation_service: AccessExplanationService,
+    Json(request): Json<ExplainAccessRequest>,
+) -> Result<Json<AccessExplanation>, ApiError> {
+    let ExplainAccessRequest {
+        email,
+        groups,
+        project_id,
+        asset_path,
+    } = request;
+
+    let access_explanation = access_explanation_service
+        .explain(
+            &user,
+            &AccessExplanationRequest {
+                email,
+                groups,
+                project_id,
+                asset_path,
+            },
+        )
+        .await?;
+
+    Ok(Json(access_explanation))
+}
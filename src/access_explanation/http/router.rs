@@ -0,0 +1,24 @@
+use std::sync::LazyLock;
+
+use axum::Extension;
+use axum::Router;
+use axum::routing::post;
+use web_route::WebRoute;
+
+use super::super::application::service::AccessExplanationService;
+use super::route_handlers;
+use super::state::State;
+
+pub static ACCESS_EXPLANATIONS: LazyLock<WebRoute> =
+    LazyLock::new(|| WebRoute::new("/access-explanations"));
+
+/// Builds a router for explaining access decisions to admins.
+pub fn build_router(access_explanation_service: AccessExplanationService) -> Router {
+    let state = State {
+        access_explanation_service,
+    };
+
+    Router::new()
+        .route(&ACCESS_EXPLANATIONS, post(route_handlers::explain_access))
+        .layer(Extension(state))
+}
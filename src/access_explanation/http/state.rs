@@ -0,0 +1,6 @@
+use super::super::application::service::AccessExplanationService;
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub access_explanation_service: AccessExplanationService,
+}
@@ -0,0 +1,6 @@
+//! Explains to admins why a user can (or can't) read a project or one of its
+//! assets, e.g. to answer support tickets about missing projects.
+
+pub mod application;
+pub mod domain;
+pub mod http;
@@ -0,0 +1,476 @@
+//! An audit log that appends events to a JSON-lines file. It implements the
+//! [`AuditLog`] trait.
+//!
+//! The events are written by a dedicated thread, so that recording them (from
+//! the request handlers) doesn't block the async runtime on file IO.
+//!
+//! Once the file would exceed its maximum size, it is rotated: `audit.jsonl`
+//! becomes `audit.jsonl.1`, `audit.jsonl.1` becomes `audit.jsonl.2`, etc. The
+//! oldest files beyond the maximum number of rotated files are deleted.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use super::super::super::domain::AuditEvent;
+use super::super::super::domain::AuditQuery;
+use super::super::super::ports::audit_log::AuditLog;
+use super::super::super::ports::audit_log::AuditLogError;
+
+/// Appends [`AuditEvent`]s, one JSON object per line, to the file at `path`.
+#[derive(Debug)]
+pub struct JsonLinesAuditLog {
+    /// The file that events are currently appended to.
+    path: PathBuf,
+
+    /// Sends the serialized events to the writer thread. Dropped (ending the
+    /// thread) when the audit log is dropped.
+    sender: Option<mpsc::Sender<WriterMessage>>,
+
+    /// The writer thread, joined when the audit log is dropped so that the
+    /// events recorded before are written.
+    writer: Option<JoinHandle<()>>,
+}
+
+/// Controls the rotation of the audit log file.
+#[derive(Debug, Clone, Copy)]
+pub struct RotationOptions {
+    /// The size (in bytes) that the file should not exceed. A single event
+    /// larger than this is still recorded.
+    pub max_file_size: u64,
+
+    /// The number of rotated files that are kept, in addition to the current
+    /// file.
+    pub max_rotated_files: usize,
+}
+
+/// The messages handled (in order) by the writer thread.
+#[derive(Debug)]
+enum WriterMessage {
+    /// A serialized event (including its newline) to append to the file.
+    Event(Vec<u8>),
+
+    /// Acknowledged once the events sent before have been written.
+    Flush(mpsc::Sender<()>),
+}
+
+impl JsonLinesAuditLog {
+    /// Creates a new [`JsonLinesAuditLog`] appending to the file at `path`
+    /// (which is created if it does not exist), and starts its writer thread.
+    pub fn new<P: AsRef<Path>>(path: P, rotation: RotationOptions) -> Self {
+        let path = path.as_ref().to_path_buf();
+
+        let (sender, receiver) = mpsc::channel();
+        let file_writer = FileWriter {
+            path: path.clone(),
+            rotation,
+            file: None,
+        };
+        let writer = std::thread::Builder::new()
+            .name("audit-log-writer".to_owned())
+            .spawn(move || file_writer.run(receiver));
+        let (sender, writer) = match writer {
+            Ok(writer) => (Some(sender), Some(writer)),
+            Err(e) => {
+                tracing::error!(error = %e, "unable to start the audit log writer");
+                (None, None)
+            }
+        };
+
+        Self {
+            path,
+            sender,
+            writer,
+        }
+    }
+
+    /// Serializes the `event` and hands it to the writer thread. Failing to
+    /// write it to the file is logged by the writer thread.
+    fn record(&self, event: &AuditEvent) -> Result<(), AuditLogError> {
+        let mut line = serde_json::to_vec(event).map_err(|e| AuditLogError::Infrastucture {
+            message: format!("unable to serialize the audit event: {e}"),
+        })?;
+        line.push(b'\n');
+
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(WriterMessage::Event(line)).ok())
+            .ok_or(AuditLogError::Infrastucture {
+                message: format!(
+                    "the writer of the audit log ({}) is not running",
+                    self.path.to_string_lossy()
+                ),
+            })
+    }
+
+    /// Returns the matching events of the file and its rotated files, once the
+    /// events recorded before have been written.
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+        self.flush();
+
+        query_events(&self.path, query)
+    }
+
+    /// Waits until the events recorded before have been written.
+    fn flush(&self) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        let (done_sender, done_receiver) = mpsc::channel();
+        if sender.send(WriterMessage::Flush(done_sender)).is_ok() {
+            let _ = done_receiver.recv();
+        }
+    }
+}
+
+impl Drop for JsonLinesAuditLog {
+    fn drop(&mut self) {
+        // The writer thread ends once it has written the remaining events.
+        self.sender = None;
+        if let Some(writer) = self.writer.take()
+            && writer.join().is_err()
+        {
+            tracing::error!("the audit log writer panicked");
+        }
+    }
+}
+
+impl AuditLog for JsonLinesAuditLog {
+    fn record(&self, event: &AuditEvent) -> Result<(), AuditLogError> {
+        Self::record(self, event)
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+        Self::query(self, query)
+    }
+}
+
+/// Appends the events to the file at `path`, rotating it as needed. Runs on the
+/// writer thread.
+struct FileWriter {
+    path: PathBuf,
+
+    /// Controls when and how the file is rotated.
+    rotation: RotationOptions,
+
+    /// The opened file at `path`. Opened when the first event is written, and
+    /// re-opened after a rotation.
+    file: Option<File>,
+}
+
+impl FileWriter {
+    /// Handles the messages until the audit log is dropped.
+    fn run(mut self, receiver: mpsc::Receiver<WriterMessage>) {
+        for message in receiver {
+            match message {
+                WriterMessage::Event(line) => {
+                    if let Err(e) = self.write(&line) {
+                        tracing::error!(error = %e, "unable to record the audit event");
+                    }
+                }
+                WriterMessage::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, line: &[u8]) -> Result<(), AuditLogError> {
+        let file_size = match self.file.as_ref() {
+            Some(file) => file.metadata().map(|metadata| metadata.len()),
+            None => std::fs::metadata(&self.path).map(|metadata| metadata.len()),
+        }
+        .unwrap_or(0);
+
+        if file_size > 0 && file_size + line.len() as u64 > self.rotation.max_file_size {
+            // The file is re-opened (at the original path) after rotating it.
+            self.file = None;
+            self.rotate()?;
+        }
+
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => self.file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .map_err(|e| infrastructure_error(&self.path, "unable to open", e))?,
+            ),
+        };
+
+        file.write_all(line)
+            .and_then(|_| file.flush())
+            .map_err(|e| infrastructure_error(&self.path, "unable to write to", e))
+    }
+
+    /// Shifts each rotated file up by one, deleting the oldest, and moves the
+    /// current file to the first rotated file.
+    fn rotate(&self) -> Result<(), AuditLogError> {
+        let rotate_error = |e| infrastructure_error(&self.path, "unable to rotate", e);
+
+        if self.rotation.max_rotated_files == 0 {
+            return std::fs::remove_file(&self.path).map_err(rotate_error);
+        }
+
+        let oldest = rotated_path(&self.path, self.rotation.max_rotated_files);
+        if oldest.exists() {
+            std::fs::remove_file(&oldest).map_err(rotate_error)?;
+        }
+
+        for number in (1..self.rotation.max_rotated_files).rev() {
+            let rotated = rotated_path(&self.path, number);
+            if rotated.exists() {
+                std::fs::rename(&rotated, rotated_path(&self.path, number + 1))
+                    .map_err(rotate_error)?;
+            }
+        }
+
+        std::fs::rename(&self.path, rotated_path(&self.path, 1)).map_err(rotate_error)
+    }
+}
+
+/// Returns the matching events of the audit log at `path` and its rotated
+/// files, without writing to them (e.g. to query the audit log of another
+/// process). Lines that can't be parsed (e.g. truncated by a crash) are skipped
+/// with a warning.
+pub fn query_events(path: &Path, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+    let mut events = Vec::new();
+
+    // Rotated files are numbered contiguously. The oldest (most rotated)
+    // file comes first.
+    let rotated_paths = (1..)
+        .map(|number| rotated_path(path, number))
+        .take_while(|rotated_path| rotated_path.exists())
+        .collect::<Vec<_>>();
+    let paths = rotated_paths.into_iter().rev().chain([path.to_path_buf()]);
+
+    for path in paths {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(infrastructure_error(&path, "unable to open", e)),
+        };
+
+        for (index, line) in BufReader::new(file).split(b'\n').enumerate() {
+            let line = line.map_err(|e| infrastructure_error(&path, "unable to read", e))?;
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+
+            let event = match serde_json::from_slice::<AuditEvent>(&line) {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!(
+                        "skipping corrupt audit log line ({} line {}): {e}",
+                        path.to_string_lossy(),
+                        index + 1
+                    );
+                    continue;
+                }
+            };
+
+            if query.matches(&event) {
+                events.push(event);
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// The path of the `number`th rotated file of `path` (e.g. `audit.jsonl.1`).
+fn rotated_path(path: &Path, number: usize) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(format!(".{number}"));
+
+    path.with_file_name(file_name)
+}
+
+fn infrastructure_error(path: &Path, context: &str, error: std::io::Error) -> AuditLogError {
+    AuditLogError::Infrastucture {
+        message: format!(
+            "{context} the audit log ({}): {error}",
+            path.to_string_lossy()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod json_lines_audit_log_tests {
+    use time::Duration;
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::audit::domain::AuditEventKind;
+    use crate::audit::domain::AuditOutcome;
+
+    /// An event that happened `minutes` after a fixed point in time.
+    fn event(minutes: i64) -> AuditEvent {
+        AuditEvent::for_user(
+            datetime!(2026-03-01 12:00 UTC) + Duration::minutes(minutes),
+            AuditEventKind::Login,
+            None,
+            AuditOutcome::Allowed,
+        )
+    }
+
+    /// The size of a single serialized [`event`] line.
+    fn line_size() -> u64 {
+        serde_json::to_vec(&event(0)).unwrap().len() as u64 + 1
+    }
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn should_append_an_event_per_line() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("audit.jsonl");
+            let audit_log = JsonLinesAuditLog::new(
+                &path,
+                RotationOptions {
+                    max_file_size: 1024 * 1024,
+                    max_rotated_files: 2,
+                },
+            );
+
+            // Act
+            audit_log.record(&event(0)).unwrap();
+            audit_log.record(&event(1)).unwrap();
+            audit_log.flush();
+
+            // Assert
+            let contents = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(contents.lines().count(), 2);
+        }
+
+        #[test]
+        fn should_rotate_once_the_maximum_size_is_exceeded() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("audit.jsonl");
+            let audit_log = JsonLinesAuditLog::new(
+                &path,
+                RotationOptions {
+                    max_file_size: line_size() * 2,
+                    max_rotated_files: 2,
+                },
+            );
+
+            // Act
+            for minutes in 0..7 {
+                audit_log.record(&event(minutes)).unwrap();
+            }
+            audit_log.flush();
+
+            // Assert
+            assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+            assert!(dir.path().join("audit.jsonl.1").exists());
+            assert!(dir.path().join("audit.jsonl.2").exists());
+            assert!(!dir.path().join("audit.jsonl.3").exists());
+        }
+    }
+
+    mod query {
+        use super::*;
+
+        #[test]
+        fn should_return_the_matching_events_across_rotated_files_oldest_first() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let audit_log = JsonLinesAuditLog::new(
+                dir.path().join("audit.jsonl"),
+                RotationOptions {
+                    max_file_size: line_size() * 2,
+                    max_rotated_files: 3,
+                },
+            );
+            for minutes in 0..6 {
+                audit_log.record(&event(minutes)).unwrap();
+            }
+            let query = AuditQuery {
+                from: Some(event(1).timestamp),
+                until: Some(event(5).timestamp),
+                ..AuditQuery::default()
+            };
+
+            // Act
+            let events = audit_log.query(&query).unwrap();
+
+            // Assert
+            assert_eq!(events, (1..5).map(event).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn should_return_an_empty_vec_if_nothing_recorded() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let audit_log = JsonLinesAuditLog::new(
+                dir.path().join("audit.jsonl"),
+                RotationOptions {
+                    max_file_size: 1024,
+                    max_rotated_files: 1,
+                },
+            );
+
+            // Act
+            let events = audit_log.query(&AuditQuery::default()).unwrap();
+
+            // Assert
+            assert!(events.is_empty());
+        }
+
+        #[test]
+        fn should_skip_corrupt_and_truncated_lines() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("audit.jsonl");
+            let line = serde_json::to_string(&event(0)).unwrap();
+            std::fs::write(
+                &path,
+                format!("{line}\n{{\"timestamp\": 42}}\n{}", &line[..line.len() / 2]),
+            )
+            .unwrap();
+            let audit_log = JsonLinesAuditLog::new(
+                &path,
+                RotationOptions {
+                    max_file_size: 1024,
+                    max_rotated_files: 1,
+                },
+            );
+
+            // Act
+            let events = audit_log.query(&AuditQuery::default()).unwrap();
+
+            // Assert
+            assert_eq!(events, vec![event(0)]);
+        }
+    }
+
+    mod query_events {
+        use super::*;
+
+        #[test]
+        fn should_not_create_the_file() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("audit.jsonl");
+
+            // Act
+            let events = query_events(&path, &AuditQuery::default()).unwrap();
+
+            // Assert
+            assert!(events.is_empty());
+            assert!(!path.exists());
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod json_lines;
+pub mod no_op;
@@ -0,0 +1,32 @@
+//! An audit log that is used when no audit log file is configured. It
+//! implements the [`AuditLog`] trait.
+//!
+//! Events are discarded, and can't be queried.
+
+use super::super::super::domain::AuditEvent;
+use super::super::super::domain::AuditQuery;
+use super::super::super::ports::audit_log::AuditLog;
+use super::super::super::ports::audit_log::AuditLogError;
+
+#[derive(Debug, Clone)]
+pub struct NoOpAuditLog;
+
+impl NoOpAuditLog {
+    fn record(&self, _event: &AuditEvent) -> Result<(), AuditLogError> {
+        Ok(())
+    }
+
+    fn query(&self, _query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+        Err(AuditLogError::NotConfigured)
+    }
+}
+
+impl AuditLog for NoOpAuditLog {
+    fn record(&self, event: &AuditEvent) -> Result<(), AuditLogError> {
+        Self::record(self, event)
+    }
+
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+        Self::query(self, query)
+    }
+}
@@ -0,0 +1,262 @@
+//! The events recorded in the audit log, e.g. so that it can be shown to a
+//! client who viewed which of their projects and when.
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::authorization::domain::action::Action;
+use crate::authorization::domain::resource::ResourceIdentifier;
+use crate::authorization::domain::resource::ResourceType;
+use crate::project::domain::ProjectId;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
+use crate::user::domain::UserId;
+
+/// A single entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AuditEvent {
+    /// When the event happened.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+
+    /// What happened.
+    pub event: AuditEventKind,
+
+    /// The id of the user, if known. For a failed login this is the entered
+    /// username.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+
+    /// The email address of the user, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailAddress>,
+
+    /// Whether the request was made with a share link rather than by a user.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub share_link: bool,
+
+    /// The action that was authorized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<Action>,
+
+    /// The type of the resource that the action was authorized on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<ResourceType>,
+
+    /// The resource that the action was authorized on, if it was an
+    /// instance-level decision.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_identifier: Option<ResourceIdentifier>,
+
+    /// The project that the resource belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<ProjectId>,
+
+    /// Whether the request was allowed.
+    pub outcome: AuditOutcome,
+
+    /// Why the request was denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The kinds of events that are recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEventKind {
+    /// An authorization decision of the authorization engine.
+    Authorization,
+
+    /// A user logged in (or failed to).
+    Login,
+
+    /// A user logged out.
+    Logout,
+}
+
+/// Whether an audited request was allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Allowed,
+    Denied,
+}
+
+impl AuditEvent {
+    /// An event of `kind` for the `user` (if known), without any resource.
+    pub fn for_user(
+        timestamp: OffsetDateTime,
+        event: AuditEventKind,
+        user: Option<&User>,
+        outcome: AuditOutcome,
+    ) -> Self {
+        Self {
+            timestamp,
+            event,
+            user_id: user.map(|user| user.id.clone()),
+            email: user.map(|user| user.email.clone()),
+            share_link: false,
+            action: None,
+            resource_type: None,
+            resource_identifier: None,
+            project_id: None,
+            outcome,
+            reason: None,
+        }
+    }
+}
+
+/// Filters the events of the audit log. Unset filters match any event.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    /// Only events about resources of this project.
+    pub project_id: Option<ProjectId>,
+
+    /// Only events of the user with this id or email address (compared
+    /// case-insensitively).
+    pub user: Option<String>,
+
+    /// Only events at or after this time.
+    pub from: Option<OffsetDateTime>,
+
+    /// Only events before this time.
+    pub until: Option<OffsetDateTime>,
+}
+
+impl AuditQuery {
+    /// Whether the `event` passes all the filters.
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        let project_matches = self
+            .project_id
+            .as_ref()
+            .is_none_or(|project_id| event.project_id.as_ref() == Some(project_id));
+
+        let user_matches = self.user.as_ref().is_none_or(|user| {
+            event
+                .user_id
+                .as_ref()
+                .is_some_and(|user_id| user_id.as_str() == user)
+                || event
+                    .email
+                    .as_ref()
+                    .is_some_and(|email| email.eq_ignore_case(&EmailAddress::new(user.clone())))
+        });
+
+        let from_matches = self.from.is_none_or(|from| from <= event.timestamp);
+        let until_matches = self.until.is_none_or(|until| event.timestamp < until);
+
+        project_matches && user_matches && from_matches && until_matches
+    }
+}
+
+#[cfg(test)]
+mod audit_domain_tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn event() -> AuditEvent {
+        AuditEvent {
+            timestamp: datetime!(2026-03-01 12:00 UTC),
+            event: AuditEventKind::Authorization,
+            user_id: Some(UserId::new("surveyor-id".to_owned())),
+            email: Some(EmailAddress::new("Surveyor@Example.com".to_owned())),
+            share_link: false,
+            action: Some(Action::Read),
+            resource_type: Some(ResourceType::new("project-asset".to_owned())),
+            resource_identifier: Some(ResourceIdentifier::new("metadata.json".to_owned())),
+            project_id: Some(ProjectId::new("survey".to_owned())),
+            outcome: AuditOutcome::Allowed,
+            reason: None,
+        }
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn should_match_without_filters() {
+            // Act
+            let is_match = AuditQuery::default().matches(&event());
+
+            // Assert
+            assert!(is_match);
+        }
+
+        #[test_case::test_case("survey", true; "same project")]
+        #[test_case::test_case("other", false; "other project")]
+        fn should_filter_by_project(project_id: &str, expected: bool) {
+            // Arrange
+            let query = AuditQuery {
+                project_id: Some(ProjectId::new(project_id.to_owned())),
+                ..AuditQuery::default()
+            };
+
+            // Act
+            let is_match = query.matches(&event());
+
+            // Assert
+            assert_eq!(is_match, expected);
+        }
+
+        #[test_case::test_case("surveyor-id", true; "user id")]
+        #[test_case::test_case("surveyor@example.com", true; "email ignoring case")]
+        #[test_case::test_case("client@example.com", false; "other user")]
+        fn should_filter_by_user(user: &str, expected: bool) {
+            // Arrange
+            let query = AuditQuery {
+                user: Some(user.to_owned()),
+                ..AuditQuery::default()
+            };
+
+            // Act
+            let is_match = query.matches(&event());
+
+            // Assert
+            assert_eq!(is_match, expected);
+        }
+
+        #[test_case::test_case(Some(datetime!(2026-03-01 12:00 UTC)), None, true; "from is inclusive")]
+        #[test_case::test_case(None, Some(datetime!(2026-03-01 12:00 UTC)), false; "until is exclusive")]
+        #[test_case::test_case(Some(datetime!(2026-03-02 00:00 UTC)), None, false; "before the range")]
+        fn should_filter_by_date_range(
+            from: Option<OffsetDateTime>,
+            until: Option<OffsetDateTime>,
+            expected: bool,
+        ) {
+            // Arrange
+            let query = AuditQuery {
+                from,
+                until,
+                ..AuditQuery::default()
+            };
+
+            // Act
+            let is_match = query.matches(&event());
+
+            // Assert
+            assert_eq!(is_match, expected);
+        }
+    }
+
+    #[test]
+    fn should_serialize_as_a_single_line_without_empty_fields() {
+        // Arrange
+        let event = AuditEvent::for_user(
+            datetime!(2026-03-01 12:00 UTC),
+            AuditEventKind::Logout,
+            None,
+            AuditOutcome::Allowed,
+        );
+
+        // Act
+        let line = serde_json::to_string(&event).unwrap();
+
+        // Assert
+        assert_eq!(
+            line,
+            r#"{"timestamp":"2026-03-01T12:00:00Z","event":"logout","outcome":"allowed"}"#
+        );
+    }
+}
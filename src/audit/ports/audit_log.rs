@@ -0,0 +1,24 @@
+use std::fmt::Debug;
+
+use super::super::domain::AuditEvent;
+use super::super::domain::AuditQuery;
+
+/// Defines the functionality needed to persist the [`AuditEvent`]s of the
+/// application (append-only), and to query them again.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait AuditLog: Debug + Send + Sync + 'static {
+    /// Appends the `event` to the audit log.
+    fn record(&self, event: &AuditEvent) -> Result<(), AuditLogError>;
+
+    /// Returns the recorded events matching the `query`, oldest first.
+    fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError>;
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AuditLogError {
+    #[error("the audit log is not configured")]
+    NotConfigured,
+
+    #[error("{message}")]
+    Infrastucture { message: String },
+}
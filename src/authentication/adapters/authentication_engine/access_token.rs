@@ -0,0 +1,377 @@
+//! Validation of (JWT) access tokens presented as `Authorization: Bearer`
+//! headers.
+
+use std::str::FromStr;
+
+use jsonwebtoken::Algorithm;
+use jsonwebtoken::DecodingKey;
+use jsonwebtoken::Validation;
+use jsonwebtoken::jwk::AlgorithmParameters;
+use jsonwebtoken::jwk::EllipticCurve;
+use jsonwebtoken::jwk::Jwk;
+use jsonwebtoken::jwk::JwkSet;
+
+use super::oidc::PotreeAuthClaims;
+use crate::authentication::domain::BearerToken;
+use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
+
+/// Validates the signature, issuer, audience and expiry of JWT access tokens
+/// against the IdP's JSON Web Key Set.
+///
+/// ID tokens are signed with the same keys, and their audience is the client
+/// id (the default audience of access tokens), so they are told apart by their
+/// type and claims and rejected.
+#[derive(Debug, Clone)]
+pub(crate) struct AccessTokenValidator {
+    /// The IdP's public signing keys.
+    jwks: JwkSet,
+
+    /// The expected `iss` claim.
+    issuer: String,
+
+    /// The expected `aud` claim.
+    audience: String,
+}
+
+impl AccessTokenValidator {
+    pub fn new(jwks: JwkSet, issuer: String, audience: String) -> Self {
+        Self {
+            jwks,
+            issuer,
+            audience,
+        }
+    }
+
+    /// Validates the `token`, returning its claims.
+    pub fn validate(
+        &self,
+        token: &BearerToken,
+    ) -> Result<PotreeAuthClaims, AuthenticationEngineError> {
+        let header = jsonwebtoken::decode_header(token.as_str()).map_err(|e| {
+            AuthenticationEngineError::Validation {
+                message: format!("unable to decode access token header: {e}"),
+            }
+        })?;
+
+        // If the token does not specify a key, it can only be unambiguously verified
+        // if the IdP has a single key.
+        let jwk = match &header.kid {
+            Some(kid) => self.jwks.find(kid),
+            None if self.jwks.keys.len() == 1 => self.jwks.keys.first(),
+            None => None,
+        }
+        .ok_or(AuthenticationEngineError::Validation {
+            message: "no matching IdP key found for the access token".to_owned(),
+        })?;
+
+        let decoding_key =
+            DecodingKey::from_jwk(jwk).map_err(|e| AuthenticationEngineError::Validation {
+                message: format!("unable to use IdP key: {e}"),
+            })?;
+
+        // The algorithm in the header is chosen by whoever made the token, so it
+        // is only accepted if the key can be used with it.
+        let algorithms = key_algorithms(jwk)?;
+        if !algorithms.contains(&header.alg) {
+            return Err(AuthenticationEngineError::Validation {
+                message: format!(
+                    "the access token's algorithm ({:?}) can't be used with the IdP key",
+                    header.alg
+                ),
+            });
+        }
+
+        let mut validation = Validation::new(header.alg);
+        validation.algorithms = algorithms;
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let token_data =
+            jsonwebtoken::decode::<serde_json::Value>(token.as_str(), &decoding_key, &validation)
+                .map_err(|e| AuthenticationEngineError::Validation {
+                message: format!("invalid access token: {e}"),
+            })?;
+
+        if is_id_token(header.typ.as_deref(), &token_data.claims) {
+            return Err(AuthenticationEngineError::Validation {
+                message: "an ID token can't be used as an access token".to_owned(),
+            });
+        }
+
+        Ok(PotreeAuthClaims(token_data.claims))
+    }
+}
+
+/// The claims that only ID tokens have: the `nonce` of the authentication
+/// request, and the hashes of the tokens issued along with them.
+const ID_TOKEN_CLAIMS: [&str; 3] = ["nonce", "at_hash", "c_hash"];
+
+/// Whether a token with the `typ` header and `claims` is an ID token. Tokens of
+/// the `at+jwt` type are access tokens (RFC 9068), while other tokens are
+/// considered ID tokens if they have any of the [`ID_TOKEN_CLAIMS`].
+fn is_id_token(typ: Option<&str>, claims: &serde_json::Value) -> bool {
+    let is_access_token = typ.is_some_and(|typ| {
+        typ.eq_ignore_ascii_case("at+jwt") || typ.eq_ignore_ascii_case("application/at+jwt")
+    });
+
+    !is_access_token
+        && ID_TOKEN_CLAIMS
+            .iter()
+            .any(|claim| claims.get(claim).is_some())
+}
+
+/// The algorithms that tokens signed with the `jwk` can use: its `alg` if it
+/// has one, otherwise the signature algorithms of its key type (and curve).
+fn key_algorithms(jwk: &Jwk) -> Result<Vec<Algorithm>, AuthenticationEngineError> {
+    if let Some(key_algorithm) = jwk.common.key_algorithm {
+        // Keys for encryption (e.g. `RSA-OAEP`) can't verify signatures.
+        let algorithm = Algorithm::from_str(&key_algorithm.to_string()).map_err(|_| {
+            AuthenticationEngineError::Validation {
+                message: format!("the IdP key's algorithm ({key_algorithm}) can't verify tokens"),
+            }
+        })?;
+
+        return Ok(vec![algorithm]);
+    }
+
+    let algorithms = match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => vec![
+            Algorithm::RS256,
+            Algorithm::RS384,
+            Algorithm::RS512,
+            Algorithm::PS256,
+            Algorithm::PS384,
+            Algorithm::PS512,
+        ],
+        AlgorithmParameters::EllipticCurve(parameters) => match parameters.curve {
+            EllipticCurve::P256 => vec![Algorithm::ES256],
+            EllipticCurve::P384 => vec![Algorithm::ES384],
+            EllipticCurve::P521 | EllipticCurve::Ed25519 => Vec::new(),
+        },
+        AlgorithmParameters::OctetKeyPair(parameters) => match parameters.curve {
+            EllipticCurve::Ed25519 => vec![Algorithm::EdDSA],
+            EllipticCurve::P256 | EllipticCurve::P384 | EllipticCurve::P521 => Vec::new(),
+        },
+        AlgorithmParameters::OctetKey(_) => {
+            vec![Algorithm::HS256, Algorithm::HS384, Algorithm::HS512]
+        }
+    };
+
+    if algorithms.is_empty() {
+        return Err(AuthenticationEngineError::Validation {
+            message: "the IdP key's type can't verify tokens".to_owned(),
+        });
+    }
+
+    Ok(algorithms)
+}
+
+#[cfg(test)]
+mod access_token_validator_tests {
+    use jsonwebtoken::EncodingKey;
+    use jsonwebtoken::Header;
+    use jsonwebtoken::get_current_timestamp;
+
+    use super::*;
+
+    const ISSUER: &str = "https://idp.example.com";
+    const AUDIENCE: &str = "potree-auth";
+    const KEY_ID: &str = "test-key";
+    const SECRET: &[u8] = b"a-very-secret-key-used-for-testing-purposes";
+
+    fn validator() -> AccessTokenValidator {
+        let jwks = serde_json::from_value::<JwkSet>(serde_json::json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": KEY_ID,
+                "alg": "HS256",
+                "k": "YS12ZXJ5LXNlY3JldC1rZXktdXNlZC1mb3ItdGVzdGluZy1wdXJwb3Nlcw",
+            }]
+        }))
+        .unwrap();
+
+        AccessTokenValidator::new(jwks, ISSUER.to_owned(), AUDIENCE.to_owned())
+    }
+
+    fn token(claims: serde_json::Value, kid: Option<&str>) -> BearerToken {
+        let header = Header {
+            kid: kid.map(str::to_owned),
+            ..Header::default()
+        };
+
+        BearerToken::new(
+            jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap(),
+        )
+    }
+
+    fn valid_claims() -> serde_json::Value {
+        serde_json::json!({
+            "sub": "user-id",
+            "iss": ISSUER,
+            "aud": AUDIENCE,
+            "exp": get_current_timestamp() + 300,
+            "email": "user@example.com",
+        })
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test_case::test_case(Some(KEY_ID); "with key id")]
+        #[test_case::test_case(None; "without key id")]
+        fn should_return_the_claims_of_a_valid_token(kid: Option<&str>) {
+            // Arrange
+            let token = token(valid_claims(), kid);
+
+            // Act
+            let claims = validator().validate(&token).unwrap();
+
+            // Assert
+            assert_eq!(claims.0, valid_claims());
+        }
+
+        #[test_case::test_case("iss", serde_json::json!("https://other.example.com"); "wrong issuer")]
+        #[test_case::test_case("aud", serde_json::json!("other-client"); "wrong audience")]
+        #[test_case::test_case("exp", serde_json::json!(get_current_timestamp() - 300); "expired")]
+        fn should_return_err_if_claims_are_invalid(claim: &str, value: serde_json::Value) {
+            // Arrange
+            let mut claims = valid_claims();
+            claims[claim] = value;
+            let token = token(claims, Some(KEY_ID));
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+
+        #[test]
+        fn should_return_err_if_signed_with_another_key() {
+            // Arrange
+            let token = BearerToken::new(
+                jsonwebtoken::encode(
+                    &Header::default(),
+                    &valid_claims(),
+                    &EncodingKey::from_secret(b"another-secret"),
+                )
+                .unwrap(),
+            );
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+
+        #[test_case::test_case("nonce"; "nonce")]
+        #[test_case::test_case("at_hash"; "access token hash")]
+        fn should_return_err_if_an_id_token(claim: &str) {
+            // Arrange
+            let mut claims = valid_claims();
+            claims[claim] = serde_json::json!("some-value");
+            let token = token(claims, Some(KEY_ID));
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { message })
+                    if message.contains("ID token")
+            ));
+        }
+
+        #[test]
+        fn should_return_the_claims_of_an_access_token_with_a_nonce() {
+            // Arrange
+            let mut claims = valid_claims();
+            claims["nonce"] = serde_json::json!("some-value");
+            let header = Header {
+                kid: Some(KEY_ID.to_owned()),
+                typ: Some("at+jwt".to_owned()),
+                ..Header::default()
+            };
+            let token = BearerToken::new(
+                jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap(),
+            );
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn should_return_err_if_algorithm_does_not_match_the_key() {
+            // Arrange
+            let header = Header {
+                kid: Some(KEY_ID.to_owned()),
+                ..Header::new(Algorithm::HS512)
+            };
+            let token = BearerToken::new(
+                jsonwebtoken::encode(&header, &valid_claims(), &EncodingKey::from_secret(SECRET))
+                    .unwrap(),
+            );
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { message })
+                    if message.contains("algorithm")
+            ));
+        }
+
+        #[test]
+        fn should_return_err_if_key_id_is_unknown() {
+            // Arrange
+            let token = token(valid_claims(), Some("unknown-key"));
+
+            // Act
+            let res = validator().validate(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+    }
+
+    mod key_algorithms {
+        use super::*;
+
+        #[test_case::test_case(serde_json::json!({"kty": "RSA", "n": "AQAB", "e": "AQAB"}), Algorithm::RS256, true; "rsa key")]
+        #[test_case::test_case(serde_json::json!({"kty": "RSA", "alg": "PS256", "n": "AQAB", "e": "AQAB"}), Algorithm::RS256, false; "rsa key with another alg")]
+        #[test_case::test_case(serde_json::json!({"kty": "EC", "crv": "P-256", "x": "AQAB", "y": "AQAB"}), Algorithm::ES256, true; "ec key")]
+        #[test_case::test_case(serde_json::json!({"kty": "EC", "crv": "P-256", "x": "AQAB", "y": "AQAB"}), Algorithm::HS256, false; "ec key with hmac")]
+        #[test_case::test_case(serde_json::json!({"kty": "RSA", "n": "AQAB", "e": "AQAB"}), Algorithm::HS256, false; "rsa key with hmac")]
+        fn should_only_allow_the_algorithms_of_the_key(
+            jwk: serde_json::Value,
+            algorithm: Algorithm,
+            expected: bool,
+        ) {
+            // Arrange
+            let jwk = serde_json::from_value::<Jwk>(jwk).unwrap();
+
+            // Act
+            let algorithms = key_algorithms(&jwk).unwrap();
+
+            // Assert
+            assert_eq!(algorithms.contains(&algorithm), expected);
+        }
+    }
+}
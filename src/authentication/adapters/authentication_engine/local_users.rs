@@ -0,0 +1,744 @@
+//! Authenticates users with the local user accounts listed in a users file
+//! (YAML), e.g. for deployments without an IdP. It implements the
+//! [`AuthenticationEngine`] trait.
+//!
+//! Each user has a `username`, an Argon2 `password_hash` (in the PHC string
+//! format, e.g. `$argon2id$v=19$...`), an `email` and optionally a `name` and
+//! `groups`. Users log in with a login form, and repeated failed attempts are
+//! throttled per client address, and per username from each client address
+//! (so that others can't lock a user out).
+//!
+//! See [`docs/resources/users.yml`] for an example.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use argon2::Argon2;
+use argon2::PasswordHash;
+use argon2::PasswordHasher;
+use argon2::PasswordVerifier;
+use argon2::password_hash::SaltString;
+use argon2::password_hash::rand_core::OsRng;
+use async_trait::async_trait;
+use http::HeaderMap;
+use http::HeaderName;
+use ipnet::IpNet;
+use time::OffsetDateTime;
+use url::Url;
+
+use super::super::super::domain::AuthorizeData;
+use super::super::super::domain::BearerToken;
+use super::super::super::domain::CallbackData;
+use super::super::super::domain::CallbackRequestParams;
+use super::super::super::domain::IdpStatus;
+use super::super::super::domain::LoginMethod;
+use super::super::super::domain::OidcSessionPersisted;
+use super::super::super::domain::PasswordCredentials;
+use super::super::super::domain::RawIdToken;
+use super::super::super::domain::SessionValidity;
+use super::super::super::ports::authentication_engine::AuthenticationEngine;
+use super::login_throttle::LoginThrottle;
+use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
+use crate::common::domain::Group;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
+use crate::user::domain::UserId;
+use crate::user::domain::UserName;
+
+/// The password hash that unknown usernames are verified against, so that
+/// they can't be told apart from known usernames by the response time.
+static DUMMY_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    Argon2::default()
+        .hash_password(b"not the password", &SaltString::generate(&mut OsRng))
+        .map(|password_hash| password_hash.to_string())
+        .unwrap_or_default()
+});
+
+/// The header in which reverse proxies append the address of the client they
+/// forward a request for.
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+#[derive(Debug, Clone)]
+pub struct LocalUsersAuthenticationEngine {
+    /// The local user accounts, by username.
+    users: Arc<HashMap<String, LocalUser>>,
+
+    /// Throttles repeated failed login attempts.
+    throttle: Arc<LoginThrottle>,
+
+    /// The networks of the reverse proxies whose `X-Forwarded-For` header
+    /// identifies the client of a request.
+    trusted_proxy_networks: Vec<IpNet>,
+}
+
+/// A local user account.
+#[derive(Clone)]
+struct LocalUser {
+    user: User,
+
+    /// The Argon2 hash of the user's password, in the PHC string format.
+    password_hash: String,
+}
+
+impl std::fmt::Debug for LocalUser {
+    /// Never reveals the password hash (e.g. in logs).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalUser")
+            .field("user", &self.user)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How repeated failed login attempts are throttled.
+#[derive(Debug, Clone)]
+pub struct LoginThrottleOptions {
+    /// The number of failed attempts (per client address, or username from a
+    /// client address) after which further attempts have to back off.
+    pub max_failed_logins: u32,
+
+    /// The longest that further attempts are rejected for, after the last
+    /// failed attempt. The backoff doubles with each failed attempt up to it.
+    pub max_backoff: Duration,
+
+    /// The networks of the reverse proxies in front of the server. Requests
+    /// from them are attributed to the client address in their
+    /// `X-Forwarded-For` header, rather than to the proxy.
+    pub trusted_proxy_networks: Vec<IpNet>,
+}
+
+/// Errors that can be experienced loading the local user accounts.
+#[derive(Debug, thiserror::Error)]
+pub enum LocalUsersError {
+    #[error("unable to read the users file ({}): {message}", path.to_string_lossy())]
+    Read { path: PathBuf, message: String },
+
+    #[error("unable to parse the users file: {message}")]
+    Parsing { message: String },
+
+    #[error("user `{username}` is invalid: {message}")]
+    InvalidUser { username: String, message: String },
+}
+
+/// The contents of a users file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UsersDocument {
+    users: Vec<UserDefinition>,
+}
+
+/// A user as it is written in a users file.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserDefinition {
+    username: String,
+    password_hash: String,
+    email: EmailAddress,
+
+    /// Falls back to the `username`.
+    #[serde(default)]
+    name: Option<UserName>,
+
+    #[serde(default)]
+    groups: Vec<Group>,
+}
+
+impl std::fmt::Debug for UserDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserDefinition")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalUsersAuthenticationEngine {
+    /// Loads and validates the users file at `path`.
+    pub fn from_file(
+        path: &Path,
+        throttle_options: LoginThrottleOptions,
+    ) -> Result<Self, LocalUsersError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| LocalUsersError::Read {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        Self::from_users(&contents, throttle_options)
+    }
+
+    /// Parses and validates the (YAML) `users` document.
+    pub fn from_users(
+        users: &str,
+        throttle_options: LoginThrottleOptions,
+    ) -> Result<Self, LocalUsersError> {
+        let document = serde_norway::from_str::<UsersDocument>(users).map_err(|e| {
+            LocalUsersError::Parsing {
+                message: e.to_string(),
+            }
+        })?;
+
+        let mut users = HashMap::with_capacity(document.users.len());
+        for definition in document.users {
+            let invalid_user = |message: &str| LocalUsersError::InvalidUser {
+                username: definition.username.clone(),
+                message: message.to_owned(),
+            };
+
+            if definition.username.trim().is_empty() {
+                return Err(invalid_user("the username must not be empty"));
+            }
+            if users.contains_key(&definition.username) {
+                return Err(invalid_user("the username must be unique"));
+            }
+            let password_hash = PasswordHash::new(&definition.password_hash)
+                .map_err(|e| invalid_user(&format!("invalid password hash: {e}")))?;
+            if argon2::Algorithm::try_from(password_hash.algorithm).is_err() {
+                return Err(invalid_user("the password hash must be an Argon2 hash"));
+            }
+
+            let user = User {
+                id: UserId::new(definition.username.clone()),
+                name: definition
+                    .name
+                    .unwrap_or_else(|| UserName::new(definition.username.clone())),
+                email: definition.email,
+                // The email address is set by whoever manages the users file.
+                email_verified: true,
+                groups: definition.groups,
+                provider: None,
+            };
+            users.insert(
+                definition.username,
+                LocalUser {
+                    user,
+                    password_hash: definition.password_hash,
+                },
+            );
+        }
+
+        Ok(Self {
+            users: Arc::new(users),
+            throttle: Arc::new(LoginThrottle::new(
+                throttle_options.max_failed_logins,
+                throttle_options.max_backoff,
+            )),
+            trusted_proxy_networks: throttle_options.trusted_proxy_networks,
+        })
+    }
+
+    /// Verifies the `credentials` against the password hash of the user,
+    /// unless there have been too many failed attempts from the client, or for
+    /// the username from the client.
+    #[tracing::instrument(skip(self, headers))]
+    async fn authenticate_password(
+        &self,
+        credentials: &PasswordCredentials,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+        now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError> {
+        let client = self.client_address(source, headers);
+        let user_key = match client {
+            Some(client) => format!("user:{}@{client}", credentials.username),
+            None => format!("user:{}", credentials.username),
+        };
+        let client_key = client.map(|client| format!("source:{client}"));
+        let keys = || std::iter::once(user_key.as_str()).chain(client_key.as_deref());
+
+        if let Some(retry_at) = self.throttle.retry_at(keys(), now) {
+            let seconds = (retry_at - now).whole_seconds() + 1;
+            tracing::warn!(
+                ?client,
+                "rejected login attempt due to too many failed attempts"
+            );
+            return Err(AuthenticationEngineError::Validation {
+                message: format!(
+                    "too many failed login attempts, try again in {seconds} second(s)"
+                ),
+            });
+        }
+
+        let local_user = self.users.get(&credentials.username);
+
+        // Hashing is CPU intensive, so it shouldn't block the async runtime.
+        let password = credentials.password.clone();
+        let password_hash = local_user.map_or_else(
+            || DUMMY_PASSWORD_HASH.clone(),
+            |local_user| local_user.password_hash.clone(),
+        );
+        let verified =
+            tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+                .await
+                .map_err(|e| AuthenticationEngineError::Infrastructure {
+                    message: format!("unable to verify password: {e}"),
+                })?;
+
+        match local_user {
+            Some(local_user) if verified => {
+                self.throttle.clear(&user_key);
+                Ok(local_user.user.clone())
+            }
+            _ => {
+                self.throttle.record_failure(keys(), now);
+                tracing::warn!(?client, "failed login attempt");
+                Err(AuthenticationEngineError::Validation {
+                    message: "invalid username or password".to_owned(),
+                })
+            }
+        }
+    }
+
+    /// The address of the client that submitted the login form. Requests from
+    /// the trusted proxies are attributed to the last address of their
+    /// `X-Forwarded-For` header that isn't a trusted proxy, as the addresses
+    /// before it can be made up by the client.
+    fn client_address(&self, source: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let mut client = source?;
+
+        let forwarded_for = headers
+            .get_all(&X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        for address in forwarded_for.into_iter().rev() {
+            if !self.is_trusted_proxy(client) {
+                break;
+            }
+            let Ok(address) = address.parse::<IpAddr>() else {
+                break;
+            };
+            client = address;
+        }
+
+        Some(client)
+    }
+
+    fn is_trusted_proxy(&self, address: IpAddr) -> bool {
+        self.trusted_proxy_networks
+            .iter()
+            .any(|network| network.contains(&address))
+    }
+
+    /// Users log in with the login form.
+    fn not_supported() -> AuthenticationEngineError {
+        AuthenticationEngineError::Infrastructure {
+            message: "local users log in with the login form".to_owned(),
+        }
+    }
+}
+
+/// Whether the `password` matches the (PHC string) `password_hash`.
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|password_hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &password_hash)
+            .is_ok()
+    })
+}
+
+#[async_trait]
+impl AuthenticationEngine for LocalUsersAuthenticationEngine {
+    async fn authorize(&self) -> Result<AuthorizeData, AuthenticationEngineError> {
+        Err(Self::not_supported())
+    }
+
+    async fn callback(
+        &self,
+        _callback_params: CallbackRequestParams,
+        _persisted_data: OidcSessionPersisted,
+    ) -> Result<CallbackData, AuthenticationEngineError> {
+        Err(Self::not_supported())
+    }
+
+    /// There is no IdP session to end.
+    async fn logout(
+        &self,
+        _id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    async fn authenticate_bearer(
+        &self,
+        _token: BearerToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(AuthenticationEngineError::Validation {
+            message: "bearer token authentication is not configured".to_owned(),
+        })
+    }
+
+    /// Proxy headers are never trusted.
+    async fn authenticate_proxy_headers(
+        &self,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    /// The users file is only read on startup, so the users of web sessions
+    /// can't have changed.
+    async fn revalidate(
+        &self,
+        _user: &User,
+        _validity: &SessionValidity,
+        _now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    fn login_method(&self) -> LoginMethod {
+        LoginMethod::Form
+    }
+
+    async fn authenticate_password(
+        &self,
+        credentials: &PasswordCredentials,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+        now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError> {
+        Self::authenticate_password(self, credentials, source, headers, now).await
+    }
+
+    fn status(&self) -> IdpStatus {
+        IdpStatus::Ready
+    }
+}
+
+#[cfg(test)]
+mod local_users_authentication_engine_tests {
+    use argon2::Algorithm;
+    use argon2::Params;
+    use argon2::Version;
+
+    use super::*;
+
+    const MAX_FAILED_LOGINS: u32 = 2;
+    const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+    fn throttle_options() -> LoginThrottleOptions {
+        LoginThrottleOptions {
+            max_failed_logins: MAX_FAILED_LOGINS,
+            max_backoff: MAX_BACKOFF,
+            trusted_proxy_networks: vec!["10.0.0.0/8".parse().unwrap()],
+        }
+    }
+
+    /// Hashes the `password` with cheap parameters, to keep the tests fast.
+    fn hash(password: &str) -> String {
+        Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            Params::new(1024, 1, 1, None).unwrap(),
+        )
+        .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+        .unwrap()
+        .to_string()
+    }
+
+    fn engine() -> LocalUsersAuthenticationEngine {
+        let users = format!(
+            r#"
+            users:
+              - username: surveyor
+                password_hash: "{}"
+                email: surveyor@example.com
+                name: A Surveyor
+                groups: [surveyors]
+            "#,
+            hash("correct horse")
+        );
+
+        LocalUsersAuthenticationEngine::from_users(&users, throttle_options()).unwrap()
+    }
+
+    fn forwarded_for(forwarded_for: &str) -> HeaderMap {
+        HeaderMap::from_iter([(X_FORWARDED_FOR.clone(), forwarded_for.parse().unwrap())])
+    }
+
+    fn credentials(username: &str, password: &str) -> PasswordCredentials {
+        PasswordCredentials {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+
+    mod from_users {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_example_users() {
+            // Act
+            let res = LocalUsersAuthenticationEngine::from_users(
+                include_str!("../../../../docs/resources/users.yml"),
+                throttle_options(),
+            );
+
+            // Assert
+            assert_eq!(res.unwrap().users.len(), 2);
+        }
+
+        #[test_case::test_case("users: [{ username: a, password_hash: not-a-hash, email: a@example.com }]"; "invalid hash")]
+        #[test_case::test_case("users: [{ username: a, password_hash: '$pbkdf2-sha256$i=1000$c2FsdA$aGFzaA', email: a@example.com }]"; "not argon2")]
+        #[test_case::test_case("users: [{ username: '', password_hash: '$argon2id$v=19$m=1024,t=1,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA', email: a@example.com }]"; "empty username")]
+        #[test_case::test_case("users: [{ username: a, email: a@example.com }]"; "missing hash")]
+        fn should_return_err_if_users_invalid(users: &str) {
+            // Act
+            let res = LocalUsersAuthenticationEngine::from_users(users, throttle_options());
+
+            // Assert
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn should_return_err_if_username_not_unique() {
+            // Arrange
+            let password_hash = hash("password");
+            let users = format!(
+                r#"
+                users:
+                  - {{ username: a, password_hash: "{password_hash}", email: a@example.com }}
+                  - {{ username: a, password_hash: "{password_hash}", email: b@example.com }}
+                "#
+            );
+
+            // Act
+            let res = LocalUsersAuthenticationEngine::from_users(&users, throttle_options());
+
+            // Assert
+            assert!(matches!(res, Err(LocalUsersError::InvalidUser { .. })));
+        }
+    }
+
+    mod authenticate_password {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_user_if_password_correct() {
+            // Act
+            let user = engine()
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    None,
+                    &HeaderMap::new(),
+                    OffsetDateTime::now_utc(),
+                )
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                user,
+                User {
+                    id: UserId::new("surveyor".to_owned()),
+                    name: UserName::new("A Surveyor".to_owned()),
+                    email: EmailAddress::new("surveyor@example.com".to_owned()),
+                    email_verified: true,
+                    groups: vec![Group::new("surveyors")],
+                    provider: None,
+                }
+            );
+        }
+
+        #[test_case::test_case("surveyor", "wrong horse"; "wrong password")]
+        #[test_case::test_case("admin", "correct horse"; "unknown user")]
+        #[tokio::test]
+        async fn should_return_err_if_credentials_invalid(username: &str, password: &str) {
+            // Act
+            let res = engine()
+                .authenticate_password(
+                    &credentials(username, password),
+                    None,
+                    &HeaderMap::new(),
+                    OffsetDateTime::now_utc(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_reject_even_the_correct_password_once_throttled() {
+            // Arrange
+            let engine = engine();
+            let now = OffsetDateTime::now_utc();
+            for _ in 0..MAX_FAILED_LOGINS {
+                let _ = engine
+                    .authenticate_password(
+                        &credentials("surveyor", "guess"),
+                        None,
+                        &HeaderMap::new(),
+                        now,
+                    )
+                    .await;
+            }
+
+            // Act
+            let throttled = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    None,
+                    &HeaderMap::new(),
+                    now,
+                )
+                .await;
+            let after_backoff = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    None,
+                    &HeaderMap::new(),
+                    now + MAX_BACKOFF,
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                throttled,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+            assert!(after_backoff.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_throttle_a_source_guessing_many_usernames() {
+            // Arrange
+            let engine = engine();
+            let now = OffsetDateTime::now_utc();
+            let source = Some("10.0.0.1".parse().unwrap());
+            for username in ["admin", "root"] {
+                let _ = engine
+                    .authenticate_password(
+                        &credentials(username, "guess"),
+                        source,
+                        &HeaderMap::new(),
+                        now,
+                    )
+                    .await;
+            }
+
+            // Act
+            let throttled = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    source,
+                    &HeaderMap::new(),
+                    now,
+                )
+                .await;
+            let other_source = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    Some("10.0.0.2".parse().unwrap()),
+                    &HeaderMap::new(),
+                    now,
+                )
+                .await;
+
+            // Assert
+            assert!(throttled.is_err());
+            assert!(other_source.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_not_throttle_the_username_from_other_sources() {
+            // Arrange
+            let engine = engine();
+            let now = OffsetDateTime::now_utc();
+            let source = Some("192.0.2.1".parse().unwrap());
+            for _ in 0..MAX_FAILED_LOGINS {
+                let _ = engine
+                    .authenticate_password(
+                        &credentials("surveyor", "guess"),
+                        source,
+                        &HeaderMap::new(),
+                        now,
+                    )
+                    .await;
+            }
+
+            // Act
+            let res = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    Some("192.0.2.2".parse().unwrap()),
+                    &HeaderMap::new(),
+                    now,
+                )
+                .await;
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_throttle_the_clients_behind_a_trusted_proxy_separately() {
+            // Arrange
+            let engine = engine();
+            let now = OffsetDateTime::now_utc();
+            let proxy = Some("10.0.0.1".parse().unwrap());
+            for username in ["admin", "root"] {
+                let _ = engine
+                    .authenticate_password(
+                        &credentials(username, "guess"),
+                        proxy,
+                        &forwarded_for("203.0.113.1"),
+                        now,
+                    )
+                    .await;
+            }
+
+            // Act
+            let throttled = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    proxy,
+                    &forwarded_for("203.0.113.1"),
+                    now,
+                )
+                .await;
+            let other_client = engine
+                .authenticate_password(
+                    &credentials("surveyor", "correct horse"),
+                    proxy,
+                    &forwarded_for("203.0.113.2"),
+                    now,
+                )
+                .await;
+
+            // Assert
+            assert!(throttled.is_err());
+            assert!(other_client.is_ok());
+        }
+    }
+
+    mod client_address {
+        use super::*;
+
+        #[test_case::test_case("192.0.2.1", None, "192.0.2.1"; "without proxy")]
+        #[test_case::test_case("10.0.0.1", Some("203.0.113.1"), "203.0.113.1"; "behind trusted proxy")]
+        #[test_case::test_case("10.0.0.1", Some("203.0.113.1, 10.0.0.2"), "203.0.113.1"; "behind trusted proxies")]
+        #[test_case::test_case("10.0.0.1", Some("198.51.100.1, 203.0.113.1"), "203.0.113.1"; "ignoring addresses made up by the client")]
+        #[test_case::test_case("10.0.0.1", Some("not-an-address"), "10.0.0.1"; "ignoring invalid addresses")]
+        #[test_case::test_case("192.0.2.1", Some("203.0.113.1"), "192.0.2.1"; "ignoring untrusted proxy")]
+        fn should_return_the_client_address(source: &str, header: Option<&str>, expected: &str) {
+            // Arrange
+            let headers = header.map_or_else(HeaderMap::new, forwarded_for);
+
+            // Act
+            let client = engine().client_address(Some(source.parse().unwrap()), &headers);
+
+            // Assert
+            assert_eq!(client, Some(expected.parse().unwrap()));
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Throttles repeated failed login attempts, to slow down brute-forcing of
+//! passwords.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+/// How long attempts are rejected for after the first failed attempt beyond
+/// the free ones. It doubles with each further failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Tracks the failed login attempts per key (e.g. per source address). Once a
+/// key has `max_failures` failed attempts, further attempts are rejected for a
+/// backoff that doubles with each failed attempt (up to `max_backoff`), rather
+/// than being locked out.
+#[derive(Debug)]
+pub(crate) struct LoginThrottle {
+    max_failures: u32,
+    max_backoff: Duration,
+    failures: Mutex<HashMap<String, FailedAttempts>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FailedAttempts {
+    count: u32,
+    last_failed_at: OffsetDateTime,
+}
+
+impl LoginThrottle {
+    pub fn new(max_failures: u32, max_backoff: Duration) -> Self {
+        Self {
+            max_failures,
+            max_backoff,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether attempts for any of the `keys` currently have to back off.
+    ///
+    /// Returns when attempts can be made again, if backing off.
+    pub fn retry_at<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        let failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+
+        keys.into_iter()
+            .filter_map(|key| failures.get(key))
+            .filter_map(|attempts| {
+                self.backoff(attempts.count)
+                    .map(|backoff| attempts.last_failed_at + backoff)
+            })
+            .filter(|retry_at| *retry_at > now)
+            .max()
+    }
+
+    /// Records a failed attempt for each of the `keys`.
+    pub fn record_failure<'a>(&self, keys: impl IntoIterator<Item = &'a str>, now: OffsetDateTime) {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Forget failed attempts that no longer count, so that the map doesn't grow
+        // indefinitely.
+        failures.retain(|_, attempts| !self.is_expired(attempts, now));
+
+        for key in keys {
+            let attempts = failures.entry(key.to_owned()).or_insert(FailedAttempts {
+                count: 0,
+                last_failed_at: now,
+            });
+            attempts.count = attempts.count.saturating_add(1);
+            attempts.last_failed_at = now;
+        }
+    }
+
+    /// Forgets the failed attempts of the `key` (e.g. after a successful
+    /// login).
+    pub fn clear(&self, key: &str) {
+        self.failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+    }
+
+    /// How long attempts are rejected for after the `count`th failed attempt,
+    /// if at all.
+    fn backoff(&self, count: u32) -> Option<Duration> {
+        let exponent = count.checked_sub(self.max_failures)?;
+
+        Some(
+            2_u32
+                .checked_pow(exponent)
+                .and_then(|factor| INITIAL_BACKOFF.checked_mul(factor))
+                .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff)),
+        )
+    }
+
+    /// Failed attempts are forgotten once `max_backoff` has passed since the
+    /// last one.
+    fn is_expired(&self, attempts: &FailedAttempts, now: OffsetDateTime) -> bool {
+        now - attempts.last_failed_at >= self.max_backoff
+    }
+}
+
+#[cfg(test)]
+mod login_throttle_tests {
+    use super::*;
+
+    const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+    fn throttle_with_failures(keys: &[&str], count: u32, now: OffsetDateTime) -> LoginThrottle {
+        let throttle = LoginThrottle::new(3, MAX_BACKOFF);
+        for _ in 0..count {
+            throttle.record_failure(keys.iter().copied(), now);
+        }
+
+        throttle
+    }
+
+    mod retry_at {
+        use super::*;
+
+        #[test_case::test_case(3, INITIAL_BACKOFF; "once max failures reached")]
+        #[test_case::test_case(5, INITIAL_BACKOFF * 4; "doubling with each further failure")]
+        #[test_case::test_case(40, MAX_BACKOFF; "up to the max backoff")]
+        fn should_back_off(count: u32, backoff: Duration) {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], count, now);
+
+            // Act
+            let retry_at = throttle.retry_at(["user:surveyor", "source:10.0.0.1"], now);
+
+            // Assert
+            assert_eq!(retry_at, Some(now + backoff));
+        }
+
+        #[test]
+        fn should_not_back_off_below_max_failures() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], 2, now);
+
+            // Act
+            let retry_at = throttle.retry_at(["source:10.0.0.1"], now);
+
+            // Assert
+            assert_eq!(retry_at, None);
+        }
+
+        #[test]
+        fn should_not_back_off_once_backoff_has_passed() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], 3, now);
+
+            // Act
+            let retry_at = throttle.retry_at(["source:10.0.0.1"], now + INITIAL_BACKOFF);
+
+            // Assert
+            assert_eq!(retry_at, None);
+        }
+
+        #[test]
+        fn should_not_back_off_other_keys() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], 3, now);
+
+            // Act
+            let retry_at = throttle.retry_at(["source:10.0.0.2"], now);
+
+            // Assert
+            assert_eq!(retry_at, None);
+        }
+    }
+
+    mod record_failure {
+        use super::*;
+
+        #[test]
+        fn should_start_counting_again_once_max_backoff_has_passed() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], 10, now);
+
+            // Act
+            throttle.record_failure(["source:10.0.0.1"], now + MAX_BACKOFF);
+
+            // Assert
+            assert_eq!(
+                throttle.retry_at(["source:10.0.0.1"], now + MAX_BACKOFF),
+                None
+            );
+        }
+    }
+
+    mod clear {
+        use super::*;
+
+        #[test]
+        fn should_forget_the_failed_attempts() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let throttle = throttle_with_failures(&["source:10.0.0.1"], 3, now);
+
+            // Act
+            throttle.clear("source:10.0.0.1");
+
+            // Assert
+            assert_eq!(throttle.retry_at(["source:10.0.0.1"], now), None);
+        }
+    }
+}
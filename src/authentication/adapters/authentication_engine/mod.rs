@@ -1,3 +1,7 @@
+mod access_token;
+pub mod local_users;
+mod login_throttle;
 pub mod no_op;
 pub mod oidc;
+pub mod trusted_proxy;
 mod utils;
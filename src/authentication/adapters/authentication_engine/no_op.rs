@@ -1,8 +1,20 @@
+use std::net::IpAddr;
+
 use async_trait::async_trait;
+use http::HeaderMap;
+use time::OffsetDateTime;
+use url::Url;
 
 use super::super::super::domain::AuthorizeData;
+use super::super::super::domain::BearerToken;
+use super::super::super::domain::CallbackData;
 use super::super::super::domain::CallbackRequestParams;
+use super::super::super::domain::IdpStatus;
+use super::super::super::domain::LoginMethod;
 use super::super::super::domain::OidcSessionPersisted;
+use super::super::super::domain::PasswordCredentials;
+use super::super::super::domain::RawIdToken;
+use super::super::super::domain::SessionValidity;
 use super::super::super::ports::authentication_engine::AuthenticationEngine;
 use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
 use crate::user::domain::User;
@@ -28,11 +40,74 @@ impl AuthenticationEngine for NoOpAuthenticationEngine {
         &self,
         _callback_params: CallbackRequestParams,
         _persisted_data: OidcSessionPersisted,
-    ) -> Result<User, AuthenticationEngineError> {
+    ) -> Result<CallbackData, AuthenticationEngineError> {
         Err(AuthenticationEngineError::Infrastructure {
             message:
                 "this is a placeholder for when the application does not require authentication"
                     .to_owned(),
         })
     }
+
+    /// There is no IdP session to end, so the user agent does not need to be
+    /// redirected anywhere.
+    #[tracing::instrument]
+    async fn logout(
+        &self,
+        _id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    #[tracing::instrument(skip(_token))]
+    async fn authenticate_bearer(
+        &self,
+        _token: BearerToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(AuthenticationEngineError::Validation {
+            message: "bearer token authentication is not configured".to_owned(),
+        })
+    }
+
+    /// Proxy headers are never trusted.
+    #[tracing::instrument(skip(_headers))]
+    async fn authenticate_proxy_headers(
+        &self,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    /// There are no web sessions to re-validate.
+    #[tracing::instrument]
+    async fn revalidate(
+        &self,
+        _user: &User,
+        _validity: &SessionValidity,
+        _now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    fn login_method(&self) -> LoginMethod {
+        LoginMethod::Redirect
+    }
+
+    #[tracing::instrument]
+    async fn authenticate_password(
+        &self,
+        _credentials: &PasswordCredentials,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+        _now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(AuthenticationEngineError::Validation {
+            message: "password authentication is not configured".to_owned(),
+        })
+    }
+
+    /// There is no IdP, so nothing can be unavailable.
+    fn status(&self) -> IdpStatus {
+        IdpStatus::Ready
+    }
 }
@@ -1,20 +1,44 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use http::HeaderMap;
+use openidconnect::AccessToken;
 use openidconnect::AdditionalClaims;
+use openidconnect::AuthenticationContextClass;
 use openidconnect::Client;
 use openidconnect::ClientId;
 use openidconnect::ClientSecret;
 use openidconnect::CsrfToken;
 use openidconnect::EmptyExtraTokenFields;
+use openidconnect::EndSessionUrl;
 use openidconnect::EndpointMaybeSet;
 use openidconnect::EndpointNotSet;
 use openidconnect::EndpointSet;
+use openidconnect::IdToken;
+use openidconnect::IdTokenClaims;
 use openidconnect::IdTokenFields;
 use openidconnect::IssuerUrl;
+use openidconnect::LoginHint;
+use openidconnect::LogoutRequest;
 use openidconnect::Nonce;
+use openidconnect::OAuth2TokenResponse;
+use openidconnect::PkceCodeChallenge;
+use openidconnect::PostLogoutRedirectUrl;
+use openidconnect::ProviderMetadataWithLogout;
 use openidconnect::RedirectUrl;
+use openidconnect::RefreshToken;
+use openidconnect::RequestTokenError;
+use openidconnect::Scope;
 use openidconnect::StandardErrorResponse;
 use openidconnect::StandardTokenResponse;
 use openidconnect::TokenResponse;
+use openidconnect::UserInfoClaims;
 use openidconnect::core::CoreAuthDisplay;
 use openidconnect::core::CoreAuthPrompt;
 use openidconnect::core::CoreAuthenticationFlow;
@@ -23,91 +47,423 @@ use openidconnect::core::CoreGenderClaim;
 use openidconnect::core::CoreJsonWebKey;
 use openidconnect::core::CoreJweContentEncryptionAlgorithm;
 use openidconnect::core::CoreJwsSigningAlgorithm;
-use openidconnect::core::CoreProviderMetadata;
 use openidconnect::core::CoreRevocableToken;
 use openidconnect::core::CoreRevocationErrorResponse;
 use openidconnect::core::CoreTokenIntrospectionResponse;
 use openidconnect::core::CoreTokenType;
 use serde::Deserialize;
 use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::sync::watch;
 use url::Url;
 
 use super::super::super::domain::AuthorizeData;
+use super::super::super::domain::BearerToken;
+use super::super::super::domain::CallbackData;
 use super::super::super::domain::CallbackRequestParams;
+use super::super::super::domain::GroupMapping;
+use super::super::super::domain::IdpStatus;
+use super::super::super::domain::LoginMethod;
 use super::super::super::domain::OidcSessionPersisted;
+use super::super::super::domain::PasswordCredentials;
+use super::super::super::domain::RawIdToken;
+use super::super::super::domain::RawRefreshToken;
+use super::super::super::domain::SessionValidity;
 use super::super::super::ports::authentication_engine::AuthenticationEngine;
+use super::access_token::AccessTokenValidator;
+use super::utils::MIN_DISCOVERY_RETRY_DELAY;
+use super::utils::extract_bearer_user;
 use super::utils::extract_user_email;
+use super::utils::extract_user_email_verified;
 use super::utils::extract_user_groups;
 use super::utils::extract_user_id;
 use super::utils::extract_user_name;
+use super::utils::merge_claims;
+use super::utils::next_retry_delay;
+use super::utils::verify_auth_time;
 use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
 use crate::user::domain::User;
 
+/// How long a request for the IdP's metadata (or keys) may take.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the result of refreshing a session's tokens is shared with the
+/// requests that still present the previous refresh token (e.g. those that
+/// loaded the session before it was saved).
+const REFRESH_RETENTION: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Clone)]
 pub struct OidcAuthenticationEngine {
-    /// The oidc client that is performing interaction with the IdP.
-    oidc_client: PotreeAuthClient,
+    /// Discovers the IdP's metadata, from which the oidc client is built.
+    discovery: ProviderDiscovery,
+
+    /// The state of the IdP's discovered metadata. It is shared with the
+    /// background discovery task, which keeps it up to date.
+    provider: Arc<RwLock<ProviderState>>,
 
     /// The name of the OIDC claim containing and array of
     /// groups that a user is part of.
     groups_claim: String,
+
+    /// Maps the IdP's group values to the groups used in project manifests.
+    group_mapping: GroupMapping,
+
+    /// Whether to fetch the user's claims from the IdP's UserInfo endpoint
+    /// (e.g. if the groups are not included in the ID token).
+    fetch_userinfo: bool,
+
+    /// Whether the email addresses of the IdP's users are trusted, even if the
+    /// IdP doesn't claim to have verified them.
+    trust_unverified_email: bool,
+
+    /// The URL to which the IdP should redirect the user-agent after logging
+    /// out.
+    post_logout_redirect_url: PostLogoutRedirectUrl,
+
+    /// Additional parameters sent to the IdP when initiating authentication.
+    request_options: AuthenticationRequestOptions,
+
+    /// How long the user of a web session is trusted before their claims are
+    /// re-validated with the IdP (using the refresh token).
+    session_max_age: Option<Duration>,
+
+    /// The (recent) refreshes of web sessions, by the refresh token they were
+    /// made with. A page (e.g. the Potree viewer) loads many assets at once,
+    /// which would otherwise each refresh the session with the same refresh
+    /// token. IdPs that rotate refresh tokens only accept one of them.
+    refreshes: Arc<Mutex<HashMap<String, SessionRefresh>>>,
+}
+
+/// The refresh of a web session's tokens, which all the requests of the
+/// session wait for.
+#[derive(Debug, Clone)]
+struct SessionRefresh {
+    /// When the refresh started, after which it is retained for the
+    /// [`REFRESH_RETENTION`].
+    started_at: OffsetDateTime,
+
+    /// The result of the refresh, once it is done.
+    result: watch::Receiver<Option<Result<CallbackData, AuthenticationEngineError>>>,
+}
+
+/// Configures an [`OidcAuthenticationEngine`].
+#[derive(Debug, Clone)]
+pub struct OidcEngineOptions {
+    /// The URL to the IdP service.
+    pub idp_url: Url,
+
+    /// The URL to which the IdP should redirect the user-agent after
+    /// successful authentication.
+    pub redirect_url: Url,
+
+    /// The `id` of the application on the IdP.
+    pub client_id: String,
+
+    /// The Authorization Code Flow client secret shared between the IdP and
+    /// the application.
+    pub client_secret: String,
+
+    /// The name of the OIDC claim containing and array of groups that a user
+    /// is part of. Nested claims can be referenced with a `.` separated path
+    /// (e.g. `realm_access.roles`).
+    pub groups_claim: String,
+
+    /// Maps the IdP's group values to the groups used in project manifests.
+    pub group_mapping: GroupMapping,
+
+    /// Whether to merge the claims from the IdP's UserInfo endpoint with those
+    /// of the ID token.
+    pub fetch_userinfo: bool,
+
+    /// Whether to trust the email addresses of the IdP's users (e.g. to grant
+    /// access by email), even if the IdP doesn't claim to have verified them
+    /// with the `email_verified` claim.
+    pub trust_unverified_email: bool,
+
+    /// The URL to which the IdP should redirect the user-agent after logging
+    /// out.
+    pub post_logout_redirect_url: Url,
+
+    /// The expected `aud` claim of bearer access tokens. Defaults to the
+    /// `client_id`.
+    pub access_token_audience: Option<String>,
+
+    /// Additional parameters sent to the IdP when initiating authentication.
+    pub request_options: AuthenticationRequestOptions,
+
+    /// How long the user of a web session is trusted before their claims are
+    /// re-validated with the IdP.
+    pub session_max_age: Option<Duration>,
+}
+
+/// Additional parameters of the OIDC authentication request (i.e. the
+/// `/authorize` endpoint).
+#[derive(Debug, Clone, Default)]
+pub struct AuthenticationRequestOptions {
+    /// Scopes requested in addition to `openid` (e.g. `groups`).
+    pub scopes: Vec<String>,
+
+    /// Whether (and how) the IdP should prompt the user (e.g. `login`,
+    /// `consent`, `select_account`).
+    pub prompt: Vec<String>,
+
+    /// A hint to the IdP about the user's login identifier (e.g. an email
+    /// address).
+    pub login_hint: Option<String>,
+
+    /// The requested Authentication Context Class References, in order of
+    /// preference.
+    pub acr_values: Vec<String>,
+
+    /// The maximum time since the user last actively authenticated with the
+    /// IdP. The IdP is required to re-authenticate the user if exceeded.
+    pub max_age: Option<Duration>,
+}
+
+/// Everything needed to discover the IdP's metadata and to build a
+/// [`DiscoveredProvider`] from it.
+#[derive(Debug, Clone)]
+struct ProviderDiscovery {
+    issuer_url: IssuerUrl,
+    redirect_url: RedirectUrl,
+    client_id: ClientId,
+    client_secret: ClientSecret,
+
+    /// The expected `aud` claim of bearer access tokens.
+    access_token_audience: String,
+
+    /// Whether the IdP is required to advertise a UserInfo endpoint.
+    fetch_userinfo: bool,
+
+    /// Used to request the IdP's metadata.
+    http_client: reqwest::Client,
+}
+
+/// The parts of the engine that are built from the IdP's discovered metadata.
+#[derive(Debug)]
+struct DiscoveredProvider {
+    /// The oidc client that is performing interaction with the IdP.
+    oidc_client: PotreeAuthClient,
+
+    /// The IdP's RP-Initiated Logout endpoint, if it advertises one in its
+    /// discovery document.
+    end_session_url: Option<EndSessionUrl>,
+
+    /// Validates access tokens presented as `Authorization: Bearer` headers.
+    access_token_validator: AccessTokenValidator,
+}
+
+/// The most recently discovered metadata of the IdP, and how up to date it is.
+#[derive(Debug)]
+struct ProviderState {
+    discovered: Option<Arc<DiscoveredProvider>>,
+    status: IdpStatus,
+}
+
+impl ProviderDiscovery {
+    /// Requests the IdP's metadata (including its signing keys) and builds the
+    /// oidc client and access token validator from it.
+    async fn discover(&self) -> Result<DiscoveredProvider, AuthenticationEngineError> {
+        let provider_metadata =
+            ProviderMetadataWithLogout::discover_async(self.issuer_url.clone(), &self.http_client)
+                .await
+                .map_err(|e| AuthenticationEngineError::IdpExchange {
+                    message: format!("unable to perform OIDC discovery: {e}"),
+                })?;
+
+        if self.fetch_userinfo && provider_metadata.userinfo_endpoint().is_none() {
+            return Err(AuthenticationEngineError::IdpExchange {
+                message: "the IdP does not advertise a `userinfo_endpoint`".to_owned(),
+            });
+        }
+
+        let end_session_url = provider_metadata
+            .additional_metadata()
+            .end_session_endpoint
+            .clone();
+
+        // Reuse the keys fetched during discovery to validate access tokens.
+        let jwks = serde_json::to_value(provider_metadata.jwks())
+            .and_then(serde_json::from_value)
+            .map_err(|e| AuthenticationEngineError::IdpExchange {
+                message: format!("unable to parse the IdP's JSON Web Key Set: {e}"),
+            })?;
+        let access_token_validator = AccessTokenValidator::new(
+            jwks,
+            provider_metadata.issuer().to_string(),
+            self.access_token_audience.clone(),
+        );
+
+        let oidc_client = PotreeAuthClient::from_provider_metadata(
+            provider_metadata,
+            self.client_id.clone(),
+            Some(self.client_secret.clone()),
+        )
+        .set_redirect_uri(self.redirect_url.clone());
+
+        Ok(DiscoveredProvider {
+            oidc_client,
+            end_session_url,
+            access_token_validator,
+        })
+    }
+}
+
+impl ProviderState {
+    /// Records the result of discovering the IdP's metadata. If it failed, the
+    /// previously discovered metadata (if any) is kept.
+    fn record(&mut self, result: Result<DiscoveredProvider, AuthenticationEngineError>) {
+        match result {
+            Ok(discovered) => {
+                self.discovered = Some(Arc::new(discovered));
+                self.status = IdpStatus::Ready;
+            }
+            Err(e) => {
+                let message = e.to_string();
+                self.status = if self.discovered.is_some() {
+                    IdpStatus::Stale { message }
+                } else {
+                    IdpStatus::Unavailable { message }
+                };
+            }
+        }
+    }
 }
 
 impl OidcAuthenticationEngine {
-    /// Creates a new [`OidcAuthenticationService`] instance with the specified
-    /// `projects_directory`.
+    /// Creates a new [`OidcAuthenticationEngine`] instance, configured by the
+    /// `options`.
     ///
-    /// # Arguments
-    ///
-    /// - `idp_url`: The URL to the IdP service.
-    /// - `redirect_url`: The URL to which the IdP should redirect the
-    ///   user-agent after successful authentication.
-    /// - `client_id`: The `id` of the application on the IdP.
-    /// - `client_secret`: The Authorization Code Flow client secret shared
-    ///   between the IdP and the application.
-    /// - `groups_claim`: The name of the OIDC claim containing and array of
-    ///   groups that a user is part of.
-    pub async fn new(
-        idp_url: Url,
-        redirect_url: Url,
-        client_id: String,
-        client_secret: String,
-        groups_claim: String,
-    ) -> Result<Self, AuthenticationEngineError> {
-        let idp_url = IssuerUrl::from_url(idp_url);
-        let redirect_url = RedirectUrl::from_url(redirect_url);
-        let client_id = ClientId::new(client_id);
-        let client_secret = ClientSecret::new(client_secret);
+    /// The IdP's metadata is not discovered until [`Self::spawn_discovery`] is
+    /// called; until then users can't authenticate.
+    pub fn new(options: OidcEngineOptions) -> Result<Self, AuthenticationEngineError> {
+        let OidcEngineOptions {
+            idp_url,
+            redirect_url,
+            client_id,
+            client_secret,
+            groups_claim,
+            group_mapping,
+            fetch_userinfo,
+            trust_unverified_email,
+            post_logout_redirect_url,
+            access_token_audience,
+            request_options,
+            session_max_age,
+        } = options;
+        let access_token_audience = access_token_audience.unwrap_or_else(|| client_id.clone());
 
-        // Sets up an http client to interact with the IdP
+        // Sets up an http client to request the IdP's metadata
         let http_client = reqwest::ClientBuilder::new()
             // Following redirects opens the client up to SSRF vulnerabilities.
             .redirect(reqwest::redirect::Policy::none())
+            // An unresponsive IdP should not stall the retries.
+            .timeout(DISCOVERY_TIMEOUT)
             .build()
             .map_err(|e| AuthenticationEngineError::Infrastructure {
                 message: format!("unable to build OIDC http client: {e}"),
             })?;
 
-        // Request the oidc config from the IdP.
-        let provider_metadata = CoreProviderMetadata::discover_async(idp_url, &http_client)
-            .await
-            .map_err(|e| AuthenticationEngineError::IdpExchange {
-                message: format!("unable to perform OIDC discovery: {e}"),
-            })?;
-
-        let oidc_client = PotreeAuthClient::from_provider_metadata(
-            provider_metadata,
-            client_id,
-            Some(client_secret),
-        )
-        .set_redirect_uri(redirect_url);
-
         Ok(Self {
-            oidc_client,
+            discovery: ProviderDiscovery {
+                issuer_url: IssuerUrl::from_url(idp_url),
+                redirect_url: RedirectUrl::from_url(redirect_url),
+                client_id: ClientId::new(client_id),
+                client_secret: ClientSecret::new(client_secret),
+                access_token_audience,
+                fetch_userinfo,
+                http_client,
+            },
+            provider: Arc::new(RwLock::new(ProviderState {
+                discovered: None,
+                status: IdpStatus::Pending,
+            })),
             groups_claim,
+            group_mapping,
+            fetch_userinfo,
+            trust_unverified_email,
+            post_logout_redirect_url: PostLogoutRedirectUrl::from_url(post_logout_redirect_url),
+            request_options,
+            session_max_age,
+            refreshes: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Spawns a background task that discovers the IdP's metadata, retrying
+    /// with an exponential backoff while the IdP is unreachable. Once
+    /// discovered, the metadata (and the IdP's signing keys) are refreshed
+    /// every `refresh_interval`, so that keys rotated by the IdP are picked up.
+    ///
+    /// The task ends once the engine (and all of its clones) have been dropped.
+    pub fn spawn_discovery(&self, refresh_interval: Duration) -> tokio::task::JoinHandle<()> {
+        let discovery = self.discovery.clone();
+        let provider = Arc::downgrade(&self.provider);
+
+        tokio::spawn(async move {
+            let mut retry_delay = MIN_DISCOVERY_RETRY_DELAY;
+
+            loop {
+                let result = discovery.discover().await;
+                if let Err(e) = &result {
+                    tracing::warn!(
+                        "unable to discover the metadata of the IdP ({}): {e}",
+                        discovery.issuer_url.as_str()
+                    );
+                }
+
+                let Some(provider) = provider.upgrade() else {
+                    return;
+                };
+                let delay = if result.is_ok() {
+                    retry_delay = MIN_DISCOVERY_RETRY_DELAY;
+                    refresh_interval
+                } else {
+                    let delay = retry_delay.min(refresh_interval);
+                    retry_delay = next_retry_delay(retry_delay);
+                    delay
+                };
+                provider
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .record(result);
+                drop(provider);
+
+                tokio::time::sleep(delay).await;
+            }
         })
     }
 
+    /// Whether users can currently authenticate with the IdP.
+    fn status(&self) -> IdpStatus {
+        self.provider
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .status
+            .clone()
+    }
+
+    /// The IdP's discovered metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationEngineError::IdpExchange`] is returned if the metadata
+    ///   has not been discovered (yet).
+    fn discovered(&self) -> Result<Arc<DiscoveredProvider>, AuthenticationEngineError> {
+        let state = self.provider.read().unwrap_or_else(PoisonError::into_inner);
+
+        state
+            .discovered
+            .clone()
+            .ok_or_else(|| AuthenticationEngineError::IdpExchange {
+                message: match &state.status {
+                    IdpStatus::Unavailable { message } => {
+                        format!("the IdP is unavailable: {message}")
+                    }
+                    _ => "the IdP's metadata has not been discovered yet".to_owned(),
+                },
+            })
+    }
+
     /// Initiates an OIDC authentication flow with the IdP.
     ///
     /// Returns the `auth_url` needed to redirect the user agent to the IdP's
@@ -115,30 +471,67 @@ impl OidcAuthenticationEngine {
     /// complete the authentication flow in the callback handler.
     #[tracing::instrument]
     async fn login(&self) -> Result<AuthorizeData, AuthenticationEngineError> {
-        let (auth_url, state, nonce) = self
+        let AuthenticationRequestOptions {
+            scopes,
+            prompt,
+            login_hint,
+            acr_values,
+            max_age,
+        } = &self.request_options;
+
+        let provider = self.discovered()?;
+
+        // Binds the authorization code to this flow (RFC 7636).
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let mut authorization_request = provider
             .oidc_client
             .authorize_url(
                 CoreAuthenticationFlow::AuthorizationCode,
                 CsrfToken::new_random,
                 Nonce::new_random,
             )
-            .url();
+            .set_pkce_challenge(pkce_challenge)
+            .add_scopes(scopes.iter().cloned().map(Scope::new));
+
+        for prompt in prompt {
+            authorization_request = authorization_request.add_prompt(auth_prompt(prompt));
+        }
+        if let Some(login_hint) = login_hint {
+            authorization_request =
+                authorization_request.set_login_hint(LoginHint::new(login_hint.clone()));
+        }
+        for acr_value in acr_values {
+            authorization_request = authorization_request
+                .add_auth_context_value(AuthenticationContextClass::new(acr_value.clone()));
+        }
+        if let Some(max_age) = max_age {
+            authorization_request = authorization_request.set_max_age(*max_age);
+        }
+
+        let (auth_url, state, nonce) = authorization_request.url();
 
         Ok(AuthorizeData {
             auth_url,
-            persisted_data: OidcSessionPersisted { state, nonce },
+            persisted_data: OidcSessionPersisted {
+                state,
+                nonce,
+                pkce_verifier,
+            },
         })
     }
 
     /// Finalizes the OIDC authentication flow with the IdP.
     ///
-    /// Returns the authenticated [`User`].
+    /// Returns the authenticated [`User`] and the raw ID token.
     #[tracing::instrument]
     async fn callback(
         &self,
         callback_params: CallbackRequestParams,
         persisted_data: OidcSessionPersisted,
-    ) -> Result<User, AuthenticationEngineError> {
+    ) -> Result<CallbackData, AuthenticationEngineError> {
+        let provider = self.discovered()?;
+
         // Sets up an http client to interact with the IdP
         let http_client = reqwest::ClientBuilder::new()
             // Following redirects opens the client up to SSRF vulnerabilities.
@@ -156,12 +549,13 @@ impl OidcAuthenticationEngine {
         }
 
         // Request the tokens from the IdP
-        let token_response = self
+        let token_response = provider
             .oidc_client
             .exchange_code(callback_params.code)
             .map_err(|e| AuthenticationEngineError::Infrastructure {
                 message: format!("unable to initialize OIDC code exchange client: {e}"),
             })?
+            .set_pkce_verifier(persisted_data.pkce_verifier)
             .request_async(&http_client)
             .await
             .map_err(|e| AuthenticationEngineError::IdpExchange {
@@ -169,23 +563,329 @@ impl OidcAuthenticationEngine {
             })?;
 
         // Extract the claims from the id token.
-        let id_token_claims = token_response
+        let id_token = token_response
             .id_token()
             .ok_or(AuthenticationEngineError::Validation {
                 message: "IdP did not return id_token".to_owned(),
+            })?;
+        let mut id_token_verifier = provider.oidc_client.id_token_verifier();
+        if let Some(max_age) = self.request_options.max_age {
+            // The IdP should have re-authenticated the user if `max_age` was exceeded.
+            id_token_verifier = id_token_verifier.set_auth_time_verifier_fn(move |auth_time| {
+                verify_auth_time(
+                    auth_time.map(|auth_time| auth_time.timestamp()),
+                    max_age,
+                    OffsetDateTime::now_utc().unix_timestamp(),
+                )
+            });
+        }
+        let id_token_claims = id_token
+            .claims(&id_token_verifier, &persisted_data.nonce)
+            .map_err(|err| AuthenticationEngineError::Validation {
+                message: format!("unable to extract claims from id_token: {err}"),
+            })?;
+
+        // The claims of the ID token take precedence over those from the UserInfo
+        // endpoint.
+        let user = self
+            .build_user(
+                &provider,
+                &http_client,
+                id_token_claims,
+                token_response.access_token(),
+            )
+            .await?;
+
+        Ok(CallbackData {
+            user,
+            id_token: Some(RawIdToken::new(id_token.to_string())),
+            refresh_token: token_response
+                .refresh_token()
+                .map(|refresh_token| RawRefreshToken::new(refresh_token.secret().clone())),
+        })
+    }
+
+    /// Refreshes the tokens of a web session once it is older than the
+    /// `session_max_age`, re-validating the user's claims with the IdP.
+    ///
+    /// Concurrent requests of the session share a single refresh, whose result
+    /// is also returned to the requests that present the same (already used)
+    /// refresh token shortly after.
+    #[tracing::instrument(skip(validity))]
+    async fn revalidate(
+        &self,
+        user: &User,
+        validity: &SessionValidity,
+        now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError> {
+        let Some(session_max_age) = self.session_max_age else {
+            return Ok(None);
+        };
+        if !validity.is_older_than(session_max_age, now) {
+            return Ok(None);
+        }
+
+        let refresh_token =
+            validity
+                .refresh_token
+                .clone()
+                .ok_or(AuthenticationEngineError::Validation {
+                    message: "no refresh token to re-validate the session with".to_owned(),
+                })?;
+
+        let mut result = {
+            let mut refreshes = self
+                .refreshes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            refreshes.retain(|_, refresh| now - refresh.started_at < REFRESH_RETENTION);
+
+            refreshes
+                .entry(refresh_token.to_string())
+                .or_insert_with(|| {
+                    // The refresh is done by a separate task, so that it is completed (and
+                    // shared) even if the request that started it is cancelled.
+                    let (sender, receiver) = watch::channel(None);
+                    let engine = self.clone();
+                    let user = user.clone();
+                    let refresh_token = refresh_token.clone();
+                    tokio::spawn(async move {
+                        let result = engine.refresh(&user, refresh_token.clone()).await;
+                        // Only successful refreshes are retained, so that failures (e.g. an
+                        // unavailable IdP) are retried by the next request.
+                        if result.is_err() {
+                            engine
+                                .refreshes
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .remove(refresh_token.as_str());
+                        }
+                        let _ = sender.send(Some(result));
+                    });
+
+                    SessionRefresh {
+                        started_at: now,
+                        result: receiver,
+                    }
+                })
+                .result
+                .clone()
+        };
+
+        let result = result
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|result| result.clone())
+            .ok_or(AuthenticationEngineError::Infrastructure {
+                message: "the session refresh ended without a result".to_owned(),
+            })?;
+
+        result.map(Some)
+    }
+
+    /// Exchanges the `refresh_token` for new tokens, re-validating the claims
+    /// of the `user`.
+    ///
+    /// # Errors
+    ///
+    /// [`AuthenticationEngineError::Validation`] is only returned if the IdP
+    /// (definitively) rejected the refresh token or the refreshed claims, while
+    /// the other errors are transient (e.g. the IdP being unavailable).
+    async fn refresh(
+        &self,
+        user: &User,
+        refresh_token: RawRefreshToken,
+    ) -> Result<CallbackData, AuthenticationEngineError> {
+        let provider = self.discovered()?;
+
+        // Sets up an http client to interact with the IdP
+        let http_client = reqwest::ClientBuilder::new()
+            // Following redirects opens the client up to SSRF vulnerabilities.
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| AuthenticationEngineError::Infrastructure {
+                message: format!("unable to build OIDC http client: {e}"),
+            })?;
+
+        let token_response = provider
+            .oidc_client
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
+            .map_err(|e| AuthenticationEngineError::Infrastructure {
+                message: format!("unable to initialize OIDC refresh client: {e}"),
             })?
-            .claims(&self.oidc_client.id_token_verifier(), &persisted_data.nonce)
+            .request_async(&http_client)
+            .await
+            .map_err(|e| match e {
+                RequestTokenError::ServerResponse(response)
+                    if *response.error() == CoreErrorResponseType::InvalidGrant =>
+                {
+                    AuthenticationEngineError::Validation {
+                        message: format!("the IdP rejected the refresh token: {response}"),
+                    }
+                }
+                e => AuthenticationEngineError::IdpExchange {
+                    message: format!("unable to perform refresh token request: {e}"),
+                },
+            })?;
+
+        let id_token = token_response
+            .id_token()
+            .ok_or(AuthenticationEngineError::Validation {
+                message: "IdP did not return id_token when refreshing".to_owned(),
+            })?;
+        // ID tokens issued when refreshing don't carry a nonce.
+        let id_token_claims = id_token
+            .claims(
+                &provider.oidc_client.id_token_verifier(),
+                |_: Option<&Nonce>| Ok(()),
+            )
             .map_err(|err| AuthenticationEngineError::Validation {
                 message: format!("unable to extract claims from id_token: {err}"),
             })?;
+        if id_token_claims.subject().as_str() != user.id.as_str() {
+            return Err(AuthenticationEngineError::Validation {
+                message: "the refreshed id_token belongs to another user".to_owned(),
+            });
+        }
+
+        let user = self
+            .build_user(
+                &provider,
+                &http_client,
+                id_token_claims,
+                token_response.access_token(),
+            )
+            .await?;
+
+        Ok(CallbackData {
+            user,
+            id_token: Some(RawIdToken::new(id_token.to_string())),
+            // Keep the current refresh token if the IdP does not rotate them.
+            refresh_token: Some(
+                token_response
+                    .refresh_token()
+                    .map(|refresh_token| RawRefreshToken::new(refresh_token.secret().clone()))
+                    .unwrap_or(refresh_token),
+            ),
+        })
+    }
+
+    /// Builds the [`User`] from the validated claims of an ID token, merged
+    /// with those from the UserInfo endpoint if `fetch_userinfo` is set.
+    async fn build_user(
+        &self,
+        provider: &DiscoveredProvider,
+        http_client: &reqwest::Client,
+        id_token_claims: &IdTokenClaims<PotreeAuthClaims, CoreGenderClaim>,
+        access_token: &AccessToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        let user_info_claims = if self.fetch_userinfo {
+            let user_info_claims: UserInfoClaims<PotreeAuthClaims, CoreGenderClaim> = provider
+                .oidc_client
+                .user_info(
+                    access_token.clone(),
+                    Some(id_token_claims.subject().clone()),
+                )
+                .map_err(|e| AuthenticationEngineError::Infrastructure {
+                    message: format!("unable to initialize OIDC UserInfo client: {e}"),
+                })?
+                .request_async(http_client)
+                .await
+                .map_err(|e| AuthenticationEngineError::IdpExchange {
+                    message: format!("unable to perform UserInfo request: {e}"),
+                })?;
+
+            Some(user_info_claims)
+        } else {
+            None
+        };
+
+        // The claims of the ID token take precedence over those from the UserInfo
+        // endpoint.
+        let claims = match &user_info_claims {
+            Some(user_info_claims) => merge_claims(
+                id_token_claims.additional_claims(),
+                user_info_claims.additional_claims(),
+            ),
+            None => id_token_claims.additional_claims().clone(),
+        };
+
+        // Only logged when a user logs in or their session is refreshed.
+        let groups = extract_user_groups(&claims, &self.groups_claim, &self.group_mapping);
+        if groups.is_empty() {
+            tracing::warn!(
+                "no groups could be resolved from the `{}` claim, the user will only have access \
+                 to projects that list them by email (check that the IdP includes the claim in \
+                 the ID token, or enable `IDP_FETCH_USERINFO`)",
+                self.groups_claim
+            );
+        }
 
         Ok(User {
             id: extract_user_id(id_token_claims),
-            name: extract_user_name(id_token_claims)?,
-            email: extract_user_email(id_token_claims)?,
-            groups: extract_user_groups(id_token_claims, &self.groups_claim),
+            name: extract_user_name(id_token_claims, user_info_claims.as_ref())?,
+            email: extract_user_email(id_token_claims, user_info_claims.as_ref())?,
+            email_verified: self.trust_unverified_email
+                || extract_user_email_verified(id_token_claims, user_info_claims.as_ref()),
+            groups,
+            provider: None,
         })
     }
+
+    /// Builds the url to the IdP's RP-Initiated Logout endpoint.
+    ///
+    /// Returns `None` if the IdP does not advertise an `end_session_endpoint`.
+    #[tracing::instrument]
+    async fn logout(
+        &self,
+        id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError> {
+        // The user is still logged out of the application if the IdP is unavailable.
+        let provider = match self.discovered() {
+            Ok(provider) => provider,
+            Err(e) => {
+                tracing::warn!("unable to end the IdP session: {e}");
+                return Ok(None);
+            }
+        };
+        let Some(end_session_url) = provider.end_session_url.clone() else {
+            tracing::debug!("IdP does not support RP-initiated logout");
+            return Ok(None);
+        };
+
+        let mut logout_request = LogoutRequest::from(end_session_url)
+            .set_client_id(provider.oidc_client.client_id().clone())
+            .set_post_logout_redirect_uri(self.post_logout_redirect_url.clone());
+
+        // An invalid hint should not prevent the user from logging out, the IdP would
+        // just prompt the user to confirm the logout.
+        if let Some(id_token) = id_token {
+            match id_token.parse::<PotreeAuthIdToken>() {
+                Ok(id_token) => logout_request = logout_request.set_id_token_hint(&id_token),
+                Err(e) => tracing::warn!("unable to parse id token for `id_token_hint`: {e}"),
+            }
+        }
+
+        Ok(Some(logout_request.http_get_url()))
+    }
+
+    /// Validates a bearer access token and extracts the [`User`] from its
+    /// claims.
+    #[tracing::instrument(skip(token))]
+    async fn authenticate_bearer(
+        &self,
+        token: BearerToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        let claims = self.discovered()?.access_token_validator.validate(&token)?;
+
+        extract_bearer_user(
+            &claims,
+            &self.groups_claim,
+            &self.group_mapping,
+            self.trust_unverified_email,
+        )
+    }
 }
 
 #[async_trait]
@@ -198,9 +898,73 @@ impl AuthenticationEngine for OidcAuthenticationEngine {
         &self,
         callback_params: CallbackRequestParams,
         persisted_data: OidcSessionPersisted,
-    ) -> Result<User, AuthenticationEngineError> {
+    ) -> Result<CallbackData, AuthenticationEngineError> {
         Self::callback(self, callback_params, persisted_data).await
     }
+
+    async fn logout(
+        &self,
+        id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError> {
+        Self::logout(self, id_token).await
+    }
+
+    async fn authenticate_bearer(
+        &self,
+        token: BearerToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        Self::authenticate_bearer(self, token).await
+    }
+
+    /// Users are authenticated by the IdP, so proxy headers are never trusted.
+    async fn authenticate_proxy_headers(
+        &self,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    async fn revalidate(
+        &self,
+        user: &User,
+        validity: &SessionValidity,
+        now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError> {
+        Self::revalidate(self, user, validity, now).await
+    }
+
+    fn login_method(&self) -> LoginMethod {
+        LoginMethod::Redirect
+    }
+
+    /// Users enter their credentials at the IdP, never in the application.
+    async fn authenticate_password(
+        &self,
+        _credentials: &PasswordCredentials,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+        _now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(AuthenticationEngineError::Validation {
+            message: "users log in with the IdP".to_owned(),
+        })
+    }
+
+    fn status(&self) -> IdpStatus {
+        Self::status(self)
+    }
+}
+
+/// Maps a configured `prompt` value onto its OIDC representation.
+fn auth_prompt(prompt: &str) -> CoreAuthPrompt {
+    match prompt {
+        "none" => CoreAuthPrompt::None,
+        "login" => CoreAuthPrompt::Login,
+        "consent" => CoreAuthPrompt::Consent,
+        "select_account" => CoreAuthPrompt::SelectAccount,
+        other => CoreAuthPrompt::Extension(other.to_owned()),
+    }
 }
 
 pub type PotreeAuthTokenFields = IdTokenFields<
@@ -213,6 +977,13 @@ pub type PotreeAuthTokenFields = IdTokenFields<
 
 pub type PotreeAuthTokenResponse = StandardTokenResponse<PotreeAuthTokenFields, CoreTokenType>;
 
+pub type PotreeAuthIdToken = IdToken<
+    PotreeAuthClaims,
+    CoreGenderClaim,
+    CoreJweContentEncryptionAlgorithm,
+    CoreJwsSigningAlgorithm,
+>;
+
 type PotreeAuthClient<
     HasAuthUrl = EndpointSet,
     HasDeviceAuthUrl = EndpointNotSet,
@@ -249,3 +1020,355 @@ type PotreeAuthClient<
 pub struct PotreeAuthClaims(pub serde_json::Value);
 
 impl AdditionalClaims for PotreeAuthClaims {}
+
+#[cfg(test)]
+mod oidc_authentication_engine_tests {
+    use std::net::SocketAddr;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use axum::Json;
+    use axum::Router;
+    use axum::routing::MethodRouter;
+    use axum::routing::get;
+    use axum::routing::post;
+    use fake::Fake;
+    use fake::Faker;
+    use http::StatusCode;
+
+    use super::*;
+
+    /// Serves the discovery document and (empty) key set of a fake IdP,
+    /// returning its URL.
+    async fn serve_idp() -> Url {
+        serve_idp_with_token_endpoint(post(|| async { StatusCode::NOT_FOUND })).await
+    }
+
+    /// Serves a fake IdP (see [`serve_idp`]) whose token endpoint is handled by
+    /// the `token_endpoint`.
+    async fn serve_idp_with_token_endpoint(token_endpoint: MethodRouter) -> Url {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let idp_url: Url = format!("http://{}/", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+
+        let discovery_document = serde_json::json!({
+            "issuer": idp_url,
+            "authorization_endpoint": idp_url.join("authorize").unwrap(),
+            "token_endpoint": idp_url.join("token").unwrap(),
+            "jwks_uri": idp_url.join("jwks").unwrap(),
+            "response_types_supported": ["code"],
+            "subject_types_supported": ["public"],
+            "id_token_signing_alg_values_supported": ["RS256"],
+        });
+        let router = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(|| async move { Json(discovery_document) }),
+            )
+            .route(
+                "/jwks",
+                get(|| async { Json(serde_json::json!({ "keys": [] })) }),
+            )
+            .route("/token", token_endpoint);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        idp_url
+    }
+
+    fn engine(idp_url: Url) -> OidcAuthenticationEngine {
+        engine_with_session_max_age(idp_url, None)
+    }
+
+    fn engine_with_session_max_age(
+        idp_url: Url,
+        session_max_age: Option<Duration>,
+    ) -> OidcAuthenticationEngine {
+        OidcAuthenticationEngine::new(OidcEngineOptions {
+            idp_url,
+            redirect_url: "https://potree-auth.example.com/auth/callback"
+                .parse()
+                .unwrap(),
+            client_id: "client-id".to_owned(),
+            client_secret: "client-secret".to_owned(),
+            groups_claim: "groups".to_owned(),
+            group_mapping: GroupMapping::default(),
+            fetch_userinfo: false,
+            trust_unverified_email: false,
+            post_logout_redirect_url: "https://potree-auth.example.com".parse().unwrap(),
+            access_token_audience: None,
+            request_options: AuthenticationRequestOptions::default(),
+            session_max_age,
+        })
+        .unwrap()
+    }
+
+    /// Waits for the background discovery to update the engine's status.
+    async fn wait_for_discovery(engine: &OidcAuthenticationEngine) -> IdpStatus {
+        for _ in 0..100 {
+            let status = engine.status();
+            if status != IdpStatus::Pending {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        engine.status()
+    }
+
+    mod spawn_discovery {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_be_ready_once_the_metadata_is_discovered() {
+            // Arrange
+            let idp_url = serve_idp().await;
+            let engine = engine(idp_url.clone());
+
+            // Act
+            engine.spawn_discovery(Duration::from_secs(60 * 60));
+
+            // Assert
+            assert_eq!(wait_for_discovery(&engine).await, IdpStatus::Ready);
+            let authorize_data = engine.login().await.unwrap();
+            assert!(
+                authorize_data
+                    .auth_url
+                    .as_str()
+                    .starts_with(idp_url.join("authorize").unwrap().as_str())
+            );
+        }
+
+        #[tokio::test]
+        async fn should_be_unavailable_if_the_idp_is_unreachable() {
+            // Arrange
+            let engine = engine("http://127.0.0.1:1/".parse().unwrap());
+
+            // Act
+            engine.spawn_discovery(Duration::from_secs(60 * 60));
+
+            // Assert
+            assert!(matches!(
+                wait_for_discovery(&engine).await,
+                IdpStatus::Unavailable { .. }
+            ));
+            assert!(matches!(
+                engine.login().await,
+                Err(AuthenticationEngineError::IdpExchange { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_end_once_the_engine_is_dropped() {
+            // Arrange
+            let engine = engine("http://127.0.0.1:1/".parse().unwrap());
+            let discovery_task = engine.spawn_discovery(Duration::from_millis(10));
+
+            // Act
+            drop(engine);
+
+            // Assert
+            tokio::time::timeout(Duration::from_secs(30), discovery_task)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+
+    mod revalidate {
+        use super::*;
+
+        /// An engine of a fake IdP whose token endpoint responds with the
+        /// `status` and `body` (after a delay), counting the requests in
+        /// `calls`.
+        async fn discovered_engine(
+            status: StatusCode,
+            body: serde_json::Value,
+            calls: Arc<AtomicUsize>,
+        ) -> OidcAuthenticationEngine {
+            let idp_url = serve_idp_with_token_endpoint(post(move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                (status, Json(body))
+            }))
+            .await;
+            let engine = engine_with_session_max_age(idp_url, Some(Duration::from_secs(60)));
+            engine.spawn_discovery(Duration::from_secs(60 * 60));
+            assert_eq!(wait_for_discovery(&engine).await, IdpStatus::Ready);
+
+            engine
+        }
+
+        fn expired_validity() -> SessionValidity {
+            SessionValidity {
+                validated_at: OffsetDateTime::UNIX_EPOCH,
+                refresh_token: Some(RawRefreshToken::new("refresh-token".to_owned())),
+            }
+        }
+
+        #[tokio::test]
+        async fn should_refresh_concurrent_requests_of_a_session_once() {
+            // Arrange
+            let calls = Arc::new(AtomicUsize::new(0));
+            let engine = discovered_engine(
+                StatusCode::BAD_REQUEST,
+                serde_json::json!({ "error": "invalid_grant" }),
+                calls.clone(),
+            )
+            .await;
+            let user: User = Faker.fake();
+            let validity = expired_validity();
+            let now = OffsetDateTime::now_utc();
+
+            // Act
+            let results = futures::future::join_all(
+                (0..10).map(|_| engine.revalidate(&user, &validity, now)),
+            )
+            .await;
+
+            // Assert
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+            assert!(
+                results.iter().all(|result| matches!(
+                    result,
+                    Err(AuthenticationEngineError::Validation { .. })
+                ))
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_a_transient_error_if_the_idp_fails() {
+            // Arrange
+            let calls = Arc::new(AtomicUsize::new(0));
+            let engine = discovered_engine(
+                StatusCode::SERVICE_UNAVAILABLE,
+                serde_json::json!({}),
+                calls.clone(),
+            )
+            .await;
+            let user: User = Faker.fake();
+            let now = OffsetDateTime::now_utc();
+
+            // Act
+            let first = engine.revalidate(&user, &expired_validity(), now).await;
+            let second = engine.revalidate(&user, &expired_validity(), now).await;
+
+            // Assert
+            assert!(matches!(
+                first,
+                Err(AuthenticationEngineError::IdpExchange { .. })
+            ));
+            assert!(matches!(
+                second,
+                Err(AuthenticationEngineError::IdpExchange { .. })
+            ));
+            // Failed refreshes aren't retained, so they are retried.
+            assert_eq!(calls.load(Ordering::SeqCst), 2);
+        }
+
+        #[tokio::test]
+        async fn should_return_a_transient_error_if_not_discovered_yet() {
+            // Arrange
+            let engine = engine_with_session_max_age(
+                "http://127.0.0.1:1/".parse().unwrap(),
+                Some(Duration::from_secs(60)),
+            );
+
+            // Act
+            let res = engine
+                .revalidate(
+                    &Faker.fake(),
+                    &expired_validity(),
+                    OffsetDateTime::now_utc(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::IdpExchange { .. })
+            ));
+        }
+    }
+
+    mod login {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_not_discovered_yet() {
+            // Arrange
+            let engine = engine("http://127.0.0.1:1/".parse().unwrap());
+
+            // Act
+            let res = engine.login().await;
+
+            // Assert
+            assert_eq!(engine.status(), IdpStatus::Pending);
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::IdpExchange { .. })
+            ));
+        }
+    }
+
+    mod logout {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_not_end_the_idp_session_if_not_discovered_yet() {
+            // Arrange
+            let engine = engine("http://127.0.0.1:1/".parse().unwrap());
+
+            // Act
+            let end_session_url = engine.logout(None).await.unwrap();
+
+            // Assert
+            assert_eq!(end_session_url, None);
+        }
+    }
+
+    mod provider_state_record {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_keep_the_discovered_metadata_if_refresh_fails() {
+            // Arrange
+            let engine = engine(serve_idp().await);
+            let discovered = engine.discovery.discover().await.unwrap();
+            let mut state = ProviderState {
+                discovered: None,
+                status: IdpStatus::Pending,
+            };
+            state.record(Ok(discovered));
+
+            // Act
+            state.record(Err(AuthenticationEngineError::IdpExchange {
+                message: "connection refused".to_owned(),
+            }));
+
+            // Assert
+            assert!(state.discovered.is_some());
+            assert!(matches!(state.status, IdpStatus::Stale { .. }));
+        }
+
+        #[test]
+        fn should_be_unavailable_if_never_discovered() {
+            // Arrange
+            let mut state = ProviderState {
+                discovered: None,
+                status: IdpStatus::Pending,
+            };
+
+            // Act
+            state.record(Err(AuthenticationEngineError::IdpExchange {
+                message: "connection refused".to_owned(),
+            }));
+
+            // Assert
+            assert!(state.discovered.is_none());
+            assert!(matches!(state.status, IdpStatus::Unavailable { .. }));
+        }
+    }
+}
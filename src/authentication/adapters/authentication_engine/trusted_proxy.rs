@@ -0,0 +1,333 @@
+//! Authenticates users from the identity headers set by an upstream
+//! authenticating proxy (e.g. oauth2-proxy, Pomerium or Cloudflare Access). It
+//! implements the [`AuthenticationEngine`] trait.
+//!
+//! The headers are only trusted on requests coming from one of the configured
+//! source networks, as anyone could set them otherwise.
+
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use http::HeaderMap;
+use http::HeaderName;
+use ipnet::IpNet;
+use time::OffsetDateTime;
+use url::Url;
+
+use super::super::super::domain::AuthorizeData;
+use super::super::super::domain::BearerToken;
+use super::super::super::domain::CallbackData;
+use super::super::super::domain::CallbackRequestParams;
+use super::super::super::domain::IdpStatus;
+use super::super::super::domain::LoginMethod;
+use super::super::super::domain::OidcSessionPersisted;
+use super::super::super::domain::PasswordCredentials;
+use super::super::super::domain::RawIdToken;
+use super::super::super::domain::SessionValidity;
+use super::super::super::ports::authentication_engine::AuthenticationEngine;
+use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
+use crate::common::domain::Group;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
+use crate::user::domain::UserId;
+use crate::user::domain::UserName;
+
+/// The names of the headers from which the user's identity is read.
+#[derive(Debug, Clone)]
+pub struct TrustedProxyHeaders {
+    /// The unique id of the user. A request without it is unauthenticated.
+    pub user_id: HeaderName,
+
+    /// The name of the user. Falls back to the user id if absent.
+    pub name: HeaderName,
+
+    /// The email address of the user.
+    pub email: HeaderName,
+
+    /// The groups of the user, separated by the `groups_separator`.
+    pub groups: HeaderName,
+
+    /// Separates the groups in the `groups` header.
+    pub groups_separator: char,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrustedProxyAuthenticationEngine {
+    /// Only requests from these networks are trusted to carry identity
+    /// headers.
+    trusted_networks: Vec<IpNet>,
+
+    /// The headers from which the user's identity is read.
+    headers: TrustedProxyHeaders,
+}
+
+impl TrustedProxyAuthenticationEngine {
+    pub fn new(trusted_networks: Vec<IpNet>, headers: TrustedProxyHeaders) -> Self {
+        Self {
+            trusted_networks,
+            headers,
+        }
+    }
+
+    #[tracing::instrument(skip(headers))]
+    fn authenticate_proxy_headers(
+        &self,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError> {
+        let trusted = source.is_some_and(|source| {
+            self.trusted_networks
+                .iter()
+                .any(|network| network.contains(&source))
+        });
+        if !trusted {
+            if headers.contains_key(&self.headers.user_id) {
+                tracing::warn!("ignoring identity headers from untrusted source: {source:?}");
+            }
+            return Ok(None);
+        }
+
+        let header = |name: &HeaderName| -> Result<Option<&str>, AuthenticationEngineError> {
+            headers
+                .get(name)
+                .map(|value| {
+                    value.to_str().map(str::trim).map_err(|e| {
+                        AuthenticationEngineError::Validation {
+                            message: format!("invalid `{name}` header: {e}"),
+                        }
+                    })
+                })
+                .transpose()
+                .map(|value| value.filter(|value| !value.is_empty()))
+        };
+
+        let Some(id) = header(&self.headers.user_id)? else {
+            return Ok(None);
+        };
+        let name = header(&self.headers.name)?.unwrap_or(id);
+        let email =
+            header(&self.headers.email)?.ok_or_else(|| AuthenticationEngineError::Validation {
+                message: format!("no `{}` header associated with user", self.headers.email),
+            })?;
+        let groups = header(&self.headers.groups)?
+            .map(|groups| {
+                groups
+                    .split(self.headers.groups_separator)
+                    .map(str::trim)
+                    .filter(|group| !group.is_empty())
+                    .map(Group::new)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(User {
+            id: UserId::new(id.to_owned()),
+            name: UserName::new(name.to_owned()),
+            email: EmailAddress::new(email.to_owned()),
+            // The proxy authenticating the user is trusted, so is the email address.
+            email_verified: true,
+            groups,
+            provider: None,
+        }))
+    }
+
+    /// The upstream proxy is responsible for logging users in.
+    fn not_supported() -> AuthenticationEngineError {
+        AuthenticationEngineError::Infrastructure {
+            message: "users are authenticated by the upstream proxy".to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthenticationEngine for TrustedProxyAuthenticationEngine {
+    async fn authorize(&self) -> Result<AuthorizeData, AuthenticationEngineError> {
+        Err(Self::not_supported())
+    }
+
+    async fn callback(
+        &self,
+        _callback_params: CallbackRequestParams,
+        _persisted_data: OidcSessionPersisted,
+    ) -> Result<CallbackData, AuthenticationEngineError> {
+        Err(Self::not_supported())
+    }
+
+    /// The session is held by the upstream proxy, which has its own logout.
+    async fn logout(
+        &self,
+        _id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    async fn authenticate_bearer(
+        &self,
+        _token: BearerToken,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(AuthenticationEngineError::Validation {
+            message: "bearer token authentication is not configured".to_owned(),
+        })
+    }
+
+    async fn authenticate_proxy_headers(
+        &self,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError> {
+        Self::authenticate_proxy_headers(self, source, headers)
+    }
+
+    /// Users are authenticated on every request, so there are no web sessions
+    /// to re-validate.
+    async fn revalidate(
+        &self,
+        _user: &User,
+        _validity: &SessionValidity,
+        _now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError> {
+        Ok(None)
+    }
+
+    /// Users log in at the upstream proxy.
+    fn login_method(&self) -> LoginMethod {
+        LoginMethod::Redirect
+    }
+
+    async fn authenticate_password(
+        &self,
+        _credentials: &PasswordCredentials,
+        _source: Option<IpAddr>,
+        _headers: &HeaderMap,
+        _now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError> {
+        Err(Self::not_supported())
+    }
+
+    /// The upstream proxy is responsible for reaching the IdP.
+    fn status(&self) -> IdpStatus {
+        IdpStatus::Ready
+    }
+}
+
+#[cfg(test)]
+mod trusted_proxy_authentication_engine_tests {
+    use super::*;
+
+    fn engine() -> TrustedProxyAuthenticationEngine {
+        TrustedProxyAuthenticationEngine::new(
+            vec!["10.0.0.0/8".parse().unwrap(), "::1/128".parse().unwrap()],
+            TrustedProxyHeaders {
+                user_id: HeaderName::from_static("x-forwarded-user"),
+                name: HeaderName::from_static("x-forwarded-preferred-username"),
+                email: HeaderName::from_static("x-forwarded-email"),
+                groups: HeaderName::from_static("x-forwarded-groups"),
+                groups_separator: ',',
+            },
+        )
+    }
+
+    fn headers(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+        headers
+            .iter()
+            .map(|(name, value)| (HeaderName::from_static(name), value.parse().unwrap()))
+            .collect()
+    }
+
+    mod authenticate_proxy_headers {
+        use super::*;
+
+        #[test]
+        fn should_return_the_user_from_a_trusted_source() {
+            // Arrange
+            let headers = headers(&[
+                ("x-forwarded-user", "user-id"),
+                ("x-forwarded-preferred-username", "A User"),
+                ("x-forwarded-email", "user@example.com"),
+                ("x-forwarded-groups", "surveyors, admin,,"),
+            ]);
+
+            // Act
+            let user = engine()
+                .authenticate_proxy_headers(Some("10.1.2.3".parse().unwrap()), &headers)
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                user,
+                Some(User {
+                    id: UserId::new("user-id".to_owned()),
+                    name: UserName::new("A User".to_owned()),
+                    email: EmailAddress::new("user@example.com".to_owned()),
+                    email_verified: true,
+                    groups: vec![Group::new("surveyors"), Group::new("admin")],
+                    provider: None,
+                })
+            );
+        }
+
+        #[test]
+        fn should_fall_back_to_the_user_id_if_no_name() {
+            // Arrange
+            let headers = headers(&[
+                ("x-forwarded-user", "user-id"),
+                ("x-forwarded-email", "user@example.com"),
+            ]);
+
+            // Act
+            let user = engine()
+                .authenticate_proxy_headers(Some("::1".parse().unwrap()), &headers)
+                .unwrap()
+                .unwrap();
+
+            // Assert
+            assert_eq!(user.name, UserName::new("user-id".to_owned()));
+            assert!(user.groups.is_empty());
+        }
+
+        #[test_case::test_case(Some("192.168.1.1"); "untrusted source")]
+        #[test_case::test_case(None; "unknown source")]
+        fn should_ignore_the_headers_if_source_not_trusted(source: Option<&str>) {
+            // Arrange
+            let headers = headers(&[
+                ("x-forwarded-user", "user-id"),
+                ("x-forwarded-email", "user@example.com"),
+            ]);
+
+            // Act
+            let user = engine()
+                .authenticate_proxy_headers(source.map(|source| source.parse().unwrap()), &headers)
+                .unwrap();
+
+            // Assert
+            assert_eq!(user, None);
+        }
+
+        #[test]
+        fn should_return_none_if_no_user_id() {
+            // Act
+            let user = engine()
+                .authenticate_proxy_headers(Some("10.1.2.3".parse().unwrap()), &HeaderMap::new())
+                .unwrap();
+
+            // Assert
+            assert_eq!(user, None);
+        }
+
+        #[test]
+        fn should_return_err_if_no_email() {
+            // Arrange
+            let headers = headers(&[("x-forwarded-user", "user-id")]);
+
+            // Act
+            let res =
+                engine().authenticate_proxy_headers(Some("10.1.2.3".parse().unwrap()), &headers);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+    }
+}
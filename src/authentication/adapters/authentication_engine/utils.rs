@@ -1,28 +1,103 @@
 use openidconnect::IdTokenClaims;
+use openidconnect::UserInfoClaims;
 use openidconnect::core::CoreGenderClaim;
 
+use super::super::super::domain::GroupMapping;
 use super::oidc::PotreeAuthClaims;
 use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
 use crate::common::domain::Group;
 use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
 use crate::user::domain::UserId;
 use crate::user::domain::UserName;
 
+/// Extracts the groups from the `groups_claim` of a token's claims, mapping
+/// them with the `group_mapping`. Works for both ID tokens and (JWT) access
+/// tokens.
+///
+/// The `groups_claim` can be a `.` separated path to a nested claim (e.g.
+/// `realm_access.roles`). The claim can either be an array of groups or a
+/// single group.
+///
+/// Callers log if no groups could be resolved, as how loudly depends on how
+/// often the claims are extracted.
 pub(crate) fn extract_user_groups(
-    id_token_claims: &IdTokenClaims<PotreeAuthClaims, CoreGenderClaim>,
+    claims: &PotreeAuthClaims,
     groups_claim: &str,
+    group_mapping: &GroupMapping,
 ) -> Vec<Group> {
-    if let Some(serde_json::Value::Array(arr)) =
-        &id_token_claims.additional_claims().0.get(groups_claim)
-    {
-        arr.iter()
-            .filter_map(|v| v.as_str().map(Group::new))
-            .collect::<Vec<_>>()
-    } else {
-        tracing::debug!("no groups claim ({}) found, setting to []", groups_claim);
+    match find_claim(&claims.0, groups_claim) {
+        Some(serde_json::Value::Array(arr)) => {
+            group_mapping.map(arr.iter().filter_map(|v| v.as_str()))
+        }
+        Some(serde_json::Value::String(group)) => group_mapping.map([group.as_str()]),
+        _ => Vec::new(),
+    }
+}
 
-        Vec::new()
+/// Finds the claim at `path`. Claim names can contain `.`s (e.g. namespaced
+/// claims such as `https://example.com/groups`), so an exact match takes
+/// precedence over a nested one.
+fn find_claim<'a>(claims: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    claims.get(path).or_else(|| {
+        path.split('.')
+            .try_fold(claims, |claims, segment| claims.get(segment))
+    })
+}
+
+/// Merges the `fallback` claims (e.g. from the UserInfo endpoint) into
+/// `claims`. Claims present in both are taken from `claims`.
+pub(crate) fn merge_claims(
+    claims: &PotreeAuthClaims,
+    fallback: &PotreeAuthClaims,
+) -> PotreeAuthClaims {
+    let mut merged = fallback.0.as_object().cloned().unwrap_or_default();
+    if let Some(claims) = claims.0.as_object() {
+        merged.extend(claims.clone());
     }
+
+    PotreeAuthClaims(serde_json::Value::Object(merged))
+}
+
+/// How far the clocks of the IdP and the application may drift apart.
+const AUTH_TIME_LEEWAY_SECONDS: i64 = 60;
+
+/// Verifies that the user authenticated with the IdP at most `max_age` ago.
+/// `auth_time` and `now` are unix timestamps (seconds).
+///
+/// The IdP is required to return the `auth_time` claim if a `max_age` was
+/// requested.
+pub(crate) fn verify_auth_time(
+    auth_time: Option<i64>,
+    max_age: std::time::Duration,
+    now: i64,
+) -> Result<(), String> {
+    let auth_time = auth_time.ok_or("no `auth_time` claim, but a `max_age` was requested")?;
+
+    let max_age = i64::try_from(max_age.as_secs()).unwrap_or(i64::MAX);
+    if now.saturating_sub(auth_time) > max_age.saturating_add(AUTH_TIME_LEEWAY_SECONDS) {
+        return Err(format!(
+            "the user authenticated more than `max_age` ({max_age}s) ago"
+        ));
+    }
+
+    Ok(())
+}
+
+/// The delay before retrying the first failed discovery of the IdP's metadata.
+pub(crate) const MIN_DISCOVERY_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The maximum delay between retries of a failing discovery, so that users can
+/// authenticate soon after the IdP becomes reachable again.
+pub(crate) const MAX_DISCOVERY_RETRY_DELAY: std::time::Duration =
+    std::time::Duration::from_secs(60);
+
+/// Doubles the delay between retries of a failing discovery (exponential
+/// backoff), up to the [`MAX_DISCOVERY_RETRY_DELAY`].
+pub(crate) fn next_retry_delay(retry_delay: std::time::Duration) -> std::time::Duration {
+    retry_delay
+        .saturating_mul(2)
+        .clamp(MIN_DISCOVERY_RETRY_DELAY, MAX_DISCOVERY_RETRY_DELAY)
 }
 
 pub(crate) fn extract_user_id(
@@ -31,32 +106,447 @@ pub(crate) fn extract_user_id(
     UserId::new(id_token_claims.subject().to_string())
 }
 
+/// Extracts the name of the user from the ID token, falling back to the
+/// claims from the UserInfo endpoint (if fetched).
 pub(crate) fn extract_user_name(
     id_token_claims: &IdTokenClaims<PotreeAuthClaims, CoreGenderClaim>,
+    user_info_claims: Option<&UserInfoClaims<PotreeAuthClaims, CoreGenderClaim>>,
 ) -> Result<UserName, AuthenticationEngineError> {
-    Ok(UserName::new(
-        id_token_claims
-            .name()
-            .ok_or(AuthenticationEngineError::Validation {
-                message: "no `name` associated with user".to_owned(),
-            })?
-            .get(None)
-            .ok_or(AuthenticationEngineError::Validation {
-                message: "no `name` associated with user".to_owned(),
-            })?
-            .to_string(),
-    ))
+    let name = id_token_claims
+        .name()
+        .and_then(|name| name.get(None))
+        .or_else(|| {
+            user_info_claims
+                .and_then(|user_info_claims| user_info_claims.name())
+                .and_then(|name| name.get(None))
+        })
+        .ok_or(AuthenticationEngineError::Validation {
+            message: "no `name` associated with user".to_owned(),
+        })?;
+
+    Ok(UserName::new(name.to_string()))
 }
 
+/// Extracts the email address of the user from the ID token, falling back to
+/// the claims from the UserInfo endpoint (if fetched).
 pub(crate) fn extract_user_email(
     id_token_claims: &IdTokenClaims<PotreeAuthClaims, CoreGenderClaim>,
+    user_info_claims: Option<&UserInfoClaims<PotreeAuthClaims, CoreGenderClaim>>,
 ) -> Result<EmailAddress, AuthenticationEngineError> {
-    Ok(EmailAddress::new(
+    let email = id_token_claims
+        .email()
+        .or_else(|| user_info_claims.and_then(|user_info_claims| user_info_claims.email()))
+        .ok_or(AuthenticationEngineError::Validation {
+            message: "no `email` associated with user".to_owned(),
+        })?;
+
+    Ok(EmailAddress::new(email.to_string()))
+}
+
+/// Whether the IdP verified the email address of the user, read from the same
+/// claims as [`extract_user_email`].
+pub(crate) fn extract_user_email_verified(
+    id_token_claims: &IdTokenClaims<PotreeAuthClaims, CoreGenderClaim>,
+    user_info_claims: Option<&UserInfoClaims<PotreeAuthClaims, CoreGenderClaim>>,
+) -> bool {
+    let email_verified = if id_token_claims.email().is_some() {
+        id_token_claims.email_verified()
+    } else {
+        user_info_claims.and_then(|user_info_claims| user_info_claims.email_verified())
+    };
+
+    email_verified == Some(true)
+}
+
+/// Builds a [`User`] from the claims of a validated (JWT) access token.
+///
+/// Access tokens don't always carry the profile claims of an ID token, so the
+/// `name` falls back to the `preferred_username` and then to the `sub`. The
+/// email address is only verified if the `email_verified` claim is `true`, or
+/// `trust_unverified_email` is set.
+pub(crate) fn extract_bearer_user(
+    claims: &PotreeAuthClaims,
+    groups_claim: &str,
+    group_mapping: &GroupMapping,
+    trust_unverified_email: bool,
+) -> Result<User, AuthenticationEngineError> {
+    let string_claim = |name: &str| claims.0.get(name).and_then(|v| v.as_str());
+
+    let id = string_claim("sub").ok_or(AuthenticationEngineError::Validation {
+        message: "no `sub` associated with access token".to_owned(),
+    })?;
+    let name = string_claim("name")
+        .or_else(|| string_claim("preferred_username"))
+        .unwrap_or(id);
+    let email = string_claim("email").ok_or(AuthenticationEngineError::Validation {
+        message: "no `email` associated with access token".to_owned(),
+    })?;
+    let email_verified = trust_unverified_email
+        || claims
+            .0
+            .get("email_verified")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+
+    // Bearer tokens are validated on every request (e.g. for each octree chunk),
+    // so this is only logged at debug level.
+    let groups = extract_user_groups(claims, groups_claim, group_mapping);
+    if groups.is_empty() {
+        tracing::debug!(
+            "no groups could be resolved from the `{groups_claim}` claim of the access token"
+        );
+    }
+
+    Ok(User {
+        id: UserId::new(id.to_owned()),
+        name: UserName::new(name.to_owned()),
+        email: EmailAddress::new(email.to_owned()),
+        email_verified,
+        groups,
+        provider: None,
+    })
+}
+
+#[cfg(test)]
+mod utils_tests {
+    use super::*;
+
+    mod extract_user_groups {
+        use super::*;
+
+        #[test]
+        fn should_map_the_groups() {
+            // Arrange
+            let claims = PotreeAuthClaims(serde_json::json!({
+                "groups": ["CN=Surveyors,OU=Groups,DC=corp", "admin", 42],
+            }));
+            let group_mapping = serde_norway::from_str::<GroupMapping>(
+                r#""CN=Surveyors,OU=Groups,DC=corp": surveyors"#,
+            )
+            .unwrap();
+
+            // Act
+            let groups = extract_user_groups(&claims, "groups", &group_mapping);
+
+            // Assert
+            assert_eq!(groups, vec![Group::new("surveyors"), Group::new("admin")]);
+        }
+
+        #[test_case::test_case(serde_json::json!({"realm_access": {"roles": ["surveyors"]}}), "realm_access.roles"; "nested claim")]
+        #[test_case::test_case(serde_json::json!({"https://example.com/groups": ["surveyors"]}), "https://example.com/groups"; "claim name containing dots")]
+        #[test_case::test_case(serde_json::json!({"group": "surveyors"}), "group"; "single group")]
+        fn should_resolve_the_groups_claim(claims: serde_json::Value, groups_claim: &str) {
+            // Act
+            let groups = extract_user_groups(
+                &PotreeAuthClaims(claims),
+                groups_claim,
+                &GroupMapping::default(),
+            );
+
+            // Assert
+            assert_eq!(groups, vec![Group::new("surveyors")]);
+        }
+
+        #[test]
+        fn should_return_no_groups_if_claim_missing() {
+            // Arrange
+            let claims = PotreeAuthClaims(serde_json::json!({}));
+
+            // Act
+            let groups = extract_user_groups(&claims, "groups", &GroupMapping::default());
+
+            // Assert
+            assert!(groups.is_empty());
+        }
+    }
+
+    mod merge_claims {
+        use super::*;
+
+        #[test]
+        fn should_prefer_the_claims_over_the_fallback() {
+            // Arrange
+            let claims = PotreeAuthClaims(serde_json::json!({
+                "email": "user@example.com",
+                "_claim_names": {"groups": "src1"},
+            }));
+            let fallback = PotreeAuthClaims(serde_json::json!({
+                "email": "other@example.com",
+                "groups": ["surveyors"],
+            }));
+
+            // Act
+            let merged = merge_claims(&claims, &fallback);
+
+            // Assert
+            assert_eq!(
+                merged.0,
+                serde_json::json!({
+                    "email": "user@example.com",
+                    "_claim_names": {"groups": "src1"},
+                    "groups": ["surveyors"],
+                })
+            );
+        }
+    }
+
+    mod verify_auth_time {
+        use std::time::Duration;
+
+        use super::*;
+
+        const NOW: i64 = 1_700_000_000;
+
+        #[test_case::test_case(NOW; "just authenticated")]
+        #[test_case::test_case(NOW - 300; "at max age")]
+        #[test_case::test_case(NOW - 300 - AUTH_TIME_LEEWAY_SECONDS; "within leeway")]
+        fn should_return_ok_if_within_max_age(auth_time: i64) {
+            // Act
+            let res = verify_auth_time(Some(auth_time), Duration::from_secs(300), NOW);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test_case::test_case(Some(NOW - 301 - AUTH_TIME_LEEWAY_SECONDS); "exceeded max age")]
+        #[test_case::test_case(None; "no auth time")]
+        fn should_return_err_if_not_within_max_age(auth_time: Option<i64>) {
+            // Act
+            let res = verify_auth_time(auth_time, Duration::from_secs(300), NOW);
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+
+    mod next_retry_delay {
+        use std::time::Duration;
+
+        use super::*;
+
+        #[test_case::test_case(MIN_DISCOVERY_RETRY_DELAY, Duration::from_secs(2); "doubles the delay")]
+        #[test_case::test_case(Duration::from_secs(45), MAX_DISCOVERY_RETRY_DELAY; "capped at max delay")]
+        #[test_case::test_case(Duration::ZERO, MIN_DISCOVERY_RETRY_DELAY; "at least min delay")]
+        fn should_back_off_exponentially(retry_delay: Duration, expected: Duration) {
+            // Act
+            let next = next_retry_delay(retry_delay);
+
+            // Assert
+            assert_eq!(next, expected);
+        }
+    }
+
+    fn id_token_claims(
+        claims: serde_json::Value,
+    ) -> IdTokenClaims<PotreeAuthClaims, CoreGenderClaim> {
+        let mut id_token_claims = serde_json::json!({
+            "iss": "https://idp.example.com",
+            "aud": "client-id",
+            "exp": 4_102_444_800_i64,
+            "iat": 1_700_000_000_i64,
+            "sub": "user-id",
+        });
         id_token_claims
-            .email()
-            .ok_or(AuthenticationEngineError::Validation {
-                message: "no `email` associated with user".to_owned(),
-            })?
-            .to_string(),
-    ))
+            .as_object_mut()
+            .unwrap()
+            .extend(claims.as_object().unwrap().clone());
+
+        serde_json::from_value(id_token_claims).unwrap()
+    }
+
+    fn user_info_claims(
+        claims: serde_json::Value,
+    ) -> UserInfoClaims<PotreeAuthClaims, CoreGenderClaim> {
+        UserInfoClaims::from_json::<std::convert::Infallible>(claims.to_string().as_bytes(), None)
+            .unwrap()
+    }
+
+    mod extract_user_name {
+        use super::*;
+
+        #[test_case::test_case(serde_json::json!({"name": "A User"}), None; "from the ID token")]
+        #[test_case::test_case(serde_json::json!({"name": "A User"}), Some(serde_json::json!({"sub": "user-id", "name": "Another User"})); "preferring the ID token")]
+        #[test_case::test_case(serde_json::json!({}), Some(serde_json::json!({"sub": "user-id", "name": "A User"})); "from the UserInfo claims")]
+        fn should_return_the_name(
+            id_token: serde_json::Value,
+            user_info: Option<serde_json::Value>,
+        ) {
+            // Arrange
+            let user_info_claims = user_info.map(user_info_claims);
+
+            // Act
+            let name =
+                extract_user_name(&id_token_claims(id_token), user_info_claims.as_ref()).unwrap();
+
+            // Assert
+            assert_eq!(name, UserName::new("A User".to_owned()));
+        }
+
+        #[test]
+        fn should_return_err_if_no_name() {
+            // Arrange
+            let user_info_claims = user_info_claims(serde_json::json!({"sub": "user-id"}));
+
+            // Act
+            let res = extract_user_name(
+                &id_token_claims(serde_json::json!({})),
+                Some(&user_info_claims),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+    }
+
+    mod extract_user_email {
+        use super::*;
+
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com"}), None; "from the ID token")]
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com"}), Some(serde_json::json!({"sub": "user-id", "email": "other@example.com"})); "preferring the ID token")]
+        #[test_case::test_case(serde_json::json!({}), Some(serde_json::json!({"sub": "user-id", "email": "user@example.com"})); "from the UserInfo claims")]
+        fn should_return_the_email(
+            id_token: serde_json::Value,
+            user_info: Option<serde_json::Value>,
+        ) {
+            // Arrange
+            let user_info_claims = user_info.map(user_info_claims);
+
+            // Act
+            let email =
+                extract_user_email(&id_token_claims(id_token), user_info_claims.as_ref()).unwrap();
+
+            // Assert
+            assert_eq!(email, EmailAddress::new("user@example.com".to_owned()));
+        }
+
+        #[test]
+        fn should_return_err_if_no_email() {
+            // Act
+            let res = extract_user_email(&id_token_claims(serde_json::json!({})), None);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+    }
+
+    mod extract_user_email_verified {
+        use super::*;
+
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com", "email_verified": true}), None, true; "verified in the ID token")]
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com", "email_verified": false}), None, false; "unverified in the ID token")]
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com"}), None, false; "no claim")]
+        #[test_case::test_case(serde_json::json!({"email": "user@example.com"}), Some(serde_json::json!({"sub": "user-id", "email": "other@example.com", "email_verified": true})), false; "ignoring the UserInfo claims of another email")]
+        #[test_case::test_case(serde_json::json!({}), Some(serde_json::json!({"sub": "user-id", "email": "user@example.com", "email_verified": true})), true; "verified in the UserInfo claims")]
+        fn should_return_whether_the_email_is_verified(
+            id_token: serde_json::Value,
+            user_info: Option<serde_json::Value>,
+            expected: bool,
+        ) {
+            // Arrange
+            let user_info_claims = user_info.map(user_info_claims);
+
+            // Act
+            let email_verified =
+                extract_user_email_verified(&id_token_claims(id_token), user_info_claims.as_ref());
+
+            // Assert
+            assert_eq!(email_verified, expected);
+        }
+    }
+
+    mod extract_bearer_user {
+        use super::*;
+
+        #[test]
+        fn should_build_a_user_from_the_claims() {
+            // Arrange
+            let claims = PotreeAuthClaims(serde_json::json!({
+                "sub": "user-id",
+                "name": "A User",
+                "email": "user@example.com",
+                "email_verified": true,
+                "roles": ["surveyors", "admin"],
+            }));
+
+            // Act
+            let user =
+                extract_bearer_user(&claims, "roles", &GroupMapping::default(), false).unwrap();
+
+            // Assert
+            assert_eq!(
+                user,
+                User {
+                    id: UserId::new("user-id".to_owned()),
+                    name: UserName::new("A User".to_owned()),
+                    email: EmailAddress::new("user@example.com".to_owned()),
+                    email_verified: true,
+                    groups: vec![Group::new("surveyors"), Group::new("admin")],
+                    provider: None,
+                }
+            );
+        }
+
+        #[test_case::test_case(serde_json::json!({"sub": "id", "preferred_username": "username", "email": "e"}), "username"; "preferred username")]
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e"}), "id"; "subject")]
+        fn should_fall_back_if_no_name_claim(claims: serde_json::Value, expected_name: &str) {
+            // Arrange
+            let claims = PotreeAuthClaims(claims);
+
+            // Act
+            let user =
+                extract_bearer_user(&claims, "groups", &GroupMapping::default(), false).unwrap();
+
+            // Assert
+            assert_eq!(user.name, UserName::new(expected_name.to_owned()));
+            assert!(user.groups.is_empty());
+        }
+
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e"}), false, false; "no claim")]
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e", "email_verified": false}), false, false; "unverified")]
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e", "email_verified": "true"}), false, false; "not a boolean")]
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e", "email_verified": true}), false, true; "verified")]
+        #[test_case::test_case(serde_json::json!({"sub": "id", "email": "e"}), true, true; "trusting unverified emails")]
+        fn should_only_verify_the_email_if_claimed_or_trusted(
+            claims: serde_json::Value,
+            trust_unverified_email: bool,
+            expected: bool,
+        ) {
+            // Arrange
+            let claims = PotreeAuthClaims(claims);
+
+            // Act
+            let user = extract_bearer_user(
+                &claims,
+                "groups",
+                &GroupMapping::default(),
+                trust_unverified_email,
+            )
+            .unwrap();
+
+            // Assert
+            assert_eq!(user.email_verified, expected);
+        }
+
+        #[test_case::test_case(serde_json::json!({"email": "e"}); "missing subject")]
+        #[test_case::test_case(serde_json::json!({"sub": "id"}); "missing email")]
+        fn should_return_err_if_required_claims_missing(claims: serde_json::Value) {
+            // Arrange
+            let claims = PotreeAuthClaims(claims);
+
+            // Act
+            let res = extract_bearer_user(&claims, "groups", &GroupMapping::default(), false);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationEngineError::Validation { .. })
+            ));
+        }
+    }
 }
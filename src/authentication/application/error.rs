@@ -10,6 +10,9 @@ pub enum AuthenticationServiceError {
 
     #[error("unable to validate IdP data: {message}")]
     Validation { message: String },
+
+    #[error("no identity provider named: {name}")]
+    UnknownProvider { name: String },
 }
 
 impl From<AuthenticationEngineError> for AuthenticationServiceError {
@@ -1,30 +1,147 @@
+use std::net::IpAddr;
 use std::sync::Arc;
 
+use http::HeaderMap;
+use time::OffsetDateTime;
+use url::Url;
+
 use super::super::domain::AuthorizeData;
+use super::super::domain::BearerToken;
+use super::super::domain::CallbackData;
 use super::super::domain::CallbackRequestParams;
+use super::super::domain::IdpStatus;
+use super::super::domain::LoginMethod;
 use super::super::domain::OidcSessionPersisted;
+use super::super::domain::PasswordCredentials;
+use super::super::domain::RawIdToken;
+use super::super::domain::SessionValidity;
 use super::super::ports::authentication_engine::AuthenticationEngine;
 use super::error::AuthenticationServiceError;
+use crate::audit::adapters::audit_log::no_op::NoOpAuditLog;
+use crate::audit::domain::AuditEvent;
+use crate::audit::domain::AuditEventKind;
+use crate::audit::domain::AuditOutcome;
+use crate::audit::ports::audit_log::AuditLog;
+use crate::common::domain::Group;
+use crate::user::domain::ProviderName;
 use crate::user::domain::User;
+use crate::user::domain::UserId;
 
 /// A service for handling user authentication with OIDC.
+///
+/// Users can authenticate with any of the configured identity providers.
 #[derive(Debug, Clone)]
 pub struct AuthenticationService {
-    authentication_engine: Arc<dyn AuthenticationEngine>,
+    /// The identity providers, in the order in which they are presented to
+    /// users. The first is used if a provider isn't specified.
+    providers: Arc<[IdentityProvider]>,
+
+    /// Where logins and logouts are recorded.
+    audit_log: Arc<dyn AuditLog>,
+}
+
+/// An identity provider with which users can authenticate.
+#[derive(Debug, Clone)]
+pub struct IdentityProvider {
+    /// Uniquely identifies the provider (e.g. in the callback route).
+    pub name: ProviderName,
+
+    /// A human readable name for the provider, shown when choosing a provider.
+    pub display_name: String,
+
+    /// Prefixed to the groups of the users it authenticates (e.g. `acme:`), so
+    /// that the groups of different providers can be told apart.
+    pub group_prefix: Option<String>,
+
+    pub authentication_engine: Arc<dyn AuthenticationEngine>,
+}
+
+impl IdentityProvider {
+    /// Records that the `user` was authenticated by this provider, prefixing
+    /// their groups with the `group_prefix`.
+    fn identify(&self, mut user: User) -> User {
+        if let Some(group_prefix) = &self.group_prefix {
+            user.groups = user
+                .groups
+                .iter()
+                .map(|group| Group::new(&format!("{group_prefix}{}", group.as_str())))
+                .collect();
+        }
+        user.provider = Some(self.name.clone());
+
+        user
+    }
 }
 
 impl AuthenticationService {
-    pub fn new(authentication_engine: Arc<dyn AuthenticationEngine>) -> Self {
+    pub fn new(providers: Vec<IdentityProvider>) -> Self {
         Self {
-            authentication_engine,
+            providers: providers.into(),
+            audit_log: Arc::new(NoOpAuditLog),
+        }
+    }
+
+    /// Records logins and logouts in the `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    /// The identity providers with which users can authenticate.
+    pub fn providers(&self) -> &[IdentityProvider] {
+        &self.providers
+    }
+
+    /// Whether users can currently authenticate with each of the identity
+    /// providers.
+    pub fn provider_statuses(&self) -> Vec<(ProviderName, IdpStatus)> {
+        self.providers
+            .iter()
+            .map(|provider| {
+                (
+                    provider.name.clone(),
+                    provider.authentication_engine.status(),
+                )
+            })
+            .collect()
+    }
+
+    /// Finds the provider `name`d, or the default (first) provider if no name
+    /// is provided.
+    fn provider(
+        &self,
+        name: Option<&ProviderName>,
+    ) -> Result<&IdentityProvider, AuthenticationServiceError> {
+        match name {
+            Some(name) => self
+                .providers
+                .iter()
+                .find(|provider| &provider.name == name)
+                .ok_or_else(|| AuthenticationServiceError::UnknownProvider {
+                    name: name.to_string(),
+                }),
+            None => {
+                self.providers
+                    .first()
+                    .ok_or_else(|| AuthenticationServiceError::Infrastructure {
+                        message: "no identity providers are configured".to_owned(),
+                    })
+            }
         }
     }
 
-    //// Called as part of the OIDC [`/authorize`] endpoint.
+    //// Called as part of the OIDC [`/authorize`] endpoint of the `provider`.
     ///
     /// [`/authorize`]: https://openid.net/specs/openid-connect-core-1_0.html#AuthorizationEndpoint
-    pub async fn authorize(&self) -> Result<AuthorizeData, AuthenticationServiceError> {
-        Ok(self.authentication_engine.authorize().await?)
+    pub async fn authorize(
+        &self,
+        provider: &ProviderName,
+    ) -> Result<AuthorizeData, AuthenticationServiceError> {
+        Ok(self
+            .provider(Some(provider))?
+            .authentication_engine
+            .authorize()
+            .await?)
     }
 
     /// After authentication, the IdP would redirect the user agent to the
@@ -37,12 +154,510 @@ impl AuthenticationService {
     /// handler.
     pub async fn callback(
         &self,
+        provider: &ProviderName,
         callback_params: CallbackRequestParams,
         persisted_data: OidcSessionPersisted,
-    ) -> Result<User, AuthenticationServiceError> {
-        Ok(self
+    ) -> Result<CallbackData, AuthenticationServiceError> {
+        let provider = self.provider(Some(provider))?;
+        let callback_data = provider
             .authentication_engine
             .callback(callback_params, persisted_data)
+            .await
+            .map_err(AuthenticationServiceError::from)
+            .map(|callback_data| CallbackData {
+                user: provider.identify(callback_data.user),
+                ..callback_data
+            });
+
+        self.record_login(&callback_data, None);
+
+        callback_data
+    }
+
+    /// How users log in with the `provider`.
+    pub fn login_method(
+        &self,
+        provider: &ProviderName,
+    ) -> Result<LoginMethod, AuthenticationServiceError> {
+        Ok(self
+            .provider(Some(provider))?
+            .authentication_engine
+            .login_method())
+    }
+
+    /// Authenticates the username and password entered by a user in the login
+    /// form of the `provider`. Repeated failed attempts (e.g. from the same
+    /// client, identified by the `source` address and `headers` of the
+    /// request) are throttled.
+    pub async fn authenticate_password(
+        &self,
+        provider: &ProviderName,
+        credentials: &PasswordCredentials,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Result<CallbackData, AuthenticationServiceError> {
+        let provider = self.provider(Some(provider))?;
+        let callback_data = provider
+            .authentication_engine
+            .authenticate_password(credentials, source, headers, OffsetDateTime::now_utc())
+            .await
+            .map_err(AuthenticationServiceError::from)
+            .map(|user| CallbackData {
+                user: provider.identify(user),
+                id_token: None,
+                refresh_token: None,
+            });
+
+        self.record_login(&callback_data, Some(&credentials.username));
+
+        callback_data
+    }
+
+    /// Called when the `user` (if still known from the session) logs out of
+    /// the application.
+    ///
+    /// Returns the url of the end session endpoint of the provider that
+    /// authenticated the user, if the user agent should be redirected to it to
+    /// also end the IdP session.
+    pub async fn logout(
+        &self,
+        user: Option<&User>,
+        id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationServiceError> {
+        self.record(AuditEvent::for_user(
+            OffsetDateTime::now_utc(),
+            AuditEventKind::Logout,
+            user,
+            AuditOutcome::Allowed,
+        ));
+
+        Ok(self
+            .provider(user.and_then(|user| user.provider.as_ref()))?
+            .authentication_engine
+            .logout(id_token)
             .await?)
     }
+
+    /// Authenticates a request made with an `Authorization: Bearer` header
+    /// (i.e. programmatic access), returning the [`User`] the token represents.
+    ///
+    /// The token is accepted if any of the providers can validate it.
+    pub async fn authenticate_bearer(
+        &self,
+        token: BearerToken,
+    ) -> Result<User, AuthenticationServiceError> {
+        let mut error = AuthenticationServiceError::Validation {
+            message: "bearer token authentication is not configured".to_owned(),
+        };
+
+        for provider in self.providers.iter() {
+            match provider
+                .authentication_engine
+                .authenticate_bearer(token.clone())
+                .await
+            {
+                Ok(user) => return Ok(provider.identify(user)),
+                Err(e) => error = e.into(),
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Authenticates a request from the identity `headers` set by an upstream
+    /// authenticating proxy, if the request comes from a trusted `source`.
+    pub async fn authenticate_proxy_headers(
+        &self,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationServiceError> {
+        for provider in self.providers.iter() {
+            if let Some(user) = provider
+                .authentication_engine
+                .authenticate_proxy_headers(source, headers)
+                .await?
+            {
+                return Ok(Some(provider.identify(user)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Re-validates the `user` of a web session with the provider that
+    /// authenticated them, if the session's `validity` has exceeded the
+    /// maximum session age.
+    ///
+    /// Returns `None` if the session is still valid as it is.
+    pub async fn revalidate(
+        &self,
+        user: &User,
+        validity: &SessionValidity,
+    ) -> Result<Option<CallbackData>, AuthenticationServiceError> {
+        let provider = self.provider(user.provider.as_ref())?;
+
+        Ok(provider
+            .authentication_engine
+            .revalidate(user, validity, OffsetDateTime::now_utc())
+            .await?
+            .map(|callback_data| CallbackData {
+                user: provider.identify(callback_data.user),
+                ..callback_data
+            }))
+    }
+
+    /// Records the result of a login. The `username` entered in a login form
+    /// identifies the user of a failed login.
+    fn record_login(
+        &self,
+        callback_data: &Result<CallbackData, AuthenticationServiceError>,
+        username: Option<&str>,
+    ) {
+        let now = OffsetDateTime::now_utc();
+
+        self.record(match callback_data {
+            Ok(callback_data) => AuditEvent::for_user(
+                now,
+                AuditEventKind::Login,
+                Some(&callback_data.user),
+                AuditOutcome::Allowed,
+            ),
+            Err(e) => AuditEvent {
+                user_id: username.map(|username| UserId::new(username.to_owned())),
+                reason: Some(e.to_string()),
+                ..AuditEvent::for_user(now, AuditEventKind::Login, None, AuditOutcome::Denied)
+            },
+        });
+    }
+
+    /// Records the `event`. Failing to record it is logged rather than failing
+    /// the request.
+    fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.audit_log.record(&event) {
+            tracing::error!(error = %e, "unable to record the authentication event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod authentication_service_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+    use crate::audit::ports::audit_log::MockAuditLog;
+    use crate::authentication::ports::authentication_engine::AuthenticationEngineError;
+    use crate::authentication::ports::authentication_engine::MockAuthenticationEngine;
+
+    fn provider(
+        name: &str,
+        group_prefix: Option<&str>,
+        authentication_engine: MockAuthenticationEngine,
+    ) -> IdentityProvider {
+        IdentityProvider {
+            name: ProviderName::new(name.to_owned()),
+            display_name: name.to_owned(),
+            group_prefix: group_prefix.map(str::to_owned),
+            authentication_engine: Arc::new(authentication_engine),
+        }
+    }
+
+    mod authenticate_bearer {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_identify_the_user_with_the_accepting_provider() {
+            // Arrange
+            let mut acme = MockAuthenticationEngine::new();
+            acme.expect_authenticate_bearer().return_const(Err(
+                AuthenticationEngineError::Validation {
+                    message: "wrong issuer".to_owned(),
+                },
+            ));
+            let mut partner = MockAuthenticationEngine::new();
+            partner.expect_authenticate_bearer().return_const(Ok(User {
+                groups: vec![Group::new("surveyors")],
+                ..Faker.fake()
+            }));
+
+            let authentication_service = AuthenticationService::new(vec![
+                provider("acme", Some("acme:"), acme),
+                provider("partner", Some("partner:"), partner),
+            ]);
+
+            // Act
+            let user = authentication_service
+                .authenticate_bearer(Faker.fake())
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(user.groups, vec![Group::new("partner:surveyors")]);
+            assert_eq!(user.provider, Some(ProviderName::new("partner".to_owned())));
+        }
+
+        #[tokio::test]
+        async fn should_return_err_if_no_provider_accepts_the_token() {
+            // Arrange
+            let mut acme = MockAuthenticationEngine::new();
+            acme.expect_authenticate_bearer().return_const(Err(
+                AuthenticationEngineError::Validation {
+                    message: "wrong issuer".to_owned(),
+                },
+            ));
+
+            let authentication_service =
+                AuthenticationService::new(vec![provider("acme", None, acme)]);
+
+            // Act
+            let res = authentication_service
+                .authenticate_bearer(Faker.fake())
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationServiceError::Validation { .. })
+            ));
+        }
+    }
+
+    mod authorize {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_provider_unknown() {
+            // Arrange
+            let authentication_service = AuthenticationService::new(vec![provider(
+                "acme",
+                None,
+                MockAuthenticationEngine::new(),
+            )]);
+
+            // Act
+            let res = authentication_service
+                .authorize(&ProviderName::new("partner".to_owned()))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationServiceError::UnknownProvider { .. })
+            ));
+        }
+    }
+
+    mod authenticate_password {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_identify_the_user_with_the_provider() {
+            // Arrange
+            let mut local = MockAuthenticationEngine::new();
+            local.expect_authenticate_password().return_const(Ok(User {
+                groups: vec![Group::new("surveyors")],
+                ..Faker.fake()
+            }));
+
+            let authentication_service =
+                AuthenticationService::new(vec![provider("local", Some("local:"), local)]);
+
+            // Act
+            let callback_data = authentication_service
+                .authenticate_password(
+                    &ProviderName::new("local".to_owned()),
+                    &PasswordCredentials {
+                        username: "surveyor".to_owned(),
+                        password: "password".to_owned(),
+                    },
+                    None,
+                    &HeaderMap::new(),
+                )
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                callback_data.user.groups,
+                vec![Group::new("local:surveyors")]
+            );
+            assert_eq!(
+                callback_data.user.provider,
+                Some(ProviderName::new("local".to_owned()))
+            );
+            assert_eq!(callback_data.id_token, None);
+        }
+
+        #[tokio::test]
+        async fn should_record_a_failed_login_with_the_entered_username() {
+            // Arrange
+            let mut local = MockAuthenticationEngine::new();
+            local.expect_authenticate_password().return_const(Err(
+                AuthenticationEngineError::Validation {
+                    message: "invalid credentials".to_owned(),
+                },
+            ));
+
+            let mut audit_log = MockAuditLog::new();
+            audit_log
+                .expect_record()
+                .withf(|event| {
+                    event.event == AuditEventKind::Login
+                        && event.outcome == AuditOutcome::Denied
+                        && event.user_id == Some(UserId::new("surveyor".to_owned()))
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let authentication_service =
+                AuthenticationService::new(vec![provider("local", None, local)])
+                    .with_audit_log(Arc::new(audit_log));
+
+            // Act
+            let res = authentication_service
+                .authenticate_password(
+                    &ProviderName::new("local".to_owned()),
+                    &PasswordCredentials {
+                        username: "surveyor".to_owned(),
+                        password: "wrong".to_owned(),
+                    },
+                    None,
+                    &HeaderMap::new(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthenticationServiceError::Validation { .. })
+            ));
+        }
+    }
+
+    mod logout {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_record_the_logout_and_use_the_users_provider() {
+            // Arrange
+            let user = User {
+                provider: Some(ProviderName::new("partner".to_owned())),
+                ..Faker.fake()
+            };
+
+            let acme = MockAuthenticationEngine::new();
+            let mut partner = MockAuthenticationEngine::new();
+            partner.expect_logout().times(1).return_const(Ok(None));
+
+            let mut audit_log = MockAuditLog::new();
+            let expected_user_id = user.id.clone();
+            audit_log
+                .expect_record()
+                .withf(move |event| {
+                    event.event == AuditEventKind::Logout
+                        && event.user_id.as_ref() == Some(&expected_user_id)
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let authentication_service = AuthenticationService::new(vec![
+                provider("acme", None, acme),
+                provider("partner", None, partner),
+            ])
+            .with_audit_log(Arc::new(audit_log));
+
+            // Act
+            let res = authentication_service.logout(Some(&user), None).await;
+
+            // Assert
+            assert!(matches!(res, Ok(None)));
+        }
+    }
+
+    mod provider_statuses {
+        use super::*;
+
+        #[test]
+        fn should_return_the_status_of_each_provider() {
+            // Arrange
+            let mut acme = MockAuthenticationEngine::new();
+            acme.expect_status().return_const(IdpStatus::Ready);
+            let mut partner = MockAuthenticationEngine::new();
+            partner
+                .expect_status()
+                .return_const(IdpStatus::Unavailable {
+                    message: "connection refused".to_owned(),
+                });
+
+            let authentication_service = AuthenticationService::new(vec![
+                provider("acme", None, acme),
+                provider("partner", None, partner),
+            ]);
+
+            // Act
+            let statuses = authentication_service.provider_statuses();
+
+            // Assert
+            assert_eq!(
+                statuses,
+                vec![
+                    (ProviderName::new("acme".to_owned()), IdpStatus::Ready),
+                    (
+                        ProviderName::new("partner".to_owned()),
+                        IdpStatus::Unavailable {
+                            message: "connection refused".to_owned()
+                        }
+                    ),
+                ]
+            );
+        }
+    }
+
+    mod revalidate {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_revalidate_with_the_provider_that_authenticated_the_user() {
+            // Arrange
+            let mut acme = MockAuthenticationEngine::new();
+            acme.expect_revalidate().never();
+            let mut partner = MockAuthenticationEngine::new();
+            partner
+                .expect_revalidate()
+                .return_const(Ok(Some(CallbackData {
+                    user: User {
+                        groups: vec![Group::new("surveyors")],
+                        ..Faker.fake()
+                    },
+                    id_token: None,
+                    refresh_token: None,
+                })));
+
+            let authentication_service = AuthenticationService::new(vec![
+                provider("acme", None, acme),
+                provider("partner", Some("partner:"), partner),
+            ]);
+            let user = User {
+                provider: Some(ProviderName::new("partner".to_owned())),
+                ..Faker.fake()
+            };
+            let validity = SessionValidity {
+                validated_at: OffsetDateTime::UNIX_EPOCH,
+                refresh_token: None,
+            };
+
+            // Act
+            let callback_data = authentication_service
+                .revalidate(&user, &validity)
+                .await
+                .unwrap()
+                .unwrap();
+
+            // Assert
+            assert_eq!(
+                callback_data.user.groups,
+                vec![Group::new("partner:surveyors")]
+            );
+        }
+    }
 }
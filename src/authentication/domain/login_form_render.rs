@@ -0,0 +1,23 @@
+use askama::Template;
+
+/// Represents the page on which a user logs in with their username and
+/// password (i.e. with a local user account).
+#[derive(Debug, Template)]
+#[template(path = "authentication/login_form.html")]
+pub struct LoginForm {
+    /// The route to which the form is submitted.
+    pub form_action: String,
+
+    /// The path to which the user is redirected after logging in.
+    pub next_path: String,
+
+    /// Protects the form against cross-site request forgery (e.g. logging the
+    /// user in to an attacker's account). It is also stored in the session.
+    pub csrf_token: String,
+
+    /// The username that was entered before a failed attempt.
+    pub username: String,
+
+    /// Why the previous attempt to log in failed.
+    pub error: Option<String>,
+}
@@ -0,0 +1,16 @@
+use askama::Template;
+
+/// Represents the page on which a user confirms that they want to log out.
+#[derive(Debug, Template)]
+#[template(path = "authentication/logout_form.html")]
+pub struct LogoutForm {
+    /// The route to which the form is submitted.
+    pub form_action: String,
+
+    /// Protects the form against cross-site request forgery (e.g. a third-party
+    /// page logging the user out). It is also stored in the session.
+    pub csrf_token: String,
+
+    /// Why the previous attempt to log out failed.
+    pub error: Option<String>,
+}
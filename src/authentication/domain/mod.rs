@@ -1,15 +1,27 @@
+pub mod login_form_render;
+pub mod logout_form_render;
+pub mod provider_chooser_render;
+
+use std::collections::HashMap;
+
 use openidconnect::AuthorizationCode;
 use openidconnect::CsrfToken;
 use openidconnect::Nonce;
+use openidconnect::PkceCodeVerifier;
 use serde::Deserialize;
 use serde::Serialize;
+use time::OffsetDateTime;
 use url::Url;
 
+use crate::common::domain::Group;
+use crate::common::domain::utils::new_type::new_type;
+use crate::user::domain::User;
+
 /// OIDC data generated from the OIDC Authentication Request (`/authorize`
 /// endpoint).
 ///
 /// The documentation on each field indicates its purpose.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct AuthorizeData {
     /// The url to which the user agent should be redirected to perform IdP
     /// authentication.
@@ -20,7 +32,7 @@ pub struct AuthorizeData {
     pub persisted_data: OidcSessionPersisted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OidcSessionPersisted {
     /// The OIDC state. Should be persisted for validation in the callback
     /// handler.
@@ -29,6 +41,10 @@ pub struct OidcSessionPersisted {
     /// The OIDC nonce. Should be persisted for validation in the callback
     /// handler.
     pub nonce: Nonce,
+
+    /// The PKCE code verifier. Should be persisted so that it can be sent to
+    /// the IdP when exchanging the authorization code.
+    pub pkce_verifier: PkceCodeVerifier,
 }
 
 /// The query params that are supplied in the callback url from the IdP.
@@ -40,3 +56,241 @@ pub struct CallbackRequestParams {
     pub code: AuthorizationCode,
     pub state: CsrfToken,
 }
+
+/// The result of successfully finalizing an OIDC authentication flow (or of
+/// re-validating the user of an existing session).
+#[derive(Debug, Clone)]
+pub struct CallbackData {
+    /// The authenticated user.
+    pub user: User,
+
+    /// The raw ID token issued by the IdP. Should be persisted so that it can
+    /// be used as the `id_token_hint` when logging the user out.
+    pub id_token: Option<RawIdToken>,
+
+    /// The refresh token issued by the IdP. Should be persisted so that the
+    /// user's claims can be re-validated without user interaction.
+    pub refresh_token: Option<RawRefreshToken>,
+}
+
+/// Tracks when the user of a web session was last validated with the IdP.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionValidity {
+    /// When the user's claims were last validated with the IdP.
+    #[serde(with = "time::serde::timestamp")]
+    pub validated_at: OffsetDateTime,
+
+    /// Used to re-validate the user's claims with the IdP.
+    pub refresh_token: Option<RawRefreshToken>,
+}
+
+impl SessionValidity {
+    /// Whether the user's claims were validated longer than `max_age` ago.
+    pub fn is_older_than(&self, max_age: std::time::Duration, now: OffsetDateTime) -> bool {
+        now - self.validated_at > max_age
+    }
+}
+
+new_type![
+    /// An access token presented by a client in an `Authorization: Bearer`
+    /// header.
+    BearerToken(String)
+];
+
+new_type![
+    /// A serialized (raw JWT) OIDC ID token.
+    #[derive(Deserialize, Serialize)]
+    RawIdToken(String)
+];
+
+new_type![
+    /// An OAuth 2.0 refresh token issued by the IdP.
+    #[derive(Deserialize, Serialize)]
+    RawRefreshToken(String)
+];
+
+/// Maps the group values issued by an IdP (e.g. full LDAP DNs or Entra ID
+/// object ids) to the [`Group`] names used in project manifests.
+///
+/// Each IdP value can be mapped to a single group or to a list of groups. IdP
+/// values without a mapping are kept as they are.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct GroupMapping(HashMap<String, GroupAliases>);
+
+/// The group(s) that an IdP group value is mapped to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum GroupAliases {
+    One(Group),
+    Many(Vec<Group>),
+}
+
+impl GroupMapping {
+    /// Maps the IdP group `values` to [`Group`]s, removing any duplicates.
+    pub fn map<'a>(&self, values: impl IntoIterator<Item = &'a str>) -> Vec<Group> {
+        let mut groups = Vec::new();
+
+        for value in values {
+            let mapped = match self.0.get(value) {
+                Some(GroupAliases::One(group)) => vec![group.clone()],
+                Some(GroupAliases::Many(groups)) => groups.clone(),
+                None => vec![Group::new(value)],
+            };
+
+            for group in mapped {
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
+            }
+        }
+
+        groups
+    }
+}
+
+/// How users log in with an identity provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginMethod {
+    /// The user agent is redirected to the IdP (i.e. the OIDC Authorization
+    /// Code flow).
+    Redirect,
+
+    /// The user enters their username and password in a login form.
+    Form,
+}
+
+/// The username and password entered by a user in the login form.
+#[derive(Clone, Deserialize)]
+pub struct PasswordCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for PasswordCredentials {
+    /// Never reveals the password (e.g. in logs).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasswordCredentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Whether users can currently authenticate with an IdP, as reported by the
+/// health endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum IdpStatus {
+    /// The IdP's metadata (e.g. endpoints, signing keys) has not been
+    /// discovered yet.
+    Pending,
+
+    /// The IdP's metadata is up to date.
+    Ready,
+
+    /// The IdP's metadata could not be refreshed; the previously discovered
+    /// metadata is still used.
+    Stale { message: String },
+
+    /// The IdP's metadata could not be discovered, so users can't authenticate
+    /// with it.
+    Unavailable { message: String },
+}
+
+impl IdpStatus {
+    /// Whether users can authenticate with the IdP.
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Ready | Self::Stale { .. })
+    }
+}
+
+#[cfg(test)]
+mod authentication_domain_tests {
+    use super::*;
+
+    mod group_mapping {
+        use super::*;
+
+        fn mapping() -> GroupMapping {
+            serde_norway::from_str(
+                r#"
+                "CN=Surveyors,OU=Groups,DC=corp,DC=example,DC=com": surveyors
+                "7b0c8a4e-2f7d-4c1b-9a53-0e6f1d2c3b4a": [clients, reviewers]
+                "#,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn should_rename_mapped_values() {
+            // Act
+            let groups = mapping().map(["CN=Surveyors,OU=Groups,DC=corp,DC=example,DC=com"]);
+
+            // Assert
+            assert_eq!(groups, vec![Group::new("surveyors")]);
+        }
+
+        #[test]
+        fn should_alias_a_value_to_many_groups() {
+            // Act
+            let groups = mapping().map(["7b0c8a4e-2f7d-4c1b-9a53-0e6f1d2c3b4a"]);
+
+            // Assert
+            assert_eq!(groups, vec![Group::new("clients"), Group::new("reviewers")]);
+        }
+
+        #[test]
+        fn should_keep_unmapped_values_and_remove_duplicates() {
+            // Act
+            let groups = mapping().map([
+                "admin",
+                "surveyors",
+                "CN=Surveyors,OU=Groups,DC=corp,DC=example,DC=com",
+            ]);
+
+            // Assert
+            assert_eq!(groups, vec![Group::new("admin"), Group::new("surveyors")]);
+        }
+    }
+
+    mod session_validity {
+        use std::time::Duration;
+
+        use super::*;
+
+        #[test_case::test_case(time::Duration::minutes(5), false; "recently validated")]
+        #[test_case::test_case(time::Duration::minutes(16), true; "validated too long ago")]
+        fn should_compare_the_age_to_max_age(age: time::Duration, expected: bool) {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let validity = SessionValidity {
+                validated_at: now - age,
+                refresh_token: None,
+            };
+
+            // Act
+            let is_older = validity.is_older_than(Duration::from_secs(15 * 60), now);
+
+            // Assert
+            assert_eq!(is_older, expected);
+        }
+
+        #[test]
+        fn should_round_trip_through_the_session() {
+            // Arrange
+            let validity = SessionValidity {
+                validated_at: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+                refresh_token: Some(RawRefreshToken::new("token".to_owned())),
+            };
+
+            // Act
+            let deserialized =
+                serde_json::from_value::<SessionValidity>(serde_json::to_value(&validity).unwrap())
+                    .unwrap();
+
+            // Assert
+            assert_eq!(deserialized, validity);
+        }
+    }
+}
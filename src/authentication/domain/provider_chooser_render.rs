@@ -0,0 +1,19 @@
+use askama::Template;
+
+/// Represents the page on which a user chooses the identity provider to log in
+/// with, if there are multiple.
+#[derive(Debug, Template)]
+#[template(path = "authentication/choose_provider.html")]
+pub struct ProviderChooser {
+    /// The identity providers that the user can choose from.
+    pub providers: Vec<ProviderOption>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderOption {
+    /// A human readable name for the provider.
+    pub display_name: String,
+
+    /// The route that initiates a login with the provider.
+    pub login_route: String,
+}
@@ -9,6 +9,9 @@ impl From<AuthenticationServiceError> for RenderError {
             | AuthenticationServiceError::Validation { message } => {
                 Self::AuthenticationFlow { message }
             }
+            AuthenticationServiceError::UnknownProvider { name } => Self::ResourceNotFound {
+                resource_name: format!("identity provider: {name}"),
+            },
         }
     }
 }
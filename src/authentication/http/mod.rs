@@ -2,8 +2,14 @@ mod error;
 mod extractors;
 mod route_handlers;
 mod router;
+mod session;
 mod state;
 
 pub use router::CALLBACK;
 pub use router::LOGIN;
+pub use router::LOGOUT;
+pub use router::PROVIDER_CALLBACK;
+pub use router::PROVIDER_LOGIN;
 pub use router::build_router;
+pub use session::SESSION_VALIDITY_KEY;
+pub(crate) use session::persist_authenticated_user;
@@ -1,23 +1,70 @@
+use std::net::SocketAddr;
+
+use askama::Template;
+use axum::Extension;
+use axum::Form;
+use axum::extract::ConnectInfo;
+use axum::extract::OriginalUri;
+use axum::extract::Path;
 use axum::extract::Query;
+use axum::response::Html;
+use axum::response::IntoResponse;
 use axum::response::Redirect;
+use axum::response::Response;
+use http::HeaderMap;
+use http::StatusCode;
+use openidconnect::CsrfToken;
+use percent_encoding::NON_ALPHANUMERIC;
+use percent_encoding::utf8_percent_encode;
 use tower_sessions::Session;
 use web_route::WebRoute;
 
+use super::super::application::error::AuthenticationServiceError;
 use super::super::application::service::AuthenticationService;
 use super::super::domain::AuthorizeData;
 use super::super::domain::CallbackRequestParams;
+use super::super::domain::LoginMethod;
 use super::super::domain::OidcSessionPersisted;
+use super::super::domain::PasswordCredentials;
+use super::super::domain::RawIdToken;
+use super::super::domain::login_form_render::LoginForm;
+use super::super::domain::logout_form_render::LogoutForm;
+use super::super::domain::provider_chooser_render::ProviderChooser;
+use super::super::domain::provider_chooser_render::ProviderOption;
+use super::router::ProviderPathParams;
+use super::session::persist_authenticated_user;
 use crate::common::utils::http::render_error::RenderError;
+use crate::user::domain::DEFAULT_PROVIDER;
+use crate::user::domain::ProviderName;
+use crate::user::domain::User;
 use crate::user::http::extractors::USER_SESSION_KEY;
 
 /// The key to which login data will be stored in a session.
 pub const LOGIN_SESSION_KEY: &str = "login_session";
 
+/// The key to which the CSRF token of the login form will be stored in a
+/// session.
+pub const LOGIN_FORM_CSRF_SESSION_KEY: &str = "login_form_csrf";
+
+/// The key to which the CSRF token of the logout form is stored in a session.
+pub const LOGOUT_FORM_CSRF_SESSION_KEY: &str = "logout_form_csrf";
+
+/// The key to which the logged in user's ID token will be stored in a session.
+pub const ID_TOKEN_SESSION_KEY: &str = "id_token_session";
+
+/// The path to which the user agent is redirected after logging out, if the IdP
+/// does not need to be involved.
+const POST_LOGOUT_PATH: &str = "/";
+
 /// The data to be persisted between the [`login`] and [`callback`] routes.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub(crate) struct LoginSessionData {
     oidc_persisted_data: OidcSessionPersisted,
     next_path: WebRoute,
+
+    /// The identity provider with which the login was initiated. The callback
+    /// must come from the same provider.
+    provider: ProviderName,
 }
 
 #[derive(serde::Deserialize)]
@@ -27,9 +74,262 @@ pub(crate) struct LoginParams {
     next_path: WebRoute,
 }
 
-/// Initiates an OIDC login flow with the configured IdP.
+/// The data submitted with the login form of a local user account provider.
+#[derive(serde::Deserialize)]
+pub(crate) struct LoginFormData {
+    username: String,
+    password: String,
+    csrf_token: String,
+    next_path: WebRoute,
+}
+
+/// The data submitted with the logout form.
+#[derive(serde::Deserialize)]
+pub(crate) struct LogoutFormData {
+    csrf_token: String,
+}
+
+/// Initiates a login with the configured IdP. If there are multiple IdPs, the
+/// user is asked to choose one first.
 pub(crate) async fn login(
     Query(LoginParams { next_path }): Query<LoginParams>,
+    OriginalUri(page_uri): OriginalUri,
+    session: Session,
+    authentication_service: AuthenticationService,
+) -> Result<Response, RenderError> {
+    // The provider login routes are relative to this route.
+    let login_route = page_uri.path().trim_end_matches('/');
+
+    let providers = authentication_service.providers();
+    if let [provider] = providers {
+        let provider = provider.name.clone();
+        let provider_login_route = format!(
+            "{login_route}/{}",
+            utf8_percent_encode(&provider, NON_ALPHANUMERIC)
+        );
+        return start_login(
+            provider,
+            provider_login_route,
+            next_path,
+            session,
+            authentication_service,
+        )
+        .await;
+    }
+
+    let next_path = utf8_percent_encode(&next_path, NON_ALPHANUMERIC);
+    let provider_chooser = ProviderChooser {
+        providers: providers
+            .iter()
+            .map(|provider| ProviderOption {
+                display_name: provider.display_name.clone(),
+                login_route: format!(
+                    "{login_route}/{}?next_path={next_path}",
+                    utf8_percent_encode(&provider.name, NON_ALPHANUMERIC)
+                ),
+            })
+            .collect(),
+    };
+
+    Ok(Html(provider_chooser.render()?).into_response())
+}
+
+/// Initiates a login with the chosen IdP.
+pub(crate) async fn provider_login(
+    Path(ProviderPathParams { provider }): Path<ProviderPathParams>,
+    Query(LoginParams { next_path }): Query<LoginParams>,
+    OriginalUri(page_uri): OriginalUri,
+    session: Session,
+    authentication_service: AuthenticationService,
+) -> Result<Response, RenderError> {
+    start_login(
+        provider,
+        page_uri.path().to_owned(),
+        next_path,
+        session,
+        authentication_service,
+    )
+    .await
+}
+
+/// Initiates a login with the `provider`: either by redirecting the user agent
+/// to it, or by showing the login form (which is submitted to the
+/// `provider_login_route`).
+async fn start_login(
+    provider: ProviderName,
+    provider_login_route: String,
+    next_path: WebRoute,
+    session: Session,
+    authentication_service: AuthenticationService,
+) -> Result<Response, RenderError> {
+    Ok(match authentication_service.login_method(&provider)? {
+        LoginMethod::Redirect => {
+            start_redirect_login(provider, next_path, session, authentication_service)
+                .await?
+                .into_response()
+        }
+        LoginMethod::Form => login_form_page(
+            &session,
+            provider_login_route,
+            &next_path,
+            String::new(),
+            None,
+        )
+        .await?
+        .into_response(),
+    })
+}
+
+/// Logs a user in with the username and password submitted with the login
+/// form of the `provider`.
+///
+/// If the credentials are rejected, the form is shown again with the reason.
+pub(crate) async fn submit_login_form(
+    Path(ProviderPathParams { provider }): Path<ProviderPathParams>,
+    OriginalUri(page_uri): OriginalUri,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    session: Session,
+    authentication_service: AuthenticationService,
+    Form(LoginFormData {
+        username,
+        password,
+        csrf_token,
+        next_path,
+    }): Form<LoginFormData>,
+) -> Result<Response, RenderError> {
+    // The form must have been rendered for this session.
+    let session_csrf_token = session
+        .get::<String>(LOGIN_FORM_CSRF_SESSION_KEY)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to retrieve the login form data".to_owned(),
+        })?;
+    if session_csrf_token.as_deref() != Some(csrf_token.as_str()) {
+        let login_form = login_form_page(
+            &session,
+            page_uri.path().to_owned(),
+            &next_path,
+            username,
+            Some("The login form has expired, please try again.".to_owned()),
+        )
+        .await?;
+
+        return Ok((StatusCode::FORBIDDEN, login_form).into_response());
+    }
+
+    let source = connect_info.map(|Extension(ConnectInfo(address))| address.ip());
+    let credentials = PasswordCredentials { username, password };
+    let callback_data = match authentication_service
+        .authenticate_password(&provider, &credentials, source, &headers)
+        .await
+    {
+        Ok(callback_data) => callback_data,
+        Err(AuthenticationServiceError::Validation { message }) => {
+            let login_form = login_form_page(
+                &session,
+                page_uri.path().to_owned(),
+                &next_path,
+                credentials.username,
+                Some(message),
+            )
+            .await?;
+
+            return Ok((StatusCode::UNAUTHORIZED, login_form).into_response());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // A new session is started for the logged in user (preventing session
+    // fixation).
+    session.clear().await;
+    session
+        .cycle_id()
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to reset session".to_owned(),
+        })?;
+
+    persist_authenticated_user(&session, callback_data)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to persist user data in the session".to_owned(),
+        })?;
+
+    Ok(Redirect::to(&next_path).into_response())
+}
+
+/// Renders the login form, which is submitted to the `form_action` route.
+///
+/// The form carries the CSRF token of the session, which is created if there
+/// isn't one yet. It is reused so that the form can be open in multiple tabs.
+async fn login_form_page(
+    session: &Session,
+    form_action: String,
+    next_path: &WebRoute,
+    username: String,
+    error: Option<String>,
+) -> Result<Html<String>, RenderError> {
+    let csrf_token = form_csrf_token(session, LOGIN_FORM_CSRF_SESSION_KEY)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to persist the login form data".to_owned(),
+        })?;
+
+    let login_form = LoginForm {
+        form_action,
+        next_path: next_path.as_ref().to_owned(),
+        csrf_token,
+        username,
+        error,
+    };
+
+    Ok(Html(login_form.render()?))
+}
+
+/// Renders the logout form (submitted to `form_action`), showing the `error`
+/// of a previous attempt if there was one.
+async fn logout_form_page(
+    session: &Session,
+    form_action: String,
+    error: Option<String>,
+) -> Result<Html<String>, RenderError> {
+    let csrf_token = form_csrf_token(session, LOGOUT_FORM_CSRF_SESSION_KEY)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to persist the logout form data".to_owned(),
+        })?;
+
+    let logout_form = LogoutForm {
+        form_action,
+        csrf_token,
+        error,
+    };
+
+    Ok(Html(logout_form.render()?))
+}
+
+/// The CSRF token of a form, stored in the session at `key`. A new one is
+/// generated if the session does not have one yet.
+async fn form_csrf_token(
+    session: &Session,
+    key: &str,
+) -> Result<String, tower_sessions::session::Error> {
+    if let Some(csrf_token) = session.get::<String>(key).await? {
+        return Ok(csrf_token);
+    }
+
+    let csrf_token = CsrfToken::new_random().secret().clone();
+    session.insert(key, &csrf_token).await?;
+
+    Ok(csrf_token)
+}
+
+/// Initiates an OIDC login flow with the `provider`, redirecting the user agent
+/// to it.
+async fn start_redirect_login(
+    provider: ProviderName,
+    next_path: WebRoute,
     session: Session,
     authentication_service: AuthenticationService,
 ) -> Result<Redirect, RenderError> {
@@ -46,7 +346,7 @@ pub(crate) async fn login(
     let AuthorizeData {
         auth_url,
         persisted_data: oidc_persisted_data,
-    } = authentication_service.authorize().await?;
+    } = authentication_service.authorize(&provider).await?;
 
     // Persist the required data so that it is available for the `callback` route to
     // complete the login.
@@ -56,6 +356,7 @@ pub(crate) async fn login(
             LoginSessionData {
                 oidc_persisted_data,
                 next_path,
+                provider,
             },
         )
         .await
@@ -66,16 +367,40 @@ pub(crate) async fn login(
     Ok(Redirect::to(auth_url.as_str()))
 }
 
-/// Initiates an OIDC login flow with the configured IdP.
+/// Finalizes an OIDC login flow with the IdP configured with the `IDP_*`
+/// options.
 pub(crate) async fn callback(
     Query(callback_params): Query<CallbackRequestParams>,
     session: Session,
     authentication_service: AuthenticationService,
+) -> Result<Redirect, RenderError> {
+    let provider = ProviderName::new(DEFAULT_PROVIDER.to_owned());
+
+    finish_login(provider, callback_params, session, authentication_service).await
+}
+
+/// Finalizes an OIDC login flow with one of multiple IdPs.
+pub(crate) async fn provider_callback(
+    Path(ProviderPathParams { provider }): Path<ProviderPathParams>,
+    Query(callback_params): Query<CallbackRequestParams>,
+    session: Session,
+    authentication_service: AuthenticationService,
+) -> Result<Redirect, RenderError> {
+    finish_login(provider, callback_params, session, authentication_service).await
+}
+
+/// Finalizes an OIDC login flow with the `provider`, logging the user in.
+async fn finish_login(
+    provider: ProviderName,
+    callback_params: CallbackRequestParams,
+    session: Session,
+    authentication_service: AuthenticationService,
 ) -> Result<Redirect, RenderError> {
     // Retrieve data that was persisted in the `login` route.
     let LoginSessionData {
         oidc_persisted_data,
         next_path,
+        provider: login_provider,
     } = session
         .get::<LoginSessionData>(LOGIN_SESSION_KEY)
         .await
@@ -86,17 +411,27 @@ pub(crate) async fn callback(
             message: "no matching session data".to_owned(),
         })?;
 
+    // Prevents a response from one IdP being used to complete the login with
+    // another (i.e. an IdP mix-up).
+    if provider != login_provider {
+        return Err(RenderError::AuthenticationFlow {
+            message: format!(
+                "login was initiated with `{login_provider}`, but the callback came from \
+                 `{provider}`"
+            ),
+        });
+    }
+
     // Get authenticated user.
-    let user = authentication_service
-        .callback(callback_params, oidc_persisted_data)
+    let callback_data = authentication_service
+        .callback(&provider, callback_params, oidc_persisted_data)
         .await?;
 
     // Clear the existing OIDC data from the session
     session.clear().await;
 
     // Insert the user into the session to log them in.
-    session
-        .insert(USER_SESSION_KEY, user)
+    persist_authenticated_user(&session, callback_data)
         .await
         .map_err(|_e| RenderError::AuthenticationFlow {
             message: "unable to persist user data in the session".to_owned(),
@@ -104,3 +439,70 @@ pub(crate) async fn callback(
 
     Ok(Redirect::to(&next_path))
 }
+
+/// Asks the user to confirm that they want to log out. Logging out changes
+/// state, so it is only done by submitting the form (see [`logout`]).
+pub(crate) async fn logout_form(
+    OriginalUri(page_uri): OriginalUri,
+    session: Session,
+) -> Result<Html<String>, RenderError> {
+    logout_form_page(&session, page_uri.path().to_owned(), None).await
+}
+
+/// Logs the user out of the application when the logout form is submitted. If
+/// the IdP supports it, the user agent is redirected to the IdP to end the IdP
+/// session as well.
+///
+/// The form must carry the CSRF token of the session, so that third-party pages
+/// can't log users out.
+pub(crate) async fn logout(
+    OriginalUri(page_uri): OriginalUri,
+    session: Session,
+    authentication_service: AuthenticationService,
+    Form(LogoutFormData { csrf_token }): Form<LogoutFormData>,
+) -> Result<Response, RenderError> {
+    // The form must have been rendered for this session.
+    let session_csrf_token = session
+        .get::<String>(LOGOUT_FORM_CSRF_SESSION_KEY)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to retrieve the logout form data".to_owned(),
+        })?;
+    if session_csrf_token.as_deref() != Some(csrf_token.as_str()) {
+        let logout_form = logout_form_page(
+            &session,
+            page_uri.path().to_owned(),
+            Some("The logout form has expired, please try again.".to_owned()),
+        )
+        .await?;
+
+        return Ok((StatusCode::FORBIDDEN, logout_form).into_response());
+    }
+
+    let id_token = session
+        .get::<RawIdToken>(ID_TOKEN_SESSION_KEY)
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to retrieve id token from the session".to_owned(),
+        })?;
+    // The user is logged out of the IdP that authenticated them.
+    let user = session.get::<User>(USER_SESSION_KEY).await.ok().flatten();
+
+    // Remove the user (and everything else) from the session.
+    session
+        .flush()
+        .await
+        .map_err(|_e| RenderError::AuthenticationFlow {
+            message: "unable to clear the session".to_owned(),
+        })?;
+
+    let end_session_url = authentication_service
+        .logout(user.as_ref(), id_token)
+        .await?;
+
+    Ok(match end_session_url {
+        Some(end_session_url) => Redirect::to(end_session_url.as_str()),
+        None => Redirect::to(POST_LOGOUT_PATH),
+    }
+    .into_response())
+}
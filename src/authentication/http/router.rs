@@ -3,17 +3,33 @@ use std::sync::LazyLock;
 use axum::Extension;
 use axum::Router;
 use axum::routing::get;
+use web_route::ParameterizedRoute;
 use web_route::WebRoute;
 
 use super::super::application::service::AuthenticationService;
 use super::route_handlers;
 use super::state::State;
+use crate::user::domain::ProviderName;
 
 pub static LOGIN: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/login"));
 pub static CALLBACK: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/callback"));
+pub static LOGOUT: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/logout"));
+pub static PROVIDER_LOGIN: LazyLock<ParameterizedRoute> =
+    LazyLock::new(|| ParameterizedRoute::new("/login/{provider}"));
+pub static PROVIDER_CALLBACK: LazyLock<ParameterizedRoute> =
+    LazyLock::new(|| ParameterizedRoute::new("/callback/{provider}"));
+
+#[derive(serde::Deserialize)]
+pub(crate) struct ProviderPathParams {
+    pub provider: ProviderName,
+}
 
 /// Builds a router that performs OIDC authentication.
 ///
+/// If there are multiple identity providers, the user first chooses one on the
+/// login route. Local user accounts log in with a login form, which is posted
+/// to the provider's login route.
+///
 /// **Note:** There should be an active [`tower_sessions`] middleware available
 /// in the router's middleware stack.
 pub fn build_router(authentication_service: AuthenticationService) -> Router {
@@ -23,6 +39,15 @@ pub fn build_router(authentication_service: AuthenticationService) -> Router {
 
     Router::new()
         .route(&LOGIN, get(route_handlers::login))
+        .route(
+            &PROVIDER_LOGIN,
+            get(route_handlers::provider_login).post(route_handlers::submit_login_form),
+        )
         .route(&CALLBACK, get(route_handlers::callback))
+        .route(&PROVIDER_CALLBACK, get(route_handlers::provider_callback))
+        .route(
+            &LOGOUT,
+            get(route_handlers::logout_form).post(route_handlers::logout),
+        )
         .layer(Extension(state))
 }
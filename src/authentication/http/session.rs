@@ -0,0 +1,41 @@
+use time::OffsetDateTime;
+use tower_sessions::Session;
+
+use super::super::domain::CallbackData;
+use super::super::domain::SessionValidity;
+use super::route_handlers::ID_TOKEN_SESSION_KEY;
+use crate::user::http::extractors::USER_SESSION_KEY;
+
+/// The key to which the [`SessionValidity`] of the logged in user will be
+/// stored in a session.
+pub const SESSION_VALIDITY_KEY: &str = "session_validity";
+
+/// Persists a user that was just authenticated (or re-validated) by the IdP in
+/// the web `session`, logging them in.
+pub(crate) async fn persist_authenticated_user(
+    session: &Session,
+    callback_data: CallbackData,
+) -> Result<(), tower_sessions::session::Error> {
+    let CallbackData {
+        user,
+        id_token,
+        refresh_token,
+    } = callback_data;
+
+    session.insert(USER_SESSION_KEY, user).await?;
+
+    // Keep the ID token so that it can be used as a hint when logging out.
+    if let Some(id_token) = id_token {
+        session.insert(ID_TOKEN_SESSION_KEY, id_token).await?;
+    }
+
+    session
+        .insert(
+            SESSION_VALIDITY_KEY,
+            SessionValidity {
+                validated_at: OffsetDateTime::now_utc(),
+                refresh_token,
+            },
+        )
+        .await
+}
@@ -1,16 +1,27 @@
 use std::fmt::Debug;
+use std::net::IpAddr;
 
 use async_trait::async_trait;
+use http::HeaderMap;
+use time::OffsetDateTime;
+use url::Url;
 
 use super::super::domain::AuthorizeData;
+use super::super::domain::BearerToken;
+use super::super::domain::CallbackData;
 use super::super::domain::CallbackRequestParams;
+use super::super::domain::IdpStatus;
+use super::super::domain::LoginMethod;
 use super::super::domain::OidcSessionPersisted;
+use super::super::domain::PasswordCredentials;
+use super::super::domain::RawIdToken;
+use super::super::domain::SessionValidity;
 use crate::user::domain::User;
 
 /// Defines the functionality that needs to be implemented for the application
 /// to perform OIDC authentication.
-#[async_trait]
 #[cfg_attr(test, mockall::automock)]
+#[async_trait]
 pub trait AuthenticationEngine: Debug + Send + Sync + 'static {
     /// Called as part of the OIDC [`/authorize`] endpoint.
     ///
@@ -29,7 +40,94 @@ pub trait AuthenticationEngine: Debug + Send + Sync + 'static {
         &self,
         callback_params: CallbackRequestParams,
         persisted_data: OidcSessionPersisted,
+    ) -> Result<CallbackData, AuthenticationEngineError>;
+
+    /// Called as part of logging a user out of the application.
+    ///
+    /// If the IdP supports [RP-Initiated Logout], returns the url to which the
+    /// user agent should be redirected to end the session at the IdP. The
+    /// `id_token` (if available) is passed to the IdP as the `id_token_hint`.
+    ///
+    /// [RP-Initiated Logout]: https://openid.net/specs/openid-connect-rpinitiated-1_0.html
+    async fn logout(
+        &self,
+        id_token: Option<RawIdToken>,
+    ) -> Result<Option<Url>, AuthenticationEngineError>;
+
+    /// Validates an access token presented as an `Authorization: Bearer`
+    /// header (e.g. by a script), returning the [`User`] it represents.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationEngineError::Validation`] is returned if the token is
+    ///   invalid (e.g. bad signature, wrong issuer or audience, expired).
+    async fn authenticate_bearer(
+        &self,
+        token: BearerToken,
     ) -> Result<User, AuthenticationEngineError>;
+
+    /// Extracts the [`User`] from the identity `headers` set by an upstream
+    /// authenticating proxy (e.g. oauth2-proxy). Only requests from a trusted
+    /// `source` address are considered.
+    ///
+    /// Returns `None` if the engine does not trust proxy headers, the `source`
+    /// is not trusted, or there are no identity headers.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationEngineError::Validation`] is returned if the identity
+    ///   headers of a trusted request are incomplete or malformed.
+    async fn authenticate_proxy_headers(
+        &self,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Result<Option<User>, AuthenticationEngineError>;
+
+    /// Re-validates the `user` of a web session with the IdP (e.g. with a
+    /// refresh token), once the `validity` of the session is older than the
+    /// engine's maximum session age.
+    ///
+    /// Returns `None` if the session does not need to be re-validated (yet).
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationEngineError::Validation`] means that the session is no
+    ///   longer valid (e.g. the IdP rejected the refresh token), and that the
+    ///   user should be logged out.
+    /// - Any other error means that the session could not be re-validated for
+    ///   now (e.g. the IdP is unavailable), and should be retried later.
+    async fn revalidate(
+        &self,
+        user: &User,
+        validity: &SessionValidity,
+        now: OffsetDateTime,
+    ) -> Result<Option<CallbackData>, AuthenticationEngineError>;
+
+    /// How users log in with the engine: by being redirected to the IdP (see
+    /// [`Self::authorize`]) or with a login form (see
+    /// [`Self::authenticate_password`]).
+    fn login_method(&self) -> LoginMethod;
+
+    /// Authenticates the username and password entered by a user in the login
+    /// form, returning the [`User`] they belong to. The `source` address and
+    /// `headers` of the request identify the client (e.g. behind a reverse
+    /// proxy) to throttle repeated failed attempts.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationEngineError::Validation`] is returned if the
+    ///   credentials are invalid, or there have been too many failed attempts.
+    async fn authenticate_password(
+        &self,
+        credentials: &PasswordCredentials,
+        source: Option<IpAddr>,
+        headers: &HeaderMap,
+        now: OffsetDateTime,
+    ) -> Result<User, AuthenticationEngineError>;
+
+    /// Whether users can currently authenticate with the engine's IdP (e.g.
+    /// if its metadata could be discovered).
+    fn status(&self) -> IdpStatus;
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -0,0 +1,593 @@
+//! Records the decisions of another authorization engine in an audit log. It
+//! implements the [`AuthorizationEngine`] trait.
+//!
+//! Viewing a project loads many of its assets (e.g. every octree chunk of a
+//! point cloud), each of which is authorized. So only the first decision on
+//! each type of resource within a project is recorded per _project session_:
+//! the subsequent decisions with the same principal, project, resource type
+//! and outcome are skipped, until none were made for the session timeout.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+use super::super::domain::action::Action;
+use super::super::domain::error::AuthorizationEngineError;
+use super::super::domain::explanation::Explanation;
+use super::super::domain::resource::Resource;
+use super::super::domain::resource::ResourceInstance;
+use super::super::ports::authorization_engine::AuthorizationEngine;
+use crate::audit::domain::AuditEvent;
+use crate::audit::domain::AuditEventKind;
+use crate::audit::domain::AuditOutcome;
+use crate::audit::ports::audit_log::AuditLog;
+use crate::common::domain::utils::clock::Clock;
+use crate::common::domain::utils::clock::SystemClock;
+use crate::share_link::domain::ShareLink;
+use crate::user::domain::User;
+
+#[derive(Debug)]
+pub struct AuditedAuthorizationEngine {
+    /// Makes the actual decisions.
+    authorization_engine: Arc<dyn AuthorizationEngine>,
+
+    /// Where the decisions are recorded.
+    audit_log: Arc<dyn AuditLog>,
+
+    /// How long a project session lasts without any decisions on the
+    /// project's resources.
+    session_timeout: Duration,
+
+    /// Used to timestamp the events and to time out project sessions.
+    clock: Arc<dyn Clock>,
+
+    /// The project sessions that had a decision recently.
+    project_sessions: Mutex<ProjectSessions>,
+}
+
+/// When each project session last had a decision.
+#[derive(Debug)]
+struct ProjectSessions {
+    last_active_at: HashMap<ProjectSession, OffsetDateTime>,
+
+    /// When the sessions that timed out were last forgotten.
+    last_pruned_at: OffsetDateTime,
+}
+
+/// Identifies a project session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ProjectSession {
+    /// The user id, share link or anonymous visitor making the requests.
+    principal: String,
+    project_id: String,
+    resource_type: String,
+    outcome: AuditOutcome,
+}
+
+/// Who a decision was made for.
+#[derive(Debug, Clone, Copy)]
+enum Principal<'a> {
+    User(Option<&'a User>),
+    ShareLink(&'a ShareLink),
+}
+
+impl AuditedAuthorizationEngine {
+    pub fn new(
+        authorization_engine: Arc<dyn AuthorizationEngine>,
+        audit_log: Arc<dyn AuditLog>,
+        session_timeout: Duration,
+    ) -> Self {
+        Self {
+            authorization_engine,
+            audit_log,
+            session_timeout,
+            clock: Arc::new(SystemClock),
+            project_sessions: Mutex::new(ProjectSessions {
+                last_active_at: HashMap::new(),
+                last_pruned_at: OffsetDateTime::UNIX_EPOCH,
+            }),
+        }
+    }
+
+    /// Replaces the clock used to timestamp events (e.g. for testing).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn can_on_type(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn Resource,
+    ) -> Result<(), AuthorizationEngineError> {
+        let result = self
+            .authorization_engine
+            .can_on_type(user, action, resource);
+        self.record(
+            Principal::User(user.as_ref()),
+            action,
+            resource,
+            None,
+            &result,
+        );
+
+        result
+    }
+
+    pub fn can_on_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        let result = self
+            .authorization_engine
+            .can_on_instance(user, action, resource);
+        self.record(
+            Principal::User(user.as_ref()),
+            action,
+            resource,
+            Some(resource),
+            &result,
+        );
+
+        result
+    }
+
+    /// Explanations are not recorded, as they aren't requests for the
+    /// resource.
+    pub fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        self.authorization_engine.explain(user, action, resource)
+    }
+
+    pub fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        let result = self
+            .authorization_engine
+            .can_on_instance_with_share_link(share_link, action, resource);
+        self.record(
+            Principal::ShareLink(share_link),
+            action,
+            resource,
+            Some(resource),
+            &result,
+        );
+
+        result
+    }
+
+    /// Records the `result` of a decision, unless it continues a project
+    /// session. Failing to record it is logged rather than failing the
+    /// request.
+    fn record(
+        &self,
+        principal: Principal,
+        action: &Action,
+        resource: &dyn Resource,
+        instance: Option<&dyn ResourceInstance>,
+        result: &Result<(), AuthorizationEngineError>,
+    ) {
+        let now = self.clock.now();
+        let resource_type = resource.resource_type();
+        let project_id = instance.and_then(|instance| instance.project_id());
+        let outcome = match result {
+            Ok(()) => AuditOutcome::Allowed,
+            Err(_) => AuditOutcome::Denied,
+        };
+
+        if let Some(project_id) = &project_id
+            && self.continues_project_session(
+                ProjectSession {
+                    principal: principal.key(),
+                    project_id: project_id.to_string(),
+                    resource_type: resource_type.to_string(),
+                    outcome,
+                },
+                now,
+            )
+        {
+            return;
+        }
+
+        let user = match principal {
+            Principal::User(user) => user,
+            Principal::ShareLink(_) => None,
+        };
+        let event = AuditEvent {
+            share_link: matches!(principal, Principal::ShareLink(_)),
+            action: Some(action.clone()),
+            resource_type: Some(resource_type),
+            resource_identifier: instance.map(|instance| instance.resource_identifier()),
+            project_id,
+            reason: result
+                .as_ref()
+                .err()
+                .map(|error| error.reason_code().to_owned()),
+            ..AuditEvent::for_user(now, AuditEventKind::Authorization, user, outcome)
+        };
+
+        if let Err(e) = self.audit_log.record(&event) {
+            tracing::error!(error = %e, "unable to record the authorization decision");
+        }
+    }
+
+    /// Whether a decision for the `project_session` at `now` continues it
+    /// (i.e. there was a decision for it within the session timeout). Either
+    /// way, the session is extended.
+    fn continues_project_session(
+        &self,
+        project_session: ProjectSession,
+        now: OffsetDateTime,
+    ) -> bool {
+        let mut project_sessions = self
+            .project_sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let continues = project_sessions
+            .last_active_at
+            .insert(project_session, now)
+            .is_some_and(|last_active_at| now - last_active_at < self.session_timeout);
+
+        // Forget the sessions that timed out, so that the map doesn't grow
+        // indefinitely. Scanning the map is only worth it once they could have.
+        if now - project_sessions.last_pruned_at >= self.session_timeout {
+            project_sessions
+                .last_active_at
+                .retain(|_, last_active_at| now - *last_active_at < self.session_timeout);
+            project_sessions.last_pruned_at = now;
+        }
+
+        continues
+    }
+}
+
+impl Principal<'_> {
+    /// Identifies the principal within a [`ProjectSession`].
+    fn key(&self) -> String {
+        match self {
+            Principal::User(Some(user)) => format!("user:{}", user.id),
+            Principal::User(None) => "anonymous".to_owned(),
+            Principal::ShareLink(share_link) => format!(
+                "share-link:{}:{}:{}",
+                share_link.project_id,
+                share_link
+                    .path_prefix
+                    .as_deref()
+                    .map(Path::to_string_lossy)
+                    .unwrap_or_default(),
+                share_link.expires_at.unix_timestamp()
+            ),
+        }
+    }
+}
+
+impl AuthorizationEngine for AuditedAuthorizationEngine {
+    fn can_on_type(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn Resource,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_type(self, user, action, resource)
+    }
+
+    fn can_on_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance(self, user, action, resource)
+    }
+
+    fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance_with_share_link(self, share_link, action, resource)
+    }
+
+    fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        Self::explain(self, user, action, resource)
+    }
+}
+
+#[cfg(test)]
+mod audited_authorization_engine_tests {
+    use fake::Fake;
+    use fake::Faker;
+    use time::macros::datetime;
+
+    use super::super::super::domain::resource::ResourceType;
+    use super::super::super::domain::resource::mocked_resource::MockedResource;
+    use super::super::super::ports::authorization_engine::MockAuthorizationEngine;
+    use super::*;
+    use crate::audit::ports::audit_log::MockAuditLog;
+    use crate::common::domain::resource_type;
+    use crate::common::domain::utils::clock::FixedClock;
+    use crate::project::domain::ProjectId;
+
+    const NOW: OffsetDateTime = datetime!(2026-03-01 12:00 UTC);
+
+    fn engine(
+        authorization_engine: MockAuthorizationEngine,
+        audit_log: MockAuditLog,
+        session_timeout: Duration,
+    ) -> AuditedAuthorizationEngine {
+        AuditedAuthorizationEngine::new(
+            Arc::new(authorization_engine),
+            Arc::new(audit_log),
+            session_timeout,
+        )
+        .with_clock(Arc::new(FixedClock(NOW)))
+    }
+
+    fn asset(project_id: &str) -> MockedResource {
+        MockedResource {
+            resource_type: ResourceType::new(resource_type::PROJECT_ASSET.to_owned()),
+            project_id: Some(ProjectId::new(project_id.to_owned())),
+            ..Faker.fake()
+        }
+    }
+
+    mod can_on_instance {
+        use super::*;
+
+        #[test]
+        fn should_record_the_decision() {
+            // Arrange
+            let user = Faker.fake::<User>();
+            let resource = MockedResource {
+                resource_type: ResourceType::new(resource_type::POTREE_RENDER.to_owned()),
+                project_id: Some(ProjectId::new("survey".to_owned())),
+                ..Faker.fake()
+            };
+
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .return_once(|_, _, _| Ok(()));
+
+            let mut audit_log = MockAuditLog::new();
+            let expected_user_id = user.id.clone();
+            let expected_identifier = resource.resource_identifier.clone();
+            audit_log
+                .expect_record()
+                .withf(move |event| {
+                    event.timestamp == NOW
+                        && event.event == AuditEventKind::Authorization
+                        && event.user_id.as_ref() == Some(&expected_user_id)
+                        && event.action == Some(Action::Read)
+                        && event.resource_identifier.as_ref() == Some(&expected_identifier)
+                        && event
+                            .project_id
+                            .as_ref()
+                            .is_some_and(|id| id.as_str() == "survey")
+                        && event.outcome == AuditOutcome::Allowed
+                        && event.reason.is_none()
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            // Act
+            let res = engine(authorization_engine, audit_log, Duration::from_secs(60))
+                .can_on_instance(&Some(user), &Action::Read, &resource);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn should_record_why_the_request_was_denied() {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .return_once(|_, _, _| Err(AuthorizationEngineError::NotAuthenticated));
+
+            let mut audit_log = MockAuditLog::new();
+            audit_log
+                .expect_record()
+                .withf(|event| {
+                    event.user_id.is_none()
+                        && event.outcome == AuditOutcome::Denied
+                        && event.reason.as_deref() == Some("not_authenticated")
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            // Act
+            let res = engine(authorization_engine, audit_log, Duration::from_secs(60))
+                .can_on_instance(&None, &Action::Read, &asset("survey"));
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ));
+        }
+
+        #[test]
+        fn should_record_one_event_per_project_session() {
+            // Arrange
+            let user = Faker.fake::<User>();
+
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .returning(|_, _, _| Ok(()));
+
+            let mut audit_log = MockAuditLog::new();
+            audit_log
+                .expect_record()
+                .withf(|event| {
+                    event
+                        .project_id
+                        .as_ref()
+                        .is_some_and(|id| id.as_str() == "survey")
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+            audit_log
+                .expect_record()
+                .withf(|event| {
+                    event
+                        .project_id
+                        .as_ref()
+                        .is_some_and(|id| id.as_str() == "other")
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let engine = engine(authorization_engine, audit_log, Duration::from_secs(60));
+
+            // Act
+            for project_id in ["survey", "survey", "other", "survey"] {
+                engine
+                    .can_on_instance(&Some(user.clone()), &Action::Read, &asset(project_id))
+                    .unwrap();
+            }
+
+            // Assert
+            // The expectations of the mocks are verified when they are dropped.
+        }
+
+        #[test]
+        fn should_record_a_new_project_session_once_timed_out() {
+            // Arrange
+            let user = Faker.fake::<User>();
+
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .returning(|_, _, _| Ok(()));
+
+            let mut audit_log = MockAuditLog::new();
+            audit_log.expect_record().times(2).returning(|_| Ok(()));
+
+            let engine = engine(authorization_engine, audit_log, Duration::ZERO);
+
+            // Act
+            for _ in 0..2 {
+                engine
+                    .can_on_instance(&Some(user.clone()), &Action::Read, &asset("survey"))
+                    .unwrap();
+            }
+
+            // Assert
+            // The expectations of the mocks are verified when they are dropped.
+        }
+    }
+
+    mod continues_project_session {
+        use super::*;
+
+        fn project_session(project_id: &str) -> ProjectSession {
+            ProjectSession {
+                principal: "user-id".to_owned(),
+                project_id: project_id.to_owned(),
+                resource_type: resource_type::PROJECT_ASSET.to_owned(),
+                outcome: AuditOutcome::Allowed,
+            }
+        }
+
+        #[test]
+        fn should_continue_a_session_within_the_timeout() {
+            // Arrange
+            let engine = engine(
+                MockAuthorizationEngine::new(),
+                MockAuditLog::new(),
+                Duration::from_secs(60),
+            );
+            engine.continues_project_session(project_session("survey"), NOW);
+
+            // Act
+            let continues = engine.continues_project_session(
+                project_session("survey"),
+                NOW + time::Duration::seconds(30),
+            );
+
+            // Assert
+            assert!(continues);
+        }
+
+        #[test]
+        fn should_forget_the_sessions_that_timed_out() {
+            // Arrange
+            let engine = engine(
+                MockAuthorizationEngine::new(),
+                MockAuditLog::new(),
+                Duration::from_secs(60),
+            );
+            engine.continues_project_session(project_session("survey"), NOW);
+
+            // Act
+            let continues = engine.continues_project_session(
+                project_session("other"),
+                NOW + time::Duration::minutes(2),
+            );
+
+            // Assert
+            assert!(!continues);
+            let project_sessions = engine.project_sessions.lock().unwrap();
+            assert_eq!(
+                project_sessions.last_active_at.keys().collect::<Vec<_>>(),
+                vec![&project_session("other")]
+            );
+        }
+    }
+
+    mod can_on_instance_with_share_link {
+        use super::*;
+
+        #[test]
+        fn should_record_that_a_share_link_was_used() {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance_with_share_link()
+                .return_once(|_, _, _| Ok(()));
+
+            let mut audit_log = MockAuditLog::new();
+            audit_log
+                .expect_record()
+                .withf(|event| event.share_link && event.user_id.is_none())
+                .times(1)
+                .returning(|_| Ok(()));
+
+            // Act
+            let res = engine(authorization_engine, audit_log, Duration::from_secs(60))
+                .can_on_instance_with_share_link(
+                    &Faker.fake::<ShareLink>(),
+                    &Action::Read,
+                    &asset("survey"),
+                );
+
+            // Assert
+            assert!(res.is_ok());
+        }
+    }
+}
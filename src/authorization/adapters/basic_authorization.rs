@@ -3,19 +3,59 @@
 //!
 //! This could be replaced by more robust policy engine in the future.
 
+use std::sync::Arc;
+
+use time::format_description::well_known::Rfc3339;
+
 use super::super::domain::action::Action;
 use super::super::domain::error::AuthorizationEngineError;
+use super::super::domain::explanation::Check;
+use super::super::domain::explanation::CheckKind;
+use super::super::domain::explanation::Explanation;
+use super::super::domain::explanation::ExplanationTrace;
 use super::super::domain::resource::Resource;
 use super::super::domain::resource::ResourceInstance;
 use super::super::ports::authorization_engine::AuthorizationEngine;
+use crate::common::domain::Group;
 use crate::common::domain::resource_type;
+use crate::common::domain::utils::clock::Clock;
+use crate::common::domain::utils::clock::SystemClock;
+use crate::share_link::domain::ShareLink;
+use crate::share_link::domain::authorization::authorize_share_link;
+use crate::user::domain::DEFAULT_ADMIN_GROUP;
 use crate::user::domain::User;
 
 /// Handles authorization business logic for the application.
 #[derive(Debug, Clone)]
-pub struct SimpleAuthorizationEngine;
+pub struct SimpleAuthorizationEngine {
+    /// Members of any of these groups are allowed to do anything.
+    admin_groups: Vec<Group>,
+
+    /// Used to determine whether access windows are open.
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for SimpleAuthorizationEngine {
+    fn default() -> Self {
+        Self::new(vec![Group::new(DEFAULT_ADMIN_GROUP)])
+    }
+}
 
 impl SimpleAuthorizationEngine {
+    pub fn new(admin_groups: Vec<Group>) -> Self {
+        Self {
+            admin_groups,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Uses the `clock` (rather than the system time) to determine whether
+    /// access windows are open.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     #[tracing::instrument(
         name = "`simple_authorization_engine`: evaluating on resource type",
         err
@@ -26,13 +66,18 @@ impl SimpleAuthorizationEngine {
         action: &Action,
         resource: &dyn Resource,
     ) -> Result<(), AuthorizationEngineError> {
-        // If there is no user then the user is not authenticated.
+        // If there is no user then the user is not authenticated. They are still
+        // allowed to list projects, only public projects pass the instance-level
+        // check.
         let Some(user) = user else {
-            return Err(AuthorizationEngineError::NotAuthenticated);
+            return match (action, resource.resource_type().as_str()) {
+                (&Action::List, resource_type::PROJECT) => Ok(()),
+                _ => Err(AuthorizationEngineError::NotAuthenticated),
+            };
         };
 
         // An admin should always be allowed to action.
-        if user.is_admin() {
+        if user.is_admin(&self.admin_groups) {
             return Ok(());
         };
 
@@ -58,37 +103,253 @@ impl SimpleAuthorizationEngine {
         action: &Action,
         resource: &dyn ResourceInstance,
     ) -> Result<(), AuthorizationEngineError> {
+        self.evaluate_instance(user, action, resource, &mut ExplanationTrace::disabled())
+    }
+
+    /// Explains the decision that [`Self::can_on_instance`] makes.
+    pub fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        let mut trace = ExplanationTrace::enabled();
+        let result = self.evaluate_instance(user, action, resource, &mut trace);
+
+        Explanation::new(&result, action, resource, trace.into_checks())
+    }
+
+    /// Decides whether the `user` may perform the `action` on the `resource`,
+    /// recording the evaluated checks in the `trace`.
+    fn evaluate_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+        trace: &mut ExplanationTrace,
+    ) -> Result<(), AuthorizationEngineError> {
+        // Only reading is allowed on resource instances.
+        let is_read = action == &Action::Read;
+        trace.record(|| {
+            Check::new(
+                CheckKind::Action,
+                is_read,
+                format!("only `read` is allowed on a resource, the action is `{action}`"),
+            )
+            .decisive(!is_read)
+        });
+        if !is_read {
+            return Err(Self::not_authorized(user.as_ref(), action, resource));
+        }
+
+        let now = self.clock.now();
+
+        // An admin should always be allowed to read.
+        let is_admin = user
+            .as_ref()
+            .is_some_and(|user| user.is_admin(&self.admin_groups));
+        trace.record(|| {
+            Check::new(
+                CheckKind::Admin,
+                is_admin,
+                format!(
+                    "the user is a member of an admin group ({})",
+                    join_groups(&self.admin_groups)
+                ),
+            )
+            .decisive(is_admin)
+        });
+        if is_admin {
+            return Ok(());
+        }
+
+        // Nobody else is allowed to read the resource outside of its access window.
+        if let Some(access_window) = resource.access_window() {
+            let is_open = access_window.contains(now);
+            trace.record(|| {
+                Check::new(
+                    CheckKind::AccessWindow,
+                    is_open,
+                    format!("the resource can be accessed {access_window}"),
+                )
+                .decisive(!is_open)
+            });
+
+            if !is_open {
+                return Err(match access_window.expired_at(now) {
+                    Some(expired_at) => AuthorizationEngineError::AccessExpired {
+                        resource_identifier: Some(resource.resource_identifier()),
+                        resource_type: resource.resource_type(),
+                        expired_at,
+                    },
+                    None => Self::not_authorized(user.as_ref(), action, resource),
+                });
+            }
+        }
+
+        // Paths restricted by a path rule additionally require one of the rule's
+        // groups, even within a public resource.
+        if let Some(asset_path) = resource.asset_path() {
+            let mut satisfies_path_rules = true;
+
+            for path_rule in resource.path_rules().unwrap_or_default() {
+                if !path_rule.matches(&asset_path) {
+                    continue;
+                }
+
+                let allowed = user.as_ref().is_some_and(|user| path_rule.allows(user));
+                trace.record(|| {
+                    Check::new(
+                        CheckKind::PathRule,
+                        allowed,
+                        format!(
+                            "the path matches `{}`, which requires a member of: {}",
+                            path_rule.patterns().join("`, `"),
+                            join_groups(path_rule.groups())
+                        ),
+                    )
+                    .decisive(!allowed && satisfies_path_rules)
+                });
+                satisfies_path_rules &= allowed;
+            }
+
+            if !satisfies_path_rules {
+                return Err(Self::not_authorized(user.as_ref(), action, resource));
+            }
+        }
+
+        // Anyone, authenticated or not, is allowed to _read_ a public resource.
+        let is_public = resource.is_public();
+        trace.record(|| {
+            Check::new(CheckKind::Public, is_public, "the resource is public").decisive(is_public)
+        });
+        if is_public {
+            return Ok(());
+        }
+
         // If there is no user then the user is not authenticated.
+        trace.record(|| {
+            Check::new(
+                CheckKind::Authenticated,
+                user.is_some(),
+                "the user is authenticated",
+            )
+            .decisive(user.is_none())
+        });
         let Some(user) = user else {
             return Err(AuthorizationEngineError::NotAuthenticated);
         };
 
-        // Allows a user to _read_ any resource of which they share a group.
-        match action {
-            &Action::Read => {
-                if user.is_admin()
-                    || resource.groups().is_some_and(|groups| {
-                        groups.iter().any(|group| user.groups.contains(group))
-                    })
-                {
-                    Ok(())
-                } else {
-                    Err(AuthorizationEngineError::NotAuthorized {
-                        user: Box::new(user.clone()),
-                        action: action.clone(),
-                        resource_identifier: Some(resource.resource_identifier()),
-                        resource_type: resource.resource_type(),
-                    })
-                }
+        // Allows a user to _read_ any resource to which their (verified) email address
+        // has been granted access.
+        let email_granted = resource
+            .user_emails()
+            .is_some_and(|emails| user.has_verified_email_in(&emails));
+        trace.record(|| {
+            Check::new(
+                CheckKind::UserEmail,
+                email_granted,
+                format!(
+                    "the user's verified email address ({}) is granted access",
+                    user.email
+                ),
+            )
+            .decisive(email_granted)
+        });
+        if email_granted {
+            return Ok(());
+        }
+
+        // Allows a user to _read_ any resource of which they share a group, while the
+        // group's access window is open.
+        let mut shared_group_windows = Vec::new();
+
+        for group in resource.groups().unwrap_or_default() {
+            let is_member = user.groups.contains(&group);
+            let access_window = resource.group_access_window(&group);
+            let is_open = access_window.is_none_or(|window| window.contains(now));
+            let decisive = is_member && is_open;
+
+            trace.record(|| {
+                let description = match access_window {
+                    Some(access_window) => format!(
+                        "the user is a member of `{}`, which can access the resource {access_window}",
+                        group.as_str()
+                    ),
+                    None => format!("the user is a member of `{}`", group.as_str()),
+                };
+
+                Check::new(CheckKind::Group, decisive, description).decisive(decisive)
+            });
+            if decisive {
+                return Ok(());
             }
-            _ => Err(AuthorizationEngineError::NotAuthorized {
+
+            if is_member {
+                shared_group_windows.push(access_window);
+            }
+        }
+
+        // Let the user know if they used to be allowed to read the resource.
+        let expired_at = shared_group_windows
+            .iter()
+            .flatten()
+            .filter_map(|access_window| access_window.expired_at(now))
+            .max();
+        trace.record(|| {
+            let description = match expired_at {
+                Some(expired_at) => format!(
+                    "no check allowed the request, the access of the user's groups expired at {}",
+                    expired_at.format(&Rfc3339).unwrap_or_default()
+                ),
+                None => "no check allowed the request".to_owned(),
+            };
+
+            Check::new(CheckKind::Default, false, description).decisive(true)
+        });
+
+        match expired_at {
+            Some(expired_at) => Err(AuthorizationEngineError::AccessExpired {
+                resource_identifier: Some(resource.resource_identifier()),
+                resource_type: resource.resource_type(),
+                expired_at,
+            }),
+            None => Err(Self::not_authorized(Some(user), action, resource)),
+        }
+    }
+
+    /// The error for a `user` that is not allowed to perform the `action` on
+    /// the `resource`.
+    fn not_authorized(
+        user: Option<&User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> AuthorizationEngineError {
+        match user {
+            Some(user) => AuthorizationEngineError::NotAuthorized {
                 user: Box::new(user.clone()),
                 action: action.clone(),
                 resource_identifier: Some(resource.resource_identifier()),
                 resource_type: resource.resource_type(),
-            }),
+            },
+            None => AuthorizationEngineError::NotAuthenticated,
         }
     }
+
+    #[tracing::instrument(
+        name = "`simple_authorization_engine`: evaluating share link on resource instance",
+        err
+    )]
+    pub fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        // Allows a share link to only _read_ resources within its project (and path
+        // prefix), while the project can be accessed.
+        authorize_share_link(share_link, action, resource, self.clock.now())
+    }
 }
 
 impl AuthorizationEngine for SimpleAuthorizationEngine {
@@ -109,6 +370,37 @@ impl AuthorizationEngine for SimpleAuthorizationEngine {
     ) -> Result<(), AuthorizationEngineError> {
         Self::can_on_instance(self, user, action, resource)
     }
+
+    fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance_with_share_link(self, share_link, action, resource)
+    }
+
+    fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        Self::explain(self, user, action, resource)
+    }
+}
+
+/// Lists the `groups` for an explanation, e.g. `` `admin`, `surveyors` ``.
+fn join_groups(groups: &[Group]) -> String {
+    if groups.is_empty() {
+        return "no groups".to_owned();
+    }
+
+    groups
+        .iter()
+        .map(|group| format!("`{}`", group.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 #[cfg(test)]
@@ -118,7 +410,7 @@ mod authorization_service_tests {
 
     use super::super::super::domain::resource::mocked_resource::MockedResource;
     use super::*;
-    use crate::common::domain::group::Group;
+    use crate::user::domain::EmailAddress;
 
     mod can_on_type {
 
@@ -128,7 +420,7 @@ mod authorization_service_tests {
         #[test]
         fn should_return_ok_if_the_user_is_an_admin() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = User::dummy_admin();
             let resource = Faker.fake::<MockedResource>();
@@ -140,10 +432,51 @@ mod authorization_service_tests {
             assert!(res.is_ok())
         }
 
+        #[test]
+        fn should_return_ok_if_the_user_is_in_a_configured_admin_group() {
+            // Arrange
+            let authorization_service =
+                SimpleAuthorizationEngine::new(vec![Group::new("potree-admins")]);
+
+            let user = User {
+                groups: vec![Group::new("potree-admins")],
+                ..Faker.fake()
+            };
+            let resource = Faker.fake::<MockedResource>();
+
+            // Act
+            let res = authorization_service.can_on_type(&Some(user), &Action::Delete, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_the_admin_group_is_not_configured() {
+            // Arrange
+            let authorization_service =
+                SimpleAuthorizationEngine::new(vec![Group::new("potree-admins")]);
+
+            let user = User::dummy_admin();
+            let resource = MockedResource {
+                resource_type: ResourceType::new(resource_type::PROJECT.to_owned()),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_type(&Some(user), &Action::Delete, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
         #[test]
         fn should_return_ok_if_listing_projects() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = MockedResource {
@@ -164,7 +497,7 @@ mod authorization_service_tests {
             )]
         fn should_return_err_if_not_listing_projects(action: &Action) {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = MockedResource {
@@ -185,7 +518,7 @@ mod authorization_service_tests {
         #[test]
         fn should_return_ok_if_reading_a_project_dashboard() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = MockedResource {
@@ -206,7 +539,7 @@ mod authorization_service_tests {
             )]
         fn should_return_err_if_not_reading_a_project_dashboard(action: &Action) {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = MockedResource {
@@ -224,10 +557,47 @@ mod authorization_service_tests {
             ))
         }
 
+        #[test]
+        fn should_return_ok_if_listing_projects_without_a_user() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                resource_type: ResourceType::new(resource_type::PROJECT.to_owned()),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_type(&None, &Action::List, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_reading_a_project_dashboard_without_a_user() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                resource_type: ResourceType::new(resource_type::PROJECTS_DASHBOARD.to_owned()),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_type(&None, &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
         #[test]
         fn should_return_err_for_other_combinations() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = Faker.fake::<MockedResource>();
@@ -251,7 +621,7 @@ mod authorization_service_tests {
         #[test]
         fn should_return_ok_if_the_user_is_an_admin() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = User::dummy_admin();
             let resource = Faker.fake::<MockedResource>();
@@ -266,7 +636,7 @@ mod authorization_service_tests {
         #[test]
         fn should_return_ok_if_the_user_shares_a_group_with_the_resource() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let shared_group = Faker.fake::<Group>();
             let user = User {
@@ -289,12 +659,13 @@ mod authorization_service_tests {
         #[test]
         fn should_return_err_if_the_user_does_not_share_a_group_with_the_resource() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
             let user = Faker.fake::<User>();
             let resource = MockedResource {
                 groups: Some(vec![]),
                 user_emails: None,
+                is_public: false,
                 ..Faker.fake()
             };
 
@@ -308,22 +679,56 @@ mod authorization_service_tests {
             ))
         }
 
+        #[test_case::test_case("reviewer@example.com"; "same case")]
+        #[test_case::test_case("Reviewer@Example.COM"; "different case")]
+        fn should_return_ok_if_the_users_email_has_been_granted_access(granted_email: &str) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let user = User {
+                email: EmailAddress::new("reviewer@example.com".to_owned()),
+                email_verified: true,
+                ..Faker.fake()
+            };
+            let resource = MockedResource {
+                groups: Some(vec![]),
+                user_emails: Some(vec![
+                    Faker.fake(),
+                    EmailAddress::new(granted_email.to_owned()),
+                ]),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance(&Some(user), &Action::Read, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
         #[test]
-        fn should_return_err_if_the_user_is_not_authenticated() {
+        fn should_return_err_if_the_users_email_is_not_verified() {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let user = User {
+                email_verified: false,
+                ..Faker.fake()
+            };
+            let resource = MockedResource {
+                groups: Some(vec![]),
+                user_emails: Some(vec![user.email.clone()]),
+                is_public: false,
+                ..Faker.fake()
+            };
 
             // Act
-            let res = authorization_service.can_on_instance(
-                &None,
-                &Action::Read,
-                &Faker.fake::<MockedResource>(),
-            );
+            let res = authorization_service.can_on_instance(&Some(user), &Action::Read, &resource);
 
             // Assert
             assert!(matches!(
                 res,
-                Err(AuthorizationEngineError::NotAuthenticated)
+                Err(AuthorizationEngineError::NotAuthorized { .. })
             ))
         }
 
@@ -331,18 +736,17 @@ mod authorization_service_tests {
         #[test_case::test_case(&Action::Create; "create")]
         #[test_case::test_case(&Action::Update; "update")]
         #[test_case::test_case(&Action::Delete; "delete")]
-        fn should_return_err_if_anything_other_than_read(action: &Action) {
+        fn should_return_err_if_email_granted_but_not_reading(action: &Action) {
             // Arrange
-            let authorization_service = SimpleAuthorizationEngine;
+            let authorization_service = SimpleAuthorizationEngine::default();
 
-            let shared_group = Faker.fake::<Group>();
             let user = User {
-                groups: [Faker.fake(), shared_group.clone()].into(),
+                email_verified: true,
                 ..Faker.fake()
             };
             let resource = MockedResource {
-                groups: Some(vec![shared_group]),
-                user_emails: None,
+                groups: Some(vec![]),
+                user_emails: Some(vec![user.email.clone()]),
                 ..Faker.fake()
             };
 
@@ -355,5 +759,722 @@ mod authorization_service_tests {
                 Err(AuthorizationEngineError::NotAuthorized { .. })
             ))
         }
+
+        #[test]
+        fn should_return_err_if_the_user_is_not_authenticated() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                is_public: false,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance(&None, &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
+        #[test_case::test_case(None; "not authenticated")]
+        #[test_case::test_case(Some(Faker.fake()); "not a member")]
+        fn should_return_ok_if_reading_a_public_resource(user: Option<User>) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                groups: Some(vec![]),
+                user_emails: Some(vec![]),
+                is_public: true,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance(&user, &Action::Read, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test_case::test_case(&Action::List; "list")]
+        #[test_case::test_case(&Action::Create; "create")]
+        #[test_case::test_case(&Action::Update; "update")]
+        #[test_case::test_case(&Action::Delete; "delete")]
+        fn should_return_err_if_public_but_not_reading(action: &Action) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                is_public: true,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance(&None, action, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
+        #[test_case::test_case(&Action::List; "list")]
+        #[test_case::test_case(&Action::Create; "create")]
+        #[test_case::test_case(&Action::Update; "update")]
+        #[test_case::test_case(&Action::Delete; "delete")]
+        fn should_return_err_if_anything_other_than_read(action: &Action) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let shared_group = Faker.fake::<Group>();
+            let user = User {
+                groups: [Faker.fake(), shared_group.clone()].into(),
+                ..Faker.fake()
+            };
+            let resource = MockedResource {
+                groups: Some(vec![shared_group]),
+                user_emails: None,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance(&Some(user), action, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+    }
+
+    mod can_on_instance_access_windows {
+        use std::collections::HashMap;
+
+        use time::Duration;
+        use time::OffsetDateTime;
+        use time::macros::datetime;
+
+        use super::*;
+        use crate::common::domain::AccessWindow;
+        use crate::common::domain::utils::clock::FixedClock;
+
+        const NOW: OffsetDateTime = datetime!(2026-03-01 12:00 UTC);
+
+        fn authorization_service() -> SimpleAuthorizationEngine {
+            SimpleAuthorizationEngine::default().with_clock(Arc::new(FixedClock(NOW)))
+        }
+
+        fn window(valid_from: Option<Duration>, valid_until: Option<Duration>) -> AccessWindow {
+            AccessWindow {
+                valid_from: valid_from.map(|offset| NOW + offset),
+                valid_until: valid_until.map(|offset| NOW + offset),
+            }
+        }
+
+        fn member_of(groups: &[&str]) -> User {
+            User {
+                groups: groups.iter().map(|group| Group::new(group)).collect(),
+                ..Faker.fake()
+            }
+        }
+
+        /// A resource of the `clients` and `surveyors` groups, the access of
+        /// `clients` being bounded by the `clients_window`.
+        fn resource(
+            access_window: Option<AccessWindow>,
+            clients_window: AccessWindow,
+        ) -> MockedResource {
+            MockedResource {
+                groups: Some(vec![Group::new("clients"), Group::new("surveyors")]),
+                user_emails: None,
+                is_public: false,
+                access_window,
+                group_access_windows: HashMap::from([(Group::new("clients"), clients_window)]),
+                ..Faker.fake()
+            }
+        }
+
+        #[test]
+        fn should_return_ok_within_the_group_access_window() {
+            // Arrange
+            let resource = resource(
+                None,
+                window(Some(Duration::days(-1)), Some(Duration::days(1))),
+            );
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(member_of(&["clients"])),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_access_expired_after_the_group_access_window() {
+            // Arrange
+            let resource = resource(None, window(None, Some(Duration::days(-1))));
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(member_of(&["clients"])),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::AccessExpired { expired_at, .. }) if expired_at == NOW - Duration::days(1)
+            ))
+        }
+
+        #[test]
+        fn should_return_not_authorized_before_the_group_access_window() {
+            // Arrange
+            let resource = resource(None, window(Some(Duration::days(1)), None));
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(member_of(&["clients"])),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_ok_if_another_shared_group_is_not_time_bounded() {
+            // Arrange
+            let resource = resource(None, window(None, Some(Duration::days(-1))));
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(member_of(&["clients", "surveyors"])),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_access_expired_after_the_resource_access_window() {
+            // Arrange
+            let resource = resource(
+                Some(window(None, Some(Duration::hours(-1)))),
+                AccessWindow::default(),
+            );
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(member_of(&["surveyors"])),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::AccessExpired { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_ok_for_an_admin_after_the_resource_access_window() {
+            // Arrange
+            let resource = resource(
+                Some(window(None, Some(Duration::hours(-1)))),
+                AccessWindow::default(),
+            );
+
+            // Act
+            let res = authorization_service().can_on_instance(
+                &Some(User::dummy_admin()),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+    }
+
+    mod can_on_instance_path_rules {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::project::domain::path_rule::PathRule;
+
+        /// A resource of the `clients` group, whose `raw/**` paths are
+        /// restricted to `surveyors`.
+        fn resource(asset_path: &str, is_public: bool) -> MockedResource {
+            MockedResource {
+                groups: Some(vec![Group::new("clients")]),
+                user_emails: None,
+                asset_path: Some(PathBuf::from(asset_path)),
+                is_public,
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..Faker.fake()
+            }
+        }
+
+        fn member_of(groups: &[&str]) -> User {
+            User {
+                groups: groups.iter().map(|group| Group::new(group)).collect(),
+                ..Faker.fake()
+            }
+        }
+
+        #[test_case::test_case("pointclouds/metadata.json", &["clients"]; "path not matching")]
+        #[test_case::test_case("raw/cloud.las", &["clients", "surveyors"]; "member of the rule's groups")]
+        fn should_return_ok_if_the_path_rules_are_satisfied(asset_path: &str, groups: &[&str]) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let res = authorization_service.can_on_instance(
+                &Some(member_of(groups)),
+                &Action::Read,
+                &resource(asset_path, false),
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_not_a_member_of_the_rules_groups() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let res = authorization_service.can_on_instance(
+                &Some(member_of(&["clients"])),
+                &Action::Read,
+                &resource("raw/cloud.las", false),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_err_if_not_authenticated_within_a_public_resource() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let res = authorization_service.can_on_instance(
+                &None,
+                &Action::Read,
+                &resource("raw/cloud.las", true),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
+        #[test]
+        fn should_return_ok_for_an_admin() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let res = authorization_service.can_on_instance(
+                &Some(User::dummy_admin()),
+                &Action::Read,
+                &resource("raw/cloud.las", false),
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+    }
+
+    mod explain {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::authorization::domain::explanation::CheckKind;
+        use crate::project::domain::path_rule::PathRule;
+
+        fn member_of(groups: &[&str]) -> User {
+            User {
+                groups: groups.iter().map(|group| Group::new(group)).collect(),
+                ..Faker.fake()
+            }
+        }
+
+        /// A private resource of the `clients` group, whose `raw/**` paths are
+        /// restricted to `surveyors`.
+        fn resource(asset_path: &str) -> MockedResource {
+            MockedResource {
+                groups: Some(vec![Group::new("clients")]),
+                user_emails: None,
+                asset_path: Some(PathBuf::from(asset_path)),
+                is_public: false,
+                access_window: None,
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..Faker.fake()
+            }
+        }
+
+        #[test_case::test_case(User::dummy_admin(), "raw/cloud.las", true, CheckKind::Admin; "admin")]
+        #[test_case::test_case(member_of(&["clients"]), "raw/cloud.las", false, CheckKind::PathRule; "path rule")]
+        #[test_case::test_case(member_of(&["clients"]), "metadata.json", true, CheckKind::Group; "shared group")]
+        #[test_case::test_case(member_of(&["other"]), "metadata.json", false, CheckKind::Default; "no shared group")]
+        fn should_mark_the_check_that_decided_the_outcome(
+            user: User,
+            asset_path: &str,
+            expected_allowed: bool,
+            expected_kind: CheckKind,
+        ) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+            let user = Some(user);
+            let resource = resource(asset_path);
+
+            // Act
+            let explanation = authorization_service.explain(&user, &Action::Read, &resource);
+
+            // Assert
+            let res = authorization_service.can_on_instance(&user, &Action::Read, &resource);
+            assert_eq!(explanation.allowed, res.is_ok());
+            assert_eq!(explanation.allowed, expected_allowed);
+            assert_eq!(explanation.decisive_check().unwrap().kind, expected_kind);
+            assert_eq!(
+                explanation
+                    .checks
+                    .iter()
+                    .filter(|check| check.decisive)
+                    .count(),
+                1
+            );
+        }
+
+        #[test]
+        fn should_list_the_evaluated_checks_in_order() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let explanation = authorization_service.explain(
+                &Some(member_of(&["clients"])),
+                &Action::Read,
+                &resource("metadata.json"),
+            );
+
+            // Assert
+            assert_eq!(
+                explanation
+                    .checks
+                    .iter()
+                    .map(|check| check.kind)
+                    .collect::<Vec<_>>(),
+                vec![
+                    CheckKind::Action,
+                    CheckKind::Admin,
+                    CheckKind::Public,
+                    CheckKind::Authenticated,
+                    CheckKind::UserEmail,
+                    CheckKind::Group,
+                ]
+            );
+            assert_eq!(
+                explanation.checks[5].description,
+                "the user is a member of `clients`"
+            );
+        }
+
+        #[test]
+        fn should_give_the_reason_if_denied() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            // Act
+            let explanation =
+                authorization_service.explain(&None, &Action::Read, &resource("metadata.json"));
+
+            // Assert
+            assert!(!explanation.allowed);
+            assert_eq!(explanation.reason.as_deref(), Some("not_authenticated"));
+            assert_eq!(
+                explanation.decisive_check().unwrap().kind,
+                CheckKind::Authenticated
+            );
+        }
+    }
+
+    mod can_on_instance_with_share_link {
+
+        use std::path::PathBuf;
+
+        use time::Duration;
+        use time::OffsetDateTime;
+        use time::macros::datetime;
+
+        use super::*;
+        use crate::common::domain::AccessWindow;
+        use crate::common::domain::utils::clock::FixedClock;
+        use crate::project::domain::ProjectId;
+        use crate::project::domain::path_rule::PathRule;
+
+        fn share_link(path_prefix: Option<&str>) -> ShareLink {
+            ShareLink {
+                project_id: ProjectId::new("project_1".to_owned()),
+                path_prefix: path_prefix.map(PathBuf::from),
+                expires_at: OffsetDateTime::now_utc(),
+            }
+        }
+
+        #[test_case::test_case(None, None; "project")]
+        #[test_case::test_case(Some("pointclouds"), None; "project with path prefix")]
+        #[test_case::test_case(None, Some("raw/cloud.las"); "asset")]
+        #[test_case::test_case(Some("pointclouds"), Some("pointclouds/metadata.json"); "asset within path prefix")]
+        fn should_return_ok_if_reading_within_scope(
+            path_prefix: Option<&str>,
+            asset_path: Option<&str>,
+        ) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                asset_path: asset_path.map(PathBuf::from),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(path_prefix),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_asset_outside_of_path_prefix() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                asset_path: Some(PathBuf::from("raw/cloud.las")),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(Some("pointclouds")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+
+        #[test_case::test_case(Some(ProjectId::new("project_2".to_owned())); "another project")]
+        #[test_case::test_case(None; "no project")]
+        fn should_return_err_if_resource_not_in_the_project(project_id: Option<ProjectId>) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(None),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+
+        #[test_case::test_case(None; "without path prefix")]
+        #[test_case::test_case(Some("raw"); "with path prefix")]
+        fn should_return_err_if_asset_restricted_by_a_path_rule(path_prefix: Option<&str>) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                asset_path: Some(PathBuf::from("raw/cloud.las")),
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(path_prefix),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_ok_if_asset_not_restricted_by_the_path_rules() {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                asset_path: Some(PathBuf::from("pointclouds/metadata.json")),
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(None),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_access_expired_after_the_project_access_window() {
+            // Arrange
+            let now = datetime!(2026-03-01 12:00 UTC);
+            let authorization_service =
+                SimpleAuthorizationEngine::default().with_clock(Arc::new(FixedClock(now)));
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                access_window: Some(AccessWindow {
+                    valid_from: None,
+                    valid_until: Some(now - Duration::days(1)),
+                }),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(None),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::AccessExpired { expired_at, .. }) if expired_at == now - Duration::days(1)
+            ))
+        }
+
+        #[test]
+        fn should_return_err_before_the_project_access_window() {
+            // Arrange
+            let now = datetime!(2026-03-01 12:00 UTC);
+            let authorization_service =
+                SimpleAuthorizationEngine::default().with_clock(Arc::new(FixedClock(now)));
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                access_window: Some(AccessWindow {
+                    valid_from: Some(now + Duration::days(1)),
+                    valid_until: None,
+                }),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(None),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+
+        #[test_case::test_case(&Action::List; "list")]
+        #[test_case::test_case(&Action::Create; "create")]
+        #[test_case::test_case(&Action::Update; "update")]
+        #[test_case::test_case(&Action::Delete; "delete")]
+        fn should_return_err_if_anything_other_than_read(action: &Action) {
+            // Arrange
+            let authorization_service = SimpleAuthorizationEngine::default();
+
+            let resource = MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                asset_path: None,
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = authorization_service.can_on_instance_with_share_link(
+                &share_link(None),
+                action,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
     }
 }
@@ -1,2 +1,4 @@
+pub mod audited;
 pub mod basic_authorization;
 pub mod no_op;
+pub mod policy_file;
@@ -5,9 +5,13 @@
 
 use super::super::domain::action::Action;
 use super::super::domain::error::AuthorizationEngineError;
+use super::super::domain::explanation::Check;
+use super::super::domain::explanation::CheckKind;
+use super::super::domain::explanation::Explanation;
 use super::super::domain::resource::Resource;
 use super::super::domain::resource::ResourceInstance;
 use super::super::ports::authorization_engine::AuthorizationEngine;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 #[derive(Debug, Clone)]
@@ -39,6 +43,36 @@ impl NoOpAuthorizationEngine {
     ) -> Result<(), AuthorizationEngineError> {
         Ok(())
     }
+
+    #[tracing::instrument(
+        name = "`no_op_authorization_engine`: evaluating share link on resource instance",
+        err
+    )]
+    fn can_on_instance_with_share_link(
+        &self,
+        _share_link: &ShareLink,
+        _action: &Action,
+        _resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Ok(())
+    }
+
+    /// Every request is allowed, as authorization is not configured.
+    fn explain(
+        &self,
+        _user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        let check = Check::new(
+            CheckKind::Default,
+            true,
+            "authorization is not configured, every request is allowed",
+        )
+        .decisive(true);
+
+        Explanation::new(&Ok(()), action, resource, vec![check])
+    }
 }
 
 impl AuthorizationEngine for NoOpAuthorizationEngine {
@@ -59,4 +93,22 @@ impl AuthorizationEngine for NoOpAuthorizationEngine {
     ) -> Result<(), AuthorizationEngineError> {
         Self::can_on_instance(self, user, action, resource)
     }
+
+    fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance_with_share_link(self, share_link, action, resource)
+    }
+
+    fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        Self::explain(self, user, action, resource)
+    }
 }
@@ -0,0 +1,1346 @@
+//! An authorization engine driven by a declarative policy file (YAML or TOML).
+//! It implements the [`AuthorizationEngine`] trait.
+//!
+//! A policy is an ordered list of rules. Each rule has an `effect` (`allow` or
+//! `deny`) and a number of conditions, all of which need to hold for the rule
+//! to match. A condition that is omitted matches anything:
+//!
+//! - `resource_types`: the type of the resource (e.g. `project`,
+//!   `project-asset`, `potree-render`, `projects-dashboard`).
+//! - `actions`: the action being performed (e.g. `read`, `list`).
+//! - `groups`: the user is a member of at least one of the groups.
+//! - `emails`: the user's email address is one of these (case-insensitive). For
+//!   `allow` rules the email address must have been verified by the IdP.
+//! - `paths`: the asset path of the resource matches one of the globs (e.g.
+//!   `raw/**`).
+//! - `require_membership`: the user shares a group with the resource, or their
+//!   (verified) email address has been granted access to it, within the access
+//!   windows of the resource and of the group (if any).
+//!
+//! `paths` and `require_membership` only hold for a specific resource instance,
+//! rules using them never match type-level checks.
+//!
+//! A matching `deny` rule always takes precedence over a matching `allow` rule,
+//! and anything that isn't explicitly allowed is denied. If an `allow` rule
+//! would have matched, but the user's access window has closed, the user is
+//! told that their access expired. Public resources (e.g.
+//! the assets of a `visibility: public` project) can be _read_ by anyone,
+//! including users that aren't authenticated, unless a `deny` rule matches.
+//! `allow` rules only ever apply to authenticated users.
+//!
+//! See [`docs/resources/policy.yml`] for an example.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use globset::GlobSet;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use super::super::domain::action::Action;
+use super::super::domain::error::AuthorizationEngineError;
+use super::super::domain::explanation::Check;
+use super::super::domain::explanation::CheckKind;
+use super::super::domain::explanation::Explanation;
+use super::super::domain::explanation::ExplanationTrace;
+use super::super::domain::resource::Resource;
+use super::super::domain::resource::ResourceInstance;
+use super::super::domain::resource::ResourceType;
+use super::super::ports::authorization_engine::AuthorizationEngine;
+use crate::common::domain::Group;
+use crate::common::domain::resource_type;
+use crate::common::domain::utils::clock::Clock;
+use crate::common::domain::utils::clock::SystemClock;
+use crate::common::domain::utils::path_glob::path_glob_set;
+use crate::share_link::domain::ShareLink;
+use crate::share_link::domain::authorization::authorize_share_link;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::User;
+
+/// The resource types that a rule may refer to.
+const KNOWN_RESOURCE_TYPES: [&str; 7] = [
+    resource_type::ACCESS_EXPLANATION,
+    resource_type::PROJECT,
+    resource_type::PROJECTS_DASHBOARD,
+    resource_type::PROJECT_ASSET,
+    resource_type::POTREE_RENDER,
+    resource_type::SHARE_LINK,
+    resource_type::VALIDATION_REPORT,
+];
+
+/// Handles authorization business logic according to a policy file.
+#[derive(Debug, Clone)]
+pub struct PolicyFileAuthorizationEngine {
+    rules: Vec<Rule>,
+
+    /// Used to determine whether access windows are open.
+    clock: Arc<dyn Clock>,
+}
+
+/// The formats in which a policy can be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyFormat {
+    Yaml,
+    Toml,
+}
+
+impl PolicyFormat {
+    /// Determines the format of a policy file from its extension. Defaults to
+    /// YAML.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum PolicyError {
+    #[error("unable to read the policy file ({}): {message}", path.to_string_lossy())]
+    Read { path: PathBuf, message: String },
+
+    #[error("unable to parse the policy: {message}")]
+    Parsing { message: String },
+
+    #[error("rule {number} of the policy is invalid: {message}")]
+    InvalidRule { number: usize, message: String },
+}
+
+/// The contents of a policy file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyDocument {
+    rules: Vec<RuleDefinition>,
+}
+
+/// A rule as it is written in a policy file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    /// An optional human readable description of the rule.
+    #[serde(default)]
+    description: Option<String>,
+
+    effect: Effect,
+
+    #[serde(default)]
+    resource_types: Vec<String>,
+
+    #[serde(default)]
+    actions: Vec<Action>,
+
+    #[serde(default)]
+    groups: Vec<Group>,
+
+    #[serde(default)]
+    emails: Vec<EmailAddress>,
+
+    #[serde(default)]
+    paths: Vec<String>,
+
+    #[serde(default)]
+    require_membership: bool,
+}
+
+/// Whether a matching rule allows or denies the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Effect {
+    Allow,
+    Deny,
+}
+
+/// A validated rule, ready to be evaluated.
+#[derive(Debug, Clone)]
+struct Rule {
+    /// The (1-based) position of the rule in the policy file.
+    number: usize,
+    description: Option<String>,
+    effect: Effect,
+    resource_types: Vec<ResourceType>,
+    actions: Vec<Action>,
+    groups: Vec<Group>,
+    emails: Vec<EmailAddress>,
+    paths: Option<GlobSet>,
+    require_membership: bool,
+}
+
+impl PolicyFileAuthorizationEngine {
+    /// Loads and validates the policy file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, PolicyError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| PolicyError::Read {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        Self::from_policy(&contents, PolicyFormat::from_path(path))
+    }
+
+    /// Parses and validates a `policy` written in `format`.
+    pub fn from_policy(policy: &str, format: PolicyFormat) -> Result<Self, PolicyError> {
+        let document = match format {
+            PolicyFormat::Yaml => {
+                serde_norway::from_str::<PolicyDocument>(policy).map_err(|e| {
+                    PolicyError::Parsing {
+                        message: e.to_string(),
+                    }
+                })?
+            }
+            PolicyFormat::Toml => {
+                toml::from_str::<PolicyDocument>(policy).map_err(|e| PolicyError::Parsing {
+                    message: e.to_string(),
+                })?
+            }
+        };
+
+        let rules = document
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, definition)| Rule::try_from_definition(index + 1, definition))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            rules,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// Uses the `clock` (rather than the system time) to determine whether
+    /// access windows are open.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    #[tracing::instrument(
+        name = "`policy_file_authorization_engine`: evaluating on resource type",
+        err
+    )]
+    pub fn can_on_type(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn Resource,
+    ) -> Result<(), AuthorizationEngineError> {
+        // If there is no user then the user is not authenticated. They are still
+        // allowed to list projects, only public projects pass the instance-level
+        // check.
+        let Some(user) = user else {
+            return match (action, resource.resource_type().as_str()) {
+                (&Action::List, resource_type::PROJECT) => Ok(()),
+                _ => Err(AuthorizationEngineError::NotAuthenticated),
+            };
+        };
+
+        let resource_type = resource.resource_type();
+
+        self.evaluate(
+            Some(user),
+            action,
+            &resource_type,
+            None,
+            &mut ExplanationTrace::disabled(),
+        )
+        .map_err(|_| AuthorizationEngineError::NotAuthorized {
+            user: Box::new(user.clone()),
+            action: action.clone(),
+            resource_identifier: None,
+            resource_type,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "`policy_file_authorization_engine`: evaluating on resource instance",
+        err
+    )]
+    pub fn can_on_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        self.evaluate_instance(user, action, resource, &mut ExplanationTrace::disabled())
+    }
+
+    /// Explains the decision that [`Self::can_on_instance`] makes.
+    pub fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        let mut trace = ExplanationTrace::enabled();
+        let result = self.evaluate_instance(user, action, resource, &mut trace);
+
+        Explanation::new(&result, action, resource, trace.into_checks())
+    }
+
+    /// Decides whether the `user` may perform the `action` on the `resource`,
+    /// recording the evaluated checks in the `trace`.
+    fn evaluate_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+        trace: &mut ExplanationTrace,
+    ) -> Result<(), AuthorizationEngineError> {
+        let resource_type = resource.resource_type();
+
+        self.evaluate(user.as_ref(), action, &resource_type, Some(resource), trace)
+            .map_err(|expired_at| match (user, expired_at) {
+                // Let the user know if they used to be allowed to perform the action.
+                (Some(_), Some(expired_at)) => AuthorizationEngineError::AccessExpired {
+                    resource_identifier: Some(resource.resource_identifier()),
+                    resource_type,
+                    expired_at,
+                },
+                (Some(user), None) => AuthorizationEngineError::NotAuthorized {
+                    user: Box::new(user.clone()),
+                    action: action.clone(),
+                    resource_identifier: Some(resource.resource_identifier()),
+                    resource_type,
+                },
+                // If there is no user then the user is not authenticated.
+                (None, _) => AuthorizationEngineError::NotAuthenticated,
+            })
+    }
+
+    /// Share links are not subject to the policy, they are allowed to _read_
+    /// resources within their project (and path prefix), while the project can
+    /// be accessed.
+    #[tracing::instrument(
+        name = "`policy_file_authorization_engine`: evaluating share link on resource instance",
+        err
+    )]
+    pub fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        authorize_share_link(share_link, action, resource, self.clock.now())
+    }
+
+    /// Evaluates the rules, `deny` rules taking precedence over `allow` rules.
+    /// Without a `user`, only public resources can be _read_. The evaluated
+    /// checks are recorded in the `trace`.
+    ///
+    /// If the request is not allowed, the error is when the user's access
+    /// expired, if an `allow` rule would otherwise have matched.
+    fn evaluate(
+        &self,
+        user: Option<&User>,
+        action: &Action,
+        resource_type: &ResourceType,
+        resource: Option<&dyn ResourceInstance>,
+        trace: &mut ExplanationTrace,
+    ) -> Result<(), Option<OffsetDateTime>> {
+        let now = self.clock.now();
+
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                (
+                    rule,
+                    rule.matches(user, action, resource_type, resource, now),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let deny = rules
+            .iter()
+            .find(|(rule, matches)| *matches && rule.effect == Effect::Deny)
+            .map(|(rule, _)| *rule);
+        let allow = rules
+            .iter()
+            .find(|(rule, matches)| *matches && rule.effect == Effect::Allow && user.is_some())
+            .map(|(rule, _)| *rule);
+        let decisive_rule = deny.or(allow).map(|rule| rule.number);
+
+        for (rule, matches) in &rules {
+            trace.record(|| {
+                Check::new(CheckKind::PolicyRule, *matches, rule.describe())
+                    .decisive(decisive_rule == Some(rule.number))
+            });
+        }
+
+        if let Some(rule) = deny {
+            tracing::debug!(rule = rule.number, description = ?rule.description, "denied by policy rule");
+            return Err(None);
+        }
+
+        if let Some(rule) = allow {
+            tracing::debug!(rule = rule.number, description = ?rule.description, "allowed by policy rule");
+            return Ok(());
+        }
+
+        if action == &Action::Read
+            && let Some(resource) = resource
+            && resource.is_public()
+        {
+            let satisfies_path_rules = satisfies_path_rules(user, resource, trace);
+            trace.record(|| {
+                Check::new(CheckKind::Public, true, "the resource is public")
+                    .decisive(satisfies_path_rules)
+            });
+
+            if satisfies_path_rules {
+                tracing::debug!("allowed as the resource is public");
+                return Ok(());
+            }
+
+            tracing::debug!("no policy rule allows the request");
+            return Err(None);
+        }
+
+        // Let the user know if they used to be allowed to perform the action.
+        let expired_at = user.zip(resource).and_then(|(user, resource)| {
+            self.rules
+                .iter()
+                .filter_map(|rule| {
+                    rule.membership_expired_at(user, action, resource_type, resource, now)
+                })
+                .max()
+        });
+        trace.record(|| {
+            let description = match expired_at {
+                Some(expired_at) => format!(
+                    "no policy rule allows the request, the user's access expired at {}",
+                    expired_at.format(&Rfc3339).unwrap_or_default()
+                ),
+                None => "no policy rule allows the request".to_owned(),
+            };
+
+            Check::new(CheckKind::Default, false, description).decisive(true)
+        });
+
+        tracing::debug!("no policy rule allows the request");
+        Err(expired_at)
+    }
+}
+
+impl Rule {
+    /// Validates a rule `definition`, `number` is its position in the policy
+    /// file.
+    fn try_from_definition(number: usize, definition: RuleDefinition) -> Result<Self, PolicyError> {
+        let RuleDefinition {
+            description,
+            effect,
+            resource_types,
+            actions,
+            groups,
+            emails,
+            paths,
+            require_membership,
+        } = definition;
+
+        if let Some(unknown) = resource_types
+            .iter()
+            .find(|resource_type| !KNOWN_RESOURCE_TYPES.contains(&resource_type.as_str()))
+        {
+            return Err(PolicyError::InvalidRule {
+                number,
+                message: format!(
+                    "unknown resource type `{unknown}`, expected one of: {}",
+                    KNOWN_RESOURCE_TYPES.join(", ")
+                ),
+            });
+        }
+
+        let paths = if paths.is_empty() {
+            None
+        } else {
+            Some(
+                path_glob_set(&paths)
+                    .map_err(|message| PolicyError::InvalidRule { number, message })?,
+            )
+        };
+
+        Ok(Self {
+            number,
+            description,
+            effect,
+            resource_types: resource_types.into_iter().map(ResourceType::new).collect(),
+            actions,
+            groups,
+            emails,
+            paths,
+            require_membership,
+        })
+    }
+
+    /// Describes the rule for an explanation, e.g. `rule 5 (deny): Clients
+    /// can't download the raw deliverables.`.
+    fn describe(&self) -> String {
+        let effect = match self.effect {
+            Effect::Allow => "allow",
+            Effect::Deny => "deny",
+        };
+
+        match &self.description {
+            Some(description) => format!("rule {} ({effect}): {description}", self.number),
+            None => format!("rule {} ({effect})", self.number),
+        }
+    }
+
+    /// Whether all the conditions of the rule hold. `resource` is `None` for
+    /// type-level checks. Conditions on the user never hold without a `user`.
+    fn matches(
+        &self,
+        user: Option<&User>,
+        action: &Action,
+        resource_type: &ResourceType,
+        resource: Option<&dyn ResourceInstance>,
+        now: OffsetDateTime,
+    ) -> bool {
+        let membership_matches = !self.require_membership
+            || user
+                .zip(resource)
+                .is_some_and(|(user, resource)| is_member(user, resource, now));
+
+        self.matches_besides_membership(user, action, resource_type, resource) && membership_matches
+    }
+
+    /// Whether all the conditions of the rule, other than `require_membership`,
+    /// hold.
+    fn matches_besides_membership(
+        &self,
+        user: Option<&User>,
+        action: &Action,
+        resource_type: &ResourceType,
+        resource: Option<&dyn ResourceInstance>,
+    ) -> bool {
+        let resource_type_matches =
+            self.resource_types.is_empty() || self.resource_types.contains(resource_type);
+
+        let action_matches = self.actions.is_empty() || self.actions.contains(action);
+
+        let group_matches = self.groups.is_empty()
+            || user.is_some_and(|user| self.groups.iter().any(|group| user.groups.contains(group)));
+
+        // An unverified email address could have been claimed by anyone, so it is
+        // still denied, but never allowed.
+        let email_matches = self.emails.is_empty()
+            || user.is_some_and(|user| match self.effect {
+                Effect::Allow => user.has_verified_email_in(&self.emails),
+                Effect::Deny => self
+                    .emails
+                    .iter()
+                    .any(|email| email.eq_ignore_case(&user.email)),
+            });
+
+        let path_matches = match &self.paths {
+            None => true,
+            Some(paths) => resource
+                .and_then(|resource| resource.asset_path())
+                .is_some_and(|asset_path| paths.is_match(asset_path)),
+        };
+
+        resource_type_matches && action_matches && group_matches && email_matches && path_matches
+    }
+
+    /// When the `user`'s membership of the `resource` expired, if this is an
+    /// `allow` rule that would otherwise have matched.
+    fn membership_expired_at(
+        &self,
+        user: &User,
+        action: &Action,
+        resource_type: &ResourceType,
+        resource: &dyn ResourceInstance,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        if self.effect != Effect::Allow
+            || !self.require_membership
+            || !self.matches_besides_membership(Some(user), action, resource_type, Some(resource))
+        {
+            return None;
+        }
+
+        membership_expired_at(user, resource, now)
+    }
+}
+
+/// Whether the `user` shares a group with the `resource`, or their (verified)
+/// email address has been granted access to it, at `now`. The path rules of the
+/// resource must also be satisfied.
+fn is_member(user: &User, resource: &dyn ResourceInstance, now: OffsetDateTime) -> bool {
+    let within_access_window = resource
+        .access_window()
+        .is_none_or(|access_window| access_window.contains(now));
+
+    let shares_group = resource.groups().is_some_and(|groups| {
+        groups.iter().any(|group| {
+            user.groups.contains(group)
+                && resource
+                    .group_access_window(group)
+                    .is_none_or(|access_window| access_window.contains(now))
+        })
+    });
+
+    let email_granted = resource
+        .user_emails()
+        .is_some_and(|emails| user.has_verified_email_in(&emails));
+
+    within_access_window
+        && (shares_group || email_granted)
+        && satisfies_path_rules(Some(user), resource, &mut ExplanationTrace::disabled())
+}
+
+/// When the `user`'s membership of the `resource` expired, i.e. they shared a
+/// group with it (or their verified email address was granted access to it)
+/// before its access window, or that of all their shared groups, closed at
+/// `now`. The path rules of the resource must also be satisfied.
+fn membership_expired_at(
+    user: &User,
+    resource: &dyn ResourceInstance,
+    now: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+    if !satisfies_path_rules(Some(user), resource, &mut ExplanationTrace::disabled()) {
+        return None;
+    }
+
+    let shared_groups = resource
+        .groups()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|group| user.groups.contains(group))
+        .collect::<Vec<_>>();
+    let email_granted = resource
+        .user_emails()
+        .is_some_and(|emails| user.has_verified_email_in(&emails));
+
+    match resource.access_window() {
+        Some(access_window) if !access_window.contains(now) => access_window
+            .expired_at(now)
+            .filter(|_| !shared_groups.is_empty() || email_granted),
+        _ => shared_groups
+            .iter()
+            .filter_map(|group| resource.group_access_window(group))
+            .filter_map(|access_window| access_window.expired_at(now))
+            .max(),
+    }
+}
+
+/// Whether the `user` is a member of the groups of every path rule matching
+/// the asset path of the `resource`. Never holds for a matching rule without a
+/// `user`. The matching path rules are recorded in the `trace`, the first that
+/// isn't satisfied being decisive.
+fn satisfies_path_rules(
+    user: Option<&User>,
+    resource: &dyn ResourceInstance,
+    trace: &mut ExplanationTrace,
+) -> bool {
+    let Some(asset_path) = resource.asset_path() else {
+        return true;
+    };
+
+    let mut satisfied = true;
+
+    for path_rule in resource.path_rules().unwrap_or_default() {
+        if !path_rule.matches(&asset_path) {
+            continue;
+        }
+
+        let allowed = user.is_some_and(|user| path_rule.allows(user));
+        trace.record(|| {
+            Check::new(
+                CheckKind::PathRule,
+                allowed,
+                format!(
+                    "the path matches `{}`, which requires a member of: {}",
+                    path_rule.patterns().join("`, `"),
+                    path_rule
+                        .groups()
+                        .iter()
+                        .map(|group| format!("`{}`", group.as_str()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+            .decisive(!allowed && satisfied)
+        });
+        satisfied &= allowed;
+    }
+
+    satisfied
+}
+
+impl AuthorizationEngine for PolicyFileAuthorizationEngine {
+    fn can_on_type(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn Resource,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_type(self, user, action, resource)
+    }
+
+    fn can_on_instance(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance(self, user, action, resource)
+    }
+
+    fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError> {
+        Self::can_on_instance_with_share_link(self, share_link, action, resource)
+    }
+
+    fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation {
+        Self::explain(self, user, action, resource)
+    }
+}
+
+#[cfg(test)]
+mod policy_file_authorization_engine_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::super::super::domain::resource::mocked_resource::MockedResource;
+    use super::*;
+    use crate::common::domain::AccessWindow;
+    use crate::common::domain::utils::clock::FixedClock;
+    use crate::project::domain::path_rule::PathRule;
+
+    /// The example policy that is shipped with the documentation.
+    const EXAMPLE_POLICY: &str = include_str!("../../../docs/resources/policy.yml");
+
+    fn example_engine() -> PolicyFileAuthorizationEngine {
+        PolicyFileAuthorizationEngine::from_policy(EXAMPLE_POLICY, PolicyFormat::Yaml).unwrap()
+    }
+
+    fn user(groups: &[&str], email: &str) -> User {
+        User {
+            groups: groups.iter().map(|group| Group::new(group)).collect(),
+            email: EmailAddress::new(email.to_owned()),
+            email_verified: true,
+            ..Faker.fake()
+        }
+    }
+
+    fn resource(
+        resource_type: &str,
+        groups: &[&str],
+        user_emails: &[&str],
+        asset_path: Option<&str>,
+    ) -> MockedResource {
+        MockedResource {
+            resource_type: ResourceType::new(resource_type.to_owned()),
+            groups: Some(groups.iter().map(|group| Group::new(group)).collect()),
+            user_emails: Some(
+                user_emails
+                    .iter()
+                    .map(|email| EmailAddress::new((*email).to_owned()))
+                    .collect(),
+            ),
+            asset_path: asset_path.map(PathBuf::from),
+            is_public: false,
+            ..Faker.fake()
+        }
+    }
+
+    mod from_policy {
+        use super::*;
+
+        #[test]
+        fn should_load_the_example_policy() {
+            // Act
+            let res =
+                PolicyFileAuthorizationEngine::from_policy(EXAMPLE_POLICY, PolicyFormat::Yaml);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn should_load_a_toml_policy() {
+            // Arrange
+            let policy = r#"
+                [[rules]]
+                effect = "allow"
+                resource_types = ["project"]
+                actions = ["list"]
+            "#;
+
+            // Act
+            let res = PolicyFileAuthorizationEngine::from_policy(policy, PolicyFormat::Toml);
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[test_case::test_case("rules: [{ effect: maybe }]"; "unknown effect")]
+        #[test_case::test_case("rules: [{ effect: allow, actions: [fly] }]"; "unknown action")]
+        #[test_case::test_case("rules: [{ effect: allow, colour: blue }]"; "unknown field")]
+        #[test_case::test_case("rule: []"; "missing rules")]
+        fn should_return_a_parsing_error_if_invalid(policy: &str) {
+            // Act
+            let res = PolicyFileAuthorizationEngine::from_policy(policy, PolicyFormat::Yaml);
+
+            // Assert
+            assert!(matches!(res, Err(PolicyError::Parsing { .. })));
+        }
+
+        #[test_case::test_case("rules: [{ effect: allow }, { effect: allow, resource_types: [projekt] }]"; "unknown resource type")]
+        #[test_case::test_case("rules: [{ effect: allow }, { effect: allow, paths: ['raw/['] }]"; "invalid glob")]
+        fn should_return_the_invalid_rule_number(policy: &str) {
+            // Act
+            let res = PolicyFileAuthorizationEngine::from_policy(policy, PolicyFormat::Yaml);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(PolicyError::InvalidRule { number: 2, .. })
+            ));
+        }
+    }
+
+    mod can_on_type {
+        use super::*;
+
+        #[test_case::test_case(&["admin"], Action::Create, resource_type::SHARE_LINK, true; "admin can create share links")]
+        #[test_case::test_case(&["surveyors"], Action::Create, resource_type::SHARE_LINK, false; "user can not create share links")]
+        #[test_case::test_case(&["surveyors"], Action::List, resource_type::PROJECT, true; "user can list projects")]
+        #[test_case::test_case(&["surveyors"], Action::Create, resource_type::PROJECT, false; "user can not create projects")]
+        #[test_case::test_case(&["surveyors"], Action::Read, resource_type::PROJECTS_DASHBOARD, true; "user can read dashboard")]
+        #[test_case::test_case(&[], Action::Read, resource_type::PROJECT, false; "membership never holds type-level")]
+        fn should_evaluate_the_example_policy(
+            groups: &[&str],
+            action: Action,
+            resource_type: &str,
+            expected_allowed: bool,
+        ) {
+            // Arrange
+            let user = user(groups, "user@example.com");
+            let resource = resource(resource_type, &[], &[], None);
+
+            // Act
+            let res = example_engine().can_on_type(&Some(user), &action, &resource);
+
+            // Assert
+            assert_eq!(res.is_ok(), expected_allowed);
+        }
+
+        #[test]
+        fn should_return_ok_if_listing_projects_without_a_user() {
+            // Arrange
+            let resource = resource(resource_type::PROJECT, &[], &[], None);
+
+            // Act
+            let res = example_engine().can_on_type(&None, &Action::List, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_the_user_is_not_authenticated() {
+            // Arrange
+            let resource = resource(resource_type::PROJECTS_DASHBOARD, &[], &[], None);
+
+            // Act
+            let res = example_engine().can_on_type(&None, &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+    }
+
+    mod can_on_instance {
+        use super::*;
+
+        #[test_case::test_case(&["surveyors"], "user@example.com", Action::Read, resource_type::PROJECT, &["surveyors"], &[], None, true; "group member can read project")]
+        #[test_case::test_case(&["other"], "user@example.com", Action::Read, resource_type::PROJECT, &["surveyors"], &[], None, false; "non-member can not read project")]
+        #[test_case::test_case(&[], "Reviewer@Example.com", Action::Read, resource_type::POTREE_RENDER, &["surveyors"], &["reviewer@example.com"], None, true; "granted email can read render")]
+        #[test_case::test_case(&["surveyors"], "user@example.com", Action::Delete, resource_type::PROJECT, &["surveyors"], &[], None, false; "member can not delete project")]
+        #[test_case::test_case(&["surveyors"], "user@example.com", Action::Read, resource_type::PROJECT_ASSET, &["surveyors"], &[], Some("pointclouds/metadata.json"), true; "member can read asset")]
+        #[test_case::test_case(&["surveyors"], "user@example.com", Action::Read, resource_type::PROJECT_ASSET, &["surveyors"], &[], Some("raw/cloud.las"), true; "member can read raw asset")]
+        #[test_case::test_case(&["surveyors", "clients"], "user@example.com", Action::Read, resource_type::PROJECT_ASSET, &["surveyors"], &[], Some("raw/cloud.las"), false; "client can not read raw asset")]
+        #[test_case::test_case(&["surveyors", "clients"], "user@example.com", Action::Read, resource_type::PROJECT_ASSET, &["surveyors"], &[], Some("raw_notes.txt"), true; "client can read asset outside of raw")]
+        #[test_case::test_case(&["admin"], "admin@example.com", Action::Delete, resource_type::PROJECT, &[], &[], None, true; "admin can do anything")]
+        #[test_case::test_case(&["admin", "clients"], "admin@example.com", Action::Read, resource_type::PROJECT_ASSET, &[], &[], Some("raw/cloud.las"), false; "deny takes precedence over admin")]
+        #[allow(clippy::too_many_arguments)]
+        fn should_evaluate_the_example_policy(
+            user_groups: &[&str],
+            user_email: &str,
+            action: Action,
+            resource_type: &str,
+            resource_groups: &[&str],
+            resource_user_emails: &[&str],
+            asset_path: Option<&str>,
+            expected_allowed: bool,
+        ) {
+            // Arrange
+            let user = user(user_groups, user_email);
+            let resource = resource(
+                resource_type,
+                resource_groups,
+                resource_user_emails,
+                asset_path,
+            );
+
+            // Act
+            let res = example_engine().can_on_instance(&Some(user), &action, &resource);
+
+            // Assert
+            assert_eq!(res.is_ok(), expected_allowed);
+            if !expected_allowed {
+                assert!(matches!(
+                    res,
+                    Err(AuthorizationEngineError::NotAuthorized { .. })
+                ));
+            }
+        }
+
+        #[test_case::test_case("rules: [{ effect: allow, require_membership: true }]"; "granted in users")]
+        #[test_case::test_case("rules: [{ effect: allow, emails: [reviewer@example.com] }]"; "allowed by email")]
+        fn should_return_err_if_the_users_email_is_not_verified(policy: &str) {
+            // Arrange
+            let engine =
+                PolicyFileAuthorizationEngine::from_policy(policy, PolicyFormat::Yaml).unwrap();
+            let user = User {
+                email_verified: false,
+                ..user(&[], "reviewer@example.com")
+            };
+            let resource = resource(
+                resource_type::PROJECT,
+                &["surveyors"],
+                &["reviewer@example.com"],
+                None,
+            );
+
+            // Act
+            let res = engine.can_on_instance(&Some(user), &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_err_if_a_deny_rule_matches_an_unverified_email() {
+            // Arrange
+            let engine = PolicyFileAuthorizationEngine::from_policy(
+                "rules: [{ effect: allow, groups: [surveyors] }, { effect: deny, emails: [reviewer@example.com] }]",
+                PolicyFormat::Yaml,
+            )
+            .unwrap();
+            let user = User {
+                email_verified: false,
+                ..user(&["surveyors"], "reviewer@example.com")
+            };
+            let resource = resource(resource_type::PROJECT, &["surveyors"], &[], None);
+
+            // Act
+            let res = engine.can_on_instance(&Some(user), &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_err_if_no_rules() {
+            // Arrange
+            let engine =
+                PolicyFileAuthorizationEngine::from_policy("rules: []", PolicyFormat::Yaml)
+                    .unwrap();
+
+            // Act
+            let res = engine.can_on_instance(
+                &Some(User::dummy_admin()),
+                &Action::Read,
+                &resource(resource_type::PROJECT, &[], &[], None),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_err_if_the_user_is_not_authenticated() {
+            // Act
+            let res = example_engine().can_on_instance(
+                &None,
+                &Action::Read,
+                &resource(resource_type::PROJECT, &["surveyors"], &[], None),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
+        #[test_case::test_case(None; "not authenticated")]
+        #[test_case::test_case(Some(user(&["other"], "user@example.com")); "not a member")]
+        fn should_return_ok_if_reading_a_public_resource(user: Option<User>) {
+            // Arrange
+            let resource = MockedResource {
+                is_public: true,
+                ..resource(
+                    resource_type::PROJECT_ASSET,
+                    &["surveyors"],
+                    &[],
+                    Some("pointclouds/metadata.json"),
+                )
+            };
+
+            // Act
+            let res = example_engine().can_on_instance(&user, &Action::Read, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_a_deny_rule_matches_a_public_resource() {
+            // Arrange
+            let engine = PolicyFileAuthorizationEngine::from_policy(
+                "rules: [{ effect: deny, paths: ['raw/**'] }]",
+                PolicyFormat::Yaml,
+            )
+            .unwrap();
+            let resource = MockedResource {
+                is_public: true,
+                ..resource(
+                    resource_type::PROJECT_ASSET,
+                    &[],
+                    &[],
+                    Some("raw/cloud.las"),
+                )
+            };
+
+            // Act
+            let res = engine.can_on_instance(&None, &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+
+        #[test_case::test_case(&["clients"], time::Duration::days(1), None, Ok(()); "window open")]
+        #[test_case::test_case(&["clients"], time::Duration::days(-1), None, Err("access_expired"); "window expired")]
+        #[test_case::test_case(&["clients", "surveyors"], time::Duration::days(-1), None, Ok(()); "window of another group open")]
+        #[test_case::test_case(&["clients"], time::Duration::days(1), Some(time::Duration::days(-1)), Err("access_expired"); "project window expired")]
+        #[test_case::test_case(&["other"], time::Duration::days(1), Some(time::Duration::days(-1)), Err("not_authorized"); "non-member after project window")]
+        fn should_only_consider_a_group_member_within_its_access_window(
+            user_groups: &[&str],
+            group_valid_for: time::Duration,
+            project_valid_for: Option<time::Duration>,
+            expected: Result<(), &str>,
+        ) {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let engine = example_engine().with_clock(Arc::new(FixedClock(now)));
+            let resource = MockedResource {
+                access_window: project_valid_for.map(|valid_for| AccessWindow {
+                    valid_from: None,
+                    valid_until: Some(now + valid_for),
+                }),
+                group_access_windows: [(
+                    Group::new("clients"),
+                    AccessWindow {
+                        valid_from: None,
+                        valid_until: Some(now + group_valid_for),
+                    },
+                )]
+                .into(),
+                ..resource(resource_type::PROJECT, &["clients", "surveyors"], &[], None)
+            };
+
+            // Act
+            let res = engine.can_on_instance(
+                &Some(user(user_groups, "client@example.com")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert_eq!(res.map_err(|e| e.reason_code()), expected);
+        }
+
+        #[test]
+        fn should_return_when_the_access_expired() {
+            // Arrange
+            let now = OffsetDateTime::now_utc();
+            let engine = example_engine().with_clock(Arc::new(FixedClock(now)));
+            let resource = MockedResource {
+                group_access_windows: [(
+                    Group::new("clients"),
+                    AccessWindow {
+                        valid_from: None,
+                        valid_until: Some(now - time::Duration::days(1)),
+                    },
+                )]
+                .into(),
+                ..resource(resource_type::PROJECT, &["clients"], &[], None)
+            };
+
+            // Act
+            let res = engine.can_on_instance(
+                &Some(user(&["clients"], "client@example.com")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::AccessExpired { expired_at, .. }) if expired_at == now - time::Duration::days(1)
+            ))
+        }
+
+        #[test_case::test_case(&["clients", "surveyors"], "raw/cloud.las", true; "member of the rule's groups")]
+        #[test_case::test_case(&["clients"], "raw/cloud.las", false; "not a member of the rule's groups")]
+        #[test_case::test_case(&["clients"], "pointclouds/metadata.json", true; "path not matching")]
+        fn should_only_consider_a_member_if_the_path_rules_are_satisfied(
+            user_groups: &[&str],
+            asset_path: &str,
+            expected_allowed: bool,
+        ) {
+            // Arrange
+            let resource = MockedResource {
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..resource(
+                    resource_type::PROJECT_ASSET,
+                    &["clients"],
+                    &[],
+                    Some(asset_path),
+                )
+            };
+            let engine = PolicyFileAuthorizationEngine::from_policy(
+                "rules: [{ effect: allow, require_membership: true }]",
+                PolicyFormat::Yaml,
+            )
+            .unwrap();
+
+            // Act
+            let res = engine.can_on_instance(
+                &Some(user(user_groups, "client@example.com")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert_eq!(res.is_ok(), expected_allowed);
+        }
+
+        #[test]
+        fn should_not_apply_allow_rules_without_a_user() {
+            // Arrange
+            let engine = PolicyFileAuthorizationEngine::from_policy(
+                "rules: [{ effect: allow }]",
+                PolicyFormat::Yaml,
+            )
+            .unwrap();
+
+            // Act
+            let res = engine.can_on_instance(
+                &None,
+                &Action::Read,
+                &resource(resource_type::PROJECT, &[], &[], None),
+            );
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::NotAuthenticated)
+            ))
+        }
+    }
+
+    mod can_on_instance_with_share_link {
+        use time::Duration;
+        use time::OffsetDateTime;
+        use time::macros::datetime;
+
+        use super::*;
+        use crate::project::domain::ProjectId;
+
+        const NOW: OffsetDateTime = datetime!(2026-03-01 12:00 UTC);
+
+        fn share_link() -> ShareLink {
+            ShareLink {
+                project_id: ProjectId::new("project_1".to_owned()),
+                path_prefix: None,
+                expires_at: NOW + Duration::days(7),
+            }
+        }
+
+        fn project_resource(access_window: Option<AccessWindow>) -> MockedResource {
+            MockedResource {
+                project_id: Some(ProjectId::new("project_1".to_owned())),
+                access_window,
+                ..resource("project-asset", &[], &[], Some("pointclouds/metadata.json"))
+            }
+        }
+
+        #[test]
+        fn should_return_ok_if_reading_within_the_project_access_window() {
+            // Arrange
+            let engine = example_engine().with_clock(Arc::new(FixedClock(NOW)));
+            let resource = project_resource(Some(AccessWindow {
+                valid_from: None,
+                valid_until: Some(NOW + Duration::days(1)),
+            }));
+
+            // Act
+            let res =
+                engine.can_on_instance_with_share_link(&share_link(), &Action::Read, &resource);
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn should_return_err_if_asset_restricted_by_a_path_rule() {
+            // Arrange
+            let engine = example_engine().with_clock(Arc::new(FixedClock(NOW)));
+            let resource = MockedResource {
+                asset_path: Some(PathBuf::from("raw/cloud.las")),
+                path_rules: Some(vec![
+                    PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")])
+                        .unwrap(),
+                ]),
+                ..project_resource(None)
+            };
+
+            // Act
+            let res =
+                engine.can_on_instance_with_share_link(&share_link(), &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+
+        #[test]
+        fn should_return_access_expired_after_the_project_access_window() {
+            // Arrange
+            let engine = example_engine().with_clock(Arc::new(FixedClock(NOW)));
+            let resource = project_resource(Some(AccessWindow {
+                valid_from: None,
+                valid_until: Some(NOW - Duration::days(1)),
+            }));
+
+            // Act
+            let res =
+                engine.can_on_instance_with_share_link(&share_link(), &Action::Read, &resource);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(AuthorizationEngineError::AccessExpired { expired_at, .. }) if expired_at == NOW - Duration::days(1)
+            ))
+        }
+    }
+
+    mod explain {
+        use super::*;
+        use crate::authorization::domain::explanation::CheckKind;
+
+        #[test]
+        fn should_mark_the_deny_rule_that_decided_the_outcome() {
+            // Arrange
+            let resource = resource(
+                resource_type::PROJECT_ASSET,
+                &["surveyors"],
+                &[],
+                Some("raw/cloud.las"),
+            );
+
+            // Act
+            let explanation = example_engine().explain(
+                &Some(user(&["surveyors", "clients"], "client@example.com")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(!explanation.allowed);
+            assert_eq!(explanation.reason.as_deref(), Some("not_authorized"));
+            assert_eq!(explanation.checks.len(), 5);
+            assert!(explanation.checks[3].holds);
+            assert!(!explanation.checks[3].decisive);
+            assert_eq!(
+                explanation.decisive_check().unwrap().description,
+                "rule 5 (deny): Clients can't download the raw deliverables."
+            );
+        }
+
+        #[test]
+        fn should_fall_back_to_the_default_if_no_rule_matches() {
+            // Arrange
+            let resource = resource(resource_type::PROJECT, &["surveyors"], &[], None);
+
+            // Act
+            let explanation = example_engine().explain(
+                &Some(user(&["other"], "user@example.com")),
+                &Action::Read,
+                &resource,
+            );
+
+            // Assert
+            assert!(!explanation.allowed);
+            assert!(explanation.checks[..4].iter().all(|check| !check.holds));
+            assert_eq!(
+                explanation.decisive_check().unwrap().kind,
+                CheckKind::Default
+            );
+        }
+
+        #[test]
+        fn should_mark_the_public_check_if_public() {
+            // Arrange
+            let resource = MockedResource {
+                is_public: true,
+                ..resource(resource_type::PROJECT, &[], &[], None)
+            };
+
+            // Act
+            let explanation = example_engine().explain(&None, &Action::Read, &resource);
+
+            // Assert
+            assert!(explanation.allowed);
+            assert_eq!(
+                explanation.decisive_check().unwrap().kind,
+                CheckKind::Public
+            );
+        }
+    }
+}
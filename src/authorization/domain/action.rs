@@ -1,7 +1,8 @@
 /// Defines actions that can be performed on a resource.
 ///
 /// Used for authorization purposes.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 #[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
 pub enum Action {
     Read,
@@ -1,6 +1,9 @@
+use time::OffsetDateTime;
+
 use super::action::Action;
 use super::resource::ResourceIdentifier;
 use super::resource::ResourceType;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -13,6 +16,34 @@ pub enum AuthorizationEngineError {
         resource_type: ResourceType,
     },
 
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
     #[error("user is not authenticated")]
     NotAuthenticated,
+
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+}
+
+impl AuthorizationEngineError {
+    /// A short code describing why a request was denied (e.g. as recorded in
+    /// the audit log).
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::NotAuthorized { .. } => "not_authorized",
+            Self::ShareLinkNotAuthorized { .. } => "share_link_not_authorized",
+            Self::NotAuthenticated => "not_authenticated",
+            Self::AccessExpired { .. } => "access_expired",
+        }
+    }
 }
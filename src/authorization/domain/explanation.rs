@@ -0,0 +1,237 @@
+//! Explains how an authorization engine reached a decision, e.g. to answer
+//! support tickets along the lines of "why can't I see this project?".
+
+use serde::Serialize;
+
+use super::action::Action;
+use super::error::AuthorizationEngineError;
+use super::resource::ResourceIdentifier;
+use super::resource::ResourceInstance;
+use super::resource::ResourceType;
+use crate::project::domain::ProjectId;
+
+/// The decision of an authorization engine on a resource instance, along with
+/// the checks that were evaluated to reach it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    /// The action that was authorized.
+    pub action: Action,
+
+    /// The type of the resource that the action was authorized on.
+    pub resource_type: ResourceType,
+
+    /// The resource that the action was authorized on.
+    pub resource_identifier: ResourceIdentifier,
+
+    /// The project that the resource belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<ProjectId>,
+
+    /// Whether the action is allowed.
+    pub allowed: bool,
+
+    /// Why the action is denied (e.g. `not_authorized`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// The checks in the order that they were evaluated. The check that
+    /// decided the outcome is marked as `decisive`.
+    pub checks: Vec<Check>,
+}
+
+impl Explanation {
+    /// Explains the `result` of authorizing the `action` on the `resource`,
+    /// which was reached by evaluating the `checks`.
+    pub fn new(
+        result: &Result<(), AuthorizationEngineError>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+        checks: Vec<Check>,
+    ) -> Self {
+        Self {
+            action: action.clone(),
+            resource_type: resource.resource_type(),
+            resource_identifier: resource.resource_identifier(),
+            project_id: resource.project_id(),
+            allowed: result.is_ok(),
+            reason: result
+                .as_ref()
+                .err()
+                .map(|error| error.reason_code().to_owned()),
+            checks,
+        }
+    }
+
+    /// The check that decided the outcome.
+    pub fn decisive_check(&self) -> Option<&Check> {
+        self.checks.iter().find(|check| check.decisive)
+    }
+}
+
+/// A single check evaluated by an authorization engine.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Check {
+    /// What was checked.
+    pub kind: CheckKind,
+
+    /// A human readable description of the check (e.g. which groups were
+    /// compared).
+    pub description: String,
+
+    /// Whether the condition of the check holds.
+    pub holds: bool,
+
+    /// Whether this check decided the outcome.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub decisive: bool,
+}
+
+impl Check {
+    pub fn new(kind: CheckKind, holds: bool, description: impl Into<String>) -> Self {
+        Self {
+            kind,
+            description: description.into(),
+            holds,
+            decisive: false,
+        }
+    }
+
+    /// Marks the check as having decided the outcome, if `decisive`.
+    pub fn decisive(mut self, decisive: bool) -> Self {
+        self.decisive = decisive;
+        self
+    }
+}
+
+/// The kinds of checks that authorization engines evaluate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckKind {
+    /// Whether the action is allowed on the resource at all.
+    Action,
+
+    /// Whether the user is a member of an admin group.
+    Admin,
+
+    /// Whether the access window of the resource is open.
+    AccessWindow,
+
+    /// Whether the user is a member of the groups required by a path rule of
+    /// the manifest.
+    PathRule,
+
+    /// Whether the resource is public.
+    Public,
+
+    /// Whether there is a user.
+    Authenticated,
+
+    /// Whether the user's email address is listed in the manifest.
+    UserEmail,
+
+    /// Whether the user is a member of a group of the manifest, within the
+    /// group's access window.
+    Group,
+
+    /// Whether a rule of the policy file matches.
+    PolicyRule,
+
+    /// What happens if no other check decided the outcome.
+    Default,
+}
+
+/// Collects the checks evaluated by an authorization engine, if enabled.
+///
+/// The engines evaluate their checks with a disabled trace for regular
+/// decisions, so that describing the checks doesn't slow down every request.
+#[derive(Debug, Default)]
+pub struct ExplanationTrace {
+    checks: Option<Vec<Check>>,
+}
+
+impl ExplanationTrace {
+    /// A trace that collects the checks.
+    pub fn enabled() -> Self {
+        Self {
+            checks: Some(Vec::new()),
+        }
+    }
+
+    /// A trace that discards the checks, without building them.
+    pub fn disabled() -> Self {
+        Self { checks: None }
+    }
+
+    /// Records the `check`, if the trace is enabled.
+    pub fn record(&mut self, check: impl FnOnce() -> Check) {
+        if let Some(checks) = self.checks.as_mut() {
+            checks.push(check());
+        }
+    }
+
+    /// The recorded checks, in the order that they were recorded.
+    pub fn into_checks(self) -> Vec<Check> {
+        self.checks.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod explanation_tests {
+    use super::*;
+
+    mod explanation_trace {
+        use super::*;
+
+        #[test]
+        fn should_collect_the_checks_if_enabled() {
+            // Arrange
+            let mut trace = ExplanationTrace::enabled();
+
+            // Act
+            trace.record(|| Check::new(CheckKind::Admin, false, "not an admin"));
+            trace.record(|| Check::new(CheckKind::Public, true, "public").decisive(true));
+
+            // Assert
+            assert_eq!(
+                trace.into_checks(),
+                vec![
+                    Check::new(CheckKind::Admin, false, "not an admin"),
+                    Check::new(CheckKind::Public, true, "public").decisive(true),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_not_build_the_checks_if_disabled() {
+            // Arrange
+            let mut trace = ExplanationTrace::disabled();
+
+            // Act
+            trace.record(|| unreachable!("the check shouldn't be built"));
+
+            // Assert
+            assert!(trace.into_checks().is_empty());
+        }
+    }
+
+    #[test]
+    fn should_serialize_the_kind_in_kebab_case_and_skip_undecisive_checks() {
+        // Arrange
+        let checks = vec![
+            Check::new(CheckKind::AccessWindow, true, "always open"),
+            Check::new(CheckKind::PathRule, false, "requires surveyors").decisive(true),
+        ];
+
+        // Act
+        let json = serde_json::to_value(&checks).unwrap();
+
+        // Assert
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"kind": "access-window", "description": "always open", "holds": true},
+                {"kind": "path-rule", "description": "requires surveyors", "holds": false, "decisive": true},
+            ])
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod action;
 pub mod error;
+pub mod explanation;
 pub mod resource;
@@ -1,7 +1,11 @@
 use std::fmt::Debug;
+use std::path::PathBuf;
 
+use crate::common::domain::AccessWindow;
 use crate::common::domain::Group;
 use crate::common::domain::utils::new_type::new_type;
+use crate::project::domain::ProjectId;
+use crate::project::domain::path_rule::PathRule;
 use crate::user::domain::EmailAddress;
 
 /// Defines a resource type that can be authorized against.
@@ -25,17 +29,45 @@ pub trait ResourceInstance: Resource {
     /// resource types won't be associated with users, in this case this
     /// should return None.
     fn user_emails(&self) -> Option<Vec<EmailAddress>>;
+
+    /// The project that the resource belongs to. Some resource types won't be
+    /// associated with a project, in this case this should return None.
+    fn project_id(&self) -> Option<ProjectId>;
+
+    /// The path of the resource within its project. Some resource types won't
+    /// be associated with a path, in this case this should return None.
+    fn asset_path(&self) -> Option<PathBuf>;
+
+    /// Whether anyone, including users that aren't authenticated, may _read_
+    /// the resource (e.g. the assets of a public project).
+    fn is_public(&self) -> bool;
+
+    /// When the resource can be accessed at all (by anyone other than admins).
+    /// Some resource types won't be time-bounded, in this case this should
+    /// return None.
+    fn access_window(&self) -> Option<AccessWindow>;
+
+    /// When the members of the `group` can access the resource. Returns None if
+    /// their access isn't time-bounded.
+    fn group_access_window(&self, group: &Group) -> Option<AccessWindow>;
+
+    /// The rules restricting paths within the resource to additional groups,
+    /// evaluated against the [`Self::asset_path`]. Some resource types won't
+    /// have path rules, in this case this should return None.
+    fn path_rules(&self) -> Option<Vec<PathRule>>;
 }
 
 new_type![
     /// The identifier of a resource type (type-level). E.g. a "user", a "group",
     /// a "file", etc.
+    #[derive(serde::Deserialize, serde::Serialize)]
     ResourceType(String)
 ];
 
 new_type![
     /// The identifier of a specific resource instance (instance-level). E.g. a
     /// user id, a group id, a file id, etc.
+    #[derive(serde::Deserialize, serde::Serialize)]
     ResourceIdentifier(String)
 ];
 
@@ -45,8 +77,14 @@ new_type![
 /// I was struggling to get mockall to deal with the supertrait mocking.
 #[cfg(any(test, feature = "mock"))]
 pub mod mocked_resource {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::AccessWindow;
     use super::EmailAddress;
     use super::Group;
+    use super::PathRule;
+    use super::ProjectId;
     use super::Resource;
     use super::ResourceIdentifier;
     use super::ResourceInstance;
@@ -58,6 +96,15 @@ pub mod mocked_resource {
         pub resource_identifier: ResourceIdentifier,
         pub groups: Option<Vec<Group>>,
         pub user_emails: Option<Vec<EmailAddress>>,
+        pub project_id: Option<ProjectId>,
+        pub asset_path: Option<PathBuf>,
+        pub is_public: bool,
+        #[dummy(default)]
+        pub access_window: Option<AccessWindow>,
+        #[dummy(default)]
+        pub group_access_windows: HashMap<Group, AccessWindow>,
+        #[dummy(default)]
+        pub path_rules: Option<Vec<PathRule>>,
     }
 
     impl Resource for MockedResource {
@@ -78,5 +125,29 @@ pub mod mocked_resource {
         fn user_emails(&self) -> Option<Vec<EmailAddress>> {
             self.user_emails.clone()
         }
+
+        fn project_id(&self) -> Option<ProjectId> {
+            self.project_id.clone()
+        }
+
+        fn asset_path(&self) -> Option<PathBuf> {
+            self.asset_path.clone()
+        }
+
+        fn is_public(&self) -> bool {
+            self.is_public
+        }
+
+        fn access_window(&self) -> Option<AccessWindow> {
+            self.access_window
+        }
+
+        fn group_access_window(&self, group: &Group) -> Option<AccessWindow> {
+            self.group_access_windows.get(group).copied()
+        }
+
+        fn path_rules(&self) -> Option<Vec<PathRule>> {
+            self.path_rules.clone()
+        }
     }
 }
@@ -2,8 +2,10 @@ use std::fmt::Debug;
 
 use super::super::domain::action::Action;
 use super::super::domain::error::AuthorizationEngineError;
+use super::super::domain::explanation::Explanation;
 use crate::authorization::domain::resource::Resource;
 use crate::authorization::domain::resource::ResourceInstance;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// Defines the functionality that needs to be implemented for the application
@@ -43,4 +45,31 @@ pub trait AuthorizationEngine: Debug + Send + Sync + 'static {
         action: &Action,
         resource: &dyn ResourceInstance,
     ) -> Result<(), AuthorizationEngineError>;
+
+    /// Determines if a `share_link` should be authorized to perform the
+    /// `action` on the specified resource (instance-level).
+    ///
+    /// A share link is a distinct principal from a user. Its expiry is
+    /// expected to have been verified before calling this.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthorizationEngineError::ShareLinkNotAuthorized`] is returned if
+    ///   the `share_link` does not cover the resource.
+    fn can_on_instance_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Result<(), AuthorizationEngineError>;
+
+    /// Explains the decision that [`Self::can_on_instance`] makes for the
+    /// `user`, listing the checks that were evaluated and which of them decided
+    /// the outcome.
+    fn explain(
+        &self,
+        user: &Option<User>,
+        action: &Action,
+        resource: &dyn ResourceInstance,
+    ) -> Explanation;
 }
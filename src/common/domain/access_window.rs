@@ -0,0 +1,141 @@
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// The period during which access to a resource (or access granted to a group)
+/// is valid, e.g. for the duration of a contract. Either end may be open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
+pub struct AccessWindow {
+    /// Access is not valid before this time.
+    #[cfg_attr(any(test, feature = "fake"), dummy(default))]
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub valid_from: Option<OffsetDateTime>,
+
+    /// Access is not valid from this time onwards.
+    #[cfg_attr(any(test, feature = "fake"), dummy(default))]
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub valid_until: Option<OffsetDateTime>,
+}
+
+impl AccessWindow {
+    /// Whether access is valid at `now`.
+    pub fn contains(&self, now: OffsetDateTime) -> bool {
+        self.valid_from.is_none_or(|valid_from| valid_from <= now)
+            && self.valid_until.is_none_or(|valid_until| now < valid_until)
+    }
+
+    /// Returns when access expired, if it has expired by `now` (rather than
+    /// not having started yet).
+    pub fn expired_at(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.valid_until.filter(|valid_until| *valid_until <= now)
+    }
+}
+
+impl std::fmt::Display for AccessWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let format =
+            |timestamp: OffsetDateTime| timestamp.format(&Rfc3339).map_err(|_e| std::fmt::Error);
+
+        match (self.valid_from, self.valid_until) {
+            (None, None) => write!(f, "at any time"),
+            (Some(valid_from), None) => write!(f, "from {}", format(valid_from)?),
+            (None, Some(valid_until)) => write!(f, "until {}", format(valid_until)?),
+            (Some(valid_from), Some(valid_until)) => write!(
+                f,
+                "from {} until {}",
+                format(valid_from)?,
+                format(valid_until)?
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod access_window_tests {
+    use time::Duration;
+    use time::macros::datetime;
+
+    use super::*;
+
+    const NOW: OffsetDateTime = datetime!(2026-03-01 12:00 UTC);
+
+    fn window(valid_from: Option<Duration>, valid_until: Option<Duration>) -> AccessWindow {
+        AccessWindow {
+            valid_from: valid_from.map(|offset| NOW + offset),
+            valid_until: valid_until.map(|offset| NOW + offset),
+        }
+    }
+
+    mod contains {
+        use super::*;
+
+        #[test_case::test_case(None, None, true; "unbounded")]
+        #[test_case::test_case(Some(Duration::days(-1)), Some(Duration::days(1)), true; "within")]
+        #[test_case::test_case(Some(Duration::ZERO), None, true; "starting now")]
+        #[test_case::test_case(Some(Duration::days(1)), None, false; "not started")]
+        #[test_case::test_case(None, Some(Duration::ZERO), false; "ending now")]
+        #[test_case::test_case(None, Some(Duration::days(-1)), false; "ended")]
+        fn should_compare_now_to_the_window(
+            valid_from: Option<Duration>,
+            valid_until: Option<Duration>,
+            expected: bool,
+        ) {
+            // Act
+            let contains = window(valid_from, valid_until).contains(NOW);
+
+            // Assert
+            assert_eq!(contains, expected);
+        }
+    }
+
+    mod expired_at {
+        use super::*;
+
+        #[test]
+        fn should_return_the_end_of_an_expired_window() {
+            // Act
+            let expired_at = window(None, Some(Duration::days(-1))).expired_at(NOW);
+
+            // Assert
+            assert_eq!(expired_at, Some(NOW - Duration::days(1)));
+        }
+
+        #[test_case::test_case(Some(Duration::days(1)), Some(Duration::days(2)); "not started")]
+        #[test_case::test_case(None, Some(Duration::days(1)); "not ended")]
+        #[test_case::test_case(None, None; "unbounded")]
+        fn should_return_none_if_not_expired(
+            valid_from: Option<Duration>,
+            valid_until: Option<Duration>,
+        ) {
+            // Act
+            let expired_at = window(valid_from, valid_until).expired_at(NOW);
+
+            // Assert
+            assert_eq!(expired_at, None);
+        }
+    }
+
+    #[test_case::test_case(None, None, "at any time"; "unbounded")]
+    #[test_case::test_case(Some(Duration::days(-1)), Some(Duration::days(1)), "from 2026-02-28T12:00:00Z until 2026-03-02T12:00:00Z"; "bounded")]
+    fn should_display_the_bounds(
+        valid_from: Option<Duration>,
+        valid_until: Option<Duration>,
+        expected: &str,
+    ) {
+        // Act
+        let displayed = window(valid_from, valid_until).to_string();
+
+        // Assert
+        assert_eq!(displayed, expected);
+    }
+}
@@ -10,4 +10,8 @@ impl Group {
     pub fn new(name: &str) -> Self {
         Self(name.to_owned())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
@@ -1,7 +1,9 @@
+pub mod access_window;
 pub mod group;
 pub mod resource_type;
 pub mod static_asset;
 pub mod utils;
 
+pub use access_window::AccessWindow;
 pub use group::Group;
 pub use static_asset::StaticAsset;
@@ -6,3 +6,6 @@ pub const PROJECT: &str = "project";
 pub const PROJECTS_DASHBOARD: &str = "projects-dashboard";
 pub const PROJECT_ASSET: &str = "project-asset";
 pub const POTREE_RENDER: &str = "potree-render";
+pub const SHARE_LINK: &str = "share-link";
+pub const ACCESS_EXPLANATION: &str = "access-explanation";
+pub const VALIDATION_REPORT: &str = "validation-report";
@@ -0,0 +1,31 @@
+use std::fmt::Debug;
+
+use time::OffsetDateTime;
+
+/// Provides the current time, so that time dependent logic (e.g. access
+/// windows) can be tested deterministically.
+pub trait Clock: Debug + Send + Sync + 'static {
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// A [`Clock`] that reads the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// A [`Clock`] that is stuck at a specific time, for testing purposes.
+#[cfg(any(test, feature = "mock"))]
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub OffsetDateTime);
+
+#[cfg(any(test, feature = "mock"))]
+impl Clock for FixedClock {
+    fn now(&self) -> OffsetDateTime {
+        self.0
+    }
+}
@@ -1,2 +1,4 @@
+pub mod clock;
 pub mod last_modified;
 pub mod new_type;
+pub mod path_glob;
@@ -0,0 +1,57 @@
+use globset::GlobBuilder;
+use globset::GlobSet;
+use globset::GlobSetBuilder;
+
+/// Builds a [`GlobSet`] matching asset paths against any of the `patterns`
+/// (e.g. `raw/**`). `*` does not match across directories, `**` does.
+///
+/// Returns a message describing the first invalid pattern.
+pub fn path_glob_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut glob_set = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| format!("invalid path glob `{pattern}`: {e}"))?;
+        glob_set.add(glob);
+    }
+
+    glob_set
+        .build()
+        .map_err(|e| format!("invalid path globs: {e}"))
+}
+
+#[cfg(test)]
+mod path_glob_tests {
+    use super::*;
+
+    mod path_glob_set {
+        use super::*;
+
+        #[test_case::test_case("raw/cloud.las", true; "nested file")]
+        #[test_case::test_case("raw/2024/cloud.las", true; "deeply nested file")]
+        #[test_case::test_case("reports/summary.pdf", true; "file in directory")]
+        #[test_case::test_case("reports/2024/summary.pdf", false; "star does not cross directories")]
+        #[test_case::test_case("raw_notes.txt", false; "similar prefix")]
+        fn should_match_the_asset_paths(asset_path: &str, expected: bool) {
+            // Arrange
+            let glob_set =
+                path_glob_set(&["raw/**".to_owned(), "reports/*.pdf".to_owned()]).unwrap();
+
+            // Act
+            let is_match = glob_set.is_match(asset_path);
+
+            // Assert
+            assert_eq!(is_match, expected);
+        }
+
+        #[test]
+        fn should_return_err_if_a_pattern_is_invalid() {
+            // Act
+            let res = path_glob_set(&["raw/**".to_owned(), "raw/[".to_owned()]);
+
+            // Assert
+            assert!(res.unwrap_err().contains("raw/["));
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use axum::response::IntoResponse;
 use http::StatusCode;
+use http::header;
+use time::OffsetDateTime;
 
 use crate::authorization::domain::action::Action;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceType;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// Errors that can be experienced by an API `axum` route handler.
@@ -27,9 +30,39 @@ pub enum ApiError {
         resource_type: ResourceType,
     },
 
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
     #[error("user is not authenticated")]
     NotAuthenticated,
 
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
+    #[error("the bearer token is invalid: {message}")]
+    InvalidBearerToken { message: String },
+
+    #[error("{message}")]
+    InvalidShareLink { message: String },
+
+    #[error("the request is invalid: {message}")]
+    InvalidRequest { message: String },
+
+    #[error("the request conflicts with the current state: {message}")]
+    Conflict { message: String },
+
+    #[error("access is forbidden: {message}")]
+    Forbidden { message: String },
+
     #[error("the server is not configured correctly: {message}")]
     ServerConfiguration { message: String },
 
@@ -50,10 +83,28 @@ impl IntoResponse for ApiError {
             ApiError::ResourceNotFound { .. } => {
                 (StatusCode::NOT_FOUND, self.to_string()).into_response()
             }
-            ApiError::NotAuthorized { .. } => {
+            ApiError::InvalidRequest { .. } => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            ApiError::Conflict { .. } => (StatusCode::CONFLICT, self.to_string()).into_response(),
+            ApiError::NotAuthorized { .. }
+            | ApiError::ShareLinkNotAuthorized { .. }
+            | ApiError::AccessExpired { .. }
+            | ApiError::InvalidShareLink { .. }
+            | ApiError::Forbidden { .. } => {
                 (StatusCode::FORBIDDEN, self.to_string()).into_response()
             }
-            ApiError::NotAuthenticated => (StatusCode::UNAUTHORIZED).into_response(),
+            // Indicate to programmatic clients how they should authenticate.
+            ApiError::NotAuthenticated => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+            )
+                .into_response(),
+            ApiError::InvalidBearerToken { .. } => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, r#"Bearer error="invalid_token""#)],
+            )
+                .into_response(),
         }
     }
 }
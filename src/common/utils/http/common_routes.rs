@@ -3,19 +3,75 @@
 
 use std::sync::LazyLock;
 
+use axum::Extension;
+use axum::Json;
 use axum::Router;
 use axum::routing::get;
-use http::StatusCode;
+use serde::Serialize;
 use web_route::WebRoute;
 
+use crate::authentication::application::service::AuthenticationService;
+use crate::authentication::domain::IdpStatus;
+use crate::user::domain::ProviderName;
+
 static HEALTH: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/_health"));
 
-pub fn build_router() -> Router {
-    Router::new().route(&HEALTH, get(health_check))
+pub fn build_router(authentication_service: AuthenticationService) -> Router {
+    Router::new()
+        .route(&HEALTH, get(health_check))
+        .layer(Extension(authentication_service))
+}
+
+/// The health of the server, and of the identity providers it depends on.
+#[derive(Debug, Serialize)]
+struct HealthReport {
+    /// `degraded` if users can't authenticate with one of the identity
+    /// providers, else `ok`.
+    status: HealthStatus,
+
+    identity_providers: Vec<IdentityProviderHealth>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HealthStatus {
+    Ok,
+    Degraded,
 }
 
-/// Responds with an empty 200. Used to check if the server is ready to accept
-/// requests.
-async fn health_check() -> StatusCode {
-    StatusCode::OK
+#[derive(Debug, Serialize)]
+struct IdentityProviderHealth {
+    name: ProviderName,
+
+    #[serde(flatten)]
+    status: IdpStatus,
+}
+
+/// Responds with a 200 and a report of the server's health. Used to check if
+/// the server is ready to accept requests.
+///
+/// An unavailable identity provider only degrades the server (rather than
+/// failing the check), as the server keeps retrying to reach it.
+async fn health_check(
+    Extension(authentication_service): Extension<AuthenticationService>,
+) -> Json<HealthReport> {
+    let identity_providers = authentication_service
+        .provider_statuses()
+        .into_iter()
+        .map(|(name, status)| IdentityProviderHealth { name, status })
+        .collect::<Vec<_>>();
+
+    let status = if identity_providers
+        .iter()
+        .all(|provider| provider.status.is_available())
+    {
+        HealthStatus::Ok
+    } else {
+        HealthStatus::Degraded
+    };
+
+    Json(HealthReport {
+        status,
+        identity_providers,
+    })
 }
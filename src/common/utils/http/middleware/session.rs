@@ -1,19 +1,174 @@
+use async_trait::async_trait;
 use axum::Router;
 use time::Duration;
 use tower_sessions::Expiry;
 use tower_sessions::MemoryStore;
 use tower_sessions::SessionManagerLayer;
+use tower_sessions::SessionStore;
 use tower_sessions::cookie;
+use tower_sessions::session::Id;
+use tower_sessions::session::Record;
+use tower_sessions::session_store;
+use tower_sessions_sqlx_store::SqliteStore;
+
+/// The options applied to the session cookie.
+#[derive(Debug, Clone)]
+pub struct SessionCookieOptions {
+    /// Whether the cookie should only be sent over HTTPS.
+    pub secure: bool,
+
+    /// The `SameSite` policy of the cookie.
+    pub same_site: cookie::SameSite,
+
+    /// How long a session can be inactive before it expires.
+    pub inactivity_expiry: Duration,
+}
+
+/// The backends that can be used to persist web sessions.
+///
+/// An enum (rather than a trait object) is used as [`SessionManagerLayer`]
+/// requires a concrete, clonable [`SessionStore`].
+#[derive(Debug, Clone)]
+pub enum SessionBackend {
+    /// Sessions are kept in memory, they are lost when the server restarts.
+    Memory(MemoryStore),
+
+    /// Sessions are persisted to a SQLite database, they survive server
+    /// restarts.
+    Sqlite(SqliteStore),
+}
+
+#[async_trait]
+impl SessionStore for SessionBackend {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        match self {
+            Self::Memory(store) => store.create(record).await,
+            Self::Sqlite(store) => store.create(record).await,
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        match self {
+            Self::Memory(store) => store.save(record).await,
+            Self::Sqlite(store) => store.save(record).await,
+        }
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        match self {
+            Self::Memory(store) => store.load(session_id).await,
+            Self::Sqlite(store) => store.load(session_id).await,
+        }
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        match self {
+            Self::Memory(store) => store.delete(session_id).await,
+            Self::Sqlite(store) => store.delete(session_id).await,
+        }
+    }
+}
 
 /// Applies a web session management layer to the router.
-pub fn apply_session_layer(router: Router, session_expiry: Duration) -> Router {
-    let session_store = MemoryStore::default();
-    let session_layer = SessionManagerLayer::new(session_store)
-        .with_secure(true)
+pub fn apply_session_layer(
+    router: Router,
+    session_backend: SessionBackend,
+    cookie_options: SessionCookieOptions,
+) -> Router {
+    let SessionCookieOptions {
+        secure,
+        same_site,
+        inactivity_expiry,
+    } = cookie_options;
+
+    let session_layer = SessionManagerLayer::new(session_backend)
+        .with_secure(secure)
         .with_http_only(true)
-        // Else the redirect from the IdP does not work?
-        .with_same_site(cookie::SameSite::None)
-        .with_expiry(Expiry::OnInactivity(session_expiry));
+        .with_same_site(same_site)
+        .with_expiry(Expiry::OnInactivity(inactivity_expiry));
 
     router.layer(session_layer)
 }
+
+#[cfg(test)]
+mod session_backend_tests {
+    use std::collections::HashMap;
+
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::sqlite::SqlitePool;
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    /// Creates a migrated SQLite backend in the `dir`.
+    async fn sqlite_backend<P: AsRef<std::path::Path>>(dir: P) -> SessionBackend {
+        let options = SqliteConnectOptions::new()
+            .filename(dir.as_ref().join("sessions.sqlite"))
+            .create_if_missing(true);
+        let store = SqliteStore::new(SqlitePool::connect_with(options).await.unwrap());
+        store.migrate().await.unwrap();
+
+        SessionBackend::Sqlite(store)
+    }
+
+    fn dummy_record() -> Record {
+        Record {
+            id: Id::default(),
+            data: HashMap::from([("key".to_owned(), serde_json::json!("value"))]),
+            expiry_date: OffsetDateTime::now_utc() + Duration::hours(1),
+        }
+    }
+
+    mod sqlite {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_load_a_saved_record() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let backend = sqlite_backend(&dir).await;
+            let mut record = dummy_record();
+
+            // Act
+            backend.create(&mut record).await.unwrap();
+            let loaded = backend.load(&record.id).await.unwrap();
+
+            // Assert
+            assert_eq!(loaded, Some(record));
+        }
+
+        #[tokio::test]
+        async fn should_persist_records_across_store_instances() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let mut record = dummy_record();
+            sqlite_backend(&dir)
+                .await
+                .create(&mut record)
+                .await
+                .unwrap();
+
+            // Act
+            let loaded = sqlite_backend(&dir).await.load(&record.id).await.unwrap();
+
+            // Assert
+            assert_eq!(loaded, Some(record));
+        }
+
+        #[tokio::test]
+        async fn should_not_load_a_deleted_record() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let backend = sqlite_backend(&dir).await;
+            let mut record = dummy_record();
+            backend.create(&mut record).await.unwrap();
+
+            // Act
+            backend.delete(&record.id).await.unwrap();
+            let loaded = backend.load(&record.id).await.unwrap();
+
+            // Assert
+            assert_eq!(loaded, None);
+        }
+    }
+}
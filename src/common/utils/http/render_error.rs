@@ -1,9 +1,11 @@
 use axum::response::IntoResponse;
 use http::StatusCode;
+use time::OffsetDateTime;
 
 use crate::authorization::domain::action::Action;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceType;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// Errors that can be experienced by a rendering (e.g. HTML) `axum` route
@@ -32,9 +34,24 @@ pub enum RenderError {
         resource_type: ResourceType,
     },
 
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
     #[error("user is not authenticated")]
     NotAuthenticated,
 
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
     #[error("the server is not configured correctly: {message}")]
     ServerConfiguration { message: String },
 
@@ -65,7 +82,9 @@ impl IntoResponse for RenderError {
             RenderError::ResourceNotFound { .. } => {
                 (StatusCode::NOT_FOUND, self.to_string()).into_response()
             }
-            RenderError::NotAuthorized { .. } => {
+            RenderError::NotAuthorized { .. }
+            | RenderError::ShareLinkNotAuthorized { .. }
+            | RenderError::AccessExpired { .. } => {
                 (StatusCode::FORBIDDEN, self.to_string()).into_response()
             }
             RenderError::NotAuthenticated | RenderError::AuthenticationFlow { .. } => {
@@ -0,0 +1,114 @@
+use std::path::Component;
+use std::path::PathBuf;
+
+use percent_encoding::percent_decode_str;
+
+/// The project asset that a request forwarded by a reverse proxy is for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedAsset {
+    /// The path of the asset, starting with the id of its project (which can
+    /// have multiple segments).
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ForwardedAssetError {
+    #[error("the forwarded uri ({uri}) is not a project asset: {message}")]
+    InvalidUri { uri: String, message: String },
+}
+
+impl ForwardedAsset {
+    /// Maps the original `uri` of a forwarded request (e.g.
+    /// `/project-assets/project_1/pointclouds/metadata.json?v=1`) onto a
+    /// project asset. The path of the `uri` should start with the
+    /// `path_prefix`, be followed by the project id and then the asset
+    /// path. Which of the segments belong to the (possibly nested) project id
+    /// is only known once the projects are read.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `uri` is not within the `path_prefix`, has
+    /// no project id, or its path would escape the data directory.
+    pub fn from_uri(uri: &str, path_prefix: &str) -> Result<Self, ForwardedAssetError> {
+        let invalid = |message: &str| ForwardedAssetError::InvalidUri {
+            uri: uri.to_owned(),
+            message: message.to_owned(),
+        };
+
+        // Discard the query and fragment.
+        let path = uri.split(['?', '#']).next().unwrap_or_default();
+
+        let path = path
+            .strip_prefix(path_prefix.trim_end_matches('/'))
+            .ok_or_else(|| invalid("outside of the path prefix"))?;
+        if !(path.is_empty() || path.starts_with('/')) {
+            return Err(invalid("outside of the path prefix"));
+        }
+
+        let path = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                percent_decode_str(segment)
+                    .decode_utf8()
+                    .map(|segment| segment.into_owned())
+                    .map_err(|_| invalid("not valid UTF-8"))
+            })
+            .collect::<Result<PathBuf, _>>()?;
+        if path.as_os_str().is_empty() {
+            return Err(invalid("no project id"));
+        }
+
+        // Decoding could have introduced `..` segments.
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(invalid("path escapes the data directory"));
+        }
+
+        Ok(Self { path })
+    }
+}
+
+#[cfg(test)]
+mod forwarded_asset_tests {
+    use super::*;
+
+    mod from_uri {
+        use super::*;
+
+        #[test_case::test_case("/project-assets/project_1/pointclouds/metadata.json", "project_1/pointclouds/metadata.json"; "asset")]
+        #[test_case::test_case("/project-assets/project_1/cloud%20one/octree.bin?v=2#top", "project_1/cloud one/octree.bin"; "encoded with query")]
+        #[test_case::test_case("/project-assets/project_1", "project_1"; "project only")]
+        #[test_case::test_case("/project-assets//project_1//metadata.json", "project_1/metadata.json"; "repeated separators")]
+        #[test_case::test_case("/project-assets/client-a/survey/metadata.json", "client-a/survey/metadata.json"; "nested project")]
+        fn should_map_the_uri_onto_a_project_asset(uri: &str, expected_path: &str) {
+            // Act
+            let forwarded_asset = ForwardedAsset::from_uri(uri, "/project-assets/").unwrap();
+
+            // Assert
+            assert_eq!(
+                forwarded_asset,
+                ForwardedAsset {
+                    path: PathBuf::from(expected_path),
+                }
+            );
+        }
+
+        #[test_case::test_case("/potree-assets/project_1/metadata.json"; "outside of prefix")]
+        #[test_case::test_case("/project-assets-2/project_1/metadata.json"; "sibling of prefix")]
+        #[test_case::test_case("/project-assets/"; "no project id")]
+        #[test_case::test_case("/project-assets/project_1/../project_2/metadata.json"; "parent directory")]
+        #[test_case::test_case("/project-assets/project_1/%2e%2e/project_2/metadata.json"; "encoded parent directory")]
+        #[test_case::test_case("/project-assets/..%2Fproject_2/metadata.json"; "encoded separator")]
+        #[test_case::test_case("/project-assets/project_1/%FF"; "invalid utf-8")]
+        fn should_return_err_if_not_a_project_asset(uri: &str) {
+            // Act
+            let res = ForwardedAsset::from_uri(uri, "/project-assets");
+
+            // Assert
+            assert!(matches!(res, Err(ForwardedAssetError::InvalidUri { .. })));
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use axum::extract::FromRequestParts;
+use http::request::Parts;
+
+use super::state::State;
+use crate::common::utils::http::api_error::ApiError;
+
+impl<S> FromRequestParts<S> for State
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let state = parts
+            .extensions
+            .get::<State>()
+            .ok_or(ApiError::StateExtraction)?;
+
+        Ok(state.clone())
+    }
+}
@@ -0,0 +1,7 @@
+mod extractors;
+mod route_handlers;
+mod router;
+mod state;
+
+pub use router::VERIFY;
+pub use router::build_router;
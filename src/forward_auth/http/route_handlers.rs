@@ -0,0 +1,96 @@
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+
+use super::super::domain::ForwardedAsset;
+use super::state::State;
+use crate::common::utils::http::api_error::ApiError;
+use crate::project_asset::application::error::ProjectAssetsServiceError;
+use crate::user::domain::User;
+use crate::user::http::extractors::UserExtractor;
+
+/// The header in which nginx (`auth_request`) passes the original request uri.
+const X_ORIGINAL_URI: HeaderName = HeaderName::from_static("x-original-uri");
+
+/// The header in which Traefik (`ForwardAuth`) passes the original request uri.
+const X_FORWARDED_URI: HeaderName = HeaderName::from_static("x-forwarded-uri");
+
+/// The id of the authenticated user.
+const X_AUTH_USER: HeaderName = HeaderName::from_static("x-auth-user");
+
+/// The email address of the authenticated user.
+const X_AUTH_EMAIL: HeaderName = HeaderName::from_static("x-auth-email");
+
+/// The (comma separated) groups of the authenticated user.
+const X_AUTH_GROUPS: HeaderName = HeaderName::from_static("x-auth-groups");
+
+/// Decides whether the original request, forwarded by a reverse proxy, is
+/// allowed to read a project asset.
+///
+/// Responds with `200` (with the identity of the user in the `X-Auth-*`
+/// headers), `401` or `403`, as expected by nginx `auth_request` and Traefik
+/// `ForwardAuth`. Assets that don't exist are reported as `403`, as proxies
+/// treat any other status as an error.
+pub(crate) async fn verify(
+    UserExtractor(user): UserExtractor,
+    State {
+        project_asset_service,
+        path_prefix,
+    }: State,
+    headers: HeaderMap,
+) -> Result<HeaderMap, ApiError> {
+    let original_uri = headers
+        .get(X_ORIGINAL_URI)
+        .or_else(|| headers.get(X_FORWARDED_URI))
+        .ok_or(ApiError::InvalidRequest {
+            message: "no `X-Original-URI` or `X-Forwarded-Uri` header".to_owned(),
+        })?
+        .to_str()
+        .map_err(|e| ApiError::InvalidRequest {
+            message: format!("invalid original uri: {e}"),
+        })?;
+
+    let forwarded_asset =
+        ForwardedAsset::from_uri(original_uri, &path_prefix).map_err(|e| ApiError::Forbidden {
+            message: e.to_string(),
+        })?;
+
+    project_asset_service
+        .authorize_asset(&user, &forwarded_asset.path)
+        .await
+        .map_err(|e| match e {
+            ProjectAssetsServiceError::ProjectNotFound { .. }
+            | ProjectAssetsServiceError::AssetNotFound { .. } => ApiError::Forbidden {
+                message: e.to_string(),
+            },
+            e => e.into(),
+        })?;
+
+    Ok(user.as_ref().map(identity_headers).unwrap_or_default())
+}
+
+/// Builds the headers identifying the `user` to the reverse proxy. Values that
+/// can't be represented in a header are omitted.
+fn identity_headers(user: &User) -> HeaderMap {
+    let groups = user
+        .groups
+        .iter()
+        .map(|group| group.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    [
+        (X_AUTH_USER, user.id.as_str()),
+        (X_AUTH_EMAIL, user.email.as_str()),
+        (X_AUTH_GROUPS, groups.as_str()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| match HeaderValue::from_str(value) {
+        Ok(value) => Some((name, value)),
+        Err(e) => {
+            tracing::warn!("unable to set the `{name}` header: {e}");
+            None
+        }
+    })
+    .collect()
+}
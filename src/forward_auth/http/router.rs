@@ -0,0 +1,29 @@
+use std::sync::LazyLock;
+
+use axum::Extension;
+use axum::Router;
+use axum::routing::any;
+use web_route::WebRoute;
+
+use super::route_handlers;
+use super::state::State;
+use crate::project_asset::application::service::ProjectAssetService;
+
+pub static VERIFY: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/verify"));
+
+/// Builds a router that answers the authorization subrequests of a reverse
+/// proxy.
+///
+/// `path_prefix` is the path under which the reverse proxy serves the project
+/// assets (e.g. `/project-assets`).
+pub fn build_router(project_asset_service: ProjectAssetService, path_prefix: String) -> Router {
+    let state = State {
+        project_asset_service,
+        path_prefix,
+    };
+
+    // Proxies don't necessarily use the method of the original request.
+    Router::new()
+        .route(&VERIFY, any(route_handlers::verify))
+        .layer(Extension(state))
+}
@@ -0,0 +1,9 @@
+use crate::project_asset::application::service::ProjectAssetService;
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub project_asset_service: ProjectAssetService,
+
+    /// The path under which the reverse proxy serves the project assets.
+    pub path_prefix: String,
+}
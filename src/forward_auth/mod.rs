@@ -0,0 +1,6 @@
+//! Lets a reverse proxy (e.g. nginx `auth_request` or Traefik `ForwardAuth`)
+//! delegate access decisions on project assets to the application, while
+//! serving the assets itself.
+
+pub mod domain;
+pub mod http;
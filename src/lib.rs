@@ -1,9 +1,14 @@
+pub mod access_explanation;
+pub mod audit;
 pub mod authentication;
 pub mod authorization;
 pub mod common;
+pub mod forward_auth;
 pub mod potree_asset;
 pub mod potree_auth;
 pub mod project;
+pub mod project_admin;
 pub mod project_asset;
 pub mod render;
+pub mod share_link;
 pub mod user;
@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use axum::ServiceExt;
 use axum::extract::Request;
 use clap::Parser;
@@ -20,21 +22,30 @@ async fn main() -> Result<(), anyhow::Error> {
     // Load environment variables from a `.env` file if it exists.
     let _ = dotenv();
 
+    // Parse arguments from the CLI.
+    let mut cli = Cli::parse();
+
+    // Run a maintenance command (e.g. querying the audit log) instead of serving
+    // the application, if one is given. Tracing isn't set up for them, so that
+    // it doesn't interleave with their output.
+    if let Some(command) = cli.command.take() {
+        command.run().await?;
+        return Ok(());
+    }
+
     // Set up tracing subscribers
     init_tracing();
 
-    // Parse arguments from the CLI.
-    let cli = Cli::parse();
-
     let listener =
         tokio::net::TcpListener::bind(format!("{}:{}", &cli.server.host, &cli.server.port)).await?;
 
-    let application = init_application(cli.into()).await?;
+    let application = init_application(cli.try_into()?).await?;
 
     tracing::info!("listening on {}", listener.local_addr()?);
     axum::serve(
         listener,
-        ServiceExt::<Request>::into_make_service(application),
+        // The peer address is needed to trust the headers of an authenticating proxy.
+        ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(application),
     )
     .with_graceful_shutdown(shutdown_signal())
     .await?;
@@ -1,28 +1,230 @@
 //! Contains CLI logic that is called from the main binary.
 
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use clap::Parser;
+use http::HeaderName;
+use ipnet::IpNet;
+use time::Date;
+use time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use tower_sessions::cookie::SameSite;
 use url::Url;
 
+use super::config::AuditConfiguration as PotreeAuthAuditConfiguration;
+use super::config::AuthorizationConfiguration as PotreeAuthAuthorizationConfiguration;
+use super::config::ForwardAuthConfiguration as PotreeAuthForwardAuthConfiguration;
 use super::config::IdpConfiguration as PotreeAuthIdpConfiguration;
+use super::config::LocalUsersConfiguration as PotreeAuthLocalUsersConfiguration;
 use super::config::PotreeAuthConfiguration;
+use super::config::ProjectStoreConfiguration;
+use super::config::ProjectsConfiguration as PotreeAuthProjectsConfiguration;
+use super::config::ProjectsWatchMode as PotreeAuthProjectsWatchMode;
+use super::config::SessionConfiguration as PotreeAuthSessionConfiguration;
+use super::config::SessionStoreConfiguration;
+use super::config::ShareLinkConfiguration as PotreeAuthShareLinkConfiguration;
+use super::config::TrustedProxyConfiguration as PotreeAuthTrustedProxyConfiguration;
+use super::data_directory::validate_data_directory;
+use super::http::factories::init_authorization_engine;
+use super::http::factories::init_project_repository;
+use super::project_import::import_projects;
+use crate::access_explanation::application::error::AccessExplanationServiceError;
+use crate::access_explanation::application::service::AccessExplanationService;
+use crate::access_explanation::domain::AccessExplanationRequest;
+use crate::audit::adapters::audit_log::json_lines::query_events;
+use crate::audit::domain::AuditQuery;
+use crate::audit::ports::audit_log::AuditLogError;
+use crate::common::domain::Group;
+use crate::project::adapters::project_repository::sqlite::SqliteProjectRepository;
+use crate::project::domain::ProjectId;
+use crate::project::ports::project_repository::ProjectRepositoryError;
+use crate::user::domain::DEFAULT_ADMIN_GROUP;
+use crate::user::domain::DEFAULT_PROVIDER;
+use crate::user::domain::EmailAddress;
+use crate::user::domain::LOCAL_PROVIDER;
+use crate::user::domain::ProviderName;
+
+/// How often (in minutes) the IdP's metadata is refreshed by default.
+const DEFAULT_METADATA_REFRESH_MINUTES: u64 = 60;
 
 #[derive(Debug, Clone, Parser)]
-#[command(version, about = None, long_about = None)]
+#[command(version, about = None, long_about = None, subcommand_negates_reqs = true)]
 pub struct Cli {
+    /// Runs a maintenance command rather than serving the application.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// The parent directory containing the projects to be served.
-    #[arg(short, long, env = "DATA_DIR")]
-    pub data_dir: PathBuf,
+    #[arg(short, long, required = true, env = "DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
 
     /// If populated will use an OIDC IdP for authentication, else won't use
     /// authentication.
     #[clap(flatten)]
     pub idp: Option<IdpConfiguration>,
 
+    /// The path to a YAML file configuring additional (named) OIDC IdPs, e.g.
+    /// those of partner organisations. Users choose one when logging in.
+    #[arg(
+        long,
+        conflicts_with = "trusted_proxy_networks",
+        env = "IDP_PROVIDERS_FILE"
+    )]
+    pub idp_providers_file: Option<PathBuf>,
+
+    /// If populated will (also) authenticate users with local user accounts.
+    #[clap(flatten)]
+    pub local_users: LocalUsersConfiguration,
+
+    /// If populated will authenticate users from the headers of an upstream
+    /// authenticating proxy, instead of an OIDC IdP.
+    #[clap(flatten)]
+    pub trusted_proxy: TrustedProxyConfiguration,
+
     /// Configures how the server should behave.
     #[clap(flatten)]
     pub server: ServerConfiguration,
+
+    /// Configures how changes to the projects are picked up.
+    #[clap(flatten)]
+    pub projects: ProjectsConfiguration,
+
+    /// Configures how web sessions are persisted.
+    #[clap(flatten)]
+    pub session: SessionConfiguration,
+
+    /// Configures the signing of share links.
+    #[clap(flatten)]
+    pub share_link: ShareLinkConfiguration,
+
+    /// Configures how authorization decisions are made.
+    #[clap(flatten)]
+    pub authorization: AuthorizationConfiguration,
+
+    /// Configures the forward-auth endpoint used by reverse proxies.
+    #[clap(flatten)]
+    pub forward_auth: ForwardAuthConfiguration,
+
+    /// Configures the audit log.
+    #[clap(flatten)]
+    pub audit: AuditConfiguration,
+}
+
+/// The maintenance commands.
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum Command {
+    /// Works with the audit log.
+    #[command(subcommand)]
+    Audit(AuditCommand),
+
+    /// Explains whether a user can read a project (or one of its assets), as
+    /// decided with the given authorization configuration. Prints the checks
+    /// that were evaluated (as JSON).
+    Explain(ExplainArgs),
+
+    /// Imports the projects defined by the manifests in the data directory
+    /// into the SQLite project store, replacing the projects with the same
+    /// ids. Prints the imported projects and the manifests that couldn't be
+    /// loaded (as JSON). Exits with a non-zero status if there are any of the
+    /// latter.
+    Import(ImportArgs),
+
+    /// Reports the problems with the projects in the data directory (as JSON),
+    /// e.g. invalid manifests or missing point clouds. Exits with a non-zero
+    /// status if there are any.
+    Validate(ValidateArgs),
+}
+
+/// The commands working with the audit log.
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum AuditCommand {
+    /// Prints the audit events matching the filters (as JSON lines), oldest
+    /// first.
+    Query(AuditQueryArgs),
+}
+
+/// The filters of the `audit query` command.
+#[derive(Debug, Clone, clap::Args)]
+pub struct AuditQueryArgs {
+    /// The audit log file (rotated files next to it are also read).
+    #[arg(long, env = "AUDIT_LOG_FILE")]
+    pub audit_log_file: PathBuf,
+
+    /// Only events about resources of this project.
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Only events of the user with this id or email address.
+    #[arg(long)]
+    pub user: Option<String>,
+
+    /// Only events at or after this time (RFC 3339, or a `YYYY-MM-DD` date in
+    /// UTC).
+    #[arg(long, value_parser = parse_timestamp)]
+    pub from: Option<OffsetDateTime>,
+
+    /// Only events before this time (RFC 3339, or a `YYYY-MM-DD` date in UTC).
+    #[arg(long, value_parser = parse_timestamp)]
+    pub until: Option<OffsetDateTime>,
+}
+
+/// The arguments of the `explain` command.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ExplainArgs {
+    /// The parent directory containing the projects.
+    #[arg(short, long, env = "DATA_DIR")]
+    pub data_dir: PathBuf,
+
+    /// The email address of the user.
+    #[arg(long)]
+    pub email: String,
+
+    /// The (comma separated) groups that the user is a member of.
+    #[arg(long, value_delimiter = ',')]
+    pub groups: Vec<String>,
+
+    /// The project that the user is trying to read.
+    #[arg(long)]
+    pub project: String,
+
+    /// The asset (relative to the project directory) that the user is trying
+    /// to read.
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Configures where the projects are stored, as when serving the
+    /// application.
+    #[clap(flatten)]
+    pub projects: ProjectsConfiguration,
+
+    /// Configures how authorization decisions are made.
+    #[clap(flatten)]
+    pub authorization: AuthorizationConfiguration,
+}
+
+/// The arguments of the `import` command.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ImportArgs {
+    /// The parent directory containing the projects.
+    #[arg(short, long, env = "DATA_DIR")]
+    pub data_dir: PathBuf,
+
+    /// The path to the SQLite database file of the project store. It is
+    /// created if it does not exist.
+    #[arg(long, env = "PROJECTS_SQLITE_PATH")]
+    pub projects_sqlite_path: PathBuf,
+}
+
+/// The arguments of the `validate` command.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ValidateArgs {
+    /// The parent directory containing the projects.
+    #[arg(short, long, env = "DATA_DIR")]
+    pub data_dir: PathBuf,
 }
 
 /// The configuration required to use an OIDC IdP for authentication.
@@ -50,14 +252,211 @@ pub struct IdpConfiguration {
     pub idp_client_secret: String,
 
     /// The claim in the OIDC Id Token that will contain an array of the groups
-    /// that the authenticated user is member of.
+    /// that the authenticated user is member of. Nested claims can be
+    /// referenced with a `.` separated path (e.g. `realm_access.roles`).
     #[arg(long, required = false, env = "IDP_GROUPS_CLAIM")]
     pub idp_groups_claim: String,
 
+    /// Whether to fetch the user's claims from the IdP's UserInfo endpoint, for
+    /// IdPs that don't include the groups (or name and email) in the ID token.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::Set, env = "IDP_FETCH_USERINFO")]
+    pub idp_fetch_userinfo: bool,
+
+    /// Whether to trust the email addresses of the IdP's users (to grant access
+    /// to projects that list them in `users`), even if the IdP doesn't claim to
+    /// have verified them. Only enable this if the IdP doesn't let users set
+    /// their own email address.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::Set, env = "IDP_TRUST_UNVERIFIED_EMAIL")]
+    pub idp_trust_unverified_email: bool,
+
+    /// The path to a YAML file mapping the IdP's group values (e.g. full LDAP
+    /// DNs or Entra ID object ids) to the group names used in `manifest.yml`.
+    #[arg(long, env = "IDP_GROUP_MAPPING_FILE")]
+    pub idp_group_mapping_file: Option<PathBuf>,
+
+    /// The name of the IdP shown to users if there are multiple IdPs to choose
+    /// from.
+    #[arg(long, default_value = "Single Sign-On", env = "IDP_DISPLAY_NAME")]
+    pub idp_display_name: String,
+
+    /// Prefixed to the groups of the IdP's users (e.g. `acme:`).
+    #[arg(long, env = "IDP_GROUP_PREFIX")]
+    pub idp_group_prefix: Option<String>,
+
     /// The URL on which the application is publicly accessible (the OIDC
     /// callback URL is calculated from this).
     #[arg(long, required = false, env = "IDP_APPLICATION_EXTERNAL_URL")]
     pub idp_application_external_url: Url,
+
+    /// The expected `aud` claim of access tokens presented as `Authorization:
+    /// Bearer` headers (e.g. by scripts). Defaults to the client id.
+    #[arg(long, env = "IDP_ACCESS_TOKEN_AUDIENCE")]
+    pub idp_access_token_audience: Option<String>,
+
+    /// The (comma separated) scopes requested in addition to `openid` (e.g.
+    /// `email,profile,groups`).
+    #[arg(long, value_delimiter = ',', env = "IDP_SCOPES")]
+    pub idp_scopes: Vec<String>,
+
+    /// The (comma separated) `prompt` values sent to the IdP (e.g. `login` to
+    /// always require the user to re-enter their credentials).
+    #[arg(long, value_delimiter = ',', env = "IDP_PROMPT")]
+    pub idp_prompt: Vec<String>,
+
+    /// A hint to the IdP about the login identifier of the user (e.g. the
+    /// email domain for home realm discovery).
+    #[arg(long, env = "IDP_LOGIN_HINT")]
+    pub idp_login_hint: Option<String>,
+
+    /// The (comma separated) requested Authentication Context Class
+    /// References, in order of preference (e.g. to require MFA).
+    #[arg(long, value_delimiter = ',', env = "IDP_ACR_VALUES")]
+    pub idp_acr_values: Vec<String>,
+
+    /// The maximum time (in seconds) since the user last actively
+    /// authenticated with the IdP. Logins exceeding it are rejected.
+    #[arg(long, env = "IDP_MAX_AGE_SECONDS")]
+    pub idp_max_age_seconds: Option<u64>,
+
+    /// How long (in minutes) the user of a web session is trusted before their
+    /// claims (e.g. groups) are re-validated with the IdP using the refresh
+    /// token. If it can't be refreshed, the user has to log in again.
+    #[arg(long, env = "IDP_SESSION_MAX_AGE_MINUTES")]
+    pub idp_session_max_age_minutes: Option<u64>,
+
+    /// How often (in minutes) the IdP's metadata and signing keys are
+    /// refreshed, so that keys rotated by the IdP are picked up without a
+    /// restart.
+    #[arg(
+        long,
+        default_value_t = DEFAULT_METADATA_REFRESH_MINUTES,
+        env = "IDP_METADATA_REFRESH_MINUTES"
+    )]
+    pub idp_metadata_refresh_minutes: u64,
+}
+
+/// The configuration required to authenticate users from the identity headers
+/// set by an upstream authenticating proxy (e.g. oauth2-proxy, Pomerium).
+///
+/// Only enabled if trusted networks are provided.
+#[derive(Debug, Clone, clap::Args)]
+pub struct TrustedProxyConfiguration {
+    /// The (comma separated) networks in CIDR notation (e.g. `10.0.0.0/8`)
+    /// from which requests are trusted to carry identity headers.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with = "idp_url",
+        env = "TRUSTED_PROXY_NETWORKS"
+    )]
+    pub trusted_proxy_networks: Vec<IpNet>,
+
+    /// The header containing the unique id of the user.
+    #[arg(
+        long,
+        default_value = "x-forwarded-user",
+        env = "TRUSTED_PROXY_USER_ID_HEADER"
+    )]
+    pub trusted_proxy_user_id_header: HeaderName,
+
+    /// The header containing the name of the user (falls back to the user id).
+    #[arg(
+        long,
+        default_value = "x-forwarded-preferred-username",
+        env = "TRUSTED_PROXY_NAME_HEADER"
+    )]
+    pub trusted_proxy_name_header: HeaderName,
+
+    /// The header containing the email address of the user.
+    #[arg(
+        long,
+        default_value = "x-forwarded-email",
+        env = "TRUSTED_PROXY_EMAIL_HEADER"
+    )]
+    pub trusted_proxy_email_header: HeaderName,
+
+    /// The header containing the groups of the user.
+    #[arg(
+        long,
+        default_value = "x-forwarded-groups",
+        env = "TRUSTED_PROXY_GROUPS_HEADER"
+    )]
+    pub trusted_proxy_groups_header: HeaderName,
+
+    /// Separates the groups in the groups header.
+    #[arg(long, default_value_t = ',', env = "TRUSTED_PROXY_GROUPS_SEPARATOR")]
+    pub trusted_proxy_groups_separator: char,
+}
+
+/// The configuration required to authenticate users with local user accounts
+/// (e.g. on machines without access to an IdP).
+///
+/// Only enabled if a users file is provided.
+#[derive(Debug, Clone, clap::Args)]
+pub struct LocalUsersConfiguration {
+    /// The path to a YAML file listing the local user accounts, with their
+    /// Argon2 password hashes, emails and groups.
+    #[arg(
+        long,
+        conflicts_with = "trusted_proxy_networks",
+        env = "LOCAL_USERS_FILE"
+    )]
+    pub local_users_file: Option<PathBuf>,
+
+    /// The name of the local user accounts shown to users if there are
+    /// multiple IdPs to choose from.
+    #[arg(
+        long,
+        default_value = "Local Account",
+        env = "LOCAL_USERS_DISPLAY_NAME"
+    )]
+    pub local_users_display_name: String,
+
+    /// The number of failed login attempts (per client address, or username
+    /// from a client address) after which further attempts have to back off.
+    #[arg(long, default_value_t = 5, env = "LOCAL_USERS_MAX_FAILED_LOGINS")]
+    pub local_users_max_failed_logins: u32,
+
+    /// The longest (in minutes) that further login attempts are rejected for,
+    /// after the last failed attempt. The backoff doubles with each failed
+    /// attempt up to it.
+    #[arg(long, default_value_t = 15, env = "LOCAL_USERS_MAX_BACKOFF_MINUTES")]
+    pub local_users_max_backoff_minutes: u64,
+
+    /// The (comma separated) networks in CIDR notation (e.g. `10.0.0.0/8`) of
+    /// the reverse proxies in front of the server. Login attempts from them
+    /// are attributed to the client address in their `X-Forwarded-For` header.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        env = "LOCAL_USERS_TRUSTED_PROXY_NETWORKS"
+    )]
+    pub local_users_trusted_proxy_networks: Vec<IpNet>,
+}
+
+/// Configures the audit log of authorization decisions, logins and logouts.
+///
+/// Only enabled if an audit log file is provided.
+#[derive(Debug, Clone, clap::Args)]
+pub struct AuditConfiguration {
+    /// The path of the JSON-lines file that audit events are appended to. It
+    /// is created if it does not exist.
+    #[arg(long, env = "AUDIT_LOG_FILE")]
+    pub audit_log_file: Option<PathBuf>,
+
+    /// The size (in MiB) after which the audit log file is rotated.
+    #[arg(long, default_value_t = 10, env = "AUDIT_LOG_MAX_FILE_SIZE_MB")]
+    pub audit_log_max_file_size_mb: u64,
+
+    /// The number of rotated audit log files that are kept.
+    #[arg(long, default_value_t = 10, env = "AUDIT_LOG_MAX_ROTATED_FILES")]
+    pub audit_log_max_rotated_files: usize,
+
+    /// How long (in minutes) a project session lasts without any requests for
+    /// the project's assets. Only the first asset access of a session is
+    /// recorded.
+    #[arg(long, default_value_t = 30, env = "AUDIT_SESSION_TIMEOUT_MINUTES")]
+    pub audit_session_timeout_minutes: u64,
 }
 
 /// Configures server specific controls.
@@ -75,13 +474,690 @@ pub struct ServerConfiguration {
     pub port: u16,
 }
 
-impl From<Cli> for PotreeAuthConfiguration {
-    fn from(value: Cli) -> Self {
-        let Cli { data_dir, idp, .. } = value;
+/// Configures where the projects are stored, and how changes to their manifests
+/// are picked up without restarting the server.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ProjectsConfiguration {
+    /// Where the projects are defined. Projects in the `manifests` store are
+    /// read from the `manifest.yml` files in the data directory, while the
+    /// `sqlite` store can be edited through the admin API.
+    #[arg(long, value_enum, default_value_t = ProjectStore::Manifests, env = "PROJECTS_STORE")]
+    pub projects_store: ProjectStore,
 
-        Self {
+    /// The path to the SQLite database file used by the `sqlite` project store.
+    /// It is created if it does not exist.
+    #[arg(
+        long,
+        required_if_eq("projects_store", "sqlite"),
+        env = "PROJECTS_SQLITE_PATH"
+    )]
+    pub projects_sqlite_path: Option<PathBuf>,
+
+    /// How the data directory is watched for changes. Network mounts (e.g. NFS
+    /// or SMB) don't emit filesystem notifications, so they need to be polled.
+    #[arg(long, value_enum, default_value_t = ProjectsWatchMode::Native, env = "PROJECTS_WATCH_MODE")]
+    pub projects_watch_mode: ProjectsWatchMode,
+
+    /// How often (in seconds) the data directory is scanned for changes when
+    /// polling.
+    #[arg(long, default_value_t = 30, env = "PROJECTS_POLL_INTERVAL_SECONDS")]
+    pub projects_poll_interval_seconds: u64,
+}
+
+/// Configures how web sessions are persisted and the session cookie.
+#[derive(Debug, Clone, clap::Args)]
+pub struct SessionConfiguration {
+    /// The backend in which web sessions are stored. Sessions in the `memory`
+    /// store are lost when the server restarts.
+    #[arg(long, value_enum, default_value_t = SessionStore::Memory, env = "SESSION_STORE")]
+    pub session_store: SessionStore,
+
+    /// The path to the SQLite database file used by the `sqlite` session store.
+    /// It is created if it does not exist.
+    #[arg(
+        long,
+        required_if_eq("session_store", "sqlite"),
+        env = "SESSION_SQLITE_PATH"
+    )]
+    pub session_sqlite_path: Option<PathBuf>,
+
+    /// Whether the session cookie should only be sent over HTTPS.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set, env = "SESSION_COOKIE_SECURE")]
+    pub session_cookie_secure: bool,
+
+    /// The `SameSite` policy of the session cookie.
+    #[arg(long, value_enum, default_value_t = CookieSameSite::None, env = "SESSION_COOKIE_SAME_SITE")]
+    pub session_cookie_same_site: CookieSameSite,
+
+    /// How long (in minutes) a session can be inactive before it expires.
+    #[arg(
+        long,
+        default_value_t = 1440,
+        env = "SESSION_INACTIVITY_EXPIRY_MINUTES"
+    )]
+    pub session_inactivity_expiry_minutes: u32,
+}
+
+/// Configures share links.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ShareLinkConfiguration {
+    /// The secret key (at least 32 bytes) used to sign share links. Share links
+    /// are disabled if this is not set.
+    #[arg(long, env = "SHARE_LINK_SIGNING_KEY", hide_env_values = true)]
+    pub share_link_signing_key: Option<String>,
+
+    /// The longest (in days) that share links can be valid for.
+    #[arg(long, default_value_t = 30, env = "SHARE_LINK_MAX_VALIDITY_DAYS")]
+    pub share_link_max_validity_days: u32,
+}
+
+/// Configures the authorization engine.
+#[derive(Debug, Clone, clap::Args)]
+pub struct AuthorizationConfiguration {
+    /// The path to a (YAML or TOML) policy file defining who can do what. If
+    /// not set, the built-in rules are used. Only applies if an IdP is
+    /// configured.
+    #[arg(long, env = "AUTHORIZATION_POLICY_FILE")]
+    pub authorization_policy_file: Option<PathBuf>,
+
+    /// The (comma separated) groups whose members are allowed to do anything.
+    /// Not used if a policy file is set.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = DEFAULT_ADMIN_GROUP,
+        env = "ADMIN_GROUPS"
+    )]
+    pub admin_groups: Vec<String>,
+}
+
+/// Configures the forward-auth endpoint (`/auth/verify`).
+#[derive(Debug, Clone, clap::Args)]
+pub struct ForwardAuthConfiguration {
+    /// The path under which a reverse proxy serves the project assets, when
+    /// using it to make access decisions (e.g. nginx `auth_request`).
+    #[arg(
+        long,
+        default_value = "/project-assets",
+        env = "FORWARD_AUTH_PATH_PREFIX"
+    )]
+    pub forward_auth_path_prefix: String,
+}
+
+/// The available project backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProjectStore {
+    Manifests,
+    Sqlite,
+}
+
+/// The available ways of watching the data directory for changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProjectsWatchMode {
+    Native,
+    Poll,
+}
+
+/// The available web session backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SessionStore {
+    Memory,
+    Sqlite,
+}
+
+/// The available `SameSite` cookie policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+/// The additional (named) OIDC IdPs, as read from the `--idp-providers-file`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProvidersFile {
+    /// The URL on which the application is publicly accessible (the OIDC
+    /// callback URLs are calculated from this).
+    application_external_url: Url,
+
+    providers: Vec<ProviderDefinition>,
+}
+
+/// An OIDC IdP in the `--idp-providers-file`. The fields match the `--idp-*`
+/// arguments.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderDefinition {
+    name: ProviderName,
+    display_name: Option<String>,
+    group_prefix: Option<String>,
+    url: Url,
+    client_id: String,
+    client_secret: String,
+    groups_claim: String,
+    #[serde(default)]
+    fetch_userinfo: bool,
+    #[serde(default)]
+    trust_unverified_email: bool,
+    group_mapping_file: Option<PathBuf>,
+    access_token_audience: Option<String>,
+    #[serde(default)]
+    scopes: Vec<String>,
+    #[serde(default)]
+    prompt: Vec<String>,
+    login_hint: Option<String>,
+    #[serde(default)]
+    acr_values: Vec<String>,
+    max_age_seconds: Option<u64>,
+    session_max_age_minutes: Option<u64>,
+    metadata_refresh_minutes: Option<u64>,
+}
+
+/// Errors that can be experienced converting the [`Cli`] arguments into a
+/// [`PotreeAuthConfiguration`].
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("the `--data-dir` argument is required to serve the application")]
+    MissingDataDir,
+
+    #[error("unable to query the audit log: {0}")]
+    AuditLog(#[from] AuditLogError),
+
+    #[error("unable to initialize the authorization engine: {message}")]
+    AuthorizationEngine { message: String },
+
+    #[error("unable to initialize the project store: {message}")]
+    ProjectStore { message: String },
+
+    #[error("unable to explain the access decision: {0}")]
+    AccessExplanation(#[from] AccessExplanationServiceError),
+
+    #[error("{problems} project(s) couldn't be imported")]
+    IncompleteImport { problems: usize },
+
+    #[error("the data directory has {problems} problem(s)")]
+    InvalidDataDir { problems: usize },
+
+    #[error("unable to read the projects: {0}")]
+    ProjectRepository(#[from] ProjectRepositoryError),
+
+    #[error("unable to write the output: {0}")]
+    Output(#[from] std::io::Error),
+
+    #[error("unable to read the IdP providers file ({path}): {message}")]
+    ProvidersFile { path: PathBuf, message: String },
+
+    #[error("invalid IdP name `{name}`: {message}")]
+    InvalidProviderName { name: String, message: String },
+}
+
+impl TryFrom<Cli> for PotreeAuthConfiguration {
+    type Error = CliError;
+
+    fn try_from(value: Cli) -> Result<Self, Self::Error> {
+        let Cli {
             data_dir,
-            idp: idp.map(Into::into),
+            projects,
+            idp,
+            idp_providers_file,
+            local_users,
+            trusted_proxy,
+            session,
+            share_link,
+            authorization,
+            forward_auth,
+            audit,
+            ..
+        } = value;
+        let data_dir = data_dir.ok_or(CliError::MissingDataDir)?;
+
+        let mut idps = idp.map(Into::into).into_iter().collect::<Vec<_>>();
+        if let Some(path) = idp_providers_file {
+            idps.extend(read_providers_file(&path)?);
+        }
+        let local_users = Option::<PotreeAuthLocalUsersConfiguration>::from(local_users);
+        validate_provider_names(&idps, local_users.is_some())?;
+
+        Ok(Self {
+            data_dir,
+            projects: projects.into(),
+            idps,
+            local_users,
+            trusted_proxy: trusted_proxy.into(),
+            session: session.into(),
+            share_link: share_link.into(),
+            authorization: authorization.into(),
+            forward_auth: forward_auth.into(),
+            audit: audit.into(),
+        })
+    }
+}
+
+impl Command {
+    /// Runs the command, writing its output to stdout.
+    pub async fn run(self) -> Result<(), CliError> {
+        match self {
+            Command::Audit(AuditCommand::Query(args)) => query_audit_log(args),
+            Command::Explain(args) => explain_access(args).await,
+            Command::Import(args) => import_manifests(args).await,
+            Command::Validate(args) => validate_projects(args).await,
+        }
+    }
+}
+
+/// Prints the explanation of whether the user of the `args` can read the
+/// project (and asset).
+async fn explain_access(args: ExplainArgs) -> Result<(), CliError> {
+    let ExplainArgs {
+        data_dir,
+        email,
+        groups,
+        project,
+        path,
+        projects,
+        authorization,
+    } = args;
+
+    // The decisions are explained as if authentication is configured, as they
+    // would all be allowed otherwise.
+    let authorization_engine =
+        init_authorization_engine(true, authorization.into()).map_err(|e| {
+            CliError::AuthorizationEngine {
+                message: e.to_string(),
+            }
+        })?;
+    // The projects are read from the same store as the server's.
+    let project_repository = init_project_repository(&data_dir, &projects.into())
+        .await
+        .map_err(|e| CliError::ProjectStore {
+            message: e.to_string(),
+        })?;
+    let access_explanation_service =
+        AccessExplanationService::new(project_repository, authorization_engine);
+
+    let access_explanation = access_explanation_service
+        .explain_locally(&AccessExplanationRequest {
+            email: EmailAddress::new(email),
+            groups: groups
+                .iter()
+                .map(|group| Group::new(group.trim()))
+                .collect(),
+            project_id: ProjectId::new(project),
+            asset_path: path,
+        })
+        .await?;
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &access_explanation).map_err(std::io::Error::from)?;
+    writeln!(stdout)?;
+
+    Ok(())
+}
+
+/// Imports the projects in the data directory of the `args` into the SQLite
+/// project store, and prints the report of the import.
+///
+/// # Errors
+///
+/// Will return an error (after printing the report) if any of the manifests
+/// couldn't be loaded, so that the command exits with a non-zero status.
+async fn import_manifests(args: ImportArgs) -> Result<(), CliError> {
+    let ImportArgs {
+        data_dir,
+        projects_sqlite_path,
+    } = args;
+
+    let project_repository = SqliteProjectRepository::new(&projects_sqlite_path).await?;
+    let import_report = import_projects(&data_dir, &project_repository).await?;
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &import_report).map_err(std::io::Error::from)?;
+    writeln!(stdout)?;
+
+    if !import_report.diagnostics.is_empty() {
+        return Err(CliError::IncompleteImport {
+            problems: import_report.diagnostics.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Prints the report of the problems with the projects in the data directory of
+/// the `args` (e.g. invalid manifests or missing point clouds).
+///
+/// # Errors
+///
+/// Will return an error (after printing the report) if there are any problems,
+/// so that the command exits with a non-zero status.
+async fn validate_projects(args: ValidateArgs) -> Result<(), CliError> {
+    let validation_report = validate_data_directory(&args.data_dir).await?;
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &validation_report).map_err(std::io::Error::from)?;
+    writeln!(stdout)?;
+
+    if !validation_report.valid {
+        return Err(CliError::InvalidDataDir {
+            problems: validation_report.diagnostics.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Prints the events of the audit log matching the filters of the `args`.
+fn query_audit_log(args: AuditQueryArgs) -> Result<(), CliError> {
+    let AuditQueryArgs {
+        audit_log_file,
+        project,
+        user,
+        from,
+        until,
+    } = args;
+
+    let events = query_events(
+        &audit_log_file,
+        &AuditQuery {
+            project_id: project.map(ProjectId::new),
+            user,
+            from,
+            until,
+        },
+    )?;
+
+    let mut stdout = std::io::stdout().lock();
+    for event in events {
+        serde_json::to_writer(&mut stdout, &event).map_err(std::io::Error::from)?;
+        writeln!(stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Parses an RFC 3339 timestamp, or a `YYYY-MM-DD` date (as midnight UTC).
+fn parse_timestamp(value: &str) -> Result<OffsetDateTime, String> {
+    if let Ok(timestamp) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(timestamp);
+    }
+
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+        .map(|date| date.midnight().assume_utc())
+        .map_err(|_e| format!("expected an RFC 3339 timestamp or a YYYY-MM-DD date: {value}"))
+}
+
+/// Reads the IdPs configured in the providers file (at `path`).
+fn read_providers_file(path: &Path) -> Result<Vec<PotreeAuthIdpConfiguration>, CliError> {
+    let providers_file_error = |message: String| CliError::ProvidersFile {
+        path: path.to_owned(),
+        message,
+    };
+
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| providers_file_error(e.to_string()))?;
+    let ProvidersFile {
+        application_external_url,
+        providers,
+    } = serde_norway::from_str::<ProvidersFile>(&contents)
+        .map_err(|e| providers_file_error(e.to_string()))?;
+
+    Ok(providers
+        .into_iter()
+        .map(|provider| provider.into_idp_configuration(application_external_url.clone()))
+        .collect())
+}
+
+/// IdP names are used in routes, so they need to be unique and URL safe. The
+/// [`LOCAL_PROVIDER`] name is reserved if there are `local_users`.
+fn validate_provider_names(
+    idps: &[PotreeAuthIdpConfiguration],
+    local_users: bool,
+) -> Result<(), CliError> {
+    for (i, idp) in idps.iter().enumerate() {
+        let invalid_provider_name = |message: &str| CliError::InvalidProviderName {
+            name: idp.name.to_string(),
+            message: message.to_owned(),
+        };
+
+        if idp.name.is_empty()
+            || !idp
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(invalid_provider_name(
+                "must only contain letters, digits, `-` and `_`",
+            ));
+        }
+        if idps[..i].iter().any(|other| other.name == idp.name) {
+            return Err(invalid_provider_name("must be unique"));
+        }
+        if local_users && idp.name.as_str() == LOCAL_PROVIDER {
+            return Err(invalid_provider_name(
+                "is reserved for the local user accounts",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+impl ProviderDefinition {
+    fn into_idp_configuration(self, external_url: Url) -> PotreeAuthIdpConfiguration {
+        let ProviderDefinition {
+            name,
+            display_name,
+            group_prefix,
+            url,
+            client_id,
+            client_secret,
+            groups_claim,
+            fetch_userinfo,
+            trust_unverified_email,
+            group_mapping_file,
+            access_token_audience,
+            scopes,
+            prompt,
+            login_hint,
+            acr_values,
+            max_age_seconds,
+            session_max_age_minutes,
+            metadata_refresh_minutes,
+        } = self;
+
+        PotreeAuthIdpConfiguration {
+            display_name: display_name.unwrap_or_else(|| name.to_string()),
+            name,
+            group_prefix,
+            idp_url: url,
+            client_id,
+            client_secret,
+            groups_claim,
+            fetch_userinfo,
+            trust_unverified_email,
+            group_mapping_file,
+            external_url,
+            access_token_audience,
+            scopes,
+            prompt,
+            login_hint,
+            acr_values,
+            max_age: max_age_seconds.map(std::time::Duration::from_secs),
+            session_max_age: session_max_age_minutes.map(minutes_to_duration),
+            metadata_refresh_interval: minutes_to_duration(
+                metadata_refresh_minutes.unwrap_or(DEFAULT_METADATA_REFRESH_MINUTES),
+            ),
+        }
+    }
+}
+
+/// Converts a (CLI) number of minutes into a [`std::time::Duration`].
+fn minutes_to_duration(minutes: u64) -> std::time::Duration {
+    std::time::Duration::from_secs(minutes.saturating_mul(60))
+}
+
+impl From<LocalUsersConfiguration> for Option<PotreeAuthLocalUsersConfiguration> {
+    fn from(value: LocalUsersConfiguration) -> Self {
+        let LocalUsersConfiguration {
+            local_users_file,
+            local_users_display_name,
+            local_users_max_failed_logins,
+            local_users_max_backoff_minutes,
+            local_users_trusted_proxy_networks,
+        } = value;
+
+        Some(PotreeAuthLocalUsersConfiguration {
+            users_file: local_users_file?,
+            display_name: local_users_display_name,
+            max_failed_logins: local_users_max_failed_logins,
+            max_backoff: minutes_to_duration(local_users_max_backoff_minutes),
+            trusted_proxy_networks: local_users_trusted_proxy_networks,
+        })
+    }
+}
+
+impl From<TrustedProxyConfiguration> for Option<PotreeAuthTrustedProxyConfiguration> {
+    fn from(value: TrustedProxyConfiguration) -> Self {
+        let TrustedProxyConfiguration {
+            trusted_proxy_networks,
+            trusted_proxy_user_id_header,
+            trusted_proxy_name_header,
+            trusted_proxy_email_header,
+            trusted_proxy_groups_header,
+            trusted_proxy_groups_separator,
+        } = value;
+
+        // Disabled unless there are networks to trust.
+        if trusted_proxy_networks.is_empty() {
+            return None;
+        }
+
+        Some(PotreeAuthTrustedProxyConfiguration {
+            trusted_networks: trusted_proxy_networks,
+            user_id_header: trusted_proxy_user_id_header,
+            name_header: trusted_proxy_name_header,
+            email_header: trusted_proxy_email_header,
+            groups_header: trusted_proxy_groups_header,
+            groups_separator: trusted_proxy_groups_separator,
+        })
+    }
+}
+
+impl From<AuditConfiguration> for PotreeAuthAuditConfiguration {
+    fn from(value: AuditConfiguration) -> Self {
+        let AuditConfiguration {
+            audit_log_file,
+            audit_log_max_file_size_mb,
+            audit_log_max_rotated_files,
+            audit_session_timeout_minutes,
+        } = value;
+
+        Self {
+            log_file: audit_log_file,
+            max_file_size: audit_log_max_file_size_mb.saturating_mul(1024 * 1024),
+            max_rotated_files: audit_log_max_rotated_files,
+            session_timeout: minutes_to_duration(audit_session_timeout_minutes),
+        }
+    }
+}
+
+impl From<ProjectsConfiguration> for PotreeAuthProjectsConfiguration {
+    fn from(value: ProjectsConfiguration) -> Self {
+        let ProjectsConfiguration {
+            projects_store,
+            projects_sqlite_path,
+            projects_watch_mode,
+            projects_poll_interval_seconds,
+        } = value;
+
+        let store = match (projects_store, projects_sqlite_path) {
+            (ProjectStore::Sqlite, Some(path)) => ProjectStoreConfiguration::Sqlite { path },
+            // `clap` ensures that the path is populated for the `sqlite` store.
+            (ProjectStore::Sqlite, None) | (ProjectStore::Manifests, _) => {
+                ProjectStoreConfiguration::Manifests
+            }
+        };
+
+        Self {
+            store,
+            watch_mode: match projects_watch_mode {
+                ProjectsWatchMode::Native => PotreeAuthProjectsWatchMode::Native,
+                ProjectsWatchMode::Poll => PotreeAuthProjectsWatchMode::Poll,
+            },
+            // A zero interval would scan continuously.
+            poll_interval: std::time::Duration::from_secs(projects_poll_interval_seconds.max(1)),
+        }
+    }
+}
+
+impl From<ForwardAuthConfiguration> for PotreeAuthForwardAuthConfiguration {
+    fn from(value: ForwardAuthConfiguration) -> Self {
+        Self {
+            path_prefix: value.forward_auth_path_prefix,
+        }
+    }
+}
+
+impl From<AuthorizationConfiguration> for PotreeAuthAuthorizationConfiguration {
+    fn from(value: AuthorizationConfiguration) -> Self {
+        let AuthorizationConfiguration {
+            authorization_policy_file,
+            admin_groups,
+        } = value;
+
+        Self {
+            policy_file: authorization_policy_file,
+            admin_groups: admin_groups
+                .iter()
+                .map(|group| Group::new(group.trim()))
+                .collect(),
+        }
+    }
+}
+
+impl From<ShareLinkConfiguration> for PotreeAuthShareLinkConfiguration {
+    fn from(value: ShareLinkConfiguration) -> Self {
+        Self {
+            signing_key: value.share_link_signing_key,
+            max_validity: Duration::days(value.share_link_max_validity_days.into()),
+        }
+    }
+}
+
+impl From<SessionConfiguration> for PotreeAuthSessionConfiguration {
+    fn from(value: SessionConfiguration) -> Self {
+        let SessionConfiguration {
+            session_store,
+            session_sqlite_path,
+            session_cookie_secure,
+            session_cookie_same_site,
+            session_inactivity_expiry_minutes,
+        } = value;
+
+        let store = match (session_store, session_sqlite_path) {
+            (SessionStore::Sqlite, Some(path)) => SessionStoreConfiguration::Sqlite { path },
+            // `clap` ensures that the path is populated for the `sqlite` store.
+            (SessionStore::Sqlite, None) | (SessionStore::Memory, _) => {
+                SessionStoreConfiguration::Memory
+            }
+        };
+
+        Self {
+            store,
+            cookie_secure: session_cookie_secure,
+            cookie_same_site: session_cookie_same_site.into(),
+            inactivity_expiry: Duration::minutes(session_inactivity_expiry_minutes.into()),
+        }
+    }
+}
+
+impl From<CookieSameSite> for SameSite {
+    fn from(value: CookieSameSite) -> Self {
+        match value {
+            CookieSameSite::Strict => Self::Strict,
+            CookieSameSite::Lax => Self::Lax,
+            CookieSameSite::None => Self::None,
         }
     }
 }
@@ -93,15 +1169,42 @@ impl From<IdpConfiguration> for PotreeAuthIdpConfiguration {
             idp_client_id,
             idp_client_secret,
             idp_groups_claim,
+            idp_fetch_userinfo,
+            idp_trust_unverified_email,
+            idp_group_mapping_file,
+            idp_display_name,
+            idp_group_prefix,
             idp_application_external_url,
+            idp_access_token_audience,
+            idp_scopes,
+            idp_prompt,
+            idp_login_hint,
+            idp_acr_values,
+            idp_max_age_seconds,
+            idp_session_max_age_minutes,
+            idp_metadata_refresh_minutes,
         } = value;
 
         Self {
+            name: ProviderName::new(DEFAULT_PROVIDER.to_owned()),
+            display_name: idp_display_name,
+            group_prefix: idp_group_prefix,
             idp_url,
             client_id: idp_client_id,
             client_secret: idp_client_secret,
             groups_claim: idp_groups_claim,
+            fetch_userinfo: idp_fetch_userinfo,
+            trust_unverified_email: idp_trust_unverified_email,
+            group_mapping_file: idp_group_mapping_file,
             external_url: idp_application_external_url,
+            access_token_audience: idp_access_token_audience,
+            scopes: idp_scopes,
+            prompt: idp_prompt,
+            login_hint: idp_login_hint,
+            acr_values: idp_acr_values,
+            max_age: idp_max_age_seconds.map(std::time::Duration::from_secs),
+            session_max_age: idp_session_max_age_minutes.map(minutes_to_duration),
+            metadata_refresh_interval: minutes_to_duration(idp_metadata_refresh_minutes),
         }
     }
 }
@@ -1,20 +1,118 @@
 use std::path::PathBuf;
 
+use http::HeaderName;
+use ipnet::IpNet;
+use time::Duration;
+use tower_sessions::cookie::SameSite;
 use url::Url;
 
+use crate::common::domain::Group;
+use crate::share_link::application::service::DEFAULT_MAX_VALIDITY as DEFAULT_SHARE_LINK_MAX_VALIDITY;
+use crate::user::domain::DEFAULT_ADMIN_GROUP;
+use crate::user::domain::ProviderName;
+
 /// The configuration required to run the application.
 #[derive(Debug, Clone)]
 pub struct PotreeAuthConfiguration {
     /// The parent directory to all the projects being served.
     pub data_dir: PathBuf,
 
-    /// Populated to use an IdP for authentication.
-    pub idp: Option<IdpConfiguration>,
+    /// Configures where the projects are defined, and how changes to them are
+    /// picked up.
+    pub projects: ProjectsConfiguration,
+
+    /// The OIDC IdPs with which users can authenticate. Authentication is
+    /// disabled if there are none (and no `local_users` or `trusted_proxy`).
+    pub idps: Vec<IdpConfiguration>,
+
+    /// Populated to authenticate users with local user accounts (in addition
+    /// to any IdPs).
+    pub local_users: Option<LocalUsersConfiguration>,
+
+    /// Populated to authenticate users from the headers of an upstream
+    /// authenticating proxy (instead of an IdP).
+    pub trusted_proxy: Option<TrustedProxyConfiguration>,
+
+    /// Configures how web sessions are persisted.
+    pub session: SessionConfiguration,
+
+    /// Configures the signing of share links.
+    pub share_link: ShareLinkConfiguration,
+
+    /// Configures how authorization decisions are made.
+    pub authorization: AuthorizationConfiguration,
+
+    /// Configures the forward-auth endpoint used by reverse proxies.
+    pub forward_auth: ForwardAuthConfiguration,
+
+    /// Configures the audit log.
+    pub audit: AuditConfiguration,
+}
+
+/// The configuration of where the projects are defined and, for manifests
+/// (which are kept in memory), how they are reloaded when they change.
+#[derive(Debug, Clone)]
+pub struct ProjectsConfiguration {
+    /// Where the projects are defined.
+    pub store: ProjectStoreConfiguration,
+
+    /// How the data directory is watched for changes.
+    pub watch_mode: ProjectsWatchMode,
+
+    /// How often the data directory is scanned for changes when polling.
+    pub poll_interval: std::time::Duration,
+}
+
+impl Default for ProjectsConfiguration {
+    fn default() -> Self {
+        Self {
+            store: ProjectStoreConfiguration::Manifests,
+            watch_mode: ProjectsWatchMode::Native,
+            poll_interval: std::time::Duration::from_secs(30),
+        }
+    }
+}
+
+/// Where the projects (but not their assets) are defined.
+#[derive(Debug, Clone)]
+pub enum ProjectStoreConfiguration {
+    /// Each project is defined by the `manifest.yml` in its project directory.
+    Manifests,
+
+    /// The projects are defined in a SQLite database file at `path`, and can be
+    /// edited through the admin API. The file is created if it does not
+    /// exist.
+    Sqlite { path: PathBuf },
+}
+
+/// How the data directory is watched for changes to the projects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectsWatchMode {
+    /// Uses the filesystem notifications of the operating system, falling back
+    /// to polling if they are unavailable.
+    Native,
+
+    /// Scans the data directory for changes, e.g. for network mounts which
+    /// don't emit notifications.
+    Poll,
 }
 
 /// The configuration required to use an OIDC IdP for authentication.
 #[derive(Debug, Clone)]
 pub struct IdpConfiguration {
+    /// Uniquely identifies the IdP. Its callback route is `/auth/callback` if
+    /// it is the [`DEFAULT_PROVIDER`][crate::user::domain::DEFAULT_PROVIDER],
+    /// else `/auth/callback/{name}`.
+    pub name: ProviderName,
+
+    /// A human readable name for the IdP, shown when choosing an IdP to log in
+    /// with.
+    pub display_name: String,
+
+    /// Prefixed to the groups of the IdP's users (e.g. `acme:`), so that the
+    /// groups of different IdPs can be told apart.
+    pub group_prefix: Option<String>,
+
     /// The URL to the IdP service.
     pub idp_url: Url,
 
@@ -26,10 +124,225 @@ pub struct IdpConfiguration {
     pub client_secret: String,
 
     /// The name of the OIDC claim containing and array of groups that a user is
-    /// part of.
+    /// part of. Nested claims can be referenced with a `.` separated path.
     pub groups_claim: String,
 
+    /// Whether to fetch the user's claims (e.g. groups) from the IdP's UserInfo
+    /// endpoint in addition to the ID token.
+    pub fetch_userinfo: bool,
+
+    /// Whether to trust the email addresses of the IdP's users, even if the IdP
+    /// doesn't claim to have verified them.
+    pub trust_unverified_email: bool,
+
+    /// The path to a YAML file mapping the IdP's group values (e.g. LDAP DNs)
+    /// to the group names used in project manifests.
+    pub group_mapping_file: Option<PathBuf>,
+
     /// The URL on which the application is publicly accessible (the OIDC
     /// callback URL is calculated from this).
     pub external_url: Url,
+
+    /// The expected `aud` claim of access tokens presented as `Authorization:
+    /// Bearer` headers. Defaults to the `client_id`.
+    pub access_token_audience: Option<String>,
+
+    /// Scopes requested in addition to `openid` (e.g. `email`, `groups`).
+    pub scopes: Vec<String>,
+
+    /// The `prompt` values sent to the IdP (e.g. `login`, `select_account`).
+    pub prompt: Vec<String>,
+
+    /// The `login_hint` sent to the IdP.
+    pub login_hint: Option<String>,
+
+    /// The requested Authentication Context Class References (e.g. to require
+    /// MFA), in order of preference.
+    pub acr_values: Vec<String>,
+
+    /// The maximum time since the user last actively authenticated with the
+    /// IdP.
+    pub max_age: Option<std::time::Duration>,
+
+    /// How long the user of a web session is trusted before their claims are
+    /// re-validated with the IdP (using the refresh token).
+    pub session_max_age: Option<std::time::Duration>,
+
+    /// How often the IdP's metadata (including its signing keys) is refreshed.
+    pub metadata_refresh_interval: std::time::Duration,
+}
+
+/// The configuration required to authenticate users from the identity headers
+/// set by an upstream authenticating proxy (e.g. oauth2-proxy).
+#[derive(Debug, Clone)]
+pub struct TrustedProxyConfiguration {
+    /// The networks from which requests are trusted to carry identity headers.
+    pub trusted_networks: Vec<IpNet>,
+
+    /// The header containing the unique id of the user.
+    pub user_id_header: HeaderName,
+
+    /// The header containing the name of the user.
+    pub name_header: HeaderName,
+
+    /// The header containing the email address of the user.
+    pub email_header: HeaderName,
+
+    /// The header containing the groups of the user.
+    pub groups_header: HeaderName,
+
+    /// Separates the groups in the `groups_header`.
+    pub groups_separator: char,
+}
+
+/// The configuration required to authenticate users with the local user
+/// accounts of a users file.
+#[derive(Debug, Clone)]
+pub struct LocalUsersConfiguration {
+    /// The path to the (YAML) file listing the local user accounts.
+    pub users_file: PathBuf,
+
+    /// A human readable name for the local user accounts, shown when choosing
+    /// an IdP to log in with.
+    pub display_name: String,
+
+    /// The number of failed login attempts (per client address, or username
+    /// from a client address) after which further attempts have to back off.
+    pub max_failed_logins: u32,
+
+    /// The longest that further login attempts are rejected for.
+    pub max_backoff: std::time::Duration,
+
+    /// The networks of the reverse proxies whose `X-Forwarded-For` header
+    /// identifies the client of a login attempt.
+    pub trusted_proxy_networks: Vec<IpNet>,
+}
+
+/// The configuration of the web sessions (i.e. where a logged in user is
+/// stored).
+#[derive(Debug, Clone)]
+pub struct SessionConfiguration {
+    /// Where the sessions should be persisted.
+    pub store: SessionStoreConfiguration,
+
+    /// Whether the session cookie should only be sent over HTTPS.
+    pub cookie_secure: bool,
+
+    /// The `SameSite` policy of the session cookie.
+    pub cookie_same_site: SameSite,
+
+    /// How long a session can be inactive before it expires.
+    pub inactivity_expiry: Duration,
+}
+
+impl Default for SessionConfiguration {
+    fn default() -> Self {
+        Self {
+            store: SessionStoreConfiguration::Memory,
+            cookie_secure: true,
+            cookie_same_site: SameSite::None,
+            inactivity_expiry: Duration::days(1),
+        }
+    }
+}
+
+/// The backend used to persist web sessions.
+#[derive(Debug, Clone)]
+pub enum SessionStoreConfiguration {
+    /// Sessions are kept in memory and are lost on restart.
+    Memory,
+
+    /// Sessions are persisted in a SQLite database file at `path`. The file is
+    /// created if it does not exist.
+    Sqlite { path: PathBuf },
+}
+
+/// The configuration of share links (i.e. time-limited links to a project for
+/// visitors without an account on the IdP).
+#[derive(Debug, Clone)]
+pub struct ShareLinkConfiguration {
+    /// The secret key used to sign share links. Share links are disabled if
+    /// this is not populated.
+    pub signing_key: Option<String>,
+
+    /// The longest that share links can be valid for.
+    pub max_validity: Duration,
+}
+
+impl Default for ShareLinkConfiguration {
+    fn default() -> Self {
+        Self {
+            signing_key: None,
+            max_validity: DEFAULT_SHARE_LINK_MAX_VALIDITY,
+        }
+    }
+}
+
+/// The configuration of the authorization engine.
+#[derive(Debug, Clone)]
+pub struct AuthorizationConfiguration {
+    /// The path to a (YAML or TOML) policy file. If this is not populated, the
+    /// built-in rules are used.
+    pub policy_file: Option<PathBuf>,
+
+    /// Members of any of these groups are allowed to do anything (with the
+    /// built-in rules).
+    pub admin_groups: Vec<Group>,
+}
+
+impl Default for AuthorizationConfiguration {
+    fn default() -> Self {
+        Self {
+            policy_file: None,
+            admin_groups: vec![Group::new(DEFAULT_ADMIN_GROUP)],
+        }
+    }
+}
+
+/// The configuration of the forward-auth endpoint (i.e. for a reverse proxy
+/// that serves the project assets itself).
+#[derive(Debug, Clone)]
+pub struct ForwardAuthConfiguration {
+    /// The path under which the reverse proxy serves the project assets. The
+    /// original uri of a forwarded request should be this path, followed by
+    /// the project id and asset path.
+    pub path_prefix: String,
+}
+
+impl Default for ForwardAuthConfiguration {
+    fn default() -> Self {
+        Self {
+            path_prefix: "/project-assets".to_owned(),
+        }
+    }
+}
+
+/// The configuration of the audit log (i.e. a record of who accessed which
+/// project, and when).
+#[derive(Debug, Clone)]
+pub struct AuditConfiguration {
+    /// The path of the JSON-lines file that audit events are appended to. The
+    /// audit log is disabled if this is not populated.
+    pub log_file: Option<PathBuf>,
+
+    /// The size (in bytes) after which the file is rotated.
+    pub max_file_size: u64,
+
+    /// The number of rotated files that are kept.
+    pub max_rotated_files: usize,
+
+    /// How long a project session lasts without any requests for the
+    /// project's assets. Only the first asset access of a session is recorded.
+    pub session_timeout: std::time::Duration,
+}
+
+impl Default for AuditConfiguration {
+    fn default() -> Self {
+        Self {
+            log_file: None,
+            max_file_size: 10 * 1024 * 1024,
+            max_rotated_files: 10,
+            session_timeout: std::time::Duration::from_secs(30 * 60),
+        }
+    }
 }
@@ -0,0 +1,389 @@
+//! Validates a whole data directory, i.e. that every project can be loaded and
+//! that its point clouds can be viewed.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::project::adapters::project_repository::manifest_file::ManifestFileProjectRepository;
+use crate::project::domain::ProjectId;
+use crate::project::domain::diagnostic::ProjectDiagnostic;
+use crate::project::domain::diagnostic::ProjectDiagnosticKind;
+use crate::project::domain::diagnostic::ValidationReport;
+use crate::project::ports::project_repository::ProjectRepositoryError;
+use crate::project_asset::domain::potree_config::POTREE_CONFIG_FILE_NAME;
+use crate::project_asset::domain::potree_config::PointCloudLocation;
+use crate::project_asset::domain::potree_config::PotreeConfig;
+use crate::project_asset::domain::potree_config::PotreeConfigError;
+
+/// Checks each project directory in the `data_dir`:
+///
+/// - its name is a URL-safe [`ProjectId`].
+/// - its manifest can be loaded.
+/// - its Potree configuration exists and can be parsed.
+/// - each point cloud of the Potree configuration refers to an existing asset
+///   of the project (point clouds served by other origins aren't checked).
+///
+/// # Errors
+///
+/// Will return an error if the `data_dir` can't be read.
+pub async fn validate_data_directory(
+    data_dir: &Path,
+) -> Result<ValidationReport, ProjectRepositoryError> {
+    let project_repository = ManifestFileProjectRepository::new(data_dir);
+
+    let mut project_ids = project_repository.list_project_ids().await?;
+    project_ids.sort();
+
+    let mut loaded_projects = 0;
+    let mut diagnostics = Vec::new();
+    for project_id in project_ids {
+        let project_dir = data_dir.join(project_id.as_str());
+
+        if !project_id.is_url_safe() {
+            diagnostics.push(ProjectDiagnostic {
+                project_id: project_id.clone(),
+                path: project_dir.clone(),
+                kind: ProjectDiagnosticKind::InvalidProjectId,
+                line: None,
+                column: None,
+                message: "the names of the project directory and its collections may only contain ASCII letters, digits, \
+                          `-`, `.`, `_` and `~`"
+                    .to_owned(),
+            });
+        }
+
+        match project_repository.load(&project_id).await {
+            Ok(_) => loaded_projects += 1,
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+
+        diagnostics.extend(validate_potree_config(&project_id, &project_dir).await);
+    }
+
+    Ok(ValidationReport::new(loaded_projects, diagnostics))
+}
+
+/// Describes the problems with the Potree configuration of the project with
+/// the `project_id` (in the `project_dir`), and its point clouds.
+async fn validate_potree_config(
+    project_id: &ProjectId,
+    project_dir: &Path,
+) -> Vec<ProjectDiagnostic> {
+    let potree_config_path = project_dir.join(POTREE_CONFIG_FILE_NAME);
+
+    let diagnostic = |kind, line, column, message| ProjectDiagnostic {
+        project_id: project_id.clone(),
+        path: potree_config_path.clone(),
+        kind,
+        line,
+        column,
+        message,
+    };
+
+    let contents = match tokio::fs::read_to_string(&potree_config_path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return vec![diagnostic(
+                ProjectDiagnosticKind::MissingPotreeConfig,
+                None,
+                None,
+                format!("the project directory does not contain a `{POTREE_CONFIG_FILE_NAME}`"),
+            )];
+        }
+        Err(e) => {
+            return vec![diagnostic(
+                ProjectDiagnosticKind::UnreadablePotreeConfig,
+                None,
+                None,
+                e.to_string(),
+            )];
+        }
+    };
+
+    let potree_config = match PotreeConfig::parse(&contents) {
+        Ok(potree_config) => potree_config,
+        Err(PotreeConfigError::Parsing {
+            message,
+            line,
+            column,
+        }) => {
+            return vec![diagnostic(
+                ProjectDiagnosticKind::InvalidPotreeConfig,
+                line,
+                column,
+                message,
+            )];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    for point_cloud in potree_config.pointclouds {
+        let name = point_cloud.name.as_deref().unwrap_or("unnamed");
+        match point_cloud.location(project_id) {
+            PointCloudLocation::Asset(path) => {
+                if !is_file(&project_dir.join(&path)).await {
+                    diagnostics.push(diagnostic(
+                        ProjectDiagnosticKind::MissingPointCloud,
+                        None,
+                        None,
+                        format!(
+                            "the url `{}` of the point cloud `{name}` refers to `{}`, which does \
+                             not exist",
+                            point_cloud.url,
+                            PathBuf::from(project_id.as_str()).join(path).display()
+                        ),
+                    ));
+                }
+            }
+            PointCloudLocation::External => {}
+            PointCloudLocation::Outside => diagnostics.push(diagnostic(
+                ProjectDiagnosticKind::InvalidPointCloudUrl,
+                None,
+                None,
+                format!(
+                    "the url `{}` of the point cloud `{name}` does not refer to an asset of the \
+                     project",
+                    point_cloud.url
+                ),
+            )),
+        }
+    }
+
+    diagnostics
+}
+
+/// Whether the `path` exists and is a file (following symlinks).
+async fn is_file(path: &Path) -> bool {
+    tokio::fs::metadata(path)
+        .await
+        .is_ok_and(|metadata| metadata.is_file())
+}
+
+#[cfg(test)]
+mod data_directory_tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const MANIFEST: &str = "name: Survey\ngroups: [surveyors]\n";
+
+    /// Creates a project directory with a valid manifest, the `potree_config`
+    /// (if any) and empty files at the `asset_paths`.
+    fn create_project(
+        data_dir: &Path,
+        project_id: &str,
+        potree_config: Option<&str>,
+        asset_paths: &[&str],
+    ) {
+        let project_dir = data_dir.join(project_id);
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("manifest.yml"), MANIFEST).unwrap();
+        if let Some(potree_config) = potree_config {
+            fs::write(project_dir.join(POTREE_CONFIG_FILE_NAME), potree_config).unwrap();
+        }
+        for asset_path in asset_paths {
+            let asset_path = project_dir.join(asset_path);
+            fs::create_dir_all(asset_path.parent().unwrap()).unwrap();
+            fs::write(asset_path, "").unwrap();
+        }
+    }
+
+    fn kinds(validation_report: &ValidationReport) -> Vec<ProjectDiagnosticKind> {
+        validation_report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect()
+    }
+
+    mod validate_data_directory {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_be_valid_if_all_point_clouds_exist() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(
+                data_dir.path(),
+                "survey",
+                Some(
+                    "{ pointclouds: [{ name: 'Lion', url: 'survey/pointclouds/cloud.js' }, \
+                     { url: 'https://example.com/cloud.js' }] }",
+                ),
+                &["pointclouds/cloud.js"],
+            );
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(validation_report, ValidationReport::new(1, Vec::new()));
+        }
+
+        #[tokio::test]
+        async fn should_report_a_missing_point_cloud() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(
+                data_dir.path(),
+                "survey",
+                Some("{ pointclouds: [{ name: 'Lion', url: 'survey/pointclouds/cloud.js' }] }"),
+                &[],
+            );
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert!(!validation_report.valid);
+            assert_eq!(validation_report.loaded_projects, 1);
+            assert_eq!(
+                kinds(&validation_report),
+                vec![ProjectDiagnosticKind::MissingPointCloud]
+            );
+            let diagnostic = &validation_report.diagnostics[0];
+            assert_eq!(
+                diagnostic.path,
+                data_dir.path().join("survey").join(POTREE_CONFIG_FILE_NAME)
+            );
+            assert!(
+                diagnostic.message.contains("`Lion`"),
+                "{}",
+                diagnostic.message
+            );
+        }
+
+        #[tokio::test]
+        async fn should_report_a_point_cloud_of_another_project() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(
+                data_dir.path(),
+                "survey",
+                Some("{ pointclouds: [{ url: 'other/pointclouds/cloud.js' }] }"),
+                &[],
+            );
+            create_project(
+                data_dir.path(),
+                "other",
+                Some("{ pointclouds: [] }"),
+                &["pointclouds/cloud.js"],
+            );
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                kinds(&validation_report),
+                vec![ProjectDiagnosticKind::InvalidPointCloudUrl]
+            );
+            assert_eq!(
+                validation_report.diagnostics[0].project_id,
+                ProjectId::new("survey".to_owned())
+            );
+        }
+
+        #[tokio::test]
+        async fn should_report_a_missing_potree_config() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(data_dir.path(), "survey", None, &[]);
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                kinds(&validation_report),
+                vec![ProjectDiagnosticKind::MissingPotreeConfig]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_report_where_the_potree_config_is_invalid() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(
+                data_dir.path(),
+                "survey",
+                Some("{\n  pointclouds: [\n    { url: 'cloud.js' ]\n}"),
+                &[],
+            );
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                kinds(&validation_report),
+                vec![ProjectDiagnosticKind::InvalidPotreeConfig]
+            );
+            assert_eq!(validation_report.diagnostics[0].line, Some(3));
+        }
+
+        #[tokio::test]
+        async fn should_report_a_project_id_that_is_not_url_safe() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(
+                data_dir.path(),
+                "survey 2024",
+                Some("{ pointclouds: [] }"),
+                &[],
+            );
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(validation_report.loaded_projects, 1);
+            assert_eq!(
+                kinds(&validation_report),
+                vec![ProjectDiagnosticKind::InvalidProjectId]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_report_an_invalid_manifest_and_still_check_the_potree_config() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            create_project(data_dir.path(), "survey", None, &[]);
+            fs::write(
+                data_dir.path().join("survey").join("manifest.yml"),
+                "groups: surveyors",
+            )
+            .unwrap();
+
+            // Act
+            let validation_report = validate_data_directory(data_dir.path()).await.unwrap();
+
+            // Assert
+            assert_eq!(validation_report.loaded_projects, 0);
+            assert_eq!(
+                kinds(&validation_report),
+                vec![
+                    ProjectDiagnosticKind::InvalidManifest,
+                    ProjectDiagnosticKind::MissingPotreeConfig,
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_the_data_dir_does_not_exist() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+
+            // Act
+            let res = validate_data_directory(&data_dir.path().join("missing")).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::Infrastucture { .. })
+            ));
+        }
+    }
+}
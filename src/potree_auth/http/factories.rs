@@ -1,59 +1,398 @@
+use std::path::Path;
 use std::sync::Arc;
 
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePool;
+use tower_sessions::MemoryStore;
+use tower_sessions::session_store::ExpiredDeletion;
+use tower_sessions_sqlx_store::SqliteStore;
+
+use super::super::config::AuditConfiguration;
+use super::super::config::AuthorizationConfiguration;
 use super::super::config::IdpConfiguration;
+use super::super::config::LocalUsersConfiguration;
+use super::super::config::ProjectStoreConfiguration;
+use super::super::config::ProjectsConfiguration;
+use super::super::config::ProjectsWatchMode;
+use super::super::config::SessionStoreConfiguration;
+use super::super::config::ShareLinkConfiguration;
+use super::super::config::TrustedProxyConfiguration;
 use super::error::PotreeAuthHttpError;
 use super::router::AUTH;
+use crate::audit::adapters::audit_log::json_lines::JsonLinesAuditLog;
+use crate::audit::adapters::audit_log::json_lines::RotationOptions;
+use crate::audit::ports::audit_log::AuditLog;
+use crate::authentication::adapters::authentication_engine::local_users::LocalUsersAuthenticationEngine;
+use crate::authentication::adapters::authentication_engine::local_users::LoginThrottleOptions;
 use crate::authentication::adapters::authentication_engine::no_op::NoOpAuthenticationEngine;
+use crate::authentication::adapters::authentication_engine::oidc::AuthenticationRequestOptions;
 use crate::authentication::adapters::authentication_engine::oidc::OidcAuthenticationEngine;
+use crate::authentication::adapters::authentication_engine::oidc::OidcEngineOptions;
+use crate::authentication::adapters::authentication_engine::trusted_proxy::TrustedProxyAuthenticationEngine;
+use crate::authentication::adapters::authentication_engine::trusted_proxy::TrustedProxyHeaders;
+use crate::authentication::application::service::IdentityProvider;
+use crate::authentication::domain::GroupMapping;
 use crate::authentication::http::CALLBACK;
+use crate::authentication::http::PROVIDER_CALLBACK;
 use crate::authentication::ports::authentication_engine::AuthenticationEngine;
+use crate::authorization::adapters::audited::AuditedAuthorizationEngine;
 use crate::authorization::adapters::basic_authorization::SimpleAuthorizationEngine;
 use crate::authorization::adapters::no_op::NoOpAuthorizationEngine;
+use crate::authorization::adapters::policy_file::PolicyFileAuthorizationEngine;
 use crate::authorization::ports::authorization_engine::AuthorizationEngine;
+use crate::common::utils::http::middleware::session::SessionBackend;
+use crate::project::adapters::project_repository::caching::CachingProjectRepository;
+use crate::project::adapters::project_repository::caching::WatchMode;
+use crate::project::adapters::project_repository::caching::WatchOptions;
+use crate::project::adapters::project_repository::sqlite::SqliteProjectRepository;
+use crate::project::ports::project_repository::ProjectRepository;
+use crate::share_link::adapters::share_link_signer::hmac::HmacShareLinkSigner;
+use crate::share_link::adapters::share_link_signer::no_op::NoOpShareLinkSigner;
+use crate::share_link::ports::share_link_signer::ShareLinkSigner;
+use crate::user::domain::DEFAULT_PROVIDER;
+use crate::user::domain::LOCAL_PROVIDER;
+use crate::user::domain::ProviderName;
+
+/// How often expired sessions are removed from a persistent session store.
+const EXPIRED_SESSION_CLEANUP_PERIOD: std::time::Duration = std::time::Duration::from_secs(60 * 60);
 
-/// Initialize an authentication engine to handle OIDC authentication.
+/// Initialize the identity providers with which users can authenticate. Each
+/// IdP has an authentication engine to handle OIDC authentication, and the
+/// local user accounts (if a `local_users_config` is provided) come after them.
+/// If a `trusted_proxy_config` is provided, users are instead authenticated by
+/// an upstream proxy.
 ///
-/// If no `idp_config` is provided, a no-op authentication engine is returned.
-pub async fn init_authentication_engine(
-    idp_config: Option<IdpConfiguration>,
-) -> Result<Arc<dyn AuthenticationEngine>, PotreeAuthHttpError> {
-    Ok(if let Some(idp_config) = idp_config {
-        let redirect_url = idp_config
-            .external_url
-            .join(&AUTH.join(CALLBACK.as_ref()))
-            .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
-                adapter_name: "OidcAuthenticationEngine".to_owned(),
-                message: format!("unable to build redirect url: {e}"),
-            })?;
-
-        let authentication_engine = OidcAuthenticationEngine::new(
-            idp_config.idp_url,
-            redirect_url,
-            idp_config.client_id,
-            idp_config.client_secret,
-            idp_config.groups_claim,
+/// If none are provided, a single provider with a no-op authentication engine
+/// is returned.
+pub fn init_identity_providers(
+    idp_configs: Vec<IdpConfiguration>,
+    local_users_config: Option<LocalUsersConfiguration>,
+    trusted_proxy_config: Option<TrustedProxyConfiguration>,
+) -> Result<Vec<IdentityProvider>, PotreeAuthHttpError> {
+    let default_provider = |authentication_engine: Arc<dyn AuthenticationEngine>| {
+        vec![IdentityProvider {
+            name: ProviderName::new(DEFAULT_PROVIDER.to_owned()),
+            display_name: DEFAULT_PROVIDER.to_owned(),
+            group_prefix: None,
+            authentication_engine,
+        }]
+    };
+
+    if let Some(trusted_proxy_config) = trusted_proxy_config {
+        return Ok(default_provider(Arc::new(
+            TrustedProxyAuthenticationEngine::new(
+                trusted_proxy_config.trusted_networks,
+                TrustedProxyHeaders {
+                    user_id: trusted_proxy_config.user_id_header,
+                    name: trusted_proxy_config.name_header,
+                    email: trusted_proxy_config.email_header,
+                    groups: trusted_proxy_config.groups_header,
+                    groups_separator: trusted_proxy_config.groups_separator,
+                },
+            ),
+        )));
+    }
+
+    if idp_configs.is_empty() && local_users_config.is_none() {
+        return Ok(default_provider(Arc::new(NoOpAuthenticationEngine)));
+    }
+
+    let mut providers = Vec::with_capacity(idp_configs.len() + 1);
+    for idp_config in idp_configs {
+        providers.push(IdentityProvider {
+            name: idp_config.name.clone(),
+            display_name: idp_config.display_name.clone(),
+            group_prefix: idp_config.group_prefix.clone(),
+            authentication_engine: init_oidc_authentication_engine(idp_config)?,
+        });
+    }
+
+    if let Some(local_users_config) = local_users_config {
+        let authentication_engine = LocalUsersAuthenticationEngine::from_file(
+            &local_users_config.users_file,
+            LoginThrottleOptions {
+                max_failed_logins: local_users_config.max_failed_logins,
+                max_backoff: local_users_config.max_backoff,
+                trusted_proxy_networks: local_users_config.trusted_proxy_networks,
+            },
         )
-        .await
         .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
-            adapter_name: "OidcAuthenticationEngine".to_owned(),
+            adapter_name: "LocalUsersAuthenticationEngine".to_owned(),
             message: e.to_string(),
         })?;
 
-        Arc::new(authentication_engine)
+        providers.push(IdentityProvider {
+            name: ProviderName::new(LOCAL_PROVIDER.to_owned()),
+            display_name: local_users_config.display_name,
+            group_prefix: None,
+            authentication_engine: Arc::new(authentication_engine),
+        });
+    }
+
+    Ok(providers)
+}
+
+/// Initialize an authentication engine to handle OIDC authentication with an
+/// IdP. The IdP's metadata is discovered (and periodically refreshed) by a
+/// background task.
+fn init_oidc_authentication_engine(
+    idp_config: IdpConfiguration,
+) -> Result<Arc<dyn AuthenticationEngine>, PotreeAuthHttpError> {
+    let adapter_name = format!("OidcAuthenticationEngine ({})", idp_config.name);
+
+    let group_mapping = match &idp_config.group_mapping_file {
+        Some(path) => read_group_mapping(path)?,
+        None => GroupMapping::default(),
+    };
+
+    // The default IdP keeps the callback route it had before there could be
+    // multiple IdPs, so that its registered redirect URL remains valid.
+    let callback_route = if idp_config.name.as_str() == DEFAULT_PROVIDER {
+        AUTH.join(CALLBACK.as_ref())
     } else {
-        Arc::new(NoOpAuthenticationEngine)
+        AUTH.join(
+            PROVIDER_CALLBACK
+                .to_web_route(&serde_json::json!({ "provider": idp_config.name }))
+                .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+                    adapter_name: adapter_name.clone(),
+                    message: format!("unable to build callback route: {e}"),
+                })?
+                .as_ref(),
+        )
+    };
+    let redirect_url = idp_config.external_url.join(&callback_route).map_err(|e| {
+        PotreeAuthHttpError::AdapterIntialization {
+            adapter_name: adapter_name.clone(),
+            message: format!("unable to build redirect url: {e}"),
+        }
+    })?;
+
+    let authentication_engine = OidcAuthenticationEngine::new(OidcEngineOptions {
+        idp_url: idp_config.idp_url,
+        redirect_url,
+        client_id: idp_config.client_id,
+        client_secret: idp_config.client_secret,
+        groups_claim: idp_config.groups_claim,
+        group_mapping,
+        fetch_userinfo: idp_config.fetch_userinfo,
+        trust_unverified_email: idp_config.trust_unverified_email,
+        post_logout_redirect_url: idp_config.external_url,
+        access_token_audience: idp_config.access_token_audience,
+        request_options: AuthenticationRequestOptions {
+            scopes: idp_config.scopes,
+            prompt: idp_config.prompt,
+            login_hint: idp_config.login_hint,
+            acr_values: idp_config.acr_values,
+            max_age: idp_config.max_age,
+        },
+        session_max_age: idp_config.session_max_age,
+    })
+    .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+        adapter_name,
+        message: e.to_string(),
+    })?;
+
+    // The IdP may not be reachable yet, so its metadata is discovered in the
+    // background rather than failing startup.
+    authentication_engine.spawn_discovery(idp_config.metadata_refresh_interval);
+
+    Ok(Arc::new(authentication_engine))
+}
+
+/// Reads the YAML file (at `path`) mapping IdP group values to groups.
+fn read_group_mapping(path: &Path) -> Result<GroupMapping, PotreeAuthHttpError> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+            adapter_name: "OidcAuthenticationEngine".to_owned(),
+            message: format!(
+                "unable to read group mapping file ({}): {e}",
+                path.to_string_lossy()
+            ),
+        })?;
+
+    serde_norway::from_str(&contents).map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+        adapter_name: "OidcAuthenticationEngine".to_owned(),
+        message: format!(
+            "unable to parse group mapping file ({}): {e}",
+            path.to_string_lossy()
+        ),
     })
 }
 
 /// Initializes the authorization engine.
 ///
 /// If `authentication_configured` (e.g. OIDC) then a valid authorization engine
-/// will be used. Else a no-op engine will be used that allow unauthenticated
-/// users to have access.
-pub fn init_authorization_engine(authentication_configured: bool) -> Arc<dyn AuthorizationEngine> {
-    if authentication_configured {
-        Arc::new(SimpleAuthorizationEngine)
-    } else {
-        Arc::new(NoOpAuthorizationEngine)
+/// will be used, driven by the policy file if one is configured. Else a no-op
+/// engine will be used that allow unauthenticated users to have access.
+pub fn init_authorization_engine(
+    authentication_configured: bool,
+    authorization_config: AuthorizationConfiguration,
+) -> Result<Arc<dyn AuthorizationEngine>, PotreeAuthHttpError> {
+    if !authentication_configured {
+        return Ok(Arc::new(NoOpAuthorizationEngine));
     }
+
+    Ok(match authorization_config.policy_file {
+        Some(policy_file) => {
+            let authorization_engine = PolicyFileAuthorizationEngine::from_file(&policy_file)
+                .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+                    adapter_name: "PolicyFileAuthorizationEngine".to_owned(),
+                    message: e.to_string(),
+                })?;
+
+            Arc::new(authorization_engine)
+        }
+        None => Arc::new(SimpleAuthorizationEngine::new(
+            authorization_config.admin_groups,
+        )),
+    })
+}
+
+/// Initializes the audit log, if an audit log file is configured.
+pub fn init_audit_log(audit_config: &AuditConfiguration) -> Option<Arc<dyn AuditLog>> {
+    let log_file = audit_config.log_file.as_ref()?;
+
+    Some(Arc::new(JsonLinesAuditLog::new(
+        log_file,
+        RotationOptions {
+            max_file_size: audit_config.max_file_size,
+            max_rotated_files: audit_config.max_rotated_files,
+        },
+    )))
+}
+
+/// Records the decisions of the `authorization_engine` in the `audit_log`, if
+/// there is one.
+pub fn audit_authorization_engine(
+    authorization_engine: Arc<dyn AuthorizationEngine>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    audit_config: &AuditConfiguration,
+) -> Arc<dyn AuthorizationEngine> {
+    match audit_log {
+        Some(audit_log) => Arc::new(AuditedAuthorizationEngine::new(
+            authorization_engine,
+            audit_log,
+            audit_config.session_timeout,
+        )),
+        None => authorization_engine,
+    }
+}
+
+/// Initializes the repository of the projects. Projects defined by manifests
+/// in the `data_dir` are kept in memory, and reloaded as the directory changes.
+/// Projects defined in a database are read from it as needed (and migrated
+/// first).
+pub async fn init_project_repository(
+    data_dir: &Path,
+    projects_config: &ProjectsConfiguration,
+) -> Result<Arc<dyn ProjectRepository>, PotreeAuthHttpError> {
+    let project_repository: Arc<dyn ProjectRepository> = match &projects_config.store {
+        ProjectStoreConfiguration::Manifests => {
+            let watch = WatchOptions {
+                mode: match projects_config.watch_mode {
+                    ProjectsWatchMode::Native => WatchMode::Native,
+                    ProjectsWatchMode::Poll => WatchMode::Poll,
+                },
+                poll_interval: projects_config.poll_interval,
+            };
+
+            Arc::new(
+                CachingProjectRepository::new(data_dir, watch)
+                    .await
+                    .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+                        adapter_name: "CachingProjectRepository".to_owned(),
+                        message: e.to_string(),
+                    })?,
+            )
+        }
+        ProjectStoreConfiguration::Sqlite { path } => {
+            Arc::new(SqliteProjectRepository::new(path).await.map_err(|e| {
+                PotreeAuthHttpError::AdapterIntialization {
+                    adapter_name: "SqliteProjectRepository".to_owned(),
+                    message: e.to_string(),
+                }
+            })?)
+        }
+    };
+
+    // Projects that can't be loaded are left out of the dashboard, so they are
+    // reported rather than silently disappearing.
+    match project_repository.diagnostics().await {
+        Ok(diagnostics) => {
+            for diagnostic in diagnostics {
+                tracing::warn!(
+                    "unable to load the project ({}): {diagnostic}",
+                    diagnostic.project_id
+                );
+            }
+        }
+        Err(e) => tracing::warn!("unable to diagnose the projects: {e}"),
+    }
+
+    Ok(project_repository)
+}
+
+/// Initializes the backend in which web sessions are persisted.
+///
+/// Persistent backends are migrated, and a background task is spawned to
+/// periodically delete expired sessions from them.
+pub async fn init_session_backend(
+    store_config: SessionStoreConfiguration,
+) -> Result<SessionBackend, PotreeAuthHttpError> {
+    Ok(match store_config {
+        SessionStoreConfiguration::Memory => SessionBackend::Memory(MemoryStore::default()),
+        SessionStoreConfiguration::Sqlite { path } => {
+            let connect_options = SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(connect_options)
+                .await
+                .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+                    adapter_name: "SqliteStore".to_owned(),
+                    message: format!(
+                        "unable to open session database ({}): {e}",
+                        path.to_string_lossy()
+                    ),
+                })?;
+
+            let store = SqliteStore::new(pool);
+            store
+                .migrate()
+                .await
+                .map_err(|e| PotreeAuthHttpError::AdapterIntialization {
+                    adapter_name: "SqliteStore".to_owned(),
+                    message: format!("unable to migrate session database: {e}"),
+                })?;
+
+            tokio::spawn(
+                store
+                    .clone()
+                    .continuously_delete_expired(EXPIRED_SESSION_CLEANUP_PERIOD),
+            );
+
+            SessionBackend::Sqlite(store)
+        }
+    })
+}
+
+/// Initializes the signer used to create and verify share links.
+///
+/// If no signing key is configured, a no-op signer is returned that rejects all
+/// share links.
+pub fn init_share_link_signer(
+    share_link_config: ShareLinkConfiguration,
+) -> Result<Arc<dyn ShareLinkSigner>, PotreeAuthHttpError> {
+    Ok(if let Some(signing_key) = share_link_config.signing_key {
+        let share_link_signer = HmacShareLinkSigner::new(signing_key.as_bytes()).map_err(|e| {
+            PotreeAuthHttpError::AdapterIntialization {
+                adapter_name: "HmacShareLinkSigner".to_owned(),
+                message: e.to_string(),
+            }
+        })?;
+
+        Arc::new(share_link_signer)
+    } else {
+        Arc::new(NoOpShareLinkSigner)
+    })
 }
@@ -1,5 +1,5 @@
 mod error;
-mod factories;
+pub(crate) mod factories;
 mod router;
 
 pub use router::init_application;
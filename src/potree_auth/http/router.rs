@@ -1,38 +1,54 @@
 use std::sync::Arc;
 use std::sync::LazyLock;
 
+use axum::Extension;
 use axum::Router;
 use axum::response::Redirect;
 use axum::routing::get;
-use time::Duration;
 use tower::Layer;
 use tower_http::normalize_path::NormalizePath;
 use tower_http::normalize_path::NormalizePathLayer;
 use web_route::ParameterizedRoute;
 use web_route::WebRoute;
 
+use super::super::config::ForwardAuthConfiguration;
 use super::super::config::PotreeAuthConfiguration;
+use super::super::config::SessionConfiguration;
 use super::error::PotreeAuthHttpError;
-use super::factories::init_authentication_engine;
+use super::factories::audit_authorization_engine;
+use super::factories::init_audit_log;
 use super::factories::init_authorization_engine;
+use super::factories::init_identity_providers;
+use super::factories::init_project_repository;
+use super::factories::init_session_backend;
+use super::factories::init_share_link_signer;
+use crate::access_explanation::application::service::AccessExplanationService;
+use crate::access_explanation::{self};
 use crate::authentication::application::service::AuthenticationService;
 use crate::authentication::http::LOGIN;
 use crate::authentication::{self};
 use crate::common;
 use crate::common::utils::http::middleware::security_headers::apply_secure_headers_middleware;
+use crate::common::utils::http::middleware::session::SessionBackend;
+use crate::common::utils::http::middleware::session::SessionCookieOptions;
 use crate::common::utils::http::middleware::session::apply_session_layer;
 use crate::common::utils::http::middleware::tracing::apply_tracing_middleware;
+use crate::forward_auth::{self};
 use crate::potree_asset::adapters::potree_asset_store::embedded::EmbeddedPotreeAssetStore;
 use crate::potree_asset::application::service::PotreeAssetService;
-use crate::project::adapters::project_repository::manifest_file::ManifestFileProjectRepository;
 use crate::project::application::service::ProjectService;
+use crate::project_admin::application::service::ProjectAdminService;
+use crate::project_admin::{self};
 use crate::project_asset::adapters::project_asset_store::serve_dir::ServeDirProjectAssets;
 use crate::project_asset::application::service::ProjectAssetService;
 use crate::project_asset::http::ASSET_PATH;
 use crate::project_asset::{self};
 use crate::render::application::service::RenderingService;
+use crate::render::http::POTREE;
 use crate::render::http::PROJECT_DASHBOARD;
 use crate::render::{self};
+use crate::share_link::application::service::ShareLinkService;
+use crate::share_link::{self};
 
 pub static AUTH: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/auth"));
 pub static POTREE_ASSETS: LazyLock<ParameterizedRoute> =
@@ -44,14 +60,31 @@ pub async fn init_application(
     config: PotreeAuthConfiguration,
 ) -> Result<NormalizePath<Router>, PotreeAuthHttpError> {
     // Initialize adapters
-    let authorization_engine = init_authorization_engine(config.idp.is_some());
-    let authentication_engine = init_authentication_engine(config.idp).await?;
-    let project_repository = Arc::new(ManifestFileProjectRepository::new(&config.data_dir));
+    let audit_log = init_audit_log(&config.audit);
+    let authorization_engine = audit_authorization_engine(
+        init_authorization_engine(
+            !config.idps.is_empty()
+                || config.local_users.is_some()
+                || config.trusted_proxy.is_some(),
+            config.authorization,
+        )?,
+        audit_log.clone(),
+        &config.audit,
+    );
+    let identity_providers =
+        init_identity_providers(config.idps, config.local_users, config.trusted_proxy)?;
+    let project_repository = init_project_repository(&config.data_dir, &config.projects).await?;
     let potree_asset_store = Arc::new(EmbeddedPotreeAssetStore);
     let project_asset_store = Arc::new(ServeDirProjectAssets::new(&config.data_dir));
+    let session_backend = init_session_backend(config.session.store.clone()).await?;
+    let share_link_max_validity = config.share_link.max_validity;
+    let share_link_signer = init_share_link_signer(config.share_link)?;
 
     // Initialize services
-    let authentication_service = AuthenticationService::new(authentication_engine);
+    let authentication_service = match audit_log {
+        Some(audit_log) => AuthenticationService::new(identity_providers).with_audit_log(audit_log),
+        None => AuthenticationService::new(identity_providers),
+    };
     let potree_asset_service = PotreeAssetService::new(potree_asset_store);
     let project_service = Arc::new(ProjectService::new(
         project_repository.clone(),
@@ -62,6 +95,16 @@ pub async fn init_application(
         project_asset_store,
         authorization_engine.clone(),
     );
+    let access_explanation_service =
+        AccessExplanationService::new(project_repository.clone(), authorization_engine.clone());
+    let project_admin_service =
+        ProjectAdminService::new(project_repository.clone(), authorization_engine.clone());
+    let share_link_service = ShareLinkService::new(
+        share_link_signer,
+        project_service.clone(),
+        authorization_engine.clone(),
+    )
+    .with_max_validity(share_link_max_validity);
     let rendering_service = RenderingService::new(
         project_service,
         authorization_engine,
@@ -74,37 +117,78 @@ pub async fn init_application(
         potree_asset_service,
         project_asset_service,
         rendering_service,
+        share_link_service,
+        access_explanation_service,
+        project_admin_service,
+        session_backend,
+        config.session,
+        config.forward_auth,
     )
 }
 
 /// Sets up the http router with its various services.
+#[allow(clippy::too_many_arguments)]
 fn build_router(
     authentication_service: AuthenticationService,
     potree_asset_service: PotreeAssetService,
     project_asset_service: ProjectAssetService,
     rendering_service: RenderingService,
+    share_link_service: ShareLinkService,
+    access_explanation_service: AccessExplanationService,
+    project_admin_service: ProjectAdminService,
+    session_backend: SessionBackend,
+    session_config: SessionConfiguration,
+    forward_auth_config: ForwardAuthConfiguration,
 ) -> Result<NormalizePath<Router>, PotreeAuthHttpError> {
     // Initialize child routers
-    let authentication_router = authentication::http::build_router(authentication_service);
+    let authentication_router = authentication::http::build_router(authentication_service.clone());
     let potree_asset_router = crate::potree_asset::http::build_router(potree_asset_service);
+    let forward_auth_router = forward_auth::http::build_router(
+        project_asset_service.clone(),
+        forward_auth_config.path_prefix,
+    );
     let project_asset_router = project_asset::http::build_router(project_asset_service);
     let rendering_router =
         render::http::build_router(rendering_service, AUTH.join(LOGIN.as_ref()))?;
-    let common_routes = common::utils::http::common_routes::build_router();
+    let share_link_router =
+        share_link::http::build_router(share_link_service.clone(), POTREE.clone());
+    let access_explanation_router =
+        access_explanation::http::build_router(access_explanation_service);
+    let project_admin_router = project_admin::http::build_router(project_admin_service);
+    let common_routes =
+        common::utils::http::common_routes::build_router(authentication_service.clone());
 
     // Build top-level router
     let router = Router::new()
         .nest(&AUTH, authentication_router)
+        .nest(&AUTH, forward_auth_router)
         .nest(&POTREE_ASSETS, potree_asset_router)
         .nest(&PROJECT_ASSETS, project_asset_router)
         .merge(rendering_router)
+        .merge(share_link_router)
+        .merge(access_explanation_router)
+        .merge(project_admin_router)
         .merge(common_routes);
 
     // Apply quality of life redirects
     let router = router.route("/", get(|| async { Redirect::to(&PROJECT_DASHBOARD) }));
 
+    // Make the authentication and share link services available to extractors
+    // (e.g. to authenticate bearer tokens or verify share tokens).
+    let router = router
+        .layer(Extension(authentication_service))
+        .layer(Extension(share_link_service));
+
     // Apply middleware
-    let router = apply_session_layer(router, Duration::days(1));
+    let router = apply_session_layer(
+        router,
+        session_backend,
+        SessionCookieOptions {
+            secure: session_config.cookie_secure,
+            same_site: session_config.cookie_same_site,
+            inactivity_expiry: session_config.inactivity_expiry,
+        },
+    );
     let router = apply_secure_headers_middleware(router)?;
     let router = apply_tracing_middleware(router);
 
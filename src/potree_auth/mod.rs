@@ -5,8 +5,10 @@
 
 mod cli;
 pub mod config;
+mod data_directory;
 mod http;
 mod observability;
+mod project_import;
 mod shutdown_signal;
 
 pub use cli::Cli;
@@ -0,0 +1,232 @@
+//! Imports the projects defined by the manifests of a data directory into
+//! another (editable) project repository, e.g. when switching to the SQLite
+//! project store.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::project::adapters::project_repository::manifest_file::ManifestFileProjectRepository;
+use crate::project::domain::ProjectId;
+use crate::project::domain::diagnostic::ProjectDiagnostic;
+use crate::project::domain::diagnostic::ProjectDiagnosticKind;
+use crate::project::ports::project_repository::ProjectRepository;
+use crate::project::ports::project_repository::ProjectRepositoryError;
+
+/// Which projects were imported, and why the others weren't.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportReport {
+    /// The projects that didn't exist in the repository yet.
+    pub created: Vec<ProjectId>,
+
+    /// The projects that already existed in the repository, and were replaced.
+    pub updated: Vec<ProjectId>,
+
+    /// Why the other projects weren't imported, i.e. their manifests could not
+    /// be loaded or their ids overlap projects in the repository.
+    pub diagnostics: Vec<ProjectDiagnostic>,
+}
+
+/// Copies each project that can be loaded from the `data_dir` into the
+/// `project_repository`, replacing any project with the same id. The groups
+/// inherited from collections become groups of the imported project.
+///
+/// Projects in the `project_repository` that aren't in the `data_dir` are left
+/// as they are, so a project whose id is nested in one of theirs (or the other
+/// way around) isn't imported.
+///
+/// # Errors
+///
+/// Will return an error if the `data_dir` can't be read, or a project can't be
+/// written to the `project_repository`.
+pub async fn import_projects(
+    data_dir: &Path,
+    project_repository: &dyn ProjectRepository,
+) -> Result<ImportReport, ProjectRepositoryError> {
+    let manifest_repository = ManifestFileProjectRepository::new(data_dir);
+
+    let mut project_ids = manifest_repository.list_project_ids().await?;
+    project_ids.sort();
+
+    let mut import_report = ImportReport {
+        created: Vec::new(),
+        updated: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    for project_id in project_ids {
+        let project = match manifest_repository.load(&project_id).await {
+            Ok(project) => project,
+            Err(diagnostic) => {
+                import_report.diagnostics.push(diagnostic);
+                continue;
+            }
+        };
+
+        match project_repository.create(&project).await {
+            Ok(()) => import_report.created.push(project_id),
+            Err(ProjectRepositoryError::AlreadyExists { .. }) => {
+                project_repository.update(&project).await?;
+                import_report.updated.push(project_id);
+            }
+            Err(e @ ProjectRepositoryError::Overlapping { .. }) => {
+                import_report.diagnostics.push(ProjectDiagnostic {
+                    path: data_dir.join(project_id.as_str()).join("manifest.yml"),
+                    project_id,
+                    kind: ProjectDiagnosticKind::OverlappingProjectId,
+                    line: None,
+                    column: None,
+                    message: e.to_string(),
+                });
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(import_report)
+}
+
+#[cfg(test)]
+mod project_import_tests {
+    use std::fs;
+
+    use fake::Fake;
+    use fake::Faker;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::common::domain::Group;
+    use crate::project::adapters::project_repository::sqlite::SqliteProjectRepository;
+    use crate::project::domain::Project;
+    use crate::project::domain::ProjectDescription;
+    use crate::project::domain::ProjectName;
+
+    fn write_manifest(data_dir: &Path, directory: &str, manifest: &str) {
+        let directory = data_dir.join(directory);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("manifest.yml"), manifest).unwrap();
+    }
+
+    mod import_projects {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_import_the_projects_with_their_inherited_groups() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            write_manifest(
+                data_dir.path(),
+                "client-a",
+                "collection: true\ngroups: [client-a]\n",
+            );
+            write_manifest(
+                data_dir.path(),
+                "client-a/survey",
+                "name: Survey\ndescription: The 2026 survey\ngroups: [surveyors]\n",
+            );
+            write_manifest(data_dir.path(), "broken", "groups: surveyors\n");
+            let database_dir = TempDir::new().unwrap();
+            let project_repository =
+                SqliteProjectRepository::new(&database_dir.path().join("projects.db"))
+                    .await
+                    .unwrap();
+
+            // Act
+            let import_report = import_projects(data_dir.path(), &project_repository)
+                .await
+                .unwrap();
+
+            // Assert
+            let project_id = ProjectId::new("client-a/survey".to_owned());
+            assert_eq!(import_report.created, vec![project_id.clone()]);
+            assert!(import_report.updated.is_empty());
+            assert_eq!(import_report.diagnostics.len(), 1);
+            assert_eq!(
+                import_report.diagnostics[0].kind,
+                ProjectDiagnosticKind::InvalidManifest
+            );
+
+            let project = project_repository.read(&project_id).await.unwrap();
+            assert_eq!(project.name, ProjectName::new("Survey".to_owned()));
+            assert_eq!(
+                project.description,
+                Some(ProjectDescription::new("The 2026 survey".to_owned()))
+            );
+            assert_eq!(
+                project.groups,
+                vec![Group::new("surveyors"), Group::new("client-a")]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_replace_projects_that_were_imported_before() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            write_manifest(
+                data_dir.path(),
+                "survey",
+                "name: Survey\ngroups: [surveyors]\n",
+            );
+            let database_dir = TempDir::new().unwrap();
+            let project_repository =
+                SqliteProjectRepository::new(&database_dir.path().join("projects.db"))
+                    .await
+                    .unwrap();
+            import_projects(data_dir.path(), &project_repository)
+                .await
+                .unwrap();
+            write_manifest(
+                data_dir.path(),
+                "survey",
+                "name: Survey\ngroups: [clients]\n",
+            );
+
+            // Act
+            let import_report = import_projects(data_dir.path(), &project_repository)
+                .await
+                .unwrap();
+
+            // Assert
+            let project_id = ProjectId::new("survey".to_owned());
+            assert!(import_report.created.is_empty());
+            assert_eq!(import_report.updated, vec![project_id.clone()]);
+            assert_eq!(
+                project_repository.read(&project_id).await.unwrap().groups,
+                vec![Group::new("clients")]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_not_import_projects_overlapping_existing_projects() {
+            // Arrange
+            let data_dir = TempDir::new().unwrap();
+            write_manifest(data_dir.path(), "client-a", "collection: true\n");
+            write_manifest(
+                data_dir.path(),
+                "client-a/survey",
+                "name: Survey\ngroups: [surveyors]\n",
+            );
+            let database_dir = TempDir::new().unwrap();
+            let project_repository =
+                SqliteProjectRepository::new(&database_dir.path().join("projects.db"))
+                    .await
+                    .unwrap();
+            let mut existing_project: Project = Faker.fake();
+            existing_project.id = ProjectId::new("client-a".to_owned());
+            existing_project.path_rules = Vec::new();
+            project_repository.create(&existing_project).await.unwrap();
+
+            // Act
+            let import_report = import_projects(data_dir.path(), &project_repository)
+                .await
+                .unwrap();
+
+            // Assert
+            assert!(import_report.created.is_empty());
+            assert_eq!(import_report.diagnostics.len(), 1);
+            assert_eq!(
+                import_report.diagnostics[0].kind,
+                ProjectDiagnosticKind::OverlappingProjectId
+            );
+        }
+    }
+}
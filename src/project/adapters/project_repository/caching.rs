@@ -0,0 +1,940 @@
+//! A [`ProjectRepository`] that keeps the projects of a
+//! [`ManifestFileProjectRepository`] in memory, so that reading a project
+//! (which happens for every asset request) doesn't touch the disk.
+//!
+//! All the manifests are loaded once on creation. Afterwards the projects
+//! directory, each collection and each project directory are watched
+//! (non-recursively, as projects can contain a large number of asset
+//! directories), and a project is reloaded whenever its directory or manifest
+//! changes. As the projects in a collection inherit its groups, all the
+//! projects are reloaded whenever a collection changes. Native filesystem
+//! notifications are used by default. Network mounts don't emit those, so the
+//! directories can be polled instead.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use notify::Config;
+use notify::Event;
+use notify::EventKind;
+use notify::PollWatcher;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::super::super::domain::Project;
+use super::super::super::domain::ProjectId;
+use super::super::super::domain::diagnostic::ProjectDiagnostic;
+use super::super::super::ports::project_repository::ProjectRepository;
+use super::super::super::ports::project_repository::ProjectRepositoryError;
+use super::manifest_file::MANIFEST_FILE_NAME;
+use super::manifest_file::ManifestFileProjectRepository;
+use super::manifest_file::project_id;
+use super::manifest_file::read_only_error;
+
+/// How long to wait for more changes after a change is noticed. Saving a file
+/// (or copying a project) often emits several events, which are then handled
+/// at once.
+const DEBOUNCE_PERIOD: Duration = Duration::from_millis(100);
+
+/// A project as it was last loaded, or why it couldn't be loaded (so that
+/// reading it keeps returning the same error, and it can be diagnosed).
+type CachedProject = Result<Project, ProjectDiagnostic>;
+
+/// The loaded projects by their id, shared with the task reloading them.
+type ProjectCache = Arc<RwLock<BTreeMap<ProjectId, CachedProject>>>;
+
+/// An in-memory cache of the projects of a [`ManifestFileProjectRepository`]
+/// that is kept up to date by watching the `projects_directory`. It implements
+/// the [`ProjectRepository`] trait.
+#[derive(Debug)]
+pub struct CachingProjectRepository {
+    /// The projects, as last loaded from their manifests.
+    cache: ProjectCache,
+
+    /// Reloads the projects when they change. It owns the watcher, so aborting
+    /// it (when the repository is dropped) stops watching the directories.
+    reload_task: JoinHandle<()>,
+}
+
+/// How the projects directory is watched for changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchMode {
+    /// Uses the notifications of the operating system. Falls back to polling if
+    /// those are unavailable (e.g. the limit of watches is reached).
+    Native,
+
+    /// Scans the directories for changes every `poll_interval`. Needed for
+    /// network mounts (e.g. NFS or SMB), which don't emit notifications.
+    ///
+    /// Changes are detected by modification time, which has a resolution of a
+    /// second.
+    Poll,
+}
+
+/// Controls how the projects directory is watched for changes.
+#[derive(Debug, Clone, Copy)]
+pub struct WatchOptions {
+    pub mode: WatchMode,
+
+    /// How often the directories are scanned when polling.
+    pub poll_interval: Duration,
+}
+
+impl CachingProjectRepository {
+    /// Creates a new [`CachingProjectRepository`] with all the projects in the
+    /// `projects_directory` loaded, and starts watching it for changes.
+    ///
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `projects_directory` can't be read or
+    /// watched.
+    pub async fn new<P: AsRef<Path>>(
+        projects_directory: P,
+        watch: WatchOptions,
+    ) -> Result<Self, ProjectRepositoryError> {
+        // Events refer to canonical paths on some platforms, which the paths of
+        // the watched directories are compared against.
+        let projects_directory =
+            std::fs::canonicalize(projects_directory.as_ref()).map_err(|e| {
+                ProjectRepositoryError::Infrastucture {
+                    message: format!(
+                        "unable to read from the directory: {} ({e})",
+                        projects_directory.as_ref().to_string_lossy()
+                    ),
+                }
+            })?;
+
+        let cache = ProjectCache::default();
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+
+        // Watching starts before the projects are loaded, so that no changes
+        // are missed in between.
+        let mut reloader = ProjectReloader {
+            inner: ManifestFileProjectRepository::new(&projects_directory),
+            watcher: init_watcher(&projects_directory, watch, event_sender)?,
+            projects_directory,
+            watched_directories: HashSet::new(),
+            collections: HashSet::new(),
+            cache: cache.clone(),
+        };
+        reloader.reload_all().await?;
+
+        Ok(Self {
+            cache,
+            reload_task: tokio::spawn(reloader.run(event_receiver)),
+        })
+    }
+
+    fn read(&self, project_id: &ProjectId) -> Result<Project, ProjectRepositoryError> {
+        self.cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(project_id)
+            .map(|project| project.clone().map_err(Into::into))
+            .unwrap_or_else(|| {
+                Err(ProjectRepositoryError::ResourceNotFound {
+                    id: project_id.clone(),
+                })
+            })
+    }
+
+    fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
+        // Like the manifest files, the projects that did not load successfully
+        // are left out.
+        let mut projects = self
+            .cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .filter_map(|project| project.as_ref().ok())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(projects)
+    }
+
+    fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        Ok(self
+            .cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .filter_map(|project| project.as_ref().err())
+            .cloned()
+            .collect())
+    }
+}
+
+impl Drop for CachingProjectRepository {
+    fn drop(&mut self) {
+        self.reload_task.abort();
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for CachingProjectRepository {
+    async fn read(&self, project_id: &ProjectId) -> Result<Project, ProjectRepositoryError> {
+        Self::read(self, project_id)
+    }
+
+    async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
+        Self::list(self)
+    }
+
+    async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        Self::diagnostics(self)
+    }
+
+    async fn create(&self, _project: &Project) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
+
+    async fn update(&self, _project: &Project) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
+
+    async fn delete(&self, _project_id: &ProjectId) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
+}
+
+/// Creates a watcher of the `projects_directory` (as configured by `watch`)
+/// that sends its events to the `event_sender`.
+fn init_watcher(
+    projects_directory: &Path,
+    watch: WatchOptions,
+    event_sender: mpsc::UnboundedSender<notify::Result<Event>>,
+) -> Result<Box<dyn Watcher + Send>, ProjectRepositoryError> {
+    // The receiver is only dropped when the repository is, after which the
+    // events are of no interest.
+    let event_handler = move |event| {
+        let _ = event_sender.send(event);
+    };
+
+    if watch.mode == WatchMode::Native {
+        let native_watcher = RecommendedWatcher::new(event_handler.clone(), Config::default())
+            .and_then(|mut watcher| {
+                watcher.watch(projects_directory, RecursiveMode::NonRecursive)?;
+                Ok(watcher)
+            });
+
+        match native_watcher {
+            Ok(watcher) => return Ok(Box::new(watcher)),
+            Err(e) => tracing::warn!(
+                "unable to watch the projects directory natively, polling instead: {e}"
+            ),
+        }
+    }
+
+    let mut watcher = PollWatcher::new(
+        event_handler,
+        Config::default().with_poll_interval(watch.poll_interval),
+    )
+    .map_err(|e| watch_error(projects_directory, &e))?;
+    watcher
+        .watch(projects_directory, RecursiveMode::NonRecursive)
+        .map_err(|e| watch_error(projects_directory, &e))?;
+
+    Ok(Box::new(watcher))
+}
+
+fn watch_error(directory: &Path, error: &notify::Error) -> ProjectRepositoryError {
+    ProjectRepositoryError::Infrastucture {
+        message: format!(
+            "unable to watch the directory: {} ({error})",
+            directory.to_string_lossy()
+        ),
+    }
+}
+
+/// Reloads the projects in the [`ProjectCache`] as the watcher notices
+/// changes.
+struct ProjectReloader {
+    /// Reads the projects from their manifests.
+    inner: ManifestFileProjectRepository,
+
+    /// Watches the `projects_directory` and the `watched_directories`.
+    watcher: Box<dyn Watcher + Send>,
+
+    /// The (canonical) directory containing all the projects.
+    projects_directory: PathBuf,
+
+    /// The collection and project directories that are currently watched.
+    watched_directories: HashSet<PathBuf>,
+
+    /// The paths (relative to the `projects_directory`) of the collections, as
+    /// last listed.
+    collections: HashSet<PathBuf>,
+
+    cache: ProjectCache,
+}
+
+/// What needs to be reloaded after one or more events.
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    /// Whether all the projects need to be reloaded, e.g. if the watcher
+    /// missed events.
+    all: bool,
+
+    /// The projects that need to be reloaded.
+    project_ids: BTreeSet<ProjectId>,
+}
+
+impl ProjectReloader {
+    /// Handles the `events` until the repository is dropped.
+    async fn run(mut self, mut events: mpsc::UnboundedReceiver<notify::Result<Event>>) {
+        while let Some(event) = events.recv().await {
+            tokio::time::sleep(DEBOUNCE_PERIOD).await;
+
+            let mut changes = Changes::default();
+            self.add_changes(&mut changes, event);
+            while let Ok(event) = events.try_recv() {
+                self.add_changes(&mut changes, event);
+            }
+
+            if changes.all {
+                if let Err(e) = self.reload_all().await {
+                    tracing::error!("unable to reload the projects: {e}");
+                }
+            } else {
+                for project_id in changes.project_ids {
+                    self.reload_project(project_id).await;
+                }
+            }
+        }
+    }
+
+    /// Adds what needs to be reloaded because of the `event` to the
+    /// `changes`.
+    fn add_changes(&self, changes: &mut Changes, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("error while watching the projects, reloading all of them: {e}");
+                changes.all = true;
+                return;
+            }
+        };
+
+        // Reading a manifest emits access events, which would otherwise cause
+        // it to be reloaded over and over.
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        if event.need_rescan() {
+            changes.all = true;
+            return;
+        }
+
+        for path in &event.paths {
+            let Ok(relative_path) = path.strip_prefix(&self.projects_directory) else {
+                changes.all = true;
+                continue;
+            };
+            if !relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                changes.all = true;
+                continue;
+            }
+
+            let parent = relative_path.parent().unwrap_or(Path::new(""));
+            let is_manifest = relative_path
+                .file_name()
+                .is_some_and(|file_name| file_name == MANIFEST_FILE_NAME);
+
+            if relative_path.as_os_str().is_empty() || self.collections.contains(relative_path) {
+                // The projects directory itself, or a collection was removed or
+                // renamed.
+                changes.all = true;
+            } else if is_manifest && self.collections.contains(parent) {
+                // A collection manifest was changed, which affects all the
+                // projects in it (and whether it is still a collection).
+                changes.all = true;
+            } else if is_manifest && self.is_project_directory(parent) {
+                // A manifest was changed.
+                changes.project_ids.insert(project_id(parent));
+            } else if self.is_collection_or_root(parent) {
+                // A project directory was created, removed or renamed.
+                changes.project_ids.insert(project_id(relative_path));
+            }
+            // Otherwise it is any other file, such as the project's assets.
+        }
+    }
+
+    /// Whether the `path` (relative to the `projects_directory`) is the
+    /// projects directory itself or a collection.
+    fn is_collection_or_root(&self, path: &Path) -> bool {
+        path.as_os_str().is_empty() || self.collections.contains(path)
+    }
+
+    /// Whether the `path` (relative to the `projects_directory`) is a directory
+    /// directly within the projects directory or a collection, which isn't a
+    /// collection itself.
+    fn is_project_directory(&self, path: &Path) -> bool {
+        !path.as_os_str().is_empty()
+            && !self.collections.contains(path)
+            && self.is_collection_or_root(path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Replaces the cache with all the projects that are currently in the
+    /// projects directory.
+    async fn reload_all(&mut self) -> Result<(), ProjectRepositoryError> {
+        let directories = self.inner.list_directories().await?;
+        let project_ids = directories.project_ids;
+
+        let loaded_projects = futures::future::join_all(
+            project_ids
+                .iter()
+                .map(|project_id| self.inner.load(project_id)),
+        )
+        .await;
+
+        let watched_directories = directories
+            .collections
+            .iter()
+            .map(|collection| self.projects_directory.join(collection))
+            .chain(
+                project_ids
+                    .iter()
+                    .map(|project_id| self.projects_directory.join(project_id.as_str())),
+            )
+            .collect::<HashSet<_>>();
+        let removed_directories = self
+            .watched_directories
+            .difference(&watched_directories)
+            .cloned()
+            .collect::<Vec<_>>();
+        for directory in removed_directories {
+            self.unwatch(&directory);
+        }
+        for directory in watched_directories {
+            self.watch(directory);
+        }
+        self.collections = directories.collections.into_iter().collect();
+
+        let projects = project_ids.into_iter().zip(loaded_projects).collect();
+
+        *self.cache.write().unwrap_or_else(PoisonError::into_inner) = projects;
+
+        Ok(())
+    }
+
+    /// Reloads the project with the `project_id`, removing it from the cache
+    /// if its directory no longer exists.
+    async fn reload_project(&mut self, project_id: ProjectId) {
+        let project_directory = self.projects_directory.join(project_id.as_str());
+        if !project_directory.is_dir() {
+            self.unwatch(&project_directory);
+            self.cache
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&project_id);
+            return;
+        }
+
+        // A new directory could be a collection (e.g. one that was copied in),
+        // along with the projects in it.
+        if self
+            .inner
+            .is_collection(Path::new(project_id.as_str()))
+            .await
+        {
+            if let Err(e) = self.reload_all().await {
+                tracing::error!("unable to reload the projects: {e}");
+            }
+            return;
+        }
+
+        self.watch(project_directory);
+
+        let project = self.inner.load(&project_id).await;
+        if let Err(diagnostic) = &project {
+            tracing::warn!("unable to load the project ({project_id}): {diagnostic}");
+        }
+
+        self.cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(project_id, project);
+    }
+
+    /// Starts watching the collection or project `directory` (for changes to
+    /// its manifest and the directories in it), if it isn't already watched.
+    fn watch(&mut self, directory: PathBuf) {
+        if self.watched_directories.contains(&directory) {
+            return;
+        }
+
+        match self.watcher.watch(&directory, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                self.watched_directories.insert(directory);
+            }
+            Err(e) => tracing::warn!(
+                "unable to watch the directory, changes to its manifest won't be noticed: {}",
+                watch_error(&directory, &e)
+            ),
+        }
+    }
+
+    /// Stops watching the (removed) collection or project `directory`.
+    fn unwatch(&mut self, directory: &Path) {
+        if self.watched_directories.remove(directory) {
+            // The watch is already gone if the directory was removed.
+            let _ = self.watcher.unwatch(directory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod caching_project_repository_tests {
+
+    use super::*;
+
+    /// How long the tests wait for a change to be picked up.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn watch_options(mode: WatchMode) -> WatchOptions {
+        WatchOptions {
+            mode,
+            poll_interval: Duration::from_millis(50),
+        }
+    }
+
+    /// Writes a manifest of a project with the `name` to the `project_id`
+    /// subdirectory of the `projects_dir` (which is created, along with its
+    /// parents, if needed).
+    fn write_manifest(projects_dir: &Path, project_id: &str, name: &str) {
+        let project_dir = projects_dir.join(project_id);
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        std::fs::write(
+            project_dir.join(MANIFEST_FILE_NAME),
+            format!("name: {name}\ngroups: []\n"),
+        )
+        .unwrap();
+    }
+
+    /// Waits until the `condition` holds for the projects of the
+    /// `repository`, panicking after the [`TIMEOUT`].
+    async fn wait_until(
+        repository: &CachingProjectRepository,
+        condition: impl Fn(&[Project]) -> bool,
+    ) {
+        let started = tokio::time::Instant::now();
+        while !condition(&repository.list().unwrap()) {
+            assert!(
+                started.elapsed() < TIMEOUT,
+                "the change wasn't picked up in time"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    fn names(projects: &[Project]) -> Vec<&str> {
+        projects
+            .iter()
+            .map(|project| project.name.as_str())
+            .collect()
+    }
+
+    mod new {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_load_all_the_projects() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_b", "Project B");
+            write_manifest(dir.path(), "project_a", "Project A");
+
+            // Act
+            let repository =
+                CachingProjectRepository::new(dir.path(), watch_options(WatchMode::Native))
+                    .await
+                    .unwrap();
+
+            // Assert
+            assert_eq!(
+                names(&repository.list().unwrap()),
+                vec!["Project A", "Project B"]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_err_if_projects_directory_does_not_exist() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+
+            // Act
+            let res = CachingProjectRepository::new(
+                dir.path().join("missing"),
+                watch_options(WatchMode::Native),
+            )
+            .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::Infrastucture { .. })
+            ));
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_project_without_reading_the_manifest() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_1", "Project 1");
+            let repository = CachingProjectRepository::new(
+                dir.path(),
+                WatchOptions {
+                    mode: WatchMode::Poll,
+                    poll_interval: Duration::from_secs(60 * 60),
+                },
+            )
+            .await
+            .unwrap();
+            // Unparsable, but not noticed before the next poll.
+            let manifest_path = dir.path().join("project_1").join(MANIFEST_FILE_NAME);
+            std::fs::write(&manifest_path, "").unwrap();
+
+            // Act
+            let project = repository
+                .read(&ProjectId::new("project_1".to_owned()))
+                .unwrap();
+
+            // Assert
+            assert_eq!(project.name.as_str(), "Project 1");
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_manifest_invalid() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("project_1")).unwrap();
+            std::fs::write(
+                dir.path().join("project_1").join(MANIFEST_FILE_NAME),
+                "not: [a manifest",
+            )
+            .unwrap();
+            let repository =
+                CachingProjectRepository::new(dir.path(), watch_options(WatchMode::Native))
+                    .await
+                    .unwrap();
+
+            // Act
+            let res = repository.read(&ProjectId::new("project_1".to_owned()));
+
+            // Assert
+            assert!(matches!(res, Err(ProjectRepositoryError::Parsing { .. })));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_project_not_found() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("empty")).unwrap();
+            let repository =
+                CachingProjectRepository::new(dir.path(), watch_options(WatchMode::Native))
+                    .await
+                    .unwrap();
+
+            // Act
+            let res = repository.read(&ProjectId::new("empty".to_owned()));
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+    }
+
+    mod diagnostics {
+        use super::*;
+        use crate::project::domain::diagnostic::ProjectDiagnosticKind;
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_report_an_added_project_without_a_manifest(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_1", "Project 1");
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+
+            // Act
+            std::fs::create_dir(dir.path().join("project_2")).unwrap();
+
+            // Assert
+            let started = tokio::time::Instant::now();
+            while repository.diagnostics().unwrap().is_empty() {
+                assert!(
+                    started.elapsed() < TIMEOUT,
+                    "the change wasn't picked up in time"
+                );
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            let diagnostics = repository.diagnostics().unwrap();
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].kind, ProjectDiagnosticKind::MissingManifest);
+        }
+    }
+
+    mod watch {
+        use super::*;
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_reload_an_edited_manifest(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_1", "Project 1");
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+
+            // Polling compares modification times, which have a resolution of a
+            // second.
+            if mode == WatchMode::Poll {
+                tokio::time::sleep(Duration::from_millis(1100)).await;
+            }
+
+            // Act
+            write_manifest(dir.path(), "project_1", "Renamed Project");
+
+            // Assert
+            wait_until(&repository, |projects| {
+                names(projects) == vec!["Renamed Project"]
+            })
+            .await;
+        }
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_load_an_added_project(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_1", "Project 1");
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+
+            // Act
+            // The manifest is written after the directory is created, as when
+            // copying a project.
+            std::fs::create_dir(dir.path().join("project_2")).unwrap();
+            tokio::time::sleep(DEBOUNCE_PERIOD * 3).await;
+            write_manifest(dir.path(), "project_2", "Project 2");
+
+            // Assert
+            wait_until(&repository, |projects| {
+                names(projects) == vec!["Project 1", "Project 2"]
+            })
+            .await;
+        }
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_remove_a_deleted_project(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "project_1", "Project 1");
+            write_manifest(dir.path(), "project_2", "Project 2");
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+
+            // Act
+            std::fs::remove_dir_all(dir.path().join("project_2")).unwrap();
+
+            // Assert
+            wait_until(&repository, |projects| names(projects) == vec!["Project 1"]).await;
+            assert!(matches!(
+                repository.read(&ProjectId::new("project_2".to_owned())),
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+    }
+
+    mod collections {
+        use super::*;
+        use crate::common::domain::Group;
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_reload_the_projects_of_an_edited_collection(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let collection_manifest = dir.path().join("client-a").join(MANIFEST_FILE_NAME);
+            write_manifest(dir.path(), "client-a/site-1", "Site 1");
+            std::fs::write(
+                &collection_manifest,
+                "collection: true\ngroups: [client-a]\n",
+            )
+            .unwrap();
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+            let site_1 = ProjectId::new("client-a/site-1".to_owned());
+            assert_eq!(
+                repository.read(&site_1).unwrap().groups,
+                vec![Group::new("client-a")]
+            );
+
+            // Polling compares modification times, which have a resolution of a
+            // second.
+            if mode == WatchMode::Poll {
+                tokio::time::sleep(Duration::from_millis(1100)).await;
+            }
+
+            // Act
+            std::fs::write(
+                &collection_manifest,
+                "collection: true\ngroups: [client-b]\n",
+            )
+            .unwrap();
+
+            // Assert
+            wait_until(&repository, |projects| {
+                projects
+                    .iter()
+                    .map(|project| &project.groups)
+                    .collect::<Vec<_>>()
+                    == vec![&vec![Group::new("client-b")]]
+            })
+            .await;
+        }
+
+        #[test_case::test_case(WatchMode::Native; "native")]
+        #[test_case::test_case(WatchMode::Poll; "poll")]
+        #[tokio::test]
+        async fn should_load_a_project_added_to_a_collection(mode: WatchMode) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            write_manifest(dir.path(), "client-a/site-1", "Site 1");
+            std::fs::write(
+                dir.path().join("client-a").join(MANIFEST_FILE_NAME),
+                "collection: true\n",
+            )
+            .unwrap();
+            let repository = CachingProjectRepository::new(dir.path(), watch_options(mode))
+                .await
+                .unwrap();
+
+            // Act
+            write_manifest(dir.path(), "client-a/site-2", "Site 2");
+
+            // Assert
+            wait_until(&repository, |projects| {
+                names(projects) == vec!["Site 1", "Site 2"]
+            })
+            .await;
+        }
+    }
+
+    mod add_changes {
+        use notify::event::ModifyKind;
+
+        use super::*;
+
+        fn reloader(projects_directory: &Path) -> ProjectReloader {
+            let (event_sender, _) = mpsc::unbounded_channel();
+
+            ProjectReloader {
+                inner: ManifestFileProjectRepository::new(projects_directory),
+                watcher: init_watcher(
+                    projects_directory,
+                    watch_options(WatchMode::Poll),
+                    event_sender,
+                )
+                .unwrap(),
+                projects_directory: projects_directory.to_path_buf(),
+                watched_directories: HashSet::new(),
+                collections: HashSet::from([PathBuf::from("client-a")]),
+                cache: ProjectCache::default(),
+            }
+        }
+
+        #[test_case::test_case("project_1/manifest.yml", false, Some("project_1"); "manifest")]
+        #[test_case::test_case("project_1", false, Some("project_1"); "project directory")]
+        #[test_case::test_case("project_1/metadata.json", false, None; "asset")]
+        #[test_case::test_case("", true, None; "projects directory")]
+        #[test_case::test_case("client-a/site-1/manifest.yml", false, Some("client-a/site-1"); "manifest in a collection")]
+        #[test_case::test_case("client-a/site-1", false, Some("client-a/site-1"); "project directory in a collection")]
+        #[test_case::test_case("client-a/site-1/metadata.json", false, None; "asset in a collection")]
+        #[test_case::test_case("client-a/manifest.yml", true, None; "collection manifest")]
+        #[test_case::test_case("client-a", true, None; "collection directory")]
+        #[tokio::test]
+        async fn should_only_reload_the_affected_project(
+            path: &str,
+            all: bool,
+            project_id: Option<&str>,
+        ) {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let reloader = reloader(dir.path());
+            let event = Event::new(EventKind::Modify(ModifyKind::Any))
+                .add_path(dir.path().join(path).components().collect());
+            let mut changes = Changes::default();
+
+            // Act
+            reloader.add_changes(&mut changes, Ok(event));
+
+            // Assert
+            assert_eq!(
+                changes,
+                Changes {
+                    all,
+                    project_ids: project_id
+                        .map(|project_id| ProjectId::new(project_id.to_owned()))
+                        .into_iter()
+                        .collect(),
+                }
+            );
+        }
+
+        #[tokio::test]
+        async fn should_ignore_access_events() {
+            // Arrange
+            let dir = tempfile::tempdir().unwrap();
+            let reloader = reloader(dir.path());
+            let event = Event::new(EventKind::Access(notify::event::AccessKind::Any))
+                .add_path(dir.path().join("project_1").join(MANIFEST_FILE_NAME));
+            let mut changes = Changes::default();
+
+            // Act
+            reloader.add_changes(&mut changes, Ok(event));
+
+            // Assert
+            assert_eq!(changes, Changes::default());
+        }
+    }
+}
@@ -3,33 +3,48 @@ use std::path::PathBuf;
 
 use async_trait::async_trait;
 use serde::Deserialize;
-use serde::Serialize;
+use serde_norway::Location;
 
 use super::super::super::domain::Project;
 use super::super::super::domain::ProjectId;
-use super::super::super::domain::ProjectName;
 use super::super::super::ports::project_repository::ProjectRepository;
 use super::super::super::ports::project_repository::ProjectRepositoryError;
 use crate::common::domain::group::Group;
-use crate::project::domain::ProjectDescription;
+use crate::project::domain::definition::ProjectDefinition;
+use crate::project::domain::diagnostic::ProjectDiagnostic;
+use crate::project::domain::diagnostic::ProjectDiagnosticKind;
 
 /// The name of the project manifest files.
-const MANIFEST_FILE_NAME: &str = "manifest.yml";
+pub const MANIFEST_FILE_NAME: &str = "manifest.yml";
 
 /// A manifest file backed implementation of the [`ProjectRepository`] trait.
 ///
 /// Expects the `projects_directory` to be the parent directory to a collection
-/// of project directories, each representing a project. The name of the
-/// directory is the name of the project (it is thus inherently a unique
-/// identifier). To be considered a valid project, the subdirectory needs to
+/// of project directories, each representing a project. The path of the
+/// directory is the id of the project (it is thus inherently a unique
+/// identifier). To be considered a valid project, the directory needs to
 /// contain a `manifest.yml` file that can be deserialized to a
-/// [`ProjectManifest`] struct.
+/// [`ProjectDefinition`] struct.
+///
+/// Projects can be nested in collections: directories whose `manifest.yml` is
+/// a [`CollectionManifest`] (`collection: true`). The projects in a collection
+/// (and in any collections nested in it) are also members of its groups.
 #[derive(Debug, Clone)]
 pub struct ManifestFileProjectRepository {
     /// The directory containing all the projects.
     projects_directory: PathBuf,
 }
 
+/// The directories found in the projects directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectDirectories {
+    /// The paths (relative to the projects directory) of the collections.
+    pub collections: Vec<PathBuf>,
+
+    /// The ids of the projects, whether they contain a valid manifest or not.
+    pub project_ids: Vec<ProjectId>,
+}
+
 impl ManifestFileProjectRepository {
     /// Creates a new [`ManifestFileProjectService`] instance with the specified
     /// `projects_directory`.
@@ -39,87 +54,262 @@ impl ManifestFileProjectRepository {
         }
     }
 
-    #[tracing::instrument]
-    async fn read(&self, project_id: ProjectId) -> Result<Project, ProjectRepositoryError> {
+    /// Loads the project with the `project_id` from its manifest (along with
+    /// the groups of the collections it is nested in), describing the problem
+    /// if it can't be loaded.
+    pub async fn load(&self, project_id: &ProjectId) -> Result<Project, ProjectDiagnostic> {
         let project_manifest_path = self
             .projects_directory
-            .join(String::from(project_id.clone()))
+            .join(project_id.as_str())
+            .join(MANIFEST_FILE_NAME);
+
+        let manifest_bytes = self
+            .read_manifest(project_id, &project_manifest_path)
+            .await?;
+
+        let diagnostic = |kind, location: Option<Location>, message| {
+            manifest_diagnostic(project_id, &project_manifest_path, kind, location, message)
+        };
+
+        if is_collection_manifest(&manifest_bytes) {
+            return Err(diagnostic(
+                ProjectDiagnosticKind::MissingManifest,
+                None,
+                "the directory is a collection rather than a project".to_owned(),
+            ));
+        }
+
+        let manifest =
+            serde_norway::from_slice::<ProjectDefinition>(&manifest_bytes).map_err(|e| {
+                diagnostic(
+                    ProjectDiagnosticKind::InvalidManifest,
+                    e.location(),
+                    e.to_string(),
+                )
+            })?;
+
+        // An invalid path rule is reported as a parsing error, rather than
+        // silently ignoring the rule (which would grant access to the assets it
+        // protects).
+        let mut project = manifest.into_project(project_id).map_err(|message| {
+            diagnostic(
+                ProjectDiagnosticKind::InvalidManifest,
+                None,
+                format!("invalid path rule: {message}"),
+            )
+        })?;
+
+        // The groups of the project itself come first, so that their access
+        // windows are kept.
+        for collection in project_id.parents() {
+            for group in self.load_collection(project_id, collection).await? {
+                if !project.groups.contains(&group) {
+                    project.groups.push(group);
+                }
+            }
+        }
+
+        Ok(project)
+    }
+
+    /// Loads the groups of the `collection` that the project with the
+    /// `project_id` is nested in, describing the problem if the directory
+    /// isn't a valid collection.
+    async fn load_collection(
+        &self,
+        project_id: &ProjectId,
+        collection: &str,
+    ) -> Result<Vec<Group>, ProjectDiagnostic> {
+        let collection_manifest_path = self
+            .projects_directory
+            .join(collection)
             .join(MANIFEST_FILE_NAME);
 
-        let manifest_bytes = tokio::fs::read(&project_manifest_path)
+        let diagnostic = |kind, location: Option<Location>, message| {
+            manifest_diagnostic(
+                project_id,
+                &collection_manifest_path,
+                kind,
+                location,
+                message,
+            )
+        };
+
+        // Otherwise an asset directory of a project could be read as a project of
+        // its own (with different groups).
+        let not_a_collection = || {
+            diagnostic(
+                ProjectDiagnosticKind::MissingManifest,
+                None,
+                format!("the parent directory `{collection}` is not a collection"),
+            )
+        };
+
+        let manifest_bytes = self
+            .read_manifest(project_id, &collection_manifest_path)
             .await
-            .map_err(|_e| ProjectRepositoryError::ResourceNotFound {
-                id: project_id.clone(),
+            .map_err(|diagnostic| match diagnostic.kind {
+                ProjectDiagnosticKind::MissingManifest => not_a_collection(),
+                _ => diagnostic,
             })?;
+        if !is_collection_manifest(&manifest_bytes) {
+            return Err(not_a_collection());
+        }
 
         let manifest =
-            serde_norway::from_slice::<ProjectManifest>(&manifest_bytes).map_err(|_e| {
-                ProjectRepositoryError::Parsing {
-                    id: project_id.clone(),
-                }
+            serde_norway::from_slice::<CollectionManifest>(&manifest_bytes).map_err(|e| {
+                diagnostic(
+                    ProjectDiagnosticKind::InvalidManifest,
+                    e.location(),
+                    e.to_string(),
+                )
             })?;
 
-        Ok(manifest.into_project(&project_id))
+        Ok(manifest.groups)
+    }
+
+    /// Reads the manifest at the `manifest_path`, describing the problem (for
+    /// the project with the `project_id`) if it can't be read.
+    async fn read_manifest(
+        &self,
+        project_id: &ProjectId,
+        manifest_path: &Path,
+    ) -> Result<Vec<u8>, ProjectDiagnostic> {
+        tokio::fs::read(manifest_path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                manifest_diagnostic(
+                    project_id,
+                    manifest_path,
+                    ProjectDiagnosticKind::MissingManifest,
+                    None,
+                    format!("the project directory does not contain a `{MANIFEST_FILE_NAME}`"),
+                )
+            } else {
+                manifest_diagnostic(
+                    project_id,
+                    manifest_path,
+                    ProjectDiagnosticKind::UnreadableManifest,
+                    None,
+                    e.to_string(),
+                )
+            }
+        })
     }
 
     #[tracing::instrument]
-    async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
-        let mut dir_contents = tokio::fs::read_dir(&self.projects_directory)
-            .await
-            .map_err(|_e| ProjectRepositoryError::Infrastucture {
+    async fn read(&self, project_id: ProjectId) -> Result<Project, ProjectRepositoryError> {
+        // The id may come from a request, it must not refer to a directory
+        // outside of the projects directory.
+        if !project_id.is_relative_path() {
+            return Err(ProjectRepositoryError::ResourceNotFound { id: project_id });
+        }
+
+        Ok(self.load(&project_id).await?)
+    }
+
+    /// Lists the ids of all the projects, i.e. the paths of the directories
+    /// that aren't collections (whether they contain a valid manifest or not).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `projects_directory` (or a collection in it)
+    /// can't be read.
+    pub async fn list_project_ids(&self) -> Result<Vec<ProjectId>, ProjectRepositoryError> {
+        Ok(self.list_directories().await?.project_ids)
+    }
+
+    /// Finds all the collections and projects, descending into the
+    /// collections (but not into the projects, which only contain assets).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `projects_directory` (or a collection in it)
+    /// can't be read.
+    pub async fn list_directories(&self) -> Result<ProjectDirectories, ProjectRepositoryError> {
+        let mut directories = ProjectDirectories::default();
+
+        // The collections that are yet to be read, starting with the projects
+        // directory itself.
+        let mut pending_collections = vec![PathBuf::new()];
+        while let Some(collection) = pending_collections.pop() {
+            let collection_directory = self.projects_directory.join(&collection);
+            let infrastructure_error = |_e| ProjectRepositoryError::Infrastucture {
                 message: format!(
                     "unable to read from the directory: {}",
-                    self.projects_directory.to_string_lossy()
+                    collection_directory.to_string_lossy()
                 ),
-            })?;
+            };
+
+            let mut dir_contents = tokio::fs::read_dir(&collection_directory)
+                .await
+                .map_err(infrastructure_error)?;
 
-        // Find all top-level directories, they represent the project ids.
-        let mut project_ids = Vec::new();
-        while let Some(entry) =
-            dir_contents
+            while let Some(entry) = dir_contents
                 .next_entry()
                 .await
-                .map_err(|_e| ProjectRepositoryError::Infrastucture {
-                    message: format!(
-                        "unable to read from the directory: {}",
-                        self.projects_directory.to_string_lossy()
-                    ),
-                })?
-        {
-            let file_type =
-                entry
-                    .file_type()
-                    .await
-                    .map_err(|_e| ProjectRepositoryError::Infrastucture {
-                        message: format!(
-                            "unable to read from the directory: {}",
-                            self.projects_directory.to_string_lossy()
-                        ),
-                    })?;
-            if file_type.is_dir() {
-                project_ids.push(entry.file_name());
+                .map_err(infrastructure_error)?
+            {
+                let file_type = entry.file_type().await.map_err(infrastructure_error)?;
+                if !file_type.is_dir() {
+                    continue;
+                }
+
+                let path = collection.join(entry.file_name());
+                if self.is_collection(&path).await {
+                    directories.collections.push(path.clone());
+                    pending_collections.push(path);
+                } else {
+                    directories.project_ids.push(project_id(&path));
+                }
             }
         }
 
-        // Asynchronously read the projects for each project id.
-        let loaded_projects = futures::future::join_all(project_ids.iter().map(|id| {
-            let id = ProjectId::new(id.to_string_lossy().to_string());
-            self.read(id)
-        }))
-        .await;
+        Ok(directories)
+    }
+
+    /// Whether the directory at the `path` (relative to the projects directory)
+    /// is a collection. Directories with a missing or invalid manifest are
+    /// considered projects, so that they are diagnosed.
+    pub async fn is_collection(&self, path: &Path) -> bool {
+        tokio::fs::read(self.projects_directory.join(path).join(MANIFEST_FILE_NAME))
+            .await
+            .is_ok_and(|manifest_bytes| is_collection_manifest(&manifest_bytes))
+    }
+
+    #[tracing::instrument]
+    async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
+        let project_ids = self.list_project_ids().await?;
+
+        // Asynchronously load the projects for each project id.
+        let loaded_projects =
+            futures::future::join_all(project_ids.iter().map(|id| self.load(id))).await;
 
         // Filter out the the projects that did not load successfully (e.g. invalid
-        // manifest file).
+        // manifest file), they are reported by `diagnostics`.
         let mut loaded_projects = loaded_projects
             .into_iter()
-            .filter(|res| res.is_ok())
-            .collect::<Result<Vec<_>, _>>()?;
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
 
         // Sort the projects by name
         loaded_projects.sort_by(|a, b| a.name.cmp(&b.name));
 
         Ok(loaded_projects)
     }
+
+    #[tracing::instrument]
+    async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        let mut project_ids = self.list_project_ids().await?;
+        project_ids.sort();
+
+        let loaded_projects =
+            futures::future::join_all(project_ids.iter().map(|id| self.load(id))).await;
+
+        Ok(loaded_projects
+            .into_iter()
+            .filter_map(Result::err)
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -131,62 +321,100 @@ impl ProjectRepository for ManifestFileProjectRepository {
     async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
         Self::list(self).await
     }
-}
 
-/// Represents the contents of a `manifest.json` file that is stored in a
-/// project directory.
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct ProjectManifest {
-    pub name: ProjectName,
-    pub description: Option<ProjectDescription>,
+    async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        Self::diagnostics(self).await
+    }
 
-    /// The groups that the project is a member of.
-    pub groups: Vec<Group>,
+    async fn create(&self, _project: &Project) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
+
+    async fn update(&self, _project: &Project) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
+
+    async fn delete(&self, _project_id: &ProjectId) -> Result<(), ProjectRepositoryError> {
+        Err(read_only_error())
+    }
 }
 
-impl ProjectManifest {
-    /// Converts a [`ProjectManifest`] into a [`Project`]. The `project_id`
-    /// represent the unique identifying slug of the project.
-    pub fn into_project(self, project_id: &ProjectId) -> Project {
-        let Self {
-            name,
-            description,
-            groups,
-        } = self;
-
-        Project {
-            id: project_id.clone(),
-            name,
-            description,
-            groups,
-        }
+/// The error returned when editing the projects, which are defined by the
+/// manifest files in the data directory rather than by the application.
+pub fn read_only_error() -> ProjectRepositoryError {
+    ProjectRepositoryError::ReadOnly {
+        message: format!(
+            "the projects are defined by the `{MANIFEST_FILE_NAME}` files in the data directory"
+        ),
     }
 }
 
-#[cfg(test)]
-impl ProjectManifest {
-    pub fn from_project(project: &Project) -> Self {
-        let Project {
-            name,
-            description,
-            groups,
-            ..
-        } = project.clone();
+/// The id of the project in the directory at the `path` (relative to the
+/// projects directory).
+pub fn project_id(path: &Path) -> ProjectId {
+    ProjectId::new(
+        path.iter()
+            .map(|segment| segment.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
 
-        Self {
-            name,
-            description,
-            groups,
-        }
+/// Describes a problem with the manifest at the `path`, which prevents the
+/// project with the `project_id` from being loaded.
+fn manifest_diagnostic(
+    project_id: &ProjectId,
+    path: &Path,
+    kind: ProjectDiagnosticKind,
+    location: Option<Location>,
+    message: String,
+) -> ProjectDiagnostic {
+    ProjectDiagnostic {
+        project_id: project_id.clone(),
+        path: path.to_path_buf(),
+        kind,
+        line: location.as_ref().map(Location::line),
+        column: location.as_ref().map(Location::column),
+        message,
     }
 }
 
+/// Whether the `manifest_bytes` are those of a [`CollectionManifest`] (rather
+/// than a [`ProjectDefinition`]).
+fn is_collection_manifest(manifest_bytes: &[u8]) -> bool {
+    serde_norway::from_slice::<ManifestKind>(manifest_bytes)
+        .is_ok_and(|manifest_kind| manifest_kind.collection)
+}
+
+/// The only field of a manifest needed to tell whether it belongs to a
+/// collection or a project.
+#[derive(Debug, Clone, Deserialize)]
+struct ManifestKind {
+    #[serde(default)]
+    collection: bool,
+}
+
+/// Represents the contents of a `manifest.yml` file that is stored in a
+/// collection directory, i.e. a directory containing projects (or other
+/// collections). It is marked by `collection: true`, see [`ManifestKind`].
+#[derive(Debug, Clone, Deserialize)]
+struct CollectionManifest {
+    /// The groups that all the projects in the collection are members of.
+    #[serde(default)]
+    pub groups: Vec<Group>,
+}
+
 #[cfg(test)]
 mod manifest_file_project_service_tests {
+    use std::collections::HashMap;
+
     use fake::Fake;
     use fake::Faker;
 
     use super::*;
+    use crate::common::domain::AccessWindow;
+    use crate::project::domain::ProjectVisibility;
+    use crate::project::domain::path_rule::PathRule;
 
     /// The name of the project manifest files for testing purposes (so tests
     /// can detect a change in filename).
@@ -197,9 +425,9 @@ mod manifest_file_project_service_tests {
     fn write_to_project_manifest<P: AsRef<Path>>(project: &Project, projects_dir: P) {
         let project_dir = PathBuf::new().join(&projects_dir).join(project.id.as_str());
 
-        std::fs::create_dir(&project_dir).unwrap();
+        std::fs::create_dir_all(&project_dir).unwrap();
 
-        let manifest_file = ProjectManifest::from_project(project);
+        let manifest_file = ProjectDefinition::from_project(project);
 
         std::fs::write(
             project_dir.join(TEST_MANIFEST_FILE_NAME),
@@ -208,6 +436,23 @@ mod manifest_file_project_service_tests {
         .unwrap();
     }
 
+    /// Writes a collection manifest with the `groups` to the `collection`
+    /// directory of the `projects_dir` (which is created if needed).
+    fn write_collection_manifest<P: AsRef<Path>>(
+        collection: &str,
+        groups: &[&str],
+        projects_dir: P,
+    ) {
+        let collection_dir = projects_dir.as_ref().join(collection);
+        std::fs::create_dir_all(&collection_dir).unwrap();
+
+        std::fs::write(
+            collection_dir.join(TEST_MANIFEST_FILE_NAME),
+            format!("collection: true\ngroups: [{}]\n", groups.join(", ")),
+        )
+        .unwrap();
+    }
+
     /// Creates an empty dir `dir_name` in the `projects_dir`.
     fn create_empty_project_dir<P: AsRef<Path>>(dir_name: &str, projects_dir: P) {
         let project_dir = PathBuf::new().join(&projects_dir).join(dir_name);
@@ -218,6 +463,98 @@ mod manifest_file_project_service_tests {
     mod read {
         use super::*;
 
+        #[tokio::test]
+        async fn should_inherit_the_groups_of_the_collections() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            write_collection_manifest("client-a", &["client-a", "surveyors"], &projects_dir);
+            write_collection_manifest("client-a/site-3", &["site-3"], &projects_dir);
+            let project = Project {
+                id: ProjectId::new("client-a/site-3/2026-survey".to_owned()),
+                groups: vec![Group::new("surveyors")],
+                ..Faker.fake()
+            };
+            write_to_project_manifest(&project, &projects_dir);
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let recovered_project = service.read(project.id.clone()).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                recovered_project.groups,
+                ["surveyors", "client-a", "site-3"].map(Group::new).to_vec()
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_a_parent_is_not_a_collection() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project = Faker.fake::<Project>();
+            write_to_project_manifest(&project, &projects_dir);
+            // A manifest within an asset directory of the project.
+            let nested_project = Project {
+                id: ProjectId::new(format!("{}/pointclouds", project.id)),
+                ..Faker.fake()
+            };
+            write_to_project_manifest(&nested_project, &projects_dir);
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let res = service.read(nested_project.id.clone()).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_reading_a_collection() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            write_collection_manifest("client-a", &["client-a"], &projects_dir);
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let res = service.read(ProjectId::new("client-a".to_owned())).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_the_id_is_outside_of_the_projects_dir() {
+            // Arrange
+            let parent_dir = tempfile::tempdir().unwrap();
+            let projects_dir = parent_dir.path().join("projects");
+            std::fs::create_dir(&projects_dir).unwrap();
+            let project = Project {
+                id: ProjectId::new("outside".to_owned()),
+                ..Faker.fake()
+            };
+            write_to_project_manifest(&project, &parent_dir);
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let res = service.read(ProjectId::new("../outside".to_owned())).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+
         #[tokio::test]
         async fn should_read_the_correct_project() {
             // Arrange
@@ -283,13 +620,238 @@ mod manifest_file_project_service_tests {
             let res = service.read(project_id.clone()).await;
 
             // Assert
-            assert!(matches!(res, Err(ProjectRepositoryError::Parsing { id }) if id == project_id));
+            assert!(
+                matches!(res, Err(ProjectRepositoryError::Parsing { id, .. }) if id == project_id)
+            );
+        }
+
+        #[tokio::test]
+        async fn should_default_to_no_users_if_not_in_manifest() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project_id = Faker.fake::<ProjectId>();
+
+            let project_dir = PathBuf::new().join(&projects_dir).join(project_id.as_str());
+
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                "name: Project\ngroups: []\n",
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let project = service.read(project_id).await.unwrap();
+
+            // Assert
+            assert!(project.users.is_empty());
+        }
+
+        #[tokio::test]
+        async fn should_read_the_access_windows() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project_id = Faker.fake::<ProjectId>();
+
+            let project_dir = PathBuf::new().join(&projects_dir).join(project_id.as_str());
+
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                r#"
+                name: Project
+                groups:
+                  - surveyors
+                  - group: clients
+                    valid_from: 2026-01-01T00:00:00Z
+                    valid_until: 2026-07-01T00:00:00Z
+                valid_until: 2027-01-01T00:00:00Z
+                "#,
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let project = service.read(project_id).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                project.groups,
+                vec![Group::new("surveyors"), Group::new("clients")]
+            );
+            assert_eq!(
+                project.group_access_windows,
+                HashMap::from([(
+                    Group::new("clients"),
+                    AccessWindow {
+                        valid_from: Some(time::macros::datetime!(2026-01-01 00:00 UTC)),
+                        valid_until: Some(time::macros::datetime!(2026-07-01 00:00 UTC)),
+                    }
+                )])
+            );
+            assert_eq!(
+                project.access_window,
+                AccessWindow {
+                    valid_from: None,
+                    valid_until: Some(time::macros::datetime!(2027-01-01 00:00 UTC)),
+                }
+            );
+        }
+
+        #[tokio::test]
+        async fn should_read_the_path_rules() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project_id = Faker.fake::<ProjectId>();
+
+            let project_dir = PathBuf::new().join(&projects_dir).join(project_id.as_str());
+
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                r#"
+                name: Project
+                groups: [clients, surveyors]
+                path_rules:
+                  - paths: ["raw/**", "reports/*.pdf"]
+                    groups: [surveyors]
+                "#,
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let project = service.read(project_id).await.unwrap();
+
+            // Assert
+            assert_eq!(
+                project.path_rules,
+                vec![
+                    PathRule::new(
+                        vec!["raw/**".to_owned(), "reports/*.pdf".to_owned()],
+                        vec![Group::new("surveyors")]
+                    )
+                    .unwrap()
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_a_path_rule_is_invalid() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project_id = Faker.fake::<ProjectId>();
+
+            let project_dir = PathBuf::new().join(&projects_dir).join(project_id.as_str());
+
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                "name: Project
+groups: []
+path_rules: [{ paths: ['raw/[*'], groups: [surveyors] }]
+",
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let res = service.read(project_id.clone()).await;
+
+            // Assert
+            assert!(
+                matches!(res, Err(ProjectRepositoryError::Parsing { id, .. }) if id == project_id)
+            );
+        }
+
+        #[test_case::test_case("", ProjectVisibility::Private; "default")]
+        #[test_case::test_case("visibility: public\n", ProjectVisibility::Public; "public")]
+        #[tokio::test]
+        async fn should_read_the_visibility(
+            manifest_visibility: &str,
+            expected: ProjectVisibility,
+        ) {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            let project_id = Faker.fake::<ProjectId>();
+
+            let project_dir = PathBuf::new().join(&projects_dir).join(project_id.as_str());
+
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                format!("name: Project\ngroups: []\n{manifest_visibility}"),
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let project = service.read(project_id).await.unwrap();
+
+            // Assert
+            assert_eq!(project.visibility, expected);
         }
     }
 
     mod list {
         use super::*;
 
+        #[tokio::test]
+        async fn should_list_the_projects_nested_in_collections() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            write_collection_manifest("client-a", &[], &projects_dir);
+            write_collection_manifest("client-a/site-3", &[], &projects_dir);
+            let projects = [
+                "top-level",
+                "client-a/site-1",
+                "client-a/site-3/2026-survey",
+            ]
+            .map(|project_id| Project {
+                id: ProjectId::new(project_id.to_owned()),
+                ..Faker.fake()
+            });
+            projects
+                .iter()
+                .for_each(|project| write_to_project_manifest(project, &projects_dir));
+            // The asset directories of a project aren't searched for projects.
+            write_to_project_manifest(
+                &Project {
+                    id: ProjectId::new("top-level/pointclouds".to_owned()),
+                    ..Faker.fake()
+                },
+                &projects_dir,
+            );
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let mut directories = service.list_directories().await.unwrap();
+
+            // Assert
+            directories.collections.sort();
+            directories.project_ids.sort();
+            assert_eq!(
+                directories,
+                ProjectDirectories {
+                    collections: vec![PathBuf::from("client-a"), PathBuf::from("client-a/site-3")],
+                    project_ids: [
+                        "client-a/site-1",
+                        "client-a/site-3/2026-survey",
+                        "top-level"
+                    ]
+                    .map(|project_id| ProjectId::new(project_id.to_owned()))
+                    .to_vec(),
+                }
+            );
+        }
+
         #[tokio::test]
         async fn should_list_all_available_valid_projects_sorted_by_name() {
             // Arrange
@@ -343,4 +905,52 @@ mod manifest_file_project_service_tests {
             ));
         }
     }
+
+    mod diagnostics {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_report_where_a_manifest_is_invalid() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            write_to_project_manifest(&Faker.fake::<Project>(), &projects_dir);
+            let project_dir = projects_dir.path().join("survey");
+            std::fs::create_dir(&project_dir).unwrap();
+            std::fs::write(
+                project_dir.join(TEST_MANIFEST_FILE_NAME),
+                "name: Survey\ngroups: surveyors\n",
+            )
+            .unwrap();
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let diagnostics = service.diagnostics().await.unwrap();
+
+            // Assert
+            assert_eq!(diagnostics.len(), 1);
+            let diagnostic = &diagnostics[0];
+            assert_eq!(diagnostic.project_id.as_str(), "survey");
+            assert_eq!(diagnostic.path, project_dir.join(TEST_MANIFEST_FILE_NAME));
+            assert_eq!(diagnostic.kind, ProjectDiagnosticKind::InvalidManifest);
+            assert_eq!((diagnostic.line, diagnostic.column), (Some(2), Some(9)));
+            assert!(diagnostic.message.contains("groups"));
+        }
+
+        #[tokio::test]
+        async fn should_report_a_missing_manifest() {
+            // Arrange
+            let projects_dir = tempfile::tempdir().unwrap();
+            create_empty_project_dir("survey", &projects_dir);
+
+            let service = ManifestFileProjectRepository::new(&projects_dir);
+
+            // Act
+            let diagnostics = service.diagnostics().await.unwrap();
+
+            // Assert
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].kind, ProjectDiagnosticKind::MissingManifest);
+        }
+    }
 }
@@ -1 +1,3 @@
+pub mod caching;
 pub mod manifest_file;
+pub mod sqlite;
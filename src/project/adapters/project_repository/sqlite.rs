@@ -0,0 +1,849 @@
+//! A SQLite backed implementation of the [`ProjectRepository`] trait, so that
+//! the projects can be edited (e.g. through the admin API) rather than being
+//! defined by manifest files.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use sqlx::Sqlite;
+use sqlx::Transaction;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePool;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use super::super::super::domain::Project;
+use super::super::super::domain::ProjectDescription;
+use super::super::super::domain::ProjectId;
+use super::super::super::domain::ProjectName;
+use super::super::super::domain::ProjectVisibility;
+use super::super::super::domain::definition::GroupDefinition;
+use super::super::super::domain::definition::PathRuleDefinition;
+use super::super::super::domain::definition::ProjectDefinition;
+use super::super::super::domain::diagnostic::ProjectDiagnostic;
+use super::super::super::domain::diagnostic::ProjectDiagnosticKind;
+use super::super::super::ports::project_repository::ProjectRepository;
+use super::super::super::ports::project_repository::ProjectRepositoryError;
+use crate::common::domain::AccessWindow;
+use crate::common::domain::Group;
+use crate::user::domain::EmailAddress;
+
+/// Creates (and later alters) the tables of the project database.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/projects");
+
+/// A SQLite database backed implementation of the [`ProjectRepository`] trait.
+///
+/// Each project is a row of the `projects` table, its groups, users and path
+/// rules are rows of separate tables (see `migrations/projects`). Unlike the
+/// manifest files, projects aren't nested in collections, so a project id with
+/// multiple segments is just an id. As with the directories of the manifest
+/// files though, an id can't be nested in another (see [`Self::create`]).
+#[derive(Debug, Clone)]
+pub struct SqliteProjectRepository {
+    pool: SqlitePool,
+
+    /// The database file, reported as the location of invalid records.
+    database_path: PathBuf,
+}
+
+impl SqliteProjectRepository {
+    /// Opens the database at `database_path` (creating it if it does not
+    /// exist), and migrates it to the latest schema.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the database can't be opened or migrated.
+    pub async fn new(database_path: &Path) -> Result<Self, ProjectRepositoryError> {
+        let connect_options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true)
+            .foreign_keys(true);
+
+        let pool = SqlitePool::connect_with(connect_options)
+            .await
+            .map_err(|e| ProjectRepositoryError::Infrastucture {
+                message: format!(
+                    "unable to open the project database ({}): {e}",
+                    database_path.to_string_lossy()
+                ),
+            })?;
+
+        MIGRATOR
+            .run(&pool)
+            .await
+            .map_err(|e| ProjectRepositoryError::Infrastucture {
+                message: format!("unable to migrate the project database: {e}"),
+            })?;
+
+        Ok(Self {
+            pool,
+            database_path: database_path.to_path_buf(),
+        })
+    }
+
+    #[tracing::instrument]
+    pub async fn read(&self, project_id: &ProjectId) -> Result<Project, ProjectRepositoryError> {
+        let record = self.load(Some(project_id)).await?.pop().ok_or_else(|| {
+            ProjectRepositoryError::ResourceNotFound {
+                id: project_id.clone(),
+            }
+        })?;
+
+        Ok(record.into_project(&self.database_path)?)
+    }
+
+    /// Lists the projects sorted by name. Projects with an invalid record are
+    /// left out, see [`Self::diagnostics`].
+    #[tracing::instrument]
+    pub async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
+        Ok(self
+            .load(None)
+            .await?
+            .into_iter()
+            .filter_map(|record| record.into_project(&self.database_path).ok())
+            .collect())
+    }
+
+    /// Describes why each of the projects that are left out of [`Self::list`]
+    /// has an invalid record.
+    #[tracing::instrument]
+    pub async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        Ok(self
+            .load(None)
+            .await?
+            .into_iter()
+            .filter_map(|record| record.into_project(&self.database_path).err())
+            .collect())
+    }
+
+    /// Persists the new `project`, unless its id is nested in the id of an
+    /// existing project or the other way around (e.g. `client` and
+    /// `client/survey`). The asset paths of such projects would overlap, so the
+    /// assets of one could be read with the access rules of the other.
+    #[tracing::instrument]
+    pub async fn create(&self, project: &Project) -> Result<(), ProjectRepositoryError> {
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        sqlx::query(
+            "INSERT INTO projects (id, name, description, visibility, valid_from, valid_until)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(project.id.as_str())
+        .bind(project.name.as_str())
+        .bind(
+            project
+                .description
+                .as_ref()
+                .map(|description| description.as_str()),
+        )
+        .bind(visibility_to_str(project.visibility))
+        .bind(format_timestamp(project.access_window.valid_from)?)
+        .bind(format_timestamp(project.access_window.valid_until)?)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(database_error) if database_error.is_unique_violation() => {
+                ProjectRepositoryError::AlreadyExists {
+                    id: project.id.clone(),
+                }
+            }
+            _ => database_error(e),
+        })?;
+
+        // Checked after inserting, so that the transaction holds the write lock
+        // and a conflicting project can't be created concurrently.
+        let overlapping_id = sqlx::query_scalar::<_, String>(
+            "SELECT id
+             FROM projects
+             WHERE substr(id, 1, length(?1) + 1) = ?1 || '/'
+                OR substr(?1, 1, length(id) + 1) = id || '/'
+             ORDER BY id
+             LIMIT 1",
+        )
+        .bind(project.id.as_str())
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+        if let Some(overlapping_id) = overlapping_id {
+            return Err(ProjectRepositoryError::Overlapping {
+                id: project.id.clone(),
+                existing_id: ProjectId::new(overlapping_id),
+            });
+        }
+
+        insert_members(&mut transaction, project).await?;
+
+        transaction.commit().await.map_err(database_error)
+    }
+
+    #[tracing::instrument]
+    pub async fn update(&self, project: &Project) -> Result<(), ProjectRepositoryError> {
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+
+        let updated = sqlx::query(
+            "UPDATE projects
+             SET name = ?, description = ?, visibility = ?, valid_from = ?, valid_until = ?
+             WHERE id = ?",
+        )
+        .bind(project.name.as_str())
+        .bind(
+            project
+                .description
+                .as_ref()
+                .map(|description| description.as_str()),
+        )
+        .bind(visibility_to_str(project.visibility))
+        .bind(format_timestamp(project.access_window.valid_from)?)
+        .bind(format_timestamp(project.access_window.valid_until)?)
+        .bind(project.id.as_str())
+        .execute(&mut *transaction)
+        .await
+        .map_err(database_error)?;
+
+        if updated.rows_affected() == 0 {
+            return Err(ProjectRepositoryError::ResourceNotFound {
+                id: project.id.clone(),
+            });
+        }
+
+        // The members are replaced rather than reconciled, as they are ordered.
+        for table in ["project_groups", "project_users", "project_path_rules"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE project_id = ?"))
+                .bind(project.id.as_str())
+                .execute(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+        }
+        insert_members(&mut transaction, project).await?;
+
+        transaction.commit().await.map_err(database_error)
+    }
+
+    /// Deletes the project, along with its groups, users and path rules.
+    #[tracing::instrument]
+    pub async fn delete(&self, project_id: &ProjectId) -> Result<(), ProjectRepositoryError> {
+        let deleted = sqlx::query("DELETE FROM projects WHERE id = ?")
+            .bind(project_id.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(database_error)?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(ProjectRepositoryError::ResourceNotFound {
+                id: project_id.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Loads the records of the project with the `project_id`, or of all the
+    /// projects (sorted by name) if it is `None`.
+    async fn load(
+        &self,
+        project_id: Option<&ProjectId>,
+    ) -> Result<Vec<ProjectRecord>, ProjectRepositoryError> {
+        let project_id = project_id.map(|project_id| project_id.as_str());
+
+        let project_rows = sqlx::query_as::<_, ProjectRow>(
+            "SELECT id, name, description, visibility, valid_from, valid_until
+             FROM projects
+             WHERE ?1 IS NULL OR id = ?1
+             ORDER BY name, id",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let group_rows = sqlx::query_as::<_, GroupRow>(
+            "SELECT project_id, group_name, valid_from, valid_until
+             FROM project_groups
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY project_id, position",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let user_rows = sqlx::query_as::<_, UserRow>(
+            "SELECT project_id, email
+             FROM project_users
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY project_id, position",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let path_rule_rows = sqlx::query_as::<_, PathRuleRow>(
+            "SELECT project_id, paths, groups
+             FROM project_path_rules
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY project_id, position",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error)?;
+
+        let mut records = project_rows
+            .into_iter()
+            .map(|project| ProjectRecord {
+                project,
+                groups: Vec::new(),
+                users: Vec::new(),
+                path_rules: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        let indices = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| (record.project.id.clone(), index))
+            .collect::<HashMap<_, _>>();
+
+        for group in group_rows {
+            if let Some(index) = indices.get(&group.project_id) {
+                records[*index].groups.push(group);
+            }
+        }
+        for user in user_rows {
+            if let Some(index) = indices.get(&user.project_id) {
+                records[*index].users.push(user);
+            }
+        }
+        for path_rule in path_rule_rows {
+            if let Some(index) = indices.get(&path_rule.project_id) {
+                records[*index].path_rules.push(path_rule);
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for SqliteProjectRepository {
+    async fn read(&self, project_id: &ProjectId) -> Result<Project, ProjectRepositoryError> {
+        Self::read(self, project_id).await
+    }
+
+    async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError> {
+        Self::list(self).await
+    }
+
+    async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError> {
+        Self::diagnostics(self).await
+    }
+
+    async fn create(&self, project: &Project) -> Result<(), ProjectRepositoryError> {
+        Self::create(self, project).await
+    }
+
+    async fn update(&self, project: &Project) -> Result<(), ProjectRepositoryError> {
+        Self::update(self, project).await
+    }
+
+    async fn delete(&self, project_id: &ProjectId) -> Result<(), ProjectRepositoryError> {
+        Self::delete(self, project_id).await
+    }
+}
+
+/// Inserts the groups, users and path rules of the `project`, in order.
+async fn insert_members(
+    transaction: &mut Transaction<'_, Sqlite>,
+    project: &Project,
+) -> Result<(), ProjectRepositoryError> {
+    for (position, group) in project.groups.iter().enumerate() {
+        let access_window = project
+            .group_access_windows
+            .get(group)
+            .copied()
+            .unwrap_or_default();
+
+        sqlx::query(
+            "INSERT INTO project_groups (project_id, position, group_name, valid_from, valid_until)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(project.id.as_str())
+        .bind(position as i64)
+        .bind(group.as_str())
+        .bind(format_timestamp(access_window.valid_from)?)
+        .bind(format_timestamp(access_window.valid_until)?)
+        .execute(&mut **transaction)
+        .await
+        .map_err(database_error)?;
+    }
+
+    for (position, email) in project.users.iter().enumerate() {
+        sqlx::query("INSERT INTO project_users (project_id, position, email) VALUES (?, ?, ?)")
+            .bind(project.id.as_str())
+            .bind(position as i64)
+            .bind(email.as_str())
+            .execute(&mut **transaction)
+            .await
+            .map_err(database_error)?;
+    }
+
+    for (position, path_rule) in project.path_rules.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO project_path_rules (project_id, position, paths, groups)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(project.id.as_str())
+        .bind(position as i64)
+        .bind(to_json(path_rule.patterns())?)
+        .bind(to_json(path_rule.groups())?)
+        .execute(&mut **transaction)
+        .await
+        .map_err(database_error)?;
+    }
+
+    Ok(())
+}
+
+/// The rows of a project, as loaded from the database.
+#[derive(Debug)]
+struct ProjectRecord {
+    project: ProjectRow,
+    groups: Vec<GroupRow>,
+    users: Vec<UserRow>,
+    path_rules: Vec<PathRuleRow>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ProjectRow {
+    id: String,
+    name: String,
+    description: Option<String>,
+    visibility: String,
+    valid_from: Option<String>,
+    valid_until: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct GroupRow {
+    project_id: String,
+    group_name: String,
+    valid_from: Option<String>,
+    valid_until: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct UserRow {
+    project_id: String,
+    email: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PathRuleRow {
+    project_id: String,
+    paths: String,
+    groups: String,
+}
+
+impl ProjectRecord {
+    /// Converts the rows into a [`Project`], describing the first invalid
+    /// value (in the database at `database_path`) if they can't be.
+    fn into_project(self, database_path: &Path) -> Result<Project, ProjectDiagnostic> {
+        let project_id = ProjectId::new(self.project.id.clone());
+
+        self.into_definition()
+            .and_then(|definition| definition.into_project(&project_id))
+            .map_err(|message| ProjectDiagnostic {
+                project_id: project_id.clone(),
+                path: database_path.to_path_buf(),
+                kind: ProjectDiagnosticKind::InvalidRecord,
+                line: None,
+                column: None,
+                message,
+            })
+    }
+
+    fn into_definition(self) -> Result<ProjectDefinition, String> {
+        let Self {
+            project,
+            groups,
+            users,
+            path_rules,
+        } = self;
+
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                let access_window = parse_access_window(group.valid_from, group.valid_until)?;
+                let group = Group::new(&group.group_name);
+
+                Ok(if access_window == AccessWindow::default() {
+                    GroupDefinition::Name(group)
+                } else {
+                    GroupDefinition::Windowed {
+                        group,
+                        access_window,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let path_rules = path_rules
+            .into_iter()
+            .map(|path_rule| {
+                Ok(PathRuleDefinition {
+                    paths: from_json(&path_rule.paths)?,
+                    groups: from_json(&path_rule.groups)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ProjectDefinition {
+            name: ProjectName::new(project.name),
+            description: project.description.map(ProjectDescription::new),
+            groups,
+            users: users
+                .into_iter()
+                .map(|user| EmailAddress::new(user.email))
+                .collect(),
+            visibility: visibility_from_str(&project.visibility)?,
+            access_window: parse_access_window(project.valid_from, project.valid_until)?,
+            path_rules,
+        })
+    }
+}
+
+fn visibility_to_str(visibility: ProjectVisibility) -> &'static str {
+    match visibility {
+        ProjectVisibility::Private => "private",
+        ProjectVisibility::Public => "public",
+    }
+}
+
+fn visibility_from_str(visibility: &str) -> Result<ProjectVisibility, String> {
+    match visibility {
+        "private" => Ok(ProjectVisibility::Private),
+        "public" => Ok(ProjectVisibility::Public),
+        _ => Err(format!("unknown visibility `{visibility}`")),
+    }
+}
+
+fn format_timestamp(
+    timestamp: Option<OffsetDateTime>,
+) -> Result<Option<String>, ProjectRepositoryError> {
+    timestamp
+        .map(|timestamp| timestamp.format(&Rfc3339))
+        .transpose()
+        .map_err(|e| ProjectRepositoryError::Infrastucture {
+            message: format!("unable to format the timestamp: {e}"),
+        })
+}
+
+fn parse_access_window(
+    valid_from: Option<String>,
+    valid_until: Option<String>,
+) -> Result<AccessWindow, String> {
+    let parse = |timestamp: Option<String>| {
+        timestamp
+            .map(|timestamp| {
+                OffsetDateTime::parse(&timestamp, &Rfc3339)
+                    .map_err(|e| format!("invalid timestamp `{timestamp}`: {e}"))
+            })
+            .transpose()
+    };
+
+    Ok(AccessWindow {
+        valid_from: parse(valid_from)?,
+        valid_until: parse(valid_until)?,
+    })
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, ProjectRepositoryError> {
+    serde_json::to_string(value).map_err(|e| ProjectRepositoryError::Infrastucture {
+        message: format!("unable to serialize the path rule: {e}"),
+    })
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|e| format!("invalid path rule `{value}`: {e}"))
+}
+
+fn database_error(error: sqlx::Error) -> ProjectRepositoryError {
+    ProjectRepositoryError::Infrastucture {
+        message: format!("unable to query the project database: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod sqlite_project_repository_tests {
+    use fake::Fake;
+    use fake::Faker;
+    use tempfile::TempDir;
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::project::domain::path_rule::PathRule;
+
+    async fn repository(database_dir: &TempDir) -> SqliteProjectRepository {
+        SqliteProjectRepository::new(&database_dir.path().join("projects.db"))
+            .await
+            .unwrap()
+    }
+
+    /// A project with (windowed) groups, users and path rules.
+    fn project(project_id: &str, name: &str) -> Project {
+        let mut project: Project = Faker.fake();
+        project.id = ProjectId::new(project_id.to_owned());
+        project.name = ProjectName::new(name.to_owned());
+        project.groups = vec![Group::new("surveyors"), Group::new("clients")];
+        project.group_access_windows = HashMap::from([(
+            Group::new("clients"),
+            AccessWindow {
+                valid_from: Some(datetime!(2026-01-01 00:00 UTC)),
+                valid_until: None,
+            },
+        )]);
+        project.access_window = AccessWindow {
+            valid_from: None,
+            valid_until: Some(datetime!(2027-01-01 00:00 UTC)),
+        };
+        project.path_rules =
+            vec![PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")]).unwrap()];
+        project
+    }
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_read_the_created_project() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            let project = project("client-a/survey", "Survey");
+
+            // Act
+            repository.create(&project).await.unwrap();
+
+            // Assert
+            assert_eq!(repository.read(&project.id).await.unwrap(), project);
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_the_project_already_exists() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            let project = project("survey", "Survey");
+            repository.create(&project).await.unwrap();
+
+            // Act
+            let res = repository.create(&project).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::AlreadyExists { id }) if id == project.id
+            ));
+        }
+
+        #[test_case::test_case("client", "client/survey"; "nested in the new project")]
+        #[test_case::test_case("client/survey", "client"; "containing the new project")]
+        #[test_case::test_case("client", "client/site-3/survey"; "deeply nested in the new project")]
+        #[tokio::test]
+        async fn should_return_an_error_if_the_project_overlaps_an_existing_project(
+            existing_id: &str,
+            project_id: &str,
+        ) {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            repository
+                .create(&project(existing_id, "Existing"))
+                .await
+                .unwrap();
+            let project = project(project_id, "Survey");
+
+            // Act
+            let res = repository.create(&project).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::Overlapping { id, existing_id: overlapping_id })
+                    if id == project.id && overlapping_id.as_str() == existing_id
+            ));
+            assert!(matches!(
+                repository.read(&project.id).await,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+
+        #[test_case::test_case("client_a", "client-a/survey"; "wildcard in the existing id")]
+        #[test_case::test_case("client", "clients/survey"; "shared prefix")]
+        #[test_case::test_case("client-a/survey", "client_a"; "wildcard in the new id")]
+        #[tokio::test]
+        async fn should_create_projects_that_only_share_a_prefix(
+            existing_id: &str,
+            project_id: &str,
+        ) {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            repository
+                .create(&project(existing_id, "Existing"))
+                .await
+                .unwrap();
+
+            // Act
+            let res = repository.create(&project(project_id, "Survey")).await;
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_persist_the_projects_between_connections() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let project = project("survey", "Survey");
+            repository(&database_dir)
+                .await
+                .create(&project)
+                .await
+                .unwrap();
+
+            // Act
+            let projects = repository(&database_dir).await.list().await.unwrap();
+
+            // Assert
+            assert_eq!(projects, vec![project]);
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_replace_the_project() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            let project = project("survey", "Survey");
+            repository.create(&project).await.unwrap();
+            let updated_project = Project {
+                description: Some(ProjectDescription::new("Updated".to_owned())),
+                groups: vec![Group::new("partners")],
+                group_access_windows: HashMap::new(),
+                users: Vec::new(),
+                path_rules: Vec::new(),
+                ..project.clone()
+            };
+
+            // Act
+            repository.update(&updated_project).await.unwrap();
+
+            // Assert
+            assert_eq!(repository.read(&project.id).await.unwrap(), updated_project);
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_the_project_does_not_exist() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+
+            // Act
+            let res = repository.update(&project("survey", "Survey")).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_delete_the_project_and_its_members() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            let project = project("survey", "Survey");
+            repository.create(&project).await.unwrap();
+
+            // Act
+            repository.delete(&project.id).await.unwrap();
+
+            // Assert
+            assert!(matches!(
+                repository.read(&project.id).await,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+            let (groups,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM project_groups")
+                .fetch_one(&repository.pool)
+                .await
+                .unwrap();
+            assert_eq!(groups, 0);
+        }
+
+        #[tokio::test]
+        async fn should_return_an_error_if_the_project_does_not_exist() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+
+            // Act
+            let res = repository
+                .delete(&ProjectId::new("survey".to_owned()))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectRepositoryError::ResourceNotFound { .. })
+            ));
+        }
+    }
+
+    mod list {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_sort_by_name_and_leave_out_invalid_records() {
+            // Arrange
+            let database_dir = TempDir::new().unwrap();
+            let repository = repository(&database_dir).await;
+            let beta = project("beta", "Beta");
+            let alpha = project("alpha", "Alpha");
+            let invalid = project("invalid", "Invalid");
+            for project in [&beta, &alpha, &invalid] {
+                repository.create(project).await.unwrap();
+            }
+            sqlx::query("UPDATE projects SET visibility = 'secret' WHERE id = 'invalid'")
+                .execute(&repository.pool)
+                .await
+                .unwrap();
+
+            // Act
+            let projects = repository.list().await.unwrap();
+            let diagnostics = repository.diagnostics().await.unwrap();
+
+            // Assert
+            assert_eq!(projects, vec![alpha, beta]);
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].project_id, invalid.id);
+            assert_eq!(diagnostics[0].kind, ProjectDiagnosticKind::InvalidRecord);
+            assert!(
+                diagnostics[0].message.contains("secret"),
+                "{}",
+                diagnostics[0].message
+            );
+        }
+    }
+}
@@ -1,9 +1,12 @@
+use time::OffsetDateTime;
+
 use crate::authorization::domain::action::Action;
 use crate::authorization::domain::error::AuthorizationEngineError;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceType;
 use crate::project::domain::ProjectId;
 use crate::project::ports::project_repository::ProjectRepositoryError;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -19,9 +22,24 @@ pub enum ProjectServiceError {
         resource_type: ResourceType,
     },
 
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
     #[error("user is not authenticated")]
     NotAuthenticated,
 
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
     #[error("{message}")]
     Infrastucture { message: String },
 }
@@ -30,8 +48,14 @@ impl From<ProjectRepositoryError> for ProjectServiceError {
     fn from(value: ProjectRepositoryError) -> Self {
         match value {
             ProjectRepositoryError::ResourceNotFound { id }
-            | ProjectRepositoryError::Parsing { id } => Self::ProjectNotFound { id },
+            | ProjectRepositoryError::Parsing { id, .. } => Self::ProjectNotFound { id },
             ProjectRepositoryError::Infrastucture { message } => Self::Infrastucture { message },
+            // Projects are only read.
+            e @ (ProjectRepositoryError::AlreadyExists { .. }
+            | ProjectRepositoryError::Overlapping { .. }
+            | ProjectRepositoryError::ReadOnly { .. }) => Self::Infrastucture {
+                message: e.to_string(),
+            },
         }
     }
 }
@@ -50,7 +74,27 @@ impl From<AuthorizationEngineError> for ProjectServiceError {
                 resource_identifier,
                 resource_type,
             },
+            AuthorizationEngineError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
             AuthorizationEngineError::NotAuthenticated => Self::NotAuthenticated,
+            AuthorizationEngineError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
         }
     }
 }
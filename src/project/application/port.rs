@@ -4,7 +4,9 @@ use async_trait::async_trait;
 
 use super::super::domain::Project;
 use super::super::domain::ProjectId;
+use super::super::domain::diagnostic::ValidationReport;
 use super::error::ProjectServiceError;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// Defines the functionality provided by the
@@ -21,6 +23,21 @@ pub trait ProjectServicePort: Debug + Send + Sync + 'static {
         project_id: &ProjectId,
     ) -> Result<Project, ProjectServiceError>;
 
+    /// Read a specific project (`project_id`) on behalf of a `share_link`
+    /// (rather than an authenticated user).
+    async fn read_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        project_id: &ProjectId,
+    ) -> Result<Project, ProjectServiceError>;
+
     /// List the projects that a user is allowed to view.
     async fn list(&self, user: &Option<User>) -> Result<Vec<Project>, ProjectServiceError>;
+
+    /// Reports which projects could not be loaded, and why. `user` is used for
+    /// authorization.
+    async fn validation_report(
+        &self,
+        user: &Option<User>,
+    ) -> Result<ValidationReport, ProjectServiceError>;
 }
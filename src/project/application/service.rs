@@ -4,12 +4,15 @@ use async_trait::async_trait;
 
 use super::super::domain::Project;
 use super::super::domain::ProjectId;
+use super::super::domain::diagnostic::ValidationReport;
 use super::super::ports::project_repository::ProjectRepository;
 use super::error::ProjectServiceError;
 use crate::authorization::domain::action::Action;
 use crate::authorization::ports::authorization_engine::AuthorizationEngine;
 use crate::project::application::port::ProjectServicePort;
 use crate::project::domain::authorization::ProjectTypeResource;
+use crate::project::domain::authorization::ValidationReportTypeResource;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// A service for interacting with projects.
@@ -45,6 +48,24 @@ impl ProjectService {
         Ok(project)
     }
 
+    /// Read a specific project (`project_id`) on behalf of a `share_link`
+    /// (rather than an authenticated user).
+    pub async fn read_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        project_id: &ProjectId,
+    ) -> Result<Project, ProjectServiceError> {
+        let project = self.project_repository.read(project_id).await?;
+
+        self.authorization_engine.can_on_instance_with_share_link(
+            share_link,
+            &Action::Read,
+            &project,
+        )?;
+
+        Ok(project)
+    }
+
     /// List the projects that a user is allowed to view.
     pub async fn list(&self, user: &Option<User>) -> Result<Vec<Project>, ProjectServiceError> {
         self.authorization_engine
@@ -64,6 +85,24 @@ impl ProjectService {
 
         Ok(allowed_projects)
     }
+
+    /// Reports which projects could not be loaded, and why. `user` is used for
+    /// authorization, by default only admins are allowed to see the report.
+    pub async fn validation_report(
+        &self,
+        user: &Option<User>,
+    ) -> Result<ValidationReport, ProjectServiceError> {
+        self.authorization_engine.can_on_type(
+            user,
+            &Action::Read,
+            &ValidationReportTypeResource,
+        )?;
+
+        let projects = self.project_repository.list().await?;
+        let diagnostics = self.project_repository.diagnostics().await?;
+
+        Ok(ValidationReport::new(projects.len(), diagnostics))
+    }
 }
 
 #[async_trait]
@@ -76,9 +115,24 @@ impl ProjectServicePort for ProjectService {
         Self::read(self, user, project_id).await
     }
 
+    async fn read_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        project_id: &ProjectId,
+    ) -> Result<Project, ProjectServiceError> {
+        Self::read_with_share_link(self, share_link, project_id).await
+    }
+
     async fn list(&self, user: &Option<User>) -> Result<Vec<Project>, ProjectServiceError> {
         Self::list(self, user).await
     }
+
+    async fn validation_report(
+        &self,
+        user: &Option<User>,
+    ) -> Result<ValidationReport, ProjectServiceError> {
+        Self::validation_report(self, user).await
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +204,43 @@ mod project_asset_service_tests {
         ))
     }
 
+    mod read_with_share_link {
+
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_share_link_not_authorized() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_read()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance_with_share_link()
+                .return_const(Err(AuthorizationEngineError::ShareLinkNotAuthorized {
+                    share_link: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: Some(Faker.fake()),
+                    resource_type: Faker.fake(),
+                }));
+
+            let project_service =
+                ProjectService::new(Arc::new(project_repository), Arc::new(authorization_engine));
+
+            // Act
+            let res = project_service
+                .read_with_share_link(&Faker.fake(), &Faker.fake())
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectServiceError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+    }
+
     mod list {
         use std::sync::Mutex;
 
@@ -232,4 +323,79 @@ mod project_asset_service_tests {
             ));
         }
     }
+
+    mod validation_report {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::project::domain::diagnostic::ProjectDiagnostic;
+        use crate::project::domain::diagnostic::ProjectDiagnosticKind;
+
+        #[tokio::test]
+        async fn should_report_the_projects_that_could_not_be_loaded() {
+            // Arrange
+            let diagnostic = ProjectDiagnostic {
+                project_id: Faker.fake(),
+                path: PathBuf::from("survey/manifest.yml"),
+                kind: ProjectDiagnosticKind::InvalidManifest,
+                line: Some(2),
+                column: Some(9),
+                message: "groups: invalid type".to_owned(),
+            };
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_list()
+                .return_const(Ok(vec![Faker.fake(), Faker.fake()]));
+            project_repository
+                .expect_diagnostics()
+                .return_const(Ok(vec![diagnostic.clone()]));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .once()
+                .return_const(Ok(()));
+
+            let project_service =
+                ProjectService::new(Arc::new(project_repository), Arc::new(authorization_engine));
+
+            // Act
+            let report = project_service
+                .validation_report(&Some(User::dummy_admin()))
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(report, ValidationReport::new(2, vec![diagnostic]));
+            assert!(!report.valid);
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authorized() {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .once()
+                .return_const(Err(AuthorizationEngineError::NotAuthorized {
+                    user: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: None,
+                    resource_type: Faker.fake(),
+                }));
+
+            let project_service = ProjectService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = project_service.validation_report(&Faker.fake()).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectServiceError::NotAuthorized { .. })
+            ));
+        }
+    }
 }
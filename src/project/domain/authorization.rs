@@ -1,10 +1,15 @@
 //! AuthZ trait implementations for a [`Project`].
 
+use std::path::PathBuf;
+
 use super::Project;
+use super::ProjectId;
+use super::path_rule::PathRule;
 use crate::authorization::domain::resource::Resource;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceInstance;
 use crate::authorization::domain::resource::ResourceType;
+use crate::common::domain::AccessWindow;
 use crate::common::domain::Group;
 use crate::common::domain::resource_type;
 use crate::user::domain::EmailAddress;
@@ -25,8 +30,32 @@ impl ResourceInstance for Project {
     }
 
     fn user_emails(&self) -> Option<Vec<EmailAddress>> {
+        Some(self.users.clone())
+    }
+
+    fn project_id(&self) -> Option<ProjectId> {
+        Some(self.id.clone())
+    }
+
+    fn asset_path(&self) -> Option<PathBuf> {
         None
     }
+
+    fn is_public(&self) -> bool {
+        self.is_public()
+    }
+
+    fn access_window(&self) -> Option<AccessWindow> {
+        Some(self.access_window)
+    }
+
+    fn group_access_window(&self, group: &Group) -> Option<AccessWindow> {
+        self.group_access_windows.get(group).copied()
+    }
+
+    fn path_rules(&self) -> Option<Vec<PathRule>> {
+        Some(self.path_rules.clone())
+    }
 }
 
 /// Represents a project type for type-level (rather than instance-level) authZ.
@@ -38,3 +67,14 @@ impl Resource for ProjectTypeResource {
         ResourceType::new(resource_type::PROJECT.to_owned())
     }
 }
+
+/// Represents the validation report of the projects for type-level authZ.
+/// E.g. whether a user is allowed to see why projects could not be loaded.
+#[derive(Debug)]
+pub struct ValidationReportTypeResource;
+
+impl Resource for ValidationReportTypeResource {
+    fn resource_type(&self) -> ResourceType {
+        ResourceType::new(resource_type::VALIDATION_REPORT.to_owned())
+    }
+}
@@ -0,0 +1,239 @@
+//! The editable definition of a [`Project`], as written in a `manifest.yml` or
+//! sent to the admin API.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Project;
+use super::ProjectDescription;
+use super::ProjectId;
+use super::ProjectName;
+use super::ProjectVisibility;
+use super::path_rule::PathRule;
+use crate::common::domain::AccessWindow;
+use crate::common::domain::Group;
+use crate::user::domain::EmailAddress;
+
+/// Everything about a [`Project`] other than its id.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ProjectDefinition {
+    pub name: ProjectName,
+    pub description: Option<ProjectDescription>,
+
+    /// The groups that the project is a member of.
+    pub groups: Vec<GroupDefinition>,
+
+    /// The email addresses of individual users that have access to the
+    /// project.
+    #[serde(default)]
+    pub users: Vec<EmailAddress>,
+
+    /// Who can read the project, in addition to its groups and users. Defaults
+    /// to `private`.
+    #[serde(default)]
+    pub visibility: ProjectVisibility,
+
+    /// When the project can be accessed at all (`valid_from`/`valid_until`).
+    #[serde(flatten)]
+    pub access_window: AccessWindow,
+
+    /// Restricts the assets matching path globs to members of additional
+    /// groups.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_rules: Vec<PathRuleDefinition>,
+}
+
+/// A path rule entry of a definition, see [`PathRule`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PathRuleDefinition {
+    /// The globs that asset paths are matched against (e.g. `raw/**`).
+    pub paths: Vec<String>,
+
+    /// Only members of at least one of these groups can read the matching
+    /// assets.
+    pub groups: Vec<Group>,
+}
+
+/// A group entry of a definition: either just the name of the group, or the
+/// group along with when its members can access the project.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum GroupDefinition {
+    Name(Group),
+    Windowed {
+        group: Group,
+
+        #[serde(flatten)]
+        access_window: AccessWindow,
+    },
+}
+
+/// A [`ProjectDefinition`] along with the id of the project, e.g. as returned
+/// by the admin API.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct IdentifiedProjectDefinition {
+    pub id: ProjectId,
+
+    #[serde(flatten)]
+    pub definition: ProjectDefinition,
+}
+
+impl ProjectDefinition {
+    /// Converts a [`ProjectDefinition`] into a [`Project`]. The `project_id`
+    /// represent the unique identifying slug of the project.
+    ///
+    /// Returns a message describing the first invalid path rule.
+    pub fn into_project(self, project_id: &ProjectId) -> Result<Project, String> {
+        let Self {
+            name,
+            description,
+            groups,
+            users,
+            visibility,
+            access_window,
+            path_rules,
+        } = self;
+
+        let mut group_access_windows = HashMap::new();
+        let groups = groups
+            .into_iter()
+            .map(|group| match group {
+                GroupDefinition::Name(group) => group,
+                GroupDefinition::Windowed {
+                    group,
+                    access_window,
+                } => {
+                    group_access_windows.insert(group.clone(), access_window);
+                    group
+                }
+            })
+            .collect();
+
+        let path_rules = path_rules
+            .into_iter()
+            .map(|PathRuleDefinition { paths, groups }| PathRule::new(paths, groups))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Project {
+            id: project_id.clone(),
+            name,
+            description,
+            groups,
+            group_access_windows,
+            access_window,
+            path_rules,
+            users,
+            visibility,
+        })
+    }
+
+    /// Describes the `project` (the inverse of [`Self::into_project`]).
+    pub fn from_project(project: &Project) -> Self {
+        let Project {
+            name,
+            description,
+            groups,
+            group_access_windows,
+            access_window,
+            path_rules,
+            users,
+            visibility,
+            ..
+        } = project.clone();
+
+        let groups = groups
+            .into_iter()
+            .map(|group| match group_access_windows.get(&group) {
+                Some(access_window) => GroupDefinition::Windowed {
+                    group,
+                    access_window: *access_window,
+                },
+                None => GroupDefinition::Name(group),
+            })
+            .collect();
+
+        let path_rules = path_rules
+            .iter()
+            .map(|path_rule| PathRuleDefinition {
+                paths: path_rule.patterns().to_vec(),
+                groups: path_rule.groups().to_vec(),
+            })
+            .collect();
+
+        Self {
+            name,
+            description,
+            groups,
+            users,
+            visibility,
+            access_window,
+            path_rules,
+        }
+    }
+}
+
+impl From<&Project> for IdentifiedProjectDefinition {
+    fn from(value: &Project) -> Self {
+        Self {
+            id: value.id.clone(),
+            definition: ProjectDefinition::from_project(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod definition_tests {
+    use fake::Fake;
+    use fake::Faker;
+    use time::macros::datetime;
+
+    use super::*;
+
+    mod into_project {
+        use super::*;
+
+        #[test]
+        fn should_round_trip_a_project() {
+            // Arrange
+            let mut project: Project = Faker.fake();
+            project.groups = vec![Group::new("surveyors"), Group::new("clients")];
+            project.group_access_windows = HashMap::from([(
+                Group::new("clients"),
+                AccessWindow {
+                    valid_from: None,
+                    valid_until: Some(datetime!(2026-06-01 00:00 UTC)),
+                },
+            )]);
+            project.path_rules = vec![
+                PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")]).unwrap(),
+            ];
+
+            // Act
+            let round_tripped = ProjectDefinition::from_project(&project)
+                .into_project(&project.id)
+                .unwrap();
+
+            // Assert
+            assert_eq!(round_tripped, project);
+        }
+
+        #[test]
+        fn should_return_an_error_if_a_path_rule_is_invalid() {
+            // Arrange
+            let definition: ProjectDefinition = serde_json::from_value(serde_json::json!({
+                "name": "Survey",
+                "groups": ["surveyors"],
+                "path_rules": [{ "paths": ["raw/[**"], "groups": ["surveyors"] }],
+            }))
+            .unwrap();
+
+            // Act
+            let res = definition.into_project(&ProjectId::new("survey".to_owned()));
+
+            // Assert
+            assert!(res.is_err());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Describes why a project could not be loaded, so that a typo in a manifest
+//! can be found rather than the project silently disappearing.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::ProjectId;
+
+/// Why the project with the `project_id` could not be loaded (or viewed).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectDiagnostic {
+    /// The project that could not be loaded.
+    pub project_id: ProjectId,
+
+    /// The file that the problem is in (e.g. the project's `manifest.yml`).
+    pub path: PathBuf,
+
+    /// What kind of problem it is.
+    pub kind: ProjectDiagnosticKind,
+
+    /// The line (starting at 1) of the problem in the file, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+
+    /// The column (starting at 1) of the problem in the file, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+
+    /// A human readable description of the problem (e.g. the error of the
+    /// deserializer).
+    pub message: String,
+}
+
+/// The kinds of problems that prevent a project from being loaded (or, for the
+/// Potree configuration, from being viewed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectDiagnosticKind {
+    /// The project directory does not contain a manifest.
+    MissingManifest,
+
+    /// The manifest exists, but could not be read (e.g. its permissions).
+    UnreadableManifest,
+
+    /// The manifest could not be parsed or is invalid (e.g. an invalid path
+    /// rule).
+    InvalidManifest,
+
+    /// The project's record in the project database is invalid (e.g. it was
+    /// edited by hand).
+    InvalidRecord,
+
+    /// The project directory's name can't be used in a URL as is.
+    InvalidProjectId,
+
+    /// The id of another project in the project database is nested in the
+    /// project's id, or the other way around, so their assets can't be told
+    /// apart.
+    OverlappingProjectId,
+
+    /// The project directory does not contain a Potree configuration.
+    MissingPotreeConfig,
+
+    /// The Potree configuration exists, but could not be read.
+    UnreadablePotreeConfig,
+
+    /// The Potree configuration could not be parsed.
+    InvalidPotreeConfig,
+
+    /// A point cloud of the Potree configuration does not refer to an asset of
+    /// the project.
+    InvalidPointCloudUrl,
+
+    /// A point cloud of the Potree configuration refers to an asset of the
+    /// project that does not exist.
+    MissingPointCloud,
+}
+
+/// Summarizes which projects could be loaded, and why the others couldn't.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationReport {
+    /// Whether all the projects could be loaded.
+    pub valid: bool,
+
+    /// The number of projects that could be loaded.
+    pub loaded_projects: usize,
+
+    /// Why the other projects could not be loaded.
+    pub diagnostics: Vec<ProjectDiagnostic>,
+}
+
+impl ValidationReport {
+    pub fn new(loaded_projects: usize, diagnostics: Vec<ProjectDiagnostic>) -> Self {
+        Self {
+            valid: diagnostics.is_empty(),
+            loaded_projects,
+            diagnostics,
+        }
+    }
+}
+
+impl ProjectDiagnostic {
+    /// Where the problem is, e.g. `data/survey/manifest.yml:3:5`.
+    pub fn location(&self) -> String {
+        let mut location = self.path.to_string_lossy().to_string();
+        if let Some(line) = self.line {
+            location.push_str(&format!(":{line}"));
+            if let Some(column) = self.column {
+                location.push_str(&format!(":{column}"));
+            }
+        }
+
+        location
+    }
+}
+
+impl Display for ProjectDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location(), self.message)
+    }
+}
+
+#[cfg(test)]
+mod project_diagnostic_tests {
+    use super::*;
+
+    fn diagnostic(line: Option<usize>, column: Option<usize>) -> ProjectDiagnostic {
+        ProjectDiagnostic {
+            project_id: ProjectId::new("survey".to_owned()),
+            path: PathBuf::from("data/survey/manifest.yml"),
+            kind: ProjectDiagnosticKind::InvalidManifest,
+            line,
+            column,
+            message: "groups: invalid type".to_owned(),
+        }
+    }
+
+    #[test_case::test_case(Some(3), Some(5), "data/survey/manifest.yml:3:5: groups: invalid type"; "with location")]
+    #[test_case::test_case(None, None, "data/survey/manifest.yml: groups: invalid type"; "without location")]
+    fn should_display_the_location_of_the_problem(
+        line: Option<usize>,
+        column: Option<usize>,
+        expected: &str,
+    ) {
+        // Act
+        let displayed = diagnostic(line, column).to_string();
+
+        // Assert
+        assert_eq!(displayed, expected);
+    }
+
+    #[test]
+    fn should_serialize_the_kind_in_kebab_case_without_an_unknown_location() {
+        // Act
+        let json = serde_json::to_value(diagnostic(None, None)).unwrap();
+
+        // Assert
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "project_id": "survey",
+                "path": "data/survey/manifest.yml",
+                "kind": "invalid-manifest",
+                "message": "groups: invalid type",
+            })
+        );
+    }
+}
@@ -1,7 +1,15 @@
 pub mod authorization;
+pub mod definition;
+pub mod diagnostic;
+pub mod path_rule;
 
+use std::collections::HashMap;
+
+use crate::common::domain::AccessWindow;
 use crate::common::domain::Group;
 use crate::common::domain::utils::new_type::new_type;
+use crate::project::domain::path_rule::PathRule;
+use crate::user::domain::EmailAddress;
 
 /// Represents the metadata associated with a 3D model project.
 #[derive(Debug, Clone, PartialEq)]
@@ -17,12 +25,92 @@ pub struct Project {
 
     /// The groups that the project is a member of.
     pub groups: Vec<Group>,
+
+    /// When the members of some of the `groups` can access the project (e.g.
+    /// for the duration of a contract). Other groups can always access it.
+    #[cfg_attr(any(test, feature = "fake"), dummy(default))]
+    pub group_access_windows: HashMap<Group, AccessWindow>,
+
+    /// When the project can be accessed at all, by anyone other than admins.
+    #[cfg_attr(any(test, feature = "fake"), dummy(default))]
+    pub access_window: AccessWindow,
+
+    /// Restricts some of the project's assets (by path) to members of
+    /// additional groups.
+    #[cfg_attr(any(test, feature = "fake"), dummy(default))]
+    pub path_rules: Vec<PathRule>,
+
+    /// The email addresses of individual users that have access to the
+    /// project, regardless of their groups.
+    pub users: Vec<EmailAddress>,
+
+    /// Who can read the project, in addition to its groups and users.
+    pub visibility: ProjectVisibility,
+}
+
+impl Project {
+    /// Whether anyone, including visitors that aren't logged in, can read the
+    /// project.
+    pub fn is_public(&self) -> bool {
+        self.visibility == ProjectVisibility::Public
+    }
+}
+
+/// Who can read a [`Project`], in addition to its groups and users.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
+pub enum ProjectVisibility {
+    /// Only the project's groups and users.
+    #[default]
+    Private,
+
+    /// Anyone, including visitors that aren't logged in (e.g. demo projects).
+    Public,
 }
 
 new_type![
-    /// The unique identifying slug of a [`Project`].
+    /// The unique identifying slug of a [`Project`]. It is the path of the
+    /// project directory within the data directory, so projects nested in
+    /// collections have ids of multiple (`/` separated) segments, e.g.
+    /// `client-a/site-3/2026-survey`.
     #[derive(serde::Deserialize, serde::Serialize)]
     ProjectId(String)
+    impl {
+        /// The segments of the id, i.e. the names of the collections that the
+        /// project is nested in followed by the name of the project directory.
+        pub fn segments(&self) -> impl Iterator<Item = &str> {
+            self.split('/')
+        }
+
+        /// The paths of the collections that the project is nested in,
+        /// outermost first (e.g. `client-a` and `client-a/site-3` for
+        /// `client-a/site-3/2026-survey`).
+        pub fn parents(&self) -> impl Iterator<Item = &str> {
+            self.match_indices('/').map(|(index, _)| &self[..index])
+        }
+
+        /// Whether the id is a relative path of directory names, i.e. it
+        /// doesn't have any empty or dot segments (which could refer to a
+        /// directory outside of the data directory).
+        pub fn is_relative_path(&self) -> bool {
+            self.segments()
+                .all(|segment| !matches!(segment, "" | "." | ".."))
+        }
+
+        /// Whether each segment of the id can be used as a path segment of a
+        /// URL without being percent-encoded, i.e. it only contains unreserved
+        /// characters ([RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2.3))
+        /// and isn't a dot segment.
+        pub fn is_url_safe(&self) -> bool {
+            self.is_relative_path()
+                && self.segments().all(|segment| {
+                    segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+                })
+        }
+    }
 ];
 
 new_type![
@@ -39,3 +127,65 @@ new_type![
     #[derive(serde::Deserialize, serde::Serialize)]
     ProjectDescription(String)
 ];
+
+#[cfg(test)]
+mod project_domain_tests {
+    use super::*;
+
+    mod is_url_safe {
+        use super::*;
+
+        #[test_case::test_case("survey-2024_v1.2~draft", true; "unreserved characters")]
+        #[test_case::test_case("client-a/site-3/2026-survey", true; "nested")]
+        #[test_case::test_case("survey 2024", false; "space")]
+        #[test_case::test_case("survey%20", false; "percent")]
+        #[test_case::test_case("überblick", false; "non ascii")]
+        #[test_case::test_case("survey#1", false; "reserved character")]
+        #[test_case::test_case("..", false; "dot segment")]
+        #[test_case::test_case("client-a//survey", false; "empty segment")]
+        #[test_case::test_case("", false; "empty")]
+        fn should_only_allow_unreserved_characters(project_id: &str, expected: bool) {
+            // Arrange
+            let project_id = ProjectId::new(project_id.to_owned());
+
+            // Act
+            let is_url_safe = project_id.is_url_safe();
+
+            // Assert
+            assert_eq!(is_url_safe, expected);
+        }
+    }
+
+    mod is_relative_path {
+        use super::*;
+
+        #[test_case::test_case("survey", true; "single segment")]
+        #[test_case::test_case("client-a/site 3/survey", true; "nested")]
+        #[test_case::test_case("client-a/../survey", false; "parent segment")]
+        #[test_case::test_case("./survey", false; "current segment")]
+        #[test_case::test_case("/survey", false; "absolute")]
+        #[test_case::test_case("survey/", false; "trailing separator")]
+        fn should_only_allow_directory_names(project_id: &str, expected: bool) {
+            // Act
+            let is_relative_path = ProjectId::new(project_id.to_owned()).is_relative_path();
+
+            // Assert
+            assert_eq!(is_relative_path, expected);
+        }
+    }
+
+    mod parents {
+        use super::*;
+
+        #[test_case::test_case("survey", &[]; "top level")]
+        #[test_case::test_case("client-a/site-3/2026-survey", &["client-a", "client-a/site-3"]; "nested")]
+        fn should_return_the_collections_outermost_first(project_id: &str, expected: &[&str]) {
+            // Act
+            let project_id = ProjectId::new(project_id.to_owned());
+            let parents = project_id.parents().collect::<Vec<_>>();
+
+            // Assert
+            assert_eq!(parents, expected);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Path-level access control within a [`Project`][super::Project].
+
+use std::path::Path;
+
+use globset::GlobSet;
+
+use crate::common::domain::Group;
+use crate::common::domain::utils::path_glob::path_glob_set;
+use crate::user::domain::User;
+
+/// Restricts the project assets matching any of its path globs (e.g.
+/// `raw/**`, `reports/*.pdf`) to the members of its groups, in addition to
+/// the access required to the project itself.
+#[derive(Debug, Clone)]
+pub struct PathRule {
+    /// The globs that asset paths are matched against.
+    patterns: Vec<String>,
+
+    /// The compiled `patterns`.
+    glob_set: GlobSet,
+
+    /// Only members of at least one of these groups can read the matching
+    /// assets.
+    groups: Vec<Group>,
+}
+
+impl PathRule {
+    /// Creates a [`PathRule`] restricting the assets matching any of the
+    /// `patterns` to members of the `groups`.
+    ///
+    /// Returns a message describing the first invalid pattern.
+    pub fn new(patterns: Vec<String>, groups: Vec<Group>) -> Result<Self, String> {
+        let glob_set = path_glob_set(&patterns)?;
+
+        Ok(Self {
+            patterns,
+            glob_set,
+            groups,
+        })
+    }
+
+    /// The globs that asset paths are matched against.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// The groups whose members can read the matching assets.
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Whether the rule applies to the asset at `asset_path` (relative to the
+    /// project directory).
+    pub fn matches(&self, asset_path: &Path) -> bool {
+        self.glob_set.is_match(asset_path)
+    }
+
+    /// Whether the `user` is a member of one of the rule's groups.
+    pub fn allows(&self, user: &User) -> bool {
+        self.groups.iter().any(|group| user.groups.contains(group))
+    }
+}
+
+impl PartialEq for PathRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns && self.groups == other.groups
+    }
+}
+
+#[cfg(test)]
+mod path_rule_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+
+    fn raw_rule() -> PathRule {
+        PathRule::new(vec!["raw/**".to_owned()], vec![Group::new("surveyors")]).unwrap()
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test_case::test_case("raw/cloud.las", true; "matching")]
+        #[test_case::test_case("pointclouds/metadata.json", false; "not matching")]
+        fn should_match_the_asset_path(asset_path: &str, expected: bool) {
+            // Act
+            let is_match = raw_rule().matches(Path::new(asset_path));
+
+            // Assert
+            assert_eq!(is_match, expected);
+        }
+    }
+
+    mod allows {
+        use super::*;
+
+        #[test_case::test_case(&["clients", "surveyors"], true; "member")]
+        #[test_case::test_case(&["clients"], false; "not a member")]
+        fn should_require_one_of_the_groups(groups: &[&str], expected: bool) {
+            // Arrange
+            let user = User {
+                groups: groups.iter().map(|group| Group::new(group)).collect(),
+                ..Faker.fake()
+            };
+
+            // Act
+            let allows = raw_rule().allows(&user);
+
+            // Assert
+            assert_eq!(allows, expected);
+        }
+    }
+}
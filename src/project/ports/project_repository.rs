@@ -4,6 +4,8 @@ use async_trait::async_trait;
 
 use super::super::domain::Project;
 use super::super::domain::ProjectId;
+use super::super::domain::diagnostic::ProjectDiagnostic;
+use super::super::domain::diagnostic::ProjectDiagnosticKind;
 
 /// Defines the functionality needed to for the application to interact with
 /// persisted [`Project`]s.
@@ -30,6 +32,49 @@ pub trait ProjectRepository: Debug + Send + Sync + 'static {
     /// - [`ProjectRepositoryError::Parsing`] if the project has an invalid
     ///   format.
     async fn list(&self) -> Result<Vec<Project>, ProjectRepositoryError>;
+
+    /// Describes why each of the projects that are left out of
+    /// [`Self::list`] could not be loaded.
+    ///
+    /// # Errors
+    ///
+    /// - [`ProjectRepositoryError::Infrastucture`] if the datastore can't be
+    ///   read.
+    async fn diagnostics(&self) -> Result<Vec<ProjectDiagnostic>, ProjectRepositoryError>;
+
+    /// Persists a new `project`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ProjectRepositoryError::AlreadyExists`] if a [`Project`] with the
+    ///   same id already exists.
+    /// - [`ProjectRepositoryError::Overlapping`] if the id of an existing
+    ///   [`Project`] is nested in the `project`'s id, or the other way around
+    ///   (e.g. `client` and `client/survey`). The assets of both would have the
+    ///   same paths.
+    /// - [`ProjectRepositoryError::ReadOnly`] if the datastore can't be edited
+    ///   by the application.
+    async fn create(&self, project: &Project) -> Result<(), ProjectRepositoryError>;
+
+    /// Replaces the persisted [`Project`] with the same id as the `project`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ProjectRepositoryError::ResourceNotFound`] if the [`Project`] can't
+    ///   be found.
+    /// - [`ProjectRepositoryError::ReadOnly`] if the datastore can't be edited
+    ///   by the application.
+    async fn update(&self, project: &Project) -> Result<(), ProjectRepositoryError>;
+
+    /// Deletes the [`Project`] with the `project_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ProjectRepositoryError::ResourceNotFound`] if the [`Project`] can't
+    ///   be found.
+    /// - [`ProjectRepositoryError::ReadOnly`] if the datastore can't be edited
+    ///   by the application.
+    async fn delete(&self, project_id: &ProjectId) -> Result<(), ProjectRepositoryError>;
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -37,9 +82,50 @@ pub enum ProjectRepositoryError {
     #[error("the `Project` ({id}) could not be found")]
     ResourceNotFound { id: ProjectId },
 
-    #[error("unable to parse the `Project` ({id}) ")]
-    Parsing { id: ProjectId },
+    #[error("unable to parse the `Project` ({id}): {message}")]
+    Parsing { id: ProjectId, message: String },
+
+    #[error("the `Project` ({id}) already exists")]
+    AlreadyExists { id: ProjectId },
+
+    #[error(
+        "the `Project` ({id}) overlaps the `Project` ({existing_id}), project ids can't be nested"
+    )]
+    Overlapping {
+        id: ProjectId,
+        existing_id: ProjectId,
+    },
+
+    #[error("the projects can't be edited: {message}")]
+    ReadOnly { message: String },
 
     #[error("unable to interact with the datastore backend: {message}")]
     Infrastucture { message: String },
 }
+
+impl From<ProjectDiagnostic> for ProjectRepositoryError {
+    fn from(value: ProjectDiagnostic) -> Self {
+        match value.kind {
+            ProjectDiagnosticKind::MissingManifest => Self::ResourceNotFound {
+                id: value.project_id,
+            },
+            ProjectDiagnosticKind::UnreadableManifest => Self::Infrastucture {
+                message: value.to_string(),
+            },
+            // Only the manifest (or database record) is read by the repositories,
+            // the other kinds are found by validating the whole project directory.
+            ProjectDiagnosticKind::InvalidManifest
+            | ProjectDiagnosticKind::InvalidRecord
+            | ProjectDiagnosticKind::InvalidProjectId
+            | ProjectDiagnosticKind::OverlappingProjectId
+            | ProjectDiagnosticKind::MissingPotreeConfig
+            | ProjectDiagnosticKind::UnreadablePotreeConfig
+            | ProjectDiagnosticKind::InvalidPotreeConfig
+            | ProjectDiagnosticKind::InvalidPointCloudUrl
+            | ProjectDiagnosticKind::MissingPointCloud => Self::Parsing {
+                message: value.to_string(),
+                id: value.project_id,
+            },
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use time::OffsetDateTime;
+
+use crate::authorization::domain::action::Action;
+use crate::authorization::domain::error::AuthorizationEngineError;
+use crate::authorization::domain::resource::ResourceIdentifier;
+use crate::authorization::domain::resource::ResourceType;
+use crate::project::domain::ProjectId;
+use crate::project::ports::project_repository::ProjectRepositoryError;
+use crate::share_link::domain::ShareLink;
+use crate::user::domain::User;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProjectAdminServiceError {
+    #[error("project ({id}) not found")]
+    ProjectNotFound { id: ProjectId },
+
+    #[error("project ({id}) already exists")]
+    ProjectAlreadyExists { id: ProjectId },
+
+    #[error("project ({id}) overlaps project ({existing_id}), project ids can't be nested")]
+    ProjectOverlapping {
+        id: ProjectId,
+        existing_id: ProjectId,
+    },
+
+    #[error("{message}")]
+    ReadOnly { message: String },
+
+    #[error("{} is not authorized to {} the {:?}: {:?}", user.name, action, resource_type, resource_identifier)]
+    NotAuthorized {
+        user: Box<User>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
+    #[error("user is not authenticated")]
+    NotAuthenticated,
+
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
+    #[error("the project is invalid: {message}")]
+    InvalidProject { message: String },
+
+    #[error("{message}")]
+    Infrastucture { message: String },
+}
+
+impl From<ProjectRepositoryError> for ProjectAdminServiceError {
+    fn from(value: ProjectRepositoryError) -> Self {
+        match value {
+            ProjectRepositoryError::ResourceNotFound { id }
+            | ProjectRepositoryError::Parsing { id, .. } => Self::ProjectNotFound { id },
+            ProjectRepositoryError::AlreadyExists { id } => Self::ProjectAlreadyExists { id },
+            ProjectRepositoryError::Overlapping { id, existing_id } => {
+                Self::ProjectOverlapping { id, existing_id }
+            }
+            ProjectRepositoryError::ReadOnly { message } => Self::ReadOnly { message },
+            ProjectRepositoryError::Infrastucture { message } => Self::Infrastucture { message },
+        }
+    }
+}
+
+impl From<AuthorizationEngineError> for ProjectAdminServiceError {
+    fn from(value: AuthorizationEngineError) -> Self {
+        match value {
+            AuthorizationEngineError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AuthorizationEngineError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AuthorizationEngineError::NotAuthenticated => Self::NotAuthenticated,
+            AuthorizationEngineError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use std::sync::Arc;
+
+use super::error::ProjectAdminServiceError;
+use crate::authorization::domain::action::Action;
+use crate::authorization::ports::authorization_engine::AuthorizationEngine;
+use crate::project::domain::Project;
+use crate::project::domain::ProjectId;
+use crate::project::domain::authorization::ProjectTypeResource;
+use crate::project::domain::definition::ProjectDefinition;
+use crate::project::ports::project_repository::ProjectRepository;
+use crate::user::domain::User;
+
+/// A service for editing the projects. Edits are authorized on the project
+/// type (rather than on a project instance), by default only admins are
+/// allowed to make them.
+#[derive(Debug, Clone)]
+pub struct ProjectAdminService {
+    /// Used to read and edit the projects regardless of their members.
+    project_repository: Arc<dyn ProjectRepository>,
+
+    /// Used to determine if a user is authorized to edit the projects.
+    authorization_engine: Arc<dyn AuthorizationEngine>,
+}
+
+impl ProjectAdminService {
+    pub fn new(
+        project_repository: Arc<dyn ProjectRepository>,
+        authorization_engine: Arc<dyn AuthorizationEngine>,
+    ) -> Self {
+        Self {
+            project_repository,
+            authorization_engine,
+        }
+    }
+
+    /// Lists all the projects, including those that the `user` isn't a member
+    /// of. `user` is used for authorization.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to read any
+    /// project.
+    pub async fn list(
+        &self,
+        user: &Option<User>,
+    ) -> Result<Vec<Project>, ProjectAdminServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Read, &ProjectTypeResource)?;
+
+        Ok(self.project_repository.list().await?)
+    }
+
+    /// Reads a specific project (`project_id`), even if the `user` isn't a
+    /// member of it. `user` is used for authorization.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to read any
+    /// project, or the project can't be found.
+    pub async fn read(
+        &self,
+        user: &Option<User>,
+        project_id: &ProjectId,
+    ) -> Result<Project, ProjectAdminServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Read, &ProjectTypeResource)?;
+
+        Ok(self.project_repository.read(project_id).await?)
+    }
+
+    /// Creates a project with the `project_id` from its `definition`. `user` is
+    /// used for authorization.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to create projects,
+    /// the `project_id` isn't URL-safe, the `definition` is invalid or the
+    /// project already exists.
+    pub async fn create(
+        &self,
+        user: &Option<User>,
+        project_id: &ProjectId,
+        definition: ProjectDefinition,
+    ) -> Result<Project, ProjectAdminServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Create, &ProjectTypeResource)?;
+
+        // The id becomes part of the project's URLs.
+        if !project_id.is_url_safe() {
+            return Err(ProjectAdminServiceError::InvalidProject {
+                message: "the segments of the project id may only contain ASCII letters, digits, \
+                          `-`, `.`, `_` and `~`"
+                    .to_owned(),
+            });
+        }
+
+        let project = into_project(project_id, definition)?;
+        self.project_repository.create(&project).await?;
+
+        Ok(project)
+    }
+
+    /// Replaces the project with the `project_id` with its new `definition`.
+    /// `user` is used for authorization.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to update projects,
+    /// the `definition` is invalid or the project can't be found.
+    pub async fn update(
+        &self,
+        user: &Option<User>,
+        project_id: &ProjectId,
+        definition: ProjectDefinition,
+    ) -> Result<Project, ProjectAdminServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Update, &ProjectTypeResource)?;
+
+        let project = into_project(project_id, definition)?;
+        self.project_repository.update(&project).await?;
+
+        Ok(project)
+    }
+
+    /// Deletes the project with the `project_id` (but not its assets). `user`
+    /// is used for authorization.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to delete projects,
+    /// or the project can't be found.
+    pub async fn delete(
+        &self,
+        user: &Option<User>,
+        project_id: &ProjectId,
+    ) -> Result<(), ProjectAdminServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Delete, &ProjectTypeResource)?;
+
+        Ok(self.project_repository.delete(project_id).await?)
+    }
+}
+
+fn into_project(
+    project_id: &ProjectId,
+    definition: ProjectDefinition,
+) -> Result<Project, ProjectAdminServiceError> {
+    definition
+        .into_project(project_id)
+        .map_err(|message| ProjectAdminServiceError::InvalidProject { message })
+}
+
+#[cfg(test)]
+mod project_admin_service_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+    use crate::authorization::domain::error::AuthorizationEngineError;
+    use crate::authorization::ports::authorization_engine::MockAuthorizationEngine;
+    use crate::common::domain::Group;
+    use crate::project::ports::project_repository::MockProjectRepository;
+    use crate::project::ports::project_repository::ProjectRepositoryError;
+
+    fn definition() -> ProjectDefinition {
+        serde_json::from_value(serde_json::json!({
+            "name": "Survey",
+            "description": "The 2026 survey",
+            "groups": ["surveyors"],
+        }))
+        .unwrap()
+    }
+
+    /// An authorization engine that only allows the `action` on the project
+    /// type.
+    fn authorization_engine(allowed_action: Action) -> MockAuthorizationEngine {
+        let mut authorization_engine = MockAuthorizationEngine::new();
+        authorization_engine
+            .expect_can_on_type()
+            .returning(move |_, action, _| match action == &allowed_action {
+                true => Ok(()),
+                false => Err(AuthorizationEngineError::NotAuthorized {
+                    user: Faker.fake(),
+                    action: action.clone(),
+                    resource_identifier: None,
+                    resource_type: Faker.fake(),
+                }),
+            });
+        authorization_engine
+    }
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_create_the_project_if_user_authorized() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_create()
+                .withf(|project| {
+                    project.id.as_str() == "client-a/survey"
+                        && project.groups == vec![Group::new("surveyors")]
+                })
+                .times(1)
+                .return_const(Ok(()));
+
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine(Action::Create)),
+            );
+
+            // Act
+            let project = project_admin_service
+                .create(
+                    &Faker.fake(),
+                    &ProjectId::new("client-a/survey".to_owned()),
+                    definition(),
+                )
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(project.name.as_str(), "Survey");
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authorized() {
+            // Arrange
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine(Action::Update)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .create(
+                    &Faker.fake(),
+                    &ProjectId::new("survey".to_owned()),
+                    definition(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::NotAuthorized {
+                    action: Action::Create,
+                    ..
+                })
+            ));
+        }
+
+        #[test_case::test_case("survey 2026"; "not url safe")]
+        #[test_case::test_case("client-a/../survey"; "dot segment")]
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_project_id_invalid(project_id: &str) {
+            // Arrange
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine(Action::Create)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .create(
+                    &Faker.fake(),
+                    &ProjectId::new(project_id.to_owned()),
+                    definition(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::InvalidProject { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_project_already_exists() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository.expect_create().return_const(Err(
+                ProjectRepositoryError::AlreadyExists { id: Faker.fake() },
+            ));
+
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine(Action::Create)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .create(
+                    &Faker.fake(),
+                    &ProjectId::new("survey".to_owned()),
+                    definition(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::ProjectAlreadyExists { .. })
+            ));
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_definition_invalid() {
+            // Arrange
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine(Action::Update)),
+            );
+            let definition = serde_json::from_value(serde_json::json!({
+                "name": "Survey",
+                "groups": [],
+                "path_rules": [{ "paths": ["raw/[**"], "groups": ["surveyors"] }],
+            }))
+            .unwrap();
+
+            // Act
+            let res = project_admin_service
+                .update(
+                    &Faker.fake(),
+                    &ProjectId::new("survey".to_owned()),
+                    definition,
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::InvalidProject { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_projects_read_only() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository.expect_update().return_const(Err(
+                ProjectRepositoryError::ReadOnly {
+                    message: Faker.fake(),
+                },
+            ));
+
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine(Action::Update)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .update(
+                    &Faker.fake(),
+                    &ProjectId::new("survey".to_owned()),
+                    definition(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::ReadOnly { .. })
+            ));
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_delete_the_project_if_user_authorized() {
+            // Arrange
+            let project_id = ProjectId::new("survey".to_owned());
+            let mut project_repository = MockProjectRepository::new();
+            project_repository
+                .expect_delete()
+                .withf({
+                    let project_id = project_id.clone();
+                    move |id| id == &project_id
+                })
+                .times(1)
+                .return_const(Ok(()));
+
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine(Action::Delete)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .delete(&Faker.fake(), &project_id)
+                .await;
+
+            // Assert
+            assert!(res.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_project_not_found() {
+            // Arrange
+            let mut project_repository = MockProjectRepository::new();
+            project_repository.expect_delete().return_const(Err(
+                ProjectRepositoryError::ResourceNotFound { id: Faker.fake() },
+            ));
+
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(project_repository),
+                Arc::new(authorization_engine(Action::Delete)),
+            );
+
+            // Act
+            let res = project_admin_service
+                .delete(&Faker.fake(), &ProjectId::new("survey".to_owned()))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::ProjectNotFound { .. })
+            ));
+        }
+    }
+
+    mod list {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authorized() {
+            // Arrange
+            let project_admin_service = ProjectAdminService::new(
+                Arc::new(MockProjectRepository::new()),
+                Arc::new(authorization_engine(Action::List)),
+            );
+
+            // Act
+            let res = project_admin_service.list(&Faker.fake()).await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAdminServiceError::NotAuthorized {
+                    action: Action::Read,
+                    ..
+                })
+            ));
+        }
+    }
+}
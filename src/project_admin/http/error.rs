@@ -0,0 +1,53 @@
+use super::super::application::error::ProjectAdminServiceError;
+use crate::common::utils::http::api_error::ApiError;
+
+impl From<ProjectAdminServiceError> for ApiError {
+    fn from(value: ProjectAdminServiceError) -> Self {
+        match value {
+            ProjectAdminServiceError::ProjectNotFound { id } => Self::ResourceNotFound {
+                resource_name: format!("project: {id}"),
+            },
+            ProjectAdminServiceError::ProjectAlreadyExists { .. }
+            | ProjectAdminServiceError::ProjectOverlapping { .. }
+            | ProjectAdminServiceError::ReadOnly { .. } => Self::Conflict {
+                message: value.to_string(),
+            },
+            ProjectAdminServiceError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ProjectAdminServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ProjectAdminServiceError::NotAuthenticated => Self::NotAuthenticated,
+            ProjectAdminServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+            ProjectAdminServiceError::InvalidProject { .. } => Self::InvalidRequest {
+                message: value.to_string(),
+            },
+            ProjectAdminServiceError::Infrastucture { message } => Self::Infrastucture { message },
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod error;
+mod extractors;
+mod route_handlers;
+mod router;
+mod state;
+
+pub use router::ADMIN_PROJECT;
+pub use router::ADMIN_PROJECTS;
+pub use router::build_router;
@@ -0,0 +1,83 @@
+use axum::Json;
+use axum::extract::Path;
+use http::StatusCode;
+
+use super::super::application::service::ProjectAdminService;
+use crate::common::utils::http::api_error::ApiError;
+use crate::project::domain::ProjectId;
+use crate::project::domain::definition::IdentifiedProjectDefinition;
+use crate::project::domain::definition::ProjectDefinition;
+use crate::user::http::extractors::UserExtractor;
+
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct ProjectPathParams {
+    pub project_id: ProjectId,
+}
+
+/// Lists the definitions of all the projects.
+#[tracing::instrument(name = "`project admin route handlers`: listing projects", err)]
+pub(crate) async fn list_projects(
+    UserExtractor(user): UserExtractor,
+    project_admin_service: ProjectAdminService,
+) -> Result<Json<Vec<IdentifiedProjectDefinition>>, ApiError> {
+    let projects = project_admin_service.list(&user).await?;
+
+    Ok(Json(projects.iter().map(Into::into).collect()))
+}
+
+/// Returns the definition of a project.
+#[tracing::instrument(name = "`project admin route handlers`: reading project", err)]
+pub(crate) async fn read_project(
+    UserExtractor(user): UserExtractor,
+    project_admin_service: ProjectAdminService,
+    Path(params): Path<ProjectPathParams>,
+) -> Result<Json<IdentifiedProjectDefinition>, ApiError> {
+    let project = project_admin_service
+        .read(&user, &params.project_id)
+        .await?;
+
+    Ok(Json((&project).into()))
+}
+
+/// Creates a project from its definition (including its id).
+#[tracing::instrument(name = "`project admin route handlers`: creating project", err)]
+pub(crate) async fn create_project(
+    UserExtractor(user): UserExtractor,
+    project_admin_service: ProjectAdminService,
+    Json(request): Json<IdentifiedProjectDefinition>,
+) -> Result<(StatusCode, Json<IdentifiedProjectDefinition>), ApiError> {
+    let IdentifiedProjectDefinition { id, definition } = request;
+
+    let project = project_admin_service.create(&user, &id, definition).await?;
+
+    Ok((StatusCode::CREATED, Json((&project).into())))
+}
+
+/// Replaces the definition of a project.
+#[tracing::instrument(name = "`project admin route handlers`: updating project", err)]
+pub(crate) async fn update_project(
+    UserExtractor(user): UserExtractor,
+    project_admin_service: ProjectAdminService,
+    Path(params): Path<ProjectPathParams>,
+    Json(definition): Json<ProjectDefinition>,
+) -> Result<Json<IdentifiedProjectDefinition>, ApiError> {
+    let project = project_admin_service
+        .update(&user, &params.project_id, definition)
+        .await?;
+
+    Ok(Json((&project).into()))
+}
+
+/// Deletes a project (but not its assets).
+#[tracing::instrument(name = "`project admin route handlers`: deleting project", err)]
+pub(crate) async fn delete_project(
+    UserExtractor(user): UserExtractor,
+    project_admin_service: ProjectAdminService,
+    Path(params): Path<ProjectPathParams>,
+) -> Result<StatusCode, ApiError> {
+    project_admin_service
+        .delete(&user, &params.project_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
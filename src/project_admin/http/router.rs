@@ -0,0 +1,38 @@
+use std::sync::LazyLock;
+
+use axum::Extension;
+use axum::Router;
+use axum::routing::get;
+use web_route::ParameterizedRoute;
+use web_route::WebRoute;
+
+use super::super::application::service::ProjectAdminService;
+use super::route_handlers;
+use super::state::State;
+
+pub static ADMIN_PROJECTS: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/admin/projects"));
+
+/// The project id is a catch-all, as the ids of projects nested in collections
+/// have multiple segments.
+pub static ADMIN_PROJECT: LazyLock<ParameterizedRoute> =
+    LazyLock::new(|| ParameterizedRoute::new("/admin/projects/{*project_id}"));
+
+/// Builds a router for editing the projects.
+pub fn build_router(project_admin_service: ProjectAdminService) -> Router {
+    let state = State {
+        project_admin_service,
+    };
+
+    Router::new()
+        .route(
+            &ADMIN_PROJECTS,
+            get(route_handlers::list_projects).post(route_handlers::create_project),
+        )
+        .route(
+            &ADMIN_PROJECT,
+            get(route_handlers::read_project)
+                .put(route_handlers::update_project)
+                .delete(route_handlers::delete_project),
+        )
+        .layer(Extension(state))
+}
@@ -0,0 +1,6 @@
+use super::super::application::service::ProjectAdminService;
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub project_admin_service: ProjectAdminService,
+}
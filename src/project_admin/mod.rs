@@ -0,0 +1,5 @@
+//! Lets admins create, edit and delete projects through a JSON API, rather than
+//! by editing manifest files (only when the projects are stored in a database).
+
+pub mod application;
+pub mod http;
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use http::HeaderMap;
@@ -8,9 +9,13 @@ use super::error::ProjectAssetsServiceError;
 use crate::authorization::domain::action::Action;
 use crate::authorization::ports::authorization_engine::AuthorizationEngine;
 use crate::common::domain::StaticAsset;
+use crate::project::application::error::ProjectServiceError;
 use crate::project::application::port::ProjectServicePort;
+use crate::project::domain::Project;
 use crate::project::domain::ProjectId;
+use crate::project_asset::domain::asset_path::split_asset_path;
 use crate::project_asset::domain::authorization::ProjectAssetResource;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// A service for interacting with project assets.
@@ -34,35 +39,117 @@ impl ProjectAssetService {
         }
     }
 
-    /// Read a specific project asset. Optional `request_headers` can be
-    /// provided to specify various instructions as to how to read and format
-    /// the resulting data.
+    /// Read a specific project asset. The `path` of the asset starts with the
+    /// id of its project. Optional `request_headers` can be provided to
+    /// specify various instructions as to how to read and format the
+    /// resulting data.
     pub async fn read_asset(
         &self,
         user: &Option<User>,
-        project_id: &ProjectId,
-        asset_path: &Path,
+        path: &Path,
         request_headers: Option<HeaderMap>,
     ) -> Result<StaticAsset, ProjectAssetsServiceError> {
-        let project = self.project_service.read(user, project_id).await?;
+        self.authorize_asset(user, path).await?;
+
+        self.get_asset(path, request_headers).await
+    }
+
+    /// Determines if the `user` is allowed to read a specific project asset
+    /// (at the `path`, starting with the id of its project), without reading
+    /// it (e.g. when the asset is served by a reverse proxy).
+    pub async fn authorize_asset(
+        &self,
+        user: &Option<User>,
+        path: &Path,
+    ) -> Result<(), ProjectAssetsServiceError> {
+        let (project, asset_path) = read_project(path, |project_id| async move {
+            self.project_service.read(user, &project_id).await
+        })
+        .await?;
 
         let project_asset = ProjectAssetResource {
             associated_project: &project,
-            asset_path,
+            asset_path: &asset_path,
         };
         self.authorization_engine
             .can_on_instance(user, &Action::Read, &project_asset)?;
 
-        // Build a path to the asset. The asset would be within its project directory.
-        let asset_path = Path::new(project_id.as_str()).join(asset_path);
+        Ok(())
+    }
+
+    /// Read a specific project asset (at the `path`, starting with the id of
+    /// its project) on behalf of a `share_link` (rather than an authenticated
+    /// user). Optional `request_headers` can be provided to specify various
+    /// instructions as to how to read and format the resulting data.
+    pub async fn read_asset_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        path: &Path,
+        request_headers: Option<HeaderMap>,
+    ) -> Result<StaticAsset, ProjectAssetsServiceError> {
+        let (project, asset_path) = read_project(path, |project_id| async move {
+            self.project_service
+                .read_with_share_link(share_link, &project_id)
+                .await
+        })
+        .await?;
+
+        let project_asset = ProjectAssetResource {
+            associated_project: &project,
+            asset_path: &asset_path,
+        };
+        self.authorization_engine.can_on_instance_with_share_link(
+            share_link,
+            &Action::Read,
+            &project_asset,
+        )?;
 
+        self.get_asset(path, request_headers).await
+    }
+
+    /// Reads an (already authorized) asset from the store.
+    async fn get_asset(
+        &self,
+        path: &Path,
+        request_headers: Option<HeaderMap>,
+    ) -> Result<StaticAsset, ProjectAssetsServiceError> {
+        // The path of the project directory is the project id, which the `path`
+        // starts with.
         Ok(self
             .project_asset_store
-            .get_asset(&asset_path, request_headers)
+            .get_asset(path, request_headers)
             .await?)
     }
 }
 
+/// Reads the project that the asset at the `path` is within (with `read`),
+/// along with the path of the asset within the project.
+///
+/// Project ids can have multiple segments, so each way of splitting the `path`
+/// is tried until a project is found. Projects can't be nested in other
+/// projects, so only one of them can be found.
+async fn read_project<F, Fut>(
+    path: &Path,
+    read: F,
+) -> Result<(Project, PathBuf), ProjectAssetsServiceError>
+where
+    F: Fn(ProjectId) -> Fut,
+    Fut: Future<Output = Result<Project, ProjectServiceError>>,
+{
+    let mut error = ProjectAssetsServiceError::AssetNotFound {
+        path: path.to_owned(),
+    };
+    for (project_id, asset_path) in split_asset_path(path) {
+        match read(project_id).await {
+            Ok(project) => return Ok((project, asset_path)),
+            Err(e @ ProjectServiceError::ProjectNotFound { .. }) => error = e.into(),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(error)
+}
+
 #[cfg(test)]
 mod project_asset_service_tests {
     use fake::Fake;
@@ -99,7 +186,11 @@ mod project_asset_service_tests {
 
             // Act
             let res = project_asset_service
-                .read_asset(&Faker.fake(), &Faker.fake(), Path::new(""), Faker.fake())
+                .read_asset(
+                    &Faker.fake(),
+                    Path::new("survey/metadata.json"),
+                    Faker.fake(),
+                )
                 .await;
 
             // Assert
@@ -138,7 +229,11 @@ mod project_asset_service_tests {
 
         // Act
         let res = project_asset_service
-            .read_asset(&Faker.fake(), &Faker.fake(), Path::new(""), Faker.fake())
+            .read_asset(
+                &Faker.fake(),
+                Path::new("survey/metadata.json"),
+                Faker.fake(),
+            )
             .await;
 
         // Assert
@@ -147,4 +242,181 @@ mod project_asset_service_tests {
             Err(ProjectAssetsServiceError::NotAuthorized { .. })
         ))
     }
+
+    mod authorize_asset {
+
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_ok_without_reading_the_asset() {
+            // Arrange
+            let mut project_datastore = MockProjectServicePort::new();
+            project_datastore
+                .expect_read()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .return_const(Ok(()));
+            let mut project_asset_store = MockProjectAssetStore::new();
+            project_asset_store.expect_get_asset().never();
+
+            let project_asset_service = ProjectAssetService::new(
+                Arc::new(project_datastore),
+                Arc::new(project_asset_store),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = project_asset_service
+                .authorize_asset(&Faker.fake(), Path::new("survey/metadata.json"))
+                .await;
+
+            // Assert
+            assert!(res.is_ok())
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authorized() {
+            // Arrange
+            let mut project_datastore = MockProjectServicePort::new();
+            project_datastore
+                .expect_read()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance()
+                .return_const(Err(AuthorizationEngineError::NotAuthorized {
+                    user: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: Some(Faker.fake()),
+                    resource_type: Faker.fake(),
+                }));
+
+            let project_asset_service = ProjectAssetService::new(
+                Arc::new(project_datastore),
+                Arc::new(MockProjectAssetStore::new()),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = project_asset_service
+                .authorize_asset(&Faker.fake(), Path::new("survey/metadata.json"))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAssetsServiceError::NotAuthorized { .. })
+            ))
+        }
+    }
+
+    mod read_project {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_find_the_project_that_the_asset_is_nested_in() {
+            // Arrange
+            let path = Path::new("client-a/survey/pointclouds/metadata.json");
+
+            // Act
+            let (project, asset_path) = read_project(path, |project_id| async move {
+                match project_id.as_str() {
+                    "client-a/survey" => Ok(Project {
+                        id: project_id,
+                        ..Faker.fake()
+                    }),
+                    _ => Err(ProjectServiceError::ProjectNotFound { id: project_id }),
+                }
+            })
+            .await
+            .unwrap();
+
+            // Assert
+            assert_eq!(project.id.as_str(), "client-a/survey");
+            assert_eq!(asset_path, Path::new("pointclouds/metadata.json"));
+        }
+
+        #[tokio::test]
+        async fn should_stop_at_the_first_project_that_is_not_authorized() {
+            // Arrange
+            let path = Path::new("client-a/survey/metadata.json");
+
+            // Act
+            let res = read_project(path, |_| async {
+                Err(ProjectServiceError::NotAuthenticated)
+            })
+            .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAssetsServiceError::NotAuthenticated)
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_no_project_is_found() {
+            // Arrange
+            let path = Path::new("client-a/survey/metadata.json");
+
+            // Act
+            let res = read_project(path, |project_id| async move {
+                Err(ProjectServiceError::ProjectNotFound { id: project_id })
+            })
+            .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAssetsServiceError::ProjectNotFound { .. })
+            ));
+        }
+    }
+
+    mod read_asset_with_share_link {
+
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_asset_not_covered_by_share_link() {
+            // Arrange
+            let mut project_datastore = MockProjectServicePort::new();
+            project_datastore
+                .expect_read_with_share_link()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance_with_share_link()
+                .return_const(Err(AuthorizationEngineError::ShareLinkNotAuthorized {
+                    share_link: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: Some(Faker.fake()),
+                    resource_type: Faker.fake(),
+                }));
+            let project_asset_store = MockProjectAssetStore::new();
+
+            let project_asset_service = ProjectAssetService::new(
+                Arc::new(project_datastore),
+                Arc::new(project_asset_store),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = project_asset_service
+                .read_asset_with_share_link(
+                    &Faker.fake(),
+                    Path::new("survey/metadata.json"),
+                    Faker.fake(),
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ProjectAssetsServiceError::ShareLinkNotAuthorized { .. })
+            ))
+        }
+    }
 }
@@ -0,0 +1,82 @@
+//! Splits the paths of project assets, whose project id can have multiple
+//! segments (e.g. `client-a/site-3/2026-survey/pointclouds/metadata.json`).
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::project::domain::ProjectId;
+
+/// The ways the `path` of an asset (starting with the id of its project) can be
+/// split into a project id and the path of the asset within that project,
+/// shortest project id first.
+///
+/// As projects can't be nested in other projects (only in collections), at most
+/// one of the project ids is that of an existing project. A path that has
+/// anything other than directory names (e.g. `..`) can't be split.
+pub fn split_asset_path(path: &Path) -> Vec<(ProjectId, PathBuf)> {
+    let Some(segments) = path
+        .components()
+        .map(|component| match component {
+            Component::Normal(segment) => segment.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+
+    (1..=segments.len())
+        .map(|project_id_length| {
+            let (project_id, asset_path) = segments.split_at(project_id_length);
+            (
+                ProjectId::new(project_id.join("/")),
+                asset_path.iter().collect(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod asset_path_tests {
+    use super::*;
+
+    mod split_asset_path {
+        use super::*;
+
+        fn split(project_id: &str, asset_path: &str) -> (ProjectId, PathBuf) {
+            (
+                ProjectId::new(project_id.to_owned()),
+                PathBuf::from(asset_path),
+            )
+        }
+
+        #[test]
+        fn should_split_the_path_at_each_segment() {
+            // Act
+            let splits = split_asset_path(Path::new("client-a/survey/pointclouds/metadata.json"));
+
+            // Assert
+            assert_eq!(
+                splits,
+                vec![
+                    split("client-a", "survey/pointclouds/metadata.json"),
+                    split("client-a/survey", "pointclouds/metadata.json"),
+                    split("client-a/survey/pointclouds", "metadata.json"),
+                    split("client-a/survey/pointclouds/metadata.json", ""),
+                ]
+            );
+        }
+
+        #[test_case::test_case("survey/../other/metadata.json"; "parent directory")]
+        #[test_case::test_case("/survey/metadata.json"; "absolute")]
+        #[test_case::test_case(""; "empty")]
+        fn should_not_split_a_path_outside_of_the_projects(path: &str) {
+            // Act
+            let splits = split_asset_path(Path::new(path));
+
+            // Assert
+            assert!(splits.is_empty());
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod asset_path;
 pub mod authorization;
+pub mod potree_config;
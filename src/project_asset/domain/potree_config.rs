@@ -0,0 +1,216 @@
+//! The parts of a project's Potree configuration (`potree.json5`) that refer to
+//! the project's assets, so that a broken point cloud can be found before a
+//! user opens the project.
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use url::Url;
+
+use crate::project::domain::ProjectId;
+
+/// The name of the Potree configuration file in the root of a project
+/// directory.
+pub const POTREE_CONFIG_FILE_NAME: &str = "potree.json5";
+
+/// The origin that the point cloud urls are resolved against. It only stands in
+/// for the origin that `potree-auth` is served at, and is never requested.
+const ORIGIN: &str = "http://potree-auth.invalid";
+
+/// The route that the project assets are served at.
+const PROJECT_ASSETS_ROUTE: &str = "/project-assets/";
+
+/// A (partial) Potree configuration. Any settings other than the point clouds
+/// are ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PotreeConfig {
+    /// The point clouds loaded into the viewer.
+    #[serde(default)]
+    pub pointclouds: Vec<PointCloudConfig>,
+}
+
+/// A point cloud loaded into the viewer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PointCloudConfig {
+    /// The name displayed in the viewer.
+    pub name: Option<String>,
+
+    /// Where the point cloud (e.g. its `cloud.js` or `metadata.json`) is
+    /// loaded from.
+    pub url: String,
+}
+
+/// What a [`PointCloudConfig::url`] refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointCloudLocation {
+    /// An asset of the project, at the path relative to the project directory.
+    Asset(PathBuf),
+
+    /// A file served by another origin, which can't be checked.
+    External,
+
+    /// Anything other than an asset of the project (e.g. an asset of another
+    /// project, or a path outside of the project assets).
+    Outside,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PotreeConfigError {
+    #[error("{message}")]
+    Parsing {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+}
+
+impl PotreeConfig {
+    /// Parses the contents of a `potree.json5` file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error (with its location, if known) if the `contents`
+    /// aren't valid JSON5, or are missing required fields of a point cloud.
+    pub fn parse(contents: &str) -> Result<Self, PotreeConfigError> {
+        json5::from_str(contents).map_err(|e| {
+            let json5::Error::Message { msg, location } = e;
+            PotreeConfigError::Parsing {
+                message: msg,
+                line: location.as_ref().map(|location| location.line),
+                column: location.as_ref().map(|location| location.column),
+            }
+        })
+    }
+}
+
+impl PointCloudConfig {
+    /// Determines what the `url` of the point cloud of the project with the
+    /// `project_id` refers to.
+    ///
+    /// Relative urls are resolved against the project assets route, as in the
+    /// example configuration (e.g. `project-1/pointclouds/cloud.js` is served
+    /// at `/project-assets/project-1/pointclouds/cloud.js`).
+    pub fn location(&self, project_id: &ProjectId) -> PointCloudLocation {
+        let Ok(base) = Url::parse(ORIGIN).and_then(|origin| origin.join(PROJECT_ASSETS_ROUTE))
+        else {
+            return PointCloudLocation::Outside;
+        };
+        let Ok(url) = base.join(&self.url) else {
+            return PointCloudLocation::Outside;
+        };
+
+        if url.origin() != base.origin() {
+            return PointCloudLocation::External;
+        }
+
+        let project_route = format!("{PROJECT_ASSETS_ROUTE}{project_id}/");
+        let Some(encoded_path) = url.path().strip_prefix(&project_route) else {
+            return PointCloudLocation::Outside;
+        };
+        let Ok(path) = percent_decode_str(encoded_path).decode_utf8() else {
+            return PointCloudLocation::Outside;
+        };
+
+        // Encoded separators or dot segments could otherwise escape the project
+        // directory once decoded.
+        let path = Path::new(path.as_ref());
+        let is_within_project = path.components().next().is_some()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_within_project {
+            return PointCloudLocation::Outside;
+        }
+
+        PointCloudLocation::Asset(path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod potree_config_tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_point_clouds_and_ignore_other_settings() {
+            // Arrange
+            let contents = r#"{
+                type: 'Potree',
+                settings: { pointBudget: 1000000, },
+                pointclouds: [
+                    { name: 'Lion', url: 'survey/pointclouds/cloud.js', position: [0, 0, 0], },
+                ],
+            }"#;
+
+            // Act
+            let potree_config = PotreeConfig::parse(contents).unwrap();
+
+            // Assert
+            assert_eq!(
+                potree_config,
+                PotreeConfig {
+                    pointclouds: vec![PointCloudConfig {
+                        name: Some("Lion".to_owned()),
+                        url: "survey/pointclouds/cloud.js".to_owned(),
+                    }],
+                }
+            );
+        }
+
+        #[test]
+        fn should_return_the_location_of_a_syntax_error() {
+            // Arrange
+            let contents = "{\n  pointclouds: [\n    { url: 'cloud.js' ]\n}";
+
+            // Act
+            let res = PotreeConfig::parse(contents);
+
+            // Assert
+            let PotreeConfigError::Parsing { line, column, .. } = res.unwrap_err();
+            assert_eq!(line, Some(3));
+            assert!(column.is_some());
+        }
+
+        #[test]
+        fn should_return_an_error_if_a_point_cloud_has_no_url() {
+            // Act
+            let res = PotreeConfig::parse("{ pointclouds: [{ name: 'Lion' }] }");
+
+            // Assert
+            let PotreeConfigError::Parsing { message, .. } = res.unwrap_err();
+            assert!(message.contains("url"), "{message}");
+        }
+    }
+
+    mod location {
+        use super::*;
+
+        #[test_case::test_case("survey/pointclouds/cloud.js", PointCloudLocation::Asset(PathBuf::from("pointclouds/cloud.js")); "relative to the project assets")]
+        #[test_case::test_case("/project-assets/survey/pointclouds/cloud.js", PointCloudLocation::Asset(PathBuf::from("pointclouds/cloud.js")); "absolute path")]
+        #[test_case::test_case("survey/point%20clouds/cloud.js?v=1", PointCloudLocation::Asset(PathBuf::from("point clouds/cloud.js")); "percent encoded with a query")]
+        #[test_case::test_case("https://example.com/survey/cloud.js", PointCloudLocation::External; "another origin")]
+        #[test_case::test_case("other/pointclouds/cloud.js", PointCloudLocation::Outside; "another project")]
+        #[test_case::test_case("survey/../other/cloud.js", PointCloudLocation::Outside; "dot segments")]
+        #[test_case::test_case("survey/..%2Fother/cloud.js", PointCloudLocation::Outside; "encoded separator")]
+        #[test_case::test_case("/potree-assets/cloud.js", PointCloudLocation::Outside; "outside the project assets")]
+        #[test_case::test_case("survey/", PointCloudLocation::Outside; "the project directory")]
+        fn should_resolve_what_the_url_refers_to(url: &str, expected: PointCloudLocation) {
+            // Arrange
+            let point_cloud = PointCloudConfig {
+                name: None,
+                url: url.to_owned(),
+            };
+
+            // Act
+            let location = point_cloud.location(&ProjectId::new("survey".to_owned()));
+
+            // Assert
+            assert_eq!(location, expected);
+        }
+    }
+}
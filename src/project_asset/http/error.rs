@@ -18,7 +18,27 @@ impl From<ProjectAssetsServiceError> for ApiError {
                 resource_identifier,
                 resource_type,
             },
+            ProjectAssetsServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
             ProjectAssetsServiceError::NotAuthenticated => Self::NotAuthenticated,
+            ProjectAssetsServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
             ProjectAssetsServiceError::AssetNotFound { path } => Self::ResourceNotFound {
                 resource_name: path.to_string_lossy().to_string(),
             },
@@ -1,20 +1,44 @@
 use axum::extract::Path;
 use http::HeaderMap;
 
+use super::super::application::error::ProjectAssetsServiceError;
 use super::super::application::service::ProjectAssetService;
 use super::router::AssetPathParams;
 use crate::common::domain::StaticAsset;
 use crate::common::utils::http::api_error::ApiError;
+use crate::share_link::http::extractors::ShareLinkExtractor;
 use crate::user::http::extractors::UserExtractor;
 
 /// Serves a static `project` asset.
+///
+/// A share link is used if there is no authenticated user, or the user is not
+/// authorized to read the asset (e.g. a colleague that isn't a member of the
+/// project's groups).
 pub(crate) async fn project_asset(
-    Path(AssetPathParams { project_id, path }): Path<AssetPathParams>,
+    Path(AssetPathParams { path }): Path<AssetPathParams>,
     UserExtractor(user): UserExtractor,
+    ShareLinkExtractor(share_link): ShareLinkExtractor,
     project_assets: ProjectAssetService,
     headers: HeaderMap,
 ) -> Result<StaticAsset, ApiError> {
-    Ok(project_assets
-        .read_asset(&user, &project_id, &path, Some(headers))
-        .await?)
+    let res = project_assets
+        .read_asset(&user, &path, Some(headers.clone()))
+        .await;
+
+    let res = match (res, share_link) {
+        (
+            Err(
+                ProjectAssetsServiceError::NotAuthenticated
+                | ProjectAssetsServiceError::NotAuthorized { .. },
+            ),
+            Some(share_link),
+        ) => {
+            project_assets
+                .read_asset_with_share_link(&share_link, &path, Some(headers))
+                .await
+        }
+        (res, _) => res,
+    };
+
+    Ok(res?)
 }
@@ -9,15 +9,15 @@ use web_route::ParameterizedRoute;
 use super::super::application::service::ProjectAssetService;
 use super::route_handlers;
 use super::state::State;
-use crate::project::domain::ProjectId;
 use crate::project_asset::http::middleware::set_cache_control::set_cache_control;
 
+/// The path of an asset, starting with the id of its project (which can have
+/// multiple segments).
 pub static ASSET_PATH: LazyLock<ParameterizedRoute> =
-    LazyLock::new(|| ParameterizedRoute::new("/{project_id}/{*path}"));
+    LazyLock::new(|| ParameterizedRoute::new("/{*path}"));
 
 #[derive(serde::Deserialize)]
 pub(crate) struct AssetPathParams {
-    pub project_id: ProjectId,
     pub path: PathBuf,
 }
 
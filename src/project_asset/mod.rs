@@ -1,5 +1,5 @@
 pub mod adapters;
 pub mod application;
-mod domain;
+pub mod domain;
 pub mod http;
 mod ports;
@@ -1,9 +1,12 @@
+use time::OffsetDateTime;
+
 use crate::authorization::domain::action::Action;
 use crate::authorization::domain::error::AuthorizationEngineError;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceType;
 use crate::project::application::error::ProjectServiceError;
 use crate::project::domain::ProjectId;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 #[derive(Debug, thiserror::Error)]
@@ -19,9 +22,24 @@ pub enum RenderingServiceError {
         resource_type: ResourceType,
     },
 
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
     #[error("user is not authenticated")]
     NotAuthenticated,
 
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
     #[error("the server is not configured correctly: {message}")]
     ServerConfiguration { message: String },
 
@@ -44,7 +62,27 @@ impl From<ProjectServiceError> for RenderingServiceError {
                 resource_identifier,
                 resource_type,
             },
+            ProjectServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
             ProjectServiceError::NotAuthenticated => Self::NotAuthenticated,
+            ProjectServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
             ProjectServiceError::Infrastucture { message } => Self::Infrastucture { message },
         }
     }
@@ -64,7 +102,27 @@ impl From<AuthorizationEngineError> for RenderingServiceError {
                 resource_identifier,
                 resource_type,
             },
+            AuthorizationEngineError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
             AuthorizationEngineError::NotAuthenticated => Self::NotAuthenticated,
+            AuthorizationEngineError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
         }
     }
 }
@@ -8,11 +8,16 @@ use super::super::domain::authorization::ProjectDashboardResource;
 use super::super::domain::not_found_render::NotFound;
 use super::super::domain::potree_render::PotreeRender;
 use super::super::domain::project_dashboard_render::ProjectDashboard;
+use super::super::domain::validation_report_render::ValidationReportPage;
 use super::error::RenderingServiceError;
 use crate::authorization::domain::action::Action;
+use crate::authorization::domain::error::AuthorizationEngineError;
 use crate::authorization::ports::authorization_engine::AuthorizationEngine;
 use crate::project::application::port::ProjectServicePort;
+use crate::project::domain::Project;
 use crate::project::domain::ProjectId;
+use crate::project_asset::domain::potree_config::POTREE_CONFIG_FILE_NAME;
+use crate::share_link::domain::ShareLink;
 use crate::user::domain::User;
 
 /// A service for rendering a project.
@@ -65,21 +70,70 @@ impl RenderingService {
         self.authorization_engine
             .can_on_instance(user, &Action::Read, &potree_render_resource)?;
 
-        Ok(PotreeRender {
-            project_title: project.name,
-            potree_static_assets_path: self.potree_assets_route.clone(),
-            potree_config_path: self.potree_config_route(project_id)?,
-        })
+        self.potree_render(project)
+    }
+
+    /// Render a `potree` project on behalf of a `share_link` (rather than an
+    /// authenticated user).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the project can not be found or the
+    /// `share_link` does not grant access to it.
+    pub async fn render_potree_with_share_link(
+        &self,
+        share_link: &ShareLink,
+        project_id: &ProjectId,
+    ) -> Result<PotreeRender, RenderingServiceError> {
+        let project = self
+            .project_service
+            .read_with_share_link(share_link, project_id)
+            .await?;
+
+        let potree_render_resource = PotreeRenderResource {
+            associated_project: &project,
+        };
+        self.authorization_engine.can_on_instance_with_share_link(
+            share_link,
+            &Action::Read,
+            &potree_render_resource,
+        )?;
+
+        self.potree_render(project)
     }
 
     /// Provides a dashboard showing all of the `user`'s projects.
+    ///
+    /// Visitors that aren't logged in are shown the public projects, if there
+    /// are any.
     pub async fn project_dashboard(
         &self,
         user: &Option<User>,
         default_project_render_route: &ParameterizedRoute,
     ) -> Result<ProjectDashboard, RenderingServiceError> {
-        self.authorization_engine
-            .can_on_type(user, &Action::Read, &ProjectDashboardResource)?;
+        match self
+            .authorization_engine
+            .can_on_type(user, &Action::Read, &ProjectDashboardResource)
+        {
+            Ok(()) => {}
+            Err(AuthorizationEngineError::NotAuthenticated) if user.is_none() => {
+                let public_projects = self.project_service.list(user).await?;
+
+                // Without any public projects, the visitor needs to log in.
+                if public_projects.is_empty() {
+                    return Err(RenderingServiceError::NotAuthenticated);
+                }
+
+                return Ok(ProjectDashboard {
+                    public_only: true,
+                    ..ProjectDashboard::from_domain_projects(
+                        public_projects,
+                        default_project_render_route,
+                    )?
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
 
         let projects = self.project_service.list(user).await?;
 
@@ -89,11 +143,34 @@ impl RenderingService {
         )?)
     }
 
+    /// Provides a page reporting which projects could not be loaded, and why.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to view it.
+    pub async fn validation_report(
+        &self,
+        user: &Option<User>,
+    ) -> Result<ValidationReportPage, RenderingServiceError> {
+        let validation_report = self.project_service.validation_report(user).await?;
+
+        Ok(validation_report.into())
+    }
+
     /// Provides a 404 page.
     pub async fn not_found(&self) -> Result<NotFound, RenderingServiceError> {
         Ok(NotFound)
     }
 
+    /// Builds the `potree` render of an (already authorized) `project`.
+    fn potree_render(&self, project: Project) -> Result<PotreeRender, RenderingServiceError> {
+        Ok(PotreeRender {
+            potree_config_path: self.potree_config_route(&project.id)?,
+            project_title: project.name,
+            potree_static_assets_path: self.potree_assets_route.clone(),
+        })
+    }
+
     /// Determine the route to the potree config file for a specific project
     /// (`project_id`).
     fn potree_config_route(
@@ -107,8 +184,7 @@ impl RenderingService {
         // Currently the only way to check that this works will be a top level
         // integration/e2e test.
         let params = serde_json::json!({
-            "project_id": project_id,
-            "path": "potree.json5",
+            "path": format!("{project_id}/{POTREE_CONFIG_FILE_NAME}"),
         });
 
         Ok(self.project_assets_route.to_web_route(&params)?)
@@ -148,7 +224,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -179,7 +255,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -196,6 +272,74 @@ mod project_rendering_service_tests {
         }
     }
 
+    mod render_potree_with_share_link {
+
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_share_link_not_authorized() {
+            // Arrange
+            let mut project_service = MockProjectServicePort::new();
+            project_service
+                .expect_read_with_share_link()
+                .return_const(Err(
+                crate::project::application::error::ProjectServiceError::ShareLinkNotAuthorized {
+                    share_link: Faker.fake(),
+                    action: Action::Read,
+                    resource_identifier: Faker.fake(),
+                    resource_type: Faker.fake(),
+                },
+            ));
+            let authorization_engine = MockAuthorizationEngine::new();
+
+            let rendering_service = RenderingService::new(
+                Arc::new(project_service),
+                Arc::new(authorization_engine),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
+                Faker.fake(),
+            );
+
+            // Act
+            let res = rendering_service
+                .render_potree_with_share_link(&Faker.fake(), &Faker.fake())
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(RenderingServiceError::ShareLinkNotAuthorized { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_render_if_share_link_authorized() {
+            // Arrange
+            let mut project_service = MockProjectServicePort::new();
+            project_service
+                .expect_read_with_share_link()
+                .return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_instance_with_share_link()
+                .return_const(Ok(()));
+
+            let rendering_service = RenderingService::new(
+                Arc::new(project_service),
+                Arc::new(authorization_engine),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
+                Faker.fake(),
+            );
+
+            // Act
+            let res = rendering_service
+                .render_potree_with_share_link(&Faker.fake(), &Faker.fake())
+                .await;
+
+            // Assert
+            assert!(res.is_ok());
+        }
+    }
+
     mod project_dashboard {
 
         use super::*;
@@ -203,7 +347,8 @@ mod project_rendering_service_tests {
         #[tokio::test]
         async fn should_return_the_correct_error_if_user_not_authenticated() {
             // Arrange
-            let project_service = MockProjectServicePort::new();
+            let mut project_service = MockProjectServicePort::new();
+            project_service.expect_list().return_const(Ok(vec![]));
             let mut authorization_engine = MockAuthorizationEngine::new();
             authorization_engine
                 .expect_can_on_type()
@@ -212,7 +357,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -221,13 +366,48 @@ mod project_rendering_service_tests {
 
             // Act
             let res = rendering_service
-                .project_dashboard(&Faker.fake(), &default_project_render_route)
+                .project_dashboard(&None, &default_project_render_route)
                 .await;
 
             // Assert
             assert!(matches!(res, Err(RenderingServiceError::NotAuthenticated)));
         }
 
+        #[tokio::test]
+        async fn should_show_the_public_projects_if_user_not_authenticated() {
+            // Arrange
+            let public_projects = fake::vec![crate::project::domain::Project; 1..5];
+
+            let mut project_service = MockProjectServicePort::new();
+            project_service
+                .expect_list()
+                .return_const(Ok(public_projects.clone()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .return_const(Err(AuthorizationEngineError::NotAuthenticated));
+
+            let rendering_service = RenderingService::new(
+                Arc::new(project_service),
+                Arc::new(authorization_engine),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
+                Faker.fake(),
+            );
+
+            let default_project_render_route =
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}");
+
+            // Act
+            let project_dashboard = rendering_service
+                .project_dashboard(&None, &default_project_render_route)
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(project_dashboard.projects.len(), public_projects.len());
+            assert!(project_dashboard.public_only);
+        }
+
         #[tokio::test]
         async fn should_return_the_correct_error_if_user_not_authorized() {
             // Arrange
@@ -245,7 +425,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -279,7 +459,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -314,7 +494,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -349,7 +529,7 @@ mod project_rendering_service_tests {
             let rendering_service = RenderingService::new(
                 Arc::new(project_service),
                 Arc::new(authorization_engine),
-                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{project_id}/{*path}"),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
                 Faker.fake(),
             );
 
@@ -367,4 +547,72 @@ mod project_rendering_service_tests {
             assert_eq!(dashboard.projects.len(), 0);
         }
     }
+
+    mod validation_report {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::project::application::error::ProjectServiceError;
+        use crate::project::domain::diagnostic::ProjectDiagnostic;
+        use crate::project::domain::diagnostic::ProjectDiagnosticKind;
+        use crate::project::domain::diagnostic::ValidationReport;
+
+        #[tokio::test]
+        async fn should_show_where_each_problem_is() {
+            // Arrange
+            let mut project_service = MockProjectServicePort::new();
+            project_service
+                .expect_validation_report()
+                .return_const(Ok(ValidationReport::new(
+                    3,
+                    vec![ProjectDiagnostic {
+                        project_id: Faker.fake(),
+                        path: PathBuf::from("data/survey/manifest.yml"),
+                        kind: ProjectDiagnosticKind::InvalidManifest,
+                        line: Some(3),
+                        column: Some(5),
+                        message: "groups: invalid type".to_owned(),
+                    }],
+                )));
+
+            let rendering_service = RenderingService::new(
+                Arc::new(project_service),
+                Arc::new(MockAuthorizationEngine::new()),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
+                Faker.fake(),
+            );
+
+            // Act
+            let page = rendering_service
+                .validation_report(&Faker.fake())
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(page.loaded_projects, 3);
+            assert_eq!(page.diagnostics[0].location, "data/survey/manifest.yml:3:5");
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authenticated() {
+            // Arrange
+            let mut project_service = MockProjectServicePort::new();
+            project_service
+                .expect_validation_report()
+                .return_const(Err(ProjectServiceError::NotAuthenticated));
+
+            let rendering_service = RenderingService::new(
+                Arc::new(project_service),
+                Arc::new(MockAuthorizationEngine::new()),
+                ParameterizedRoute::new(Faker.fake::<WebRoute>()).join("/{*path}"),
+                Faker.fake(),
+            );
+
+            // Act
+            let res = rendering_service.validation_report(&None).await;
+
+            // Assert
+            assert!(matches!(res, Err(RenderingServiceError::NotAuthenticated)));
+        }
+    }
 }
@@ -0,0 +1,19 @@
+use askama::Template;
+use time::OffsetDateTime;
+
+/// Displays a page letting the user know that their access to a project has
+/// expired (rather than a generic 404).
+#[derive(Debug, Template)]
+#[template(path = "error/access_expired.html")]
+pub struct AccessExpired {
+    /// The date on which access expired.
+    pub expired_on: String,
+}
+
+impl AccessExpired {
+    pub fn new(expired_at: OffsetDateTime) -> Self {
+        Self {
+            expired_on: expired_at.date().to_string(),
+        }
+    }
+}
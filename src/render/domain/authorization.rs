@@ -1,10 +1,15 @@
+use std::path::PathBuf;
+
 use crate::authorization::domain::resource::Resource;
 use crate::authorization::domain::resource::ResourceIdentifier;
 use crate::authorization::domain::resource::ResourceInstance;
 use crate::authorization::domain::resource::ResourceType;
+use crate::common::domain::AccessWindow;
 use crate::common::domain::Group;
 use crate::common::domain::resource_type;
 use crate::project::domain::Project;
+use crate::project::domain::ProjectId;
+use crate::project::domain::path_rule::PathRule;
 use crate::user::domain::EmailAddress;
 
 /// A struct that is used to provide the required authZ data to the
@@ -30,8 +35,35 @@ impl ResourceInstance for PotreeRenderResource<'_> {
     }
 
     fn user_emails(&self) -> Option<Vec<EmailAddress>> {
+        Some(self.associated_project.users.clone())
+    }
+
+    fn project_id(&self) -> Option<ProjectId> {
+        Some(self.associated_project.id.clone())
+    }
+
+    fn asset_path(&self) -> Option<PathBuf> {
         None
     }
+
+    fn is_public(&self) -> bool {
+        self.associated_project.is_public()
+    }
+
+    fn access_window(&self) -> Option<AccessWindow> {
+        Some(self.associated_project.access_window)
+    }
+
+    fn group_access_window(&self, group: &Group) -> Option<AccessWindow> {
+        self.associated_project
+            .group_access_windows
+            .get(group)
+            .copied()
+    }
+
+    fn path_rules(&self) -> Option<Vec<PathRule>> {
+        Some(self.associated_project.path_rules.clone())
+    }
 }
 
 /// A struct that is used to provide the required authZ data to the
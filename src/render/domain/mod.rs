@@ -1,5 +1,7 @@
+pub mod access_expired_render;
 pub mod authorization;
 pub mod error;
 pub mod not_found_render;
 pub mod potree_render;
 pub mod project_dashboard_render;
+pub mod validation_report_render;
@@ -14,6 +14,13 @@ use crate::render::domain::error::RenderDomainError;
 pub struct ProjectDashboard {
     /// The projects that the user is allowed to read.
     pub projects: Vec<Project>,
+
+    /// Whether only the public projects are shown, as the visitor isn't logged
+    /// in.
+    pub public_only: bool,
+
+    /// Where a visitor that is only shown the public projects can log in.
+    pub login_url: Option<String>,
 }
 
 impl ProjectDashboard {
@@ -32,7 +39,11 @@ impl ProjectDashboard {
             .map(|p| Project::from_domain_project(p, default_render_route))
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Self { projects })
+        Ok(Self {
+            projects,
+            public_only: false,
+            login_url: None,
+        })
     }
 }
 
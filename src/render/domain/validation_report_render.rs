@@ -0,0 +1,47 @@
+use askama::Template;
+
+use crate::project::domain::ProjectId;
+use crate::project::domain::diagnostic::ValidationReport;
+
+/// Represents the Validation Report page.
+///
+/// Lets admins see which projects could not be loaded, and why (e.g. a typo in
+/// a manifest).
+#[derive(Debug, Template)]
+#[template(path = "validation_report/index.html")]
+pub struct ValidationReportPage {
+    /// The number of projects that could be loaded.
+    pub loaded_projects: usize,
+
+    /// The projects that could not be loaded.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The project that could not be loaded.
+    pub project_id: ProjectId,
+
+    /// Where the problem is (e.g. `data/survey/manifest.yml:3:5`).
+    pub location: String,
+
+    /// What the problem is.
+    pub message: String,
+}
+
+impl From<ValidationReport> for ValidationReportPage {
+    fn from(value: ValidationReport) -> Self {
+        Self {
+            loaded_projects: value.loaded_projects,
+            diagnostics: value
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| Diagnostic {
+                    location: diagnostic.location(),
+                    project_id: diagnostic.project_id,
+                    message: diagnostic.message,
+                })
+                .collect(),
+        }
+    }
+}
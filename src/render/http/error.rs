@@ -18,7 +18,27 @@ impl From<RenderingServiceError> for RenderError {
                 resource_identifier,
                 resource_type,
             },
+            RenderingServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
             RenderingServiceError::NotAuthenticated => Self::NotAuthenticated,
+            RenderingServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
             RenderingServiceError::ServerConfiguration { message } => {
                 Self::ServerConfiguration { message }
             }
@@ -6,5 +6,7 @@ mod router;
 mod state;
 mod utils;
 
+pub use router::POTREE;
 pub use router::PROJECT_DASHBOARD;
+pub use router::VALIDATION_REPORT;
 pub use router::build_router;
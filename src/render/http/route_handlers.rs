@@ -4,28 +4,52 @@ use axum::extract::Path;
 use axum::response::Html;
 use axum::response::IntoResponse;
 use axum::response::Response;
+use http::StatusCode;
 
 use super::super::application::service::RenderingService;
 use super::extractors::LoginRoute;
 use super::router::PotreePathParams;
 use crate::common::utils::http::render_error::RenderError;
 use crate::render::application::error::RenderingServiceError;
+use crate::render::domain::access_expired_render::AccessExpired;
 use crate::render::http::router::POTREE;
+use crate::render::http::utils::login_url;
 use crate::render::http::utils::redirect_to_404;
 use crate::render::http::utils::redirect_to_login;
+use crate::share_link::http::extractors::ShareLinkExtractor;
 use crate::user::http::extractors::UserExtractor;
 
 /// Renders a `potree` project.
+///
+/// A share link is used if there is no authenticated user, or the user is not
+/// authorized to render the project (e.g. a colleague that isn't a member of
+/// the project's groups).
 #[tracing::instrument(name = "`rendering route handlers`: rendering potree project", err)]
 pub async fn potree_render(
     Path(PotreePathParams { project_id }): Path<PotreePathParams>,
     UserExtractor(user): UserExtractor,
+    ShareLinkExtractor(share_link): ShareLinkExtractor,
     rendering_service: RenderingService,
     LoginRoute(login_route): LoginRoute,
     OriginalUri(page_uri): OriginalUri,
 ) -> Result<Response, RenderError> {
     let res = rendering_service.render_potree(&user, &project_id).await;
 
+    let res = match (res, &share_link) {
+        (
+            Err(
+                RenderingServiceError::NotAuthenticated
+                | RenderingServiceError::NotAuthorized { .. },
+            ),
+            Some(share_link),
+        ) => {
+            rendering_service
+                .render_potree_with_share_link(share_link, &project_id)
+                .await
+        }
+        (res, _) => res,
+    };
+
     // Redirect the user agent to the login route if they are not authenticated.
     if let Err(RenderingServiceError::NotAuthenticated) = res {
         tracing::warn!("user not authenticated, redirecting to login");
@@ -38,12 +62,26 @@ pub async fn potree_render(
         return Ok(redirect_to_404().into_response());
     }
 
+    // Let the user know if their access to the project has expired.
+    if let Err(RenderingServiceError::AccessExpired { expired_at, .. }) = res {
+        tracing::warn!(user = ?user, project_id = ?project_id, %expired_at, "access to project expired");
+        let access_expired = AccessExpired::new(expired_at);
+        return Ok((StatusCode::FORBIDDEN, Html(access_expired.render()?)).into_response());
+    }
+
     // Redirect user to 404 page if the user is not authorized to view the project.
     if let Err(RenderingServiceError::NotAuthorized { .. }) = res {
         tracing::error!(user = ?user, project_id = ?project_id, "user not authorized to render project");
         return Ok(redirect_to_404().into_response());
     }
 
+    // Redirect user to 404 page if the share link does not grant access to the
+    // project.
+    if let Err(RenderingServiceError::ShareLinkNotAuthorized { .. }) = res {
+        tracing::error!(share_link = ?share_link, project_id = ?project_id, "share link not authorized to render project");
+        return Ok(redirect_to_404().into_response());
+    }
+
     let potree_template = res?;
 
     Ok(Html(potree_template.render()?).into_response())
@@ -72,11 +110,45 @@ pub async fn project_dashboard(
         return Ok(redirect_to_404().into_response());
     }
 
-    let project_dashboard = res?;
+    let mut project_dashboard = res?;
+
+    // Visitors that are only shown the public projects can log in to see the
+    // others.
+    if project_dashboard.public_only {
+        project_dashboard.login_url = Some(login_url(&login_route, page_uri.path()));
+    }
 
     Ok(Html(project_dashboard.render()?).into_response())
 }
 
+/// Displays which projects could not be loaded, and why.
+#[tracing::instrument(name = "`rendering route handlers`: rendering validation report", err)]
+pub async fn validation_report(
+    UserExtractor(user): UserExtractor,
+    rendering_service: RenderingService,
+    LoginRoute(login_route): LoginRoute,
+    OriginalUri(page_uri): OriginalUri,
+) -> Result<Response, RenderError> {
+    let res = rendering_service.validation_report(&user).await;
+
+    // Redirect the user agent to the login route if they are not authenticated.
+    if let Err(RenderingServiceError::NotAuthenticated) = res {
+        tracing::warn!("user not authenticated, redirecting to login");
+        return Ok(redirect_to_login(&login_route, page_uri.path()).into_response());
+    }
+
+    // Redirect user to 404 page if the user is not authorized to view the
+    // validation report.
+    if let Err(RenderingServiceError::NotAuthorized { .. }) = res {
+        tracing::error!(user = ?user, "user not authorized to view validation report");
+        return Ok(redirect_to_404().into_response());
+    }
+
+    let validation_report = res?;
+
+    Ok(Html(validation_report.render()?).into_response())
+}
+
 /// Display a 404 page.
 #[tracing::instrument(name = "`rendering route handlers`: rendering 404", err)]
 pub async fn not_found(rendering_service: RenderingService) -> Result<Response, RenderError> {
@@ -13,10 +13,14 @@ use crate::common::utils::http::initialization_error::InitializationError;
 use crate::project::domain::ProjectId;
 use crate::render::http::middleware::potree_csp::set_potree_csp;
 
+/// The id of projects nested in collections has multiple segments, hence the
+/// wildcard.
 pub static POTREE: LazyLock<ParameterizedRoute> =
-    LazyLock::new(|| ParameterizedRoute::new("/potree/{project_id}"));
+    LazyLock::new(|| ParameterizedRoute::new("/potree/{*project_id}"));
 pub static PROJECT_DASHBOARD: LazyLock<ParameterizedRoute> =
     LazyLock::new(|| ParameterizedRoute::new("/projects"));
+pub static VALIDATION_REPORT: LazyLock<ParameterizedRoute> =
+    LazyLock::new(|| ParameterizedRoute::new("/validation-report"));
 pub static NOT_FOUND: LazyLock<ParameterizedRoute> =
     LazyLock::new(|| ParameterizedRoute::new("/404"));
 
@@ -44,6 +48,7 @@ pub fn build_router(
             get(route_handlers::potree_render).layer(set_potree_csp()?),
         )
         .route(&PROJECT_DASHBOARD, get(route_handlers::project_dashboard))
+        .route(&VALIDATION_REPORT, get(route_handlers::validation_report))
         .route(&NOT_FOUND, get(route_handlers::not_found))
         .fallback(get(route_handlers::not_found))
         .layer(Extension(state));
@@ -3,7 +3,13 @@ use axum::response::Redirect;
 use crate::render::http::router::NOT_FOUND;
 
 pub fn redirect_to_login(login_route: &str, page_path: &str) -> Redirect {
-    Redirect::to(&format!("{}?next_path={}", login_route, page_path))
+    Redirect::to(&login_url(login_route, page_path))
+}
+
+/// The url of the login route, after which the user agent is returned to
+/// `page_path`.
+pub fn login_url(login_route: &str, page_path: &str) -> String {
+    format!("{}?next_path={}", login_route, page_path)
 }
 
 pub fn redirect_to_404() -> Redirect {
@@ -0,0 +1,272 @@
+//! Signs share links with an HMAC (SHA-256) over a JSON payload. It implements
+//! the [`ShareLinkSigner`] trait.
+//!
+//! A token has the form `<payload>.<signature>`, where both parts are base64
+//! (URL safe, no padding) encoded.
+
+use std::path::PathBuf;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::Hmac;
+use hmac::Mac;
+use sha2::Sha256;
+use time::OffsetDateTime;
+
+use super::super::super::domain::ShareLink;
+use super::super::super::domain::ShareToken;
+use super::super::super::ports::share_link_signer::ShareLinkSigner;
+use super::super::super::ports::share_link_signer::ShareLinkSignerError;
+use crate::project::domain::ProjectId;
+
+/// The minimum length (in bytes) of the signing key, so that signatures can't
+/// feasibly be brute forced.
+const MIN_SIGNING_KEY_LENGTH: usize = 32;
+
+/// Separates the payload from the signature in a token.
+const TOKEN_SEPARATOR: char = '.';
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone)]
+pub struct HmacShareLinkSigner {
+    /// The secret key used to sign and verify tokens.
+    signing_key: Vec<u8>,
+}
+
+/// The signed contents of a token.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct SharePayload {
+    project_id: ProjectId,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path_prefix: Option<PathBuf>,
+
+    /// Unix timestamp (seconds).
+    expires_at: i64,
+}
+
+impl HmacShareLinkSigner {
+    pub fn new(signing_key: &[u8]) -> Result<Self, ShareLinkSignerError> {
+        if signing_key.len() < MIN_SIGNING_KEY_LENGTH {
+            return Err(ShareLinkSignerError::InvalidKey {
+                message: format!("must be at least {MIN_SIGNING_KEY_LENGTH} bytes long"),
+            });
+        }
+
+        Ok(Self {
+            signing_key: signing_key.to_vec(),
+        })
+    }
+
+    fn sign(&self, share_link: &ShareLink) -> Result<ShareToken, ShareLinkSignerError> {
+        let payload = SharePayload {
+            project_id: share_link.project_id.clone(),
+            path_prefix: share_link.path_prefix.clone(),
+            expires_at: share_link.expires_at.unix_timestamp(),
+        };
+
+        let payload =
+            serde_json::to_vec(&payload).map_err(|e| ShareLinkSignerError::Infrastucture {
+                message: format!("unable to serialize share link: {e}"),
+            })?;
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&payload).finalize().into_bytes());
+
+        Ok(ShareToken::new(format!(
+            "{payload}{TOKEN_SEPARATOR}{signature}"
+        )))
+    }
+
+    fn verify(&self, token: &ShareToken) -> Result<ShareLink, ShareLinkSignerError> {
+        let (payload, signature) =
+            token
+                .split_once(TOKEN_SEPARATOR)
+                .ok_or(ShareLinkSignerError::InvalidToken {
+                    message: "malformed token".to_owned(),
+                })?;
+
+        let signature =
+            URL_SAFE_NO_PAD
+                .decode(signature)
+                .map_err(|e| ShareLinkSignerError::InvalidToken {
+                    message: format!("unable to decode signature: {e}"),
+                })?;
+
+        // Verified in constant time.
+        self.mac(payload).verify_slice(&signature).map_err(|_| {
+            ShareLinkSignerError::InvalidToken {
+                message: "signature mismatch".to_owned(),
+            }
+        })?;
+
+        let payload =
+            URL_SAFE_NO_PAD
+                .decode(payload)
+                .map_err(|e| ShareLinkSignerError::InvalidToken {
+                    message: format!("unable to decode payload: {e}"),
+                })?;
+        let payload = serde_json::from_slice::<SharePayload>(&payload).map_err(|e| {
+            ShareLinkSignerError::InvalidToken {
+                message: format!("unable to parse payload: {e}"),
+            }
+        })?;
+
+        let expires_at = OffsetDateTime::from_unix_timestamp(payload.expires_at).map_err(|e| {
+            ShareLinkSignerError::InvalidToken {
+                message: format!("invalid expiry: {e}"),
+            }
+        })?;
+
+        Ok(ShareLink {
+            project_id: payload.project_id,
+            path_prefix: payload.path_prefix,
+            expires_at,
+        })
+    }
+
+    /// Initializes a MAC over the (encoded) `payload`.
+    fn mac(&self, payload: &str) -> HmacSha256 {
+        // HMAC accepts keys of any length.
+        let mut mac =
+            HmacSha256::new_from_slice(&self.signing_key).expect("HMAC can take a key of any size");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+impl std::fmt::Debug for HmacShareLinkSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the signing key into logs.
+        f.debug_struct("HmacShareLinkSigner")
+            .field("signing_key", &"[redacted]")
+            .finish()
+    }
+}
+
+impl ShareLinkSigner for HmacShareLinkSigner {
+    fn sign(&self, share_link: &ShareLink) -> Result<ShareToken, ShareLinkSignerError> {
+        Self::sign(self, share_link)
+    }
+
+    fn verify(&self, token: &ShareToken) -> Result<ShareLink, ShareLinkSignerError> {
+        Self::verify(self, token)
+    }
+}
+
+#[cfg(test)]
+mod hmac_share_link_signer_tests {
+    use time::Duration;
+
+    use super::*;
+
+    const SIGNING_KEY: &[u8] = b"a-very-secret-key-used-for-testing-purposes";
+
+    fn dummy_share_link() -> ShareLink {
+        ShareLink {
+            project_id: ProjectId::new("project_1".to_owned()),
+            path_prefix: Some(PathBuf::from("pointclouds")),
+            // Truncated to whole seconds, as that is the resolution of a token.
+            expires_at: OffsetDateTime::from_unix_timestamp(
+                (OffsetDateTime::now_utc() + Duration::hours(1)).unix_timestamp(),
+            )
+            .unwrap(),
+        }
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn should_return_err_if_key_too_short() {
+            // Act
+            let res = HmacShareLinkSigner::new(b"too-short");
+
+            // Assert
+            assert!(matches!(res, Err(ShareLinkSignerError::InvalidKey { .. })));
+        }
+    }
+
+    mod verify {
+        use super::*;
+
+        #[test]
+        fn should_return_the_signed_share_link() {
+            // Arrange
+            let signer = HmacShareLinkSigner::new(SIGNING_KEY).unwrap();
+            let share_link = dummy_share_link();
+            let token = signer.sign(&share_link).unwrap();
+
+            // Act
+            let verified = signer.verify(&token).unwrap();
+
+            // Assert
+            assert_eq!(verified, share_link);
+        }
+
+        #[test]
+        fn should_return_err_if_signed_with_another_key() {
+            // Arrange
+            let token = HmacShareLinkSigner::new(b"another-very-secret-key-used-for-testing")
+                .unwrap()
+                .sign(&dummy_share_link())
+                .unwrap();
+
+            // Act
+            let res = HmacShareLinkSigner::new(SIGNING_KEY)
+                .unwrap()
+                .verify(&token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkSignerError::InvalidToken { .. })
+            ));
+        }
+
+        #[test]
+        fn should_return_err_if_payload_tampered_with() {
+            // Arrange
+            let signer = HmacShareLinkSigner::new(SIGNING_KEY).unwrap();
+            let token = signer.sign(&dummy_share_link()).unwrap();
+            let (_, signature) = token.split_once(TOKEN_SEPARATOR).unwrap();
+
+            let tampered_payload = URL_SAFE_NO_PAD.encode(
+                serde_json::json!({
+                    "project_id": "project_2",
+                    "expires_at": i64::MAX,
+                })
+                .to_string(),
+            );
+            let tampered_token =
+                ShareToken::new(format!("{tampered_payload}{TOKEN_SEPARATOR}{signature}"));
+
+            // Act
+            let res = signer.verify(&tampered_token);
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkSignerError::InvalidToken { .. })
+            ));
+        }
+
+        #[test_case::test_case(""; "empty")]
+        #[test_case::test_case("no-separator"; "no separator")]
+        #[test_case::test_case("payload.not-base64!"; "invalid signature encoding")]
+        fn should_return_err_if_malformed(token: &str) {
+            // Arrange
+            let signer = HmacShareLinkSigner::new(SIGNING_KEY).unwrap();
+
+            // Act
+            let res = signer.verify(&ShareToken::new(token.to_owned()));
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkSignerError::InvalidToken { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod hmac;
+pub mod no_op;
@@ -0,0 +1,32 @@
+//! A share link signer that is used when no signing key is configured. It
+//! implements the [`ShareLinkSigner`] trait.
+//!
+//! All share links are rejected, as they can't be verified.
+
+use super::super::super::domain::ShareLink;
+use super::super::super::domain::ShareToken;
+use super::super::super::ports::share_link_signer::ShareLinkSigner;
+use super::super::super::ports::share_link_signer::ShareLinkSignerError;
+
+#[derive(Debug, Clone)]
+pub struct NoOpShareLinkSigner;
+
+impl NoOpShareLinkSigner {
+    fn sign(&self, _share_link: &ShareLink) -> Result<ShareToken, ShareLinkSignerError> {
+        Err(ShareLinkSignerError::NotConfigured)
+    }
+
+    fn verify(&self, _token: &ShareToken) -> Result<ShareLink, ShareLinkSignerError> {
+        Err(ShareLinkSignerError::NotConfigured)
+    }
+}
+
+impl ShareLinkSigner for NoOpShareLinkSigner {
+    fn sign(&self, share_link: &ShareLink) -> Result<ShareToken, ShareLinkSignerError> {
+        Self::sign(self, share_link)
+    }
+
+    fn verify(&self, token: &ShareToken) -> Result<ShareLink, ShareLinkSignerError> {
+        Self::verify(self, token)
+    }
+}
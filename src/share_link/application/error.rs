@@ -0,0 +1,152 @@
+use time::OffsetDateTime;
+
+use super::super::ports::share_link_signer::ShareLinkSignerError;
+use crate::authorization::domain::action::Action;
+use crate::authorization::domain::error::AuthorizationEngineError;
+use crate::authorization::domain::resource::ResourceIdentifier;
+use crate::authorization::domain::resource::ResourceType;
+use crate::project::application::error::ProjectServiceError;
+use crate::project::domain::ProjectId;
+use crate::share_link::domain::ShareLink;
+use crate::user::domain::User;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ShareLinkServiceError {
+    #[error("project ({id}) not found")]
+    ProjectNotFound { id: ProjectId },
+
+    #[error("{} is not authorized to {} the {:?}: {:?}", user.name, action, resource_type, resource_identifier)]
+    NotAuthorized {
+        user: Box<User>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
+    #[error("share link for {} is not authorized to {} the {:?}: {:?}", share_link.project_id, action, resource_type, resource_identifier)]
+    ShareLinkNotAuthorized {
+        share_link: Box<ShareLink>,
+        action: Action,
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+    },
+
+    #[error("user is not authenticated")]
+    NotAuthenticated,
+
+    #[error("access to the {resource_type:?} expired at {expired_at}: {resource_identifier:?}")]
+    AccessExpired {
+        resource_identifier: Option<ResourceIdentifier>,
+        resource_type: ResourceType,
+        expired_at: OffsetDateTime,
+    },
+
+    #[error("the share link request is invalid: {message}")]
+    InvalidRequest { message: String },
+
+    #[error("the share link is invalid: {message}")]
+    InvalidShareLink { message: String },
+
+    #[error("the share link expired")]
+    Expired,
+
+    #[error("the server is not configured correctly: {message}")]
+    ServerConfiguration { message: String },
+
+    #[error("{message}")]
+    Infrastucture { message: String },
+}
+
+impl From<ProjectServiceError> for ShareLinkServiceError {
+    fn from(value: ProjectServiceError) -> Self {
+        match value {
+            ProjectServiceError::ProjectNotFound { id } => Self::ProjectNotFound { id },
+            ProjectServiceError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ProjectServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ProjectServiceError::NotAuthenticated => Self::NotAuthenticated,
+            ProjectServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+            ProjectServiceError::Infrastucture { message } => Self::Infrastucture { message },
+        }
+    }
+}
+
+impl From<AuthorizationEngineError> for ShareLinkServiceError {
+    fn from(value: AuthorizationEngineError) -> Self {
+        match value {
+            AuthorizationEngineError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AuthorizationEngineError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            AuthorizationEngineError::NotAuthenticated => Self::NotAuthenticated,
+            AuthorizationEngineError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+        }
+    }
+}
+
+impl From<ShareLinkSignerError> for ShareLinkServiceError {
+    fn from(value: ShareLinkSignerError) -> Self {
+        match value {
+            ShareLinkSignerError::NotConfigured | ShareLinkSignerError::InvalidKey { .. } => {
+                Self::ServerConfiguration {
+                    message: value.to_string(),
+                }
+            }
+            ShareLinkSignerError::InvalidToken { message } => Self::InvalidShareLink { message },
+            ShareLinkSignerError::Infrastucture { message } => Self::Infrastucture { message },
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::path::Component;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use time::Duration;
+use time::OffsetDateTime;
+
+use super::super::domain::ShareLink;
+use super::super::domain::ShareToken;
+use super::super::domain::authorization::ShareLinkTypeResource;
+use super::super::ports::share_link_signer::ShareLinkSigner;
+use super::error::ShareLinkServiceError;
+use crate::authorization::domain::action::Action;
+use crate::authorization::ports::authorization_engine::AuthorizationEngine;
+use crate::project::application::port::ProjectServicePort;
+use crate::project::domain::ProjectId;
+use crate::user::domain::User;
+
+/// The longest that share links can be valid for, unless configured otherwise.
+pub const DEFAULT_MAX_VALIDITY: Duration = Duration::days(30);
+
+/// A service for creating and verifying share links.
+#[derive(Debug, Clone)]
+pub struct ShareLinkService {
+    /// Used to sign and verify share tokens.
+    share_link_signer: Arc<dyn ShareLinkSigner>,
+
+    /// Used to ensure that a shared project exists.
+    project_service: Arc<dyn ProjectServicePort>,
+
+    /// Used to determine if a user is authorized to create share links.
+    authorization_engine: Arc<dyn AuthorizationEngine>,
+
+    /// The longest that share links can be valid for.
+    max_validity: Duration,
+}
+
+impl ShareLinkService {
+    pub fn new(
+        share_link_signer: Arc<dyn ShareLinkSigner>,
+        project_service: Arc<dyn ProjectServicePort>,
+        authorization_engine: Arc<dyn AuthorizationEngine>,
+    ) -> Self {
+        Self {
+            share_link_signer,
+            project_service,
+            authorization_engine,
+            max_validity: DEFAULT_MAX_VALIDITY,
+        }
+    }
+
+    /// Limits how long share links can be valid for.
+    pub fn with_max_validity(mut self, max_validity: Duration) -> Self {
+        self.max_validity = max_validity;
+        self
+    }
+
+    /// Create a share link to a project (`project_id`) that is valid for
+    /// `valid_for` (at most the configured maximum validity). If a
+    /// `path_prefix` is provided, only the project assets within it can be
+    /// read with the share link.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `user` is not authorized to create share
+    /// links or to read the project, or if `valid_for` exceeds the maximum
+    /// validity.
+    pub async fn create(
+        &self,
+        user: &Option<User>,
+        project_id: &ProjectId,
+        path_prefix: Option<PathBuf>,
+        valid_for: Duration,
+    ) -> Result<(ShareLink, ShareToken), ShareLinkServiceError> {
+        self.authorization_engine
+            .can_on_type(user, &Action::Create, &ShareLinkTypeResource)?;
+
+        if !valid_for.is_positive() {
+            return Err(ShareLinkServiceError::InvalidRequest {
+                message: "the validity period must be positive".to_owned(),
+            });
+        }
+        if valid_for > self.max_validity {
+            return Err(ShareLinkServiceError::InvalidRequest {
+                message: format!(
+                    "the validity period must not exceed {} minutes",
+                    self.max_validity.whole_minutes()
+                ),
+            });
+        }
+        let expires_at = OffsetDateTime::now_utc().checked_add(valid_for).ok_or(
+            ShareLinkServiceError::InvalidRequest {
+                message: "the validity period is out of range".to_owned(),
+            },
+        )?;
+
+        // Only allow relative paths that stay within the project directory.
+        if path_prefix.as_ref().is_some_and(|path_prefix| {
+            !path_prefix
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        }) {
+            return Err(ShareLinkServiceError::InvalidRequest {
+                message: "the path prefix must be relative to the project directory".to_owned(),
+            });
+        }
+
+        // Ensures that the project exists and that the user is allowed to read it.
+        let project = self.project_service.read(user, project_id).await?;
+
+        let share_link = ShareLink {
+            project_id: project.id,
+            path_prefix,
+            expires_at,
+        };
+        let share_token = self.share_link_signer.sign(&share_link)?;
+
+        Ok((share_link, share_token))
+    }
+
+    /// Verifies a `share_token`, returning the [`ShareLink`] it represents.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the token has been tampered with or the share
+    /// link has expired.
+    pub fn verify(&self, share_token: &ShareToken) -> Result<ShareLink, ShareLinkServiceError> {
+        let share_link = self.share_link_signer.verify(share_token)?;
+
+        if share_link.is_expired(OffsetDateTime::now_utc()) {
+            return Err(ShareLinkServiceError::Expired);
+        }
+
+        Ok(share_link)
+    }
+}
+
+#[cfg(test)]
+mod share_link_service_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::super::super::ports::share_link_signer::MockShareLinkSigner;
+    use super::super::super::ports::share_link_signer::ShareLinkSignerError;
+    use super::*;
+    use crate::authorization::domain::error::AuthorizationEngineError;
+    use crate::authorization::ports::authorization_engine::MockAuthorizationEngine;
+    use crate::project::application::port::MockProjectServicePort;
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn should_return_the_signed_share_link() {
+            // Arrange
+            let mut share_link_signer = MockShareLinkSigner::new();
+            share_link_signer
+                .expect_sign()
+                .return_const(Ok(ShareToken::new("token".to_owned())));
+            let mut project_service = MockProjectServicePort::new();
+            project_service.expect_read().return_const(Ok(Faker.fake()));
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .return_const(Ok(()));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(share_link_signer),
+                Arc::new(project_service),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let (share_link, share_token) = share_link_service
+                .create(
+                    &Faker.fake(),
+                    &Faker.fake(),
+                    Some(PathBuf::from("pointclouds")),
+                    Duration::days(7),
+                )
+                .await
+                .unwrap();
+
+            // Assert
+            assert_eq!(share_token.as_str(), "token");
+            assert_eq!(share_link.path_prefix, Some(PathBuf::from("pointclouds")));
+            assert!(!share_link.is_expired(OffsetDateTime::now_utc() + Duration::days(6)));
+            assert!(share_link.is_expired(OffsetDateTime::now_utc() + Duration::days(8)));
+        }
+
+        #[tokio::test]
+        async fn should_return_the_correct_error_if_user_not_authorized() {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine.expect_can_on_type().return_const(Err(
+                AuthorizationEngineError::NotAuthorized {
+                    user: Faker.fake(),
+                    action: Action::Create,
+                    resource_identifier: None,
+                    resource_type: Faker.fake(),
+                },
+            ));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(MockShareLinkSigner::new()),
+                Arc::new(MockProjectServicePort::new()),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = share_link_service
+                .create(&Faker.fake(), &Faker.fake(), None, Duration::days(7))
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkServiceError::NotAuthorized { .. })
+            ));
+        }
+
+        #[test_case::test_case(None, Duration::ZERO; "zero validity")]
+        #[test_case::test_case(None, DEFAULT_MAX_VALIDITY + Duration::minutes(1); "validity exceeding the maximum")]
+        #[test_case::test_case(None, Duration::minutes(u32::MAX.into()); "validity out of range")]
+        #[test_case::test_case(Some("../project_2"), Duration::days(1); "parent directory path prefix")]
+        #[test_case::test_case(Some("/etc"), Duration::days(1); "absolute path prefix")]
+        #[tokio::test]
+        async fn should_return_err_if_request_invalid(
+            path_prefix: Option<&str>,
+            valid_for: Duration,
+        ) {
+            // Arrange
+            let mut authorization_engine = MockAuthorizationEngine::new();
+            authorization_engine
+                .expect_can_on_type()
+                .return_const(Ok(()));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(MockShareLinkSigner::new()),
+                Arc::new(MockProjectServicePort::new()),
+                Arc::new(authorization_engine),
+            );
+
+            // Act
+            let res = share_link_service
+                .create(
+                    &Faker.fake(),
+                    &Faker.fake(),
+                    path_prefix.map(PathBuf::from),
+                    valid_for,
+                )
+                .await;
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkServiceError::InvalidRequest { .. })
+            ));
+        }
+    }
+
+    mod verify {
+        use super::*;
+
+        #[test]
+        fn should_return_the_share_link_if_valid() {
+            // Arrange
+            let share_link = Faker.fake::<ShareLink>();
+            let mut share_link_signer = MockShareLinkSigner::new();
+            share_link_signer
+                .expect_verify()
+                .return_const(Ok(share_link.clone()));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(share_link_signer),
+                Arc::new(MockProjectServicePort::new()),
+                Arc::new(MockAuthorizationEngine::new()),
+            );
+
+            // Act
+            let res = share_link_service.verify(&Faker.fake());
+
+            // Assert
+            assert_eq!(res.unwrap(), share_link);
+        }
+
+        #[test]
+        fn should_return_the_correct_error_if_expired() {
+            // Arrange
+            let mut share_link_signer = MockShareLinkSigner::new();
+            share_link_signer
+                .expect_verify()
+                .return_const(Ok(ShareLink {
+                    expires_at: OffsetDateTime::now_utc() - Duration::minutes(1),
+                    ..Faker.fake()
+                }));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(share_link_signer),
+                Arc::new(MockProjectServicePort::new()),
+                Arc::new(MockAuthorizationEngine::new()),
+            );
+
+            // Act
+            let res = share_link_service.verify(&Faker.fake());
+
+            // Assert
+            assert!(matches!(res, Err(ShareLinkServiceError::Expired)));
+        }
+
+        #[test]
+        fn should_return_the_correct_error_if_tampered_with() {
+            // Arrange
+            let mut share_link_signer = MockShareLinkSigner::new();
+            share_link_signer.expect_verify().return_const(Err(
+                ShareLinkSignerError::InvalidToken {
+                    message: "signature mismatch".to_owned(),
+                },
+            ));
+
+            let share_link_service = ShareLinkService::new(
+                Arc::new(share_link_signer),
+                Arc::new(MockProjectServicePort::new()),
+                Arc::new(MockAuthorizationEngine::new()),
+            );
+
+            // Act
+            let res = share_link_service.verify(&Faker.fake());
+
+            // Assert
+            assert!(matches!(
+                res,
+                Err(ShareLinkServiceError::InvalidShareLink { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! AuthZ trait implementations for share links, and the decisions made for
+//! them by every authorization engine.
+
+use time::OffsetDateTime;
+
+use super::ShareLink;
+use crate::authorization::domain::action::Action;
+use crate::authorization::domain::error::AuthorizationEngineError;
+use crate::authorization::domain::resource::Resource;
+use crate::authorization::domain::resource::ResourceInstance;
+use crate::authorization::domain::resource::ResourceType;
+use crate::common::domain::resource_type;
+
+/// Represents a share link type for type-level (rather than instance-level)
+/// authZ. E.g. whether a user is allowed to create share links.
+#[derive(Debug)]
+pub struct ShareLinkTypeResource;
+
+impl Resource for ShareLinkTypeResource {
+    fn resource_type(&self) -> ResourceType {
+        ResourceType::new(resource_type::SHARE_LINK.to_owned())
+    }
+}
+
+/// Decides whether the `share_link` may perform the `action` on the `resource`
+/// at the point in time `now`.
+///
+/// Share links can only _read_ the resources within their project (and path
+/// prefix), and only within the resource's access window. They don't carry any
+/// groups, so the assets restricted by the project's path rules (e.g. `raw/**`)
+/// can't be read with them.
+pub fn authorize_share_link(
+    share_link: &ShareLink,
+    action: &Action,
+    resource: &dyn ResourceInstance,
+    now: OffsetDateTime,
+) -> Result<(), AuthorizationEngineError> {
+    let not_authorized = || AuthorizationEngineError::ShareLinkNotAuthorized {
+        share_link: Box::new(share_link.clone()),
+        action: action.clone(),
+        resource_identifier: Some(resource.resource_identifier()),
+        resource_type: resource.resource_type(),
+    };
+
+    let covered = resource
+        .project_id()
+        .is_some_and(|project_id| share_link.covers(&project_id, resource.asset_path().as_deref()));
+    if action != &Action::Read || !covered {
+        return Err(not_authorized());
+    }
+
+    // A share link issued before the project's access window closed can't
+    // extend it.
+    if let Some(access_window) = resource.access_window()
+        && !access_window.contains(now)
+    {
+        return Err(match access_window.expired_at(now) {
+            Some(expired_at) => AuthorizationEngineError::AccessExpired {
+                resource_identifier: Some(resource.resource_identifier()),
+                resource_type: resource.resource_type(),
+                expired_at,
+            },
+            None => not_authorized(),
+        });
+    }
+
+    // The path rules require members of their groups, even within the path
+    // prefix.
+    let restricted = resource.asset_path().is_some_and(|asset_path| {
+        resource
+            .path_rules()
+            .unwrap_or_default()
+            .iter()
+            .any(|path_rule| path_rule.matches(&asset_path))
+    });
+    if restricted {
+        return Err(not_authorized());
+    }
+
+    Ok(())
+}
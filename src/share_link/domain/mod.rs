@@ -0,0 +1,131 @@
+pub mod authorization;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use time::OffsetDateTime;
+
+use crate::common::domain::utils::new_type::new_type;
+use crate::project::domain::ProjectId;
+
+/// A time-limited grant to read a single project, given to someone who does
+/// not have an account on the IdP (e.g. a client).
+///
+/// A [`ShareLink`] is a distinct principal from a
+/// [`User`][`crate::user::domain::User`], so that accesses made with it can be
+/// told apart from those of authenticated users.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
+pub struct ShareLink {
+    /// The project that the share link grants access to.
+    pub project_id: ProjectId,
+
+    /// If populated, only the project assets within this path (relative to the
+    /// project directory) can be read.
+    pub path_prefix: Option<PathBuf>,
+
+    /// The point in time after which the share link is no longer valid.
+    #[cfg_attr(
+        any(test, feature = "fake"),
+        dummy(expr = "OffsetDateTime::now_utc() + time::Duration::hours(1)")
+    )]
+    pub expires_at: OffsetDateTime,
+}
+
+impl ShareLink {
+    /// Whether the share link covers the project (`project_id`) and, if
+    /// provided, the `asset_path` within it.
+    ///
+    /// Resources without an asset path (e.g. the project itself or its `potree`
+    /// render) are covered regardless of the `path_prefix`.
+    pub fn covers(&self, project_id: &ProjectId, asset_path: Option<&Path>) -> bool {
+        if &self.project_id != project_id {
+            return false;
+        }
+
+        match (&self.path_prefix, asset_path) {
+            (Some(path_prefix), Some(asset_path)) => asset_path.starts_with(path_prefix),
+            _ => true,
+        }
+    }
+
+    /// Whether the share link has expired at the point in time `now`.
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        now >= self.expires_at
+    }
+}
+
+new_type![
+    /// The signed, URL safe representation of a [`ShareLink`].
+    #[derive(serde::Deserialize, serde::Serialize)]
+    ShareToken(String)
+];
+
+#[cfg(test)]
+mod share_link_tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+
+    fn share_link(path_prefix: Option<&str>) -> ShareLink {
+        ShareLink {
+            project_id: ProjectId::new("project_1".to_owned()),
+            path_prefix: path_prefix.map(PathBuf::from),
+            expires_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    mod covers {
+        use super::*;
+
+        #[test_case::test_case(None, None; "no prefix or asset path")]
+        #[test_case::test_case(None, Some("raw/cloud.las"); "no prefix")]
+        #[test_case::test_case(Some("pointclouds"), None; "no asset path")]
+        #[test_case::test_case(Some("pointclouds"), Some("pointclouds/metadata.json"); "asset within prefix")]
+        fn should_return_true_if_within_scope(path_prefix: Option<&str>, asset_path: Option<&str>) {
+            // Arrange
+            let share_link = share_link(path_prefix);
+
+            // Act
+            let covered = share_link.covers(
+                &ProjectId::new("project_1".to_owned()),
+                asset_path.map(Path::new),
+            );
+
+            // Assert
+            assert!(covered);
+        }
+
+        #[test_case::test_case(Some("pointclouds"), "raw/cloud.las"; "asset outside prefix")]
+        #[test_case::test_case(Some("pointclouds"), "pointclouds_raw/cloud.las"; "asset sharing a string prefix")]
+        fn should_return_false_if_asset_outside_of_prefix(
+            path_prefix: Option<&str>,
+            asset_path: &str,
+        ) {
+            // Arrange
+            let share_link = share_link(path_prefix);
+
+            // Act
+            let covered = share_link.covers(
+                &ProjectId::new("project_1".to_owned()),
+                Some(Path::new(asset_path)),
+            );
+
+            // Assert
+            assert!(!covered);
+        }
+
+        #[test]
+        fn should_return_false_if_another_project() {
+            // Arrange
+            let share_link = share_link(None);
+
+            // Act
+            let covered = share_link.covers(&Faker.fake(), None);
+
+            // Assert
+            assert!(!covered);
+        }
+    }
+}
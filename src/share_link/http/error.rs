@@ -0,0 +1,54 @@
+use super::super::application::error::ShareLinkServiceError;
+use crate::common::utils::http::api_error::ApiError;
+
+impl From<ShareLinkServiceError> for ApiError {
+    fn from(value: ShareLinkServiceError) -> Self {
+        match value {
+            ShareLinkServiceError::ProjectNotFound { id } => Self::ResourceNotFound {
+                resource_name: format!("project: {id}"),
+            },
+            ShareLinkServiceError::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::NotAuthorized {
+                user,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ShareLinkServiceError::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            } => Self::ShareLinkNotAuthorized {
+                share_link,
+                action,
+                resource_identifier,
+                resource_type,
+            },
+            ShareLinkServiceError::NotAuthenticated => Self::NotAuthenticated,
+            ShareLinkServiceError::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            } => Self::AccessExpired {
+                resource_identifier,
+                resource_type,
+                expired_at,
+            },
+            ShareLinkServiceError::InvalidRequest { message } => Self::InvalidRequest { message },
+            ShareLinkServiceError::InvalidShareLink { .. } | ShareLinkServiceError::Expired => {
+                Self::InvalidShareLink {
+                    message: value.to_string(),
+                }
+            }
+            ShareLinkServiceError::ServerConfiguration { message } => {
+                Self::ServerConfiguration { message }
+            }
+            ShareLinkServiceError::Infrastucture { message } => Self::Infrastucture { message },
+        }
+    }
+}
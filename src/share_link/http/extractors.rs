@@ -0,0 +1,129 @@
+use axum::extract::FromRequestParts;
+use axum::extract::Query;
+use http::request::Parts;
+use tower_sessions::Session;
+use web_route::ParameterizedRoute;
+
+use super::super::application::service::ShareLinkService;
+use super::super::domain::ShareLink;
+use super::super::domain::ShareToken;
+use super::state::State;
+use crate::common::utils::http::api_error::ApiError;
+
+/// The query parameter in which a share token is provided.
+pub const SHARE_TOKEN_QUERY_PARAM: &str = "share_token";
+
+/// The key to which the share token of a share link visitor will be stored in a
+/// session.
+pub const SHARE_TOKEN_SESSION_KEY: &str = "share_token_session";
+
+impl<S> FromRequestParts<S> for State
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let state = parts
+            .extensions
+            .get::<State>()
+            .ok_or(ApiError::StateExtraction)?;
+
+        Ok(state.clone())
+    }
+}
+
+impl<S> FromRequestParts<S> for ShareLinkService
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = State::from_request_parts(parts, state).await?;
+        Ok(state.share_link_service)
+    }
+}
+
+pub struct PotreeRenderRoute(pub ParameterizedRoute);
+
+impl<S> FromRequestParts<S> for PotreeRenderRoute
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = State::from_request_parts(parts, state).await?;
+        Ok(Self(state.potree_render_route))
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ShareTokenQuery {
+    share_token: Option<ShareToken>,
+}
+
+pub struct ShareLinkExtractor(pub Option<ShareLink>);
+
+/// Defines how `axum` should extract a [`ShareLink`] from a request.
+///
+/// A share token in the `share_token` query parameter is verified and stored in
+/// the web session, so that the subsequent requests made by the `potree`
+/// viewer (which don't carry the query parameter) are also covered. Else the
+/// share token is read from the web session.
+impl<S> FromRequestParts<S> for ShareLinkExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let share_link_service = parts
+            .extensions
+            .get::<ShareLinkService>()
+            .cloned()
+            .ok_or(ApiError::ServerConfiguration {
+                message: "could not find the share link service in request - ensure that it is added as an extension".to_owned(),
+            })?;
+
+        let session = Session::from_request_parts(parts, state).await.map_err(|_err| ApiError::ServerConfiguration{message:"could not find tower sessions in request - ensure that tower sessions is in the middleware stack".to_owned()})?;
+
+        let query_token = Query::<ShareTokenQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(query)| query.share_token);
+
+        if let Some(share_token) = query_token {
+            let share_link = share_link_service.verify(&share_token)?;
+
+            session
+                .insert(SHARE_TOKEN_SESSION_KEY, &share_token)
+                .await
+                .map_err(|e| ApiError::Infrastucture {
+                    message: format!("unable to store share token in session: {e}"),
+                })?;
+
+            return Ok(Self(Some(share_link)));
+        }
+
+        let Some(share_token) = session
+            .get::<ShareToken>(SHARE_TOKEN_SESSION_KEY)
+            .await
+            .ok()
+            .flatten()
+        else {
+            return Ok(Self(None));
+        };
+
+        // A share link that has since expired is forgotten, rather than rejecting
+        // the request.
+        match share_link_service.verify(&share_token) {
+            Ok(share_link) => Ok(Self(Some(share_link))),
+            Err(e) => {
+                tracing::warn!("discarding share token stored in session: {e}");
+                let _ = session.remove::<ShareToken>(SHARE_TOKEN_SESSION_KEY).await;
+                Ok(Self(None))
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod error;
+pub mod extractors;
+mod route_handlers;
+mod router;
+mod state;
+
+pub use router::SHARE_LINKS;
+pub use router::build_router;
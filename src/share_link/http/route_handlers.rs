@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use axum::Json;
+
+use super::super::application::service::ShareLinkService;
+use super::super::domain::ShareToken;
+use super::extractors::PotreeRenderRoute;
+use super::extractors::SHARE_TOKEN_QUERY_PARAM;
+use crate::common::utils::http::api_error::ApiError;
+use crate::project::domain::ProjectId;
+use crate::user::http::extractors::UserExtractor;
+
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct CreateShareLinkRequest {
+    /// The project to be shared.
+    pub project_id: ProjectId,
+
+    /// If populated, only the project assets within this path can be read.
+    pub path_prefix: Option<PathBuf>,
+
+    /// How long (in minutes) the share link should be valid for.
+    pub valid_for_minutes: u32,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct CreateShareLinkResponse {
+    /// The signed token to be appended to a URL as the `share_token` query
+    /// parameter.
+    pub share_token: ShareToken,
+
+    /// When the share link expires (Unix timestamp in seconds).
+    pub expires_at: i64,
+
+    /// The path (including the share token) at which the shared project can be
+    /// viewed.
+    pub potree_render_path: String,
+}
+
+/// Creates a share link to a project.
+#[tracing::instrument(name = "`share link route handlers`: creating share link", err)]
+pub(crate) async fn create_share_link(
+    UserExtractor(user): UserExtractor,
+    share_link_service: ShareLinkService,
+    PotreeRenderRoute(potree_render_route): PotreeRenderRoute,
+    Json(request): Json<CreateShareLinkRequest>,
+) -> Result<Json<CreateShareLinkResponse>, ApiError> {
+    let (share_link, share_token) = share_link_service
+        .create(
+            &user,
+            &request.project_id,
+            request.path_prefix,
+            time::Duration::minutes(request.valid_for_minutes.into()),
+        )
+        .await?;
+
+    let potree_render_route = potree_render_route
+        .to_web_route(&serde_json::json!({ "project_id": share_link.project_id }))
+        .map_err(|e| ApiError::ServerConfiguration {
+            message: format!("unable to build `WebRoute`: {e}"),
+        })?;
+
+    Ok(Json(CreateShareLinkResponse {
+        potree_render_path: format!(
+            "{}?{SHARE_TOKEN_QUERY_PARAM}={}",
+            potree_render_route.as_ref(),
+            share_token.as_str()
+        ),
+        share_token,
+        expires_at: share_link.expires_at.unix_timestamp(),
+    }))
+}
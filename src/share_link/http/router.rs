@@ -0,0 +1,30 @@
+use std::sync::LazyLock;
+
+use axum::Extension;
+use axum::Router;
+use axum::routing::post;
+use web_route::ParameterizedRoute;
+use web_route::WebRoute;
+
+use super::super::application::service::ShareLinkService;
+use super::route_handlers;
+use super::state::State;
+
+pub static SHARE_LINKS: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/share-links"));
+
+/// Builds a router for creating share links.
+///
+/// `potree_render_route` defines where a shared project can be viewed.
+pub fn build_router(
+    share_link_service: ShareLinkService,
+    potree_render_route: ParameterizedRoute,
+) -> Router {
+    let state = State {
+        share_link_service,
+        potree_render_route,
+    };
+
+    Router::new()
+        .route(&SHARE_LINKS, post(route_handlers::create_share_link))
+        .layer(Extension(state))
+}
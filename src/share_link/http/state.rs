@@ -0,0 +1,9 @@
+use web_route::ParameterizedRoute;
+
+use super::super::application::service::ShareLinkService;
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub share_link_service: ShareLinkService,
+    pub potree_render_route: ParameterizedRoute,
+}
@@ -0,0 +1,5 @@
+pub mod adapters;
+pub mod application;
+pub mod domain;
+pub mod http;
+pub mod ports;
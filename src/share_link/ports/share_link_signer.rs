@@ -0,0 +1,34 @@
+use std::fmt::Debug;
+
+use super::super::domain::ShareLink;
+use super::super::domain::ShareToken;
+
+/// Defines the functionality needed to sign [`ShareLink`]s into tamper-proof
+/// [`ShareToken`]s, and to verify them again.
+#[cfg_attr(test, mockall::automock)]
+pub trait ShareLinkSigner: Debug + Send + Sync + 'static {
+    /// Signs the `share_link` into a token that can be handed out as part of a
+    /// URL.
+    fn sign(&self, share_link: &ShareLink) -> Result<ShareToken, ShareLinkSignerError>;
+
+    /// Verifies the signature of the `token`, returning the [`ShareLink`] that
+    /// it represents.
+    ///
+    /// > The expiry of the share link is not checked.
+    fn verify(&self, token: &ShareToken) -> Result<ShareLink, ShareLinkSignerError>;
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ShareLinkSignerError {
+    #[error("share links are not configured")]
+    NotConfigured,
+
+    #[error("the share link signing key is invalid: {message}")]
+    InvalidKey { message: String },
+
+    #[error("the share token is invalid: {message}")]
+    InvalidToken { message: String },
+
+    #[error("{message}")]
+    Infrastucture { message: String },
+}
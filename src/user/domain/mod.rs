@@ -6,6 +6,15 @@ use serde::Serialize;
 use crate::common::domain::Group;
 use crate::common::domain::utils::new_type::new_type;
 
+/// The group whose members are admins, unless configured otherwise.
+pub const DEFAULT_ADMIN_GROUP: &str = "admin";
+
+/// The name of the identity provider configured with the `IDP_*` options.
+pub const DEFAULT_PROVIDER: &str = "default";
+
+/// The name of the identity provider of the local user accounts.
+pub const LOCAL_PROVIDER: &str = "local";
+
 /// Represents an authenticated user of the application.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fake"), derive(fake::Dummy))]
@@ -14,15 +23,33 @@ pub struct User {
     pub name: UserName,
     pub email: EmailAddress,
 
+    /// Whether the user's identity provider verified that they own the `email`
+    /// address (or is trusted to). Only a verified email address is granted
+    /// access, as some IdPs let users set any email address.
+    #[serde(default)]
+    pub email_verified: bool,
+
     /// The groups that a user is member of, thus has access to their respective
     /// projects.
     pub groups: Vec<Group>,
+
+    /// The identity provider that authenticated the user, if there are
+    /// multiple.
+    #[serde(default)]
+    pub provider: Option<ProviderName>,
 }
 
 impl User {
-    /// Determines if the user is an admin.
-    pub fn is_admin(&self) -> bool {
-        self.groups.contains(&Group::new("admin"))
+    /// Determines if the user is an admin, i.e. a member of any of the
+    /// `admin_groups`.
+    pub fn is_admin(&self, admin_groups: &[Group]) -> bool {
+        self.groups.iter().any(|group| admin_groups.contains(group))
+    }
+
+    /// Determines if the user's email address is one of the `emails` (ignoring
+    /// case), and it has been verified.
+    pub fn has_verified_email_in(&self, emails: &[EmailAddress]) -> bool {
+        self.email_verified && emails.iter().any(|email| email.eq_ignore_case(&self.email))
     }
 }
 
@@ -35,7 +62,7 @@ impl User {
         use crate::common::domain::Group;
 
         Self {
-            groups: [Group::new("admin"), Faker.fake()].into(),
+            groups: [Group::new(DEFAULT_ADMIN_GROUP), Faker.fake()].into(),
             ..Faker.fake()
         }
     }
@@ -59,6 +86,13 @@ new_type![
         #[cfg_attr(any(test, feature = "fake"), dummy(faker = "fake::faker::internet::en::FreeEmail()"))]
         String
     )
+    impl {
+        /// Compares two email addresses, ignoring case (e.g. as IdPs don't
+        /// consistently preserve it).
+        pub fn eq_ignore_case(&self, other: &EmailAddress) -> bool {
+            self.to_lowercase() == other.to_lowercase()
+        }
+    }
 ];
 
 new_type![
@@ -67,6 +101,13 @@ new_type![
     UserId(String)
 ];
 
+new_type![
+    /// The name of an identity provider with which a [`User`] can
+    /// authenticate (e.g. `acme`).
+    #[derive(Deserialize, Serialize)]
+    ProviderName(String)
+];
+
 #[cfg(test)]
 mod user_tests {
     use fake::Fake;
@@ -83,7 +124,7 @@ mod user_tests {
             let user = User::dummy_admin();
 
             // Act
-            let res = user.is_admin();
+            let res = user.is_admin(&[Group::new(DEFAULT_ADMIN_GROUP)]);
 
             // Assert
             assert!(res);
@@ -95,7 +136,59 @@ mod user_tests {
             let user = User { ..Faker.fake() };
 
             // Act
-            let res = user.is_admin();
+            let res = user.is_admin(&[Group::new(DEFAULT_ADMIN_GROUP)]);
+
+            // Assert
+            assert!(!res);
+        }
+
+        #[test]
+        fn should_return_true_if_user_is_part_of_a_configured_admin_group() {
+            // Arrange
+            let user = User {
+                groups: [Faker.fake(), Group::new("potree-admins")].into(),
+                ..Faker.fake()
+            };
+
+            // Act
+            let res = user.is_admin(&[Group::new("superusers"), Group::new("potree-admins")]);
+
+            // Assert
+            assert!(res);
+        }
+
+        #[test]
+        fn should_return_false_if_admin_group_not_configured() {
+            // Arrange
+            let user = User::dummy_admin();
+
+            // Act
+            let res = user.is_admin(&[Group::new("potree-admins")]);
+
+            // Assert
+            assert!(!res);
+        }
+    }
+
+    mod eq_ignore_case {
+        use super::*;
+
+        #[test_case::test_case("reviewer@example.com", "reviewer@example.com"; "same case")]
+        #[test_case::test_case("Reviewer@Example.com", "reviewer@example.COM"; "different case")]
+        fn should_return_true_if_equal_ignoring_case(a: &str, b: &str) {
+            // Act
+            let res =
+                EmailAddress::new(a.to_owned()).eq_ignore_case(&EmailAddress::new(b.to_owned()));
+
+            // Assert
+            assert!(res);
+        }
+
+        #[test]
+        fn should_return_false_if_different() {
+            // Act
+            let res = EmailAddress::new("reviewer@example.com".to_owned())
+                .eq_ignore_case(&EmailAddress::new("other@example.com".to_owned()));
 
             // Assert
             assert!(!res);
@@ -1,18 +1,37 @@
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
 use axum::extract::FromRequestParts;
+use http::header;
 use http::request::Parts;
+use time::OffsetDateTime;
 use tower_sessions::Session;
 
 use super::super::domain::User;
+use crate::authentication::application::error::AuthenticationServiceError;
+use crate::authentication::application::service::AuthenticationService;
+use crate::authentication::domain::BearerToken;
+use crate::authentication::domain::SessionValidity;
+use crate::authentication::http::SESSION_VALIDITY_KEY;
+use crate::authentication::http::persist_authenticated_user;
 use crate::common::utils::http::api_error::ApiError;
 
 /// The key to which the logged in user data will be stored in a session.
 pub const USER_SESSION_KEY: &str = "user_session";
 
+/// The scheme of an `Authorization` header carrying an access token. Schemes
+/// are case-insensitive (RFC 7235).
+const BEARER_SCHEME: &str = "Bearer";
+
 pub struct UserExtractor(pub Option<User>);
 
 /// Defines how `axum` should extract a [`User`] from a request.
 ///
-/// The user is stored in the web session.
+/// If the request has an `Authorization: Bearer` header the user is
+/// authenticated from the access token (programmatic access). Else, if the
+/// request comes from a trusted authenticating proxy, the user is read from its
+/// identity headers. Else the user is read from the web session, which is
+/// re-validated with the IdP once it exceeds its maximum age.
 impl<S> FromRequestParts<S> for UserExtractor
 where
     S: Send + Sync,
@@ -20,9 +39,169 @@ where
     type Rejection = ApiError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authentication_service = parts
+            .extensions
+            .get::<AuthenticationService>()
+            .cloned()
+            .ok_or(ApiError::ServerConfiguration {
+                message: "could not find the authentication service in request - ensure that it is added as an extension".to_owned(),
+            })?;
+
+        if let Some(token) = bearer_token(parts)? {
+            let user = authentication_service
+                .authenticate_bearer(token)
+                .await
+                .map_err(|e| ApiError::InvalidBearerToken {
+                    message: e.to_string(),
+                })?;
+
+            return Ok(Self(Some(user)));
+        }
+
+        // Only available if the server was started with connect info.
+        let source = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+        if let Some(user) = authentication_service
+            .authenticate_proxy_headers(source, &parts.headers)
+            .await
+            .map_err(|e| ApiError::InvalidRequest {
+                message: e.to_string(),
+            })?
+        {
+            return Ok(Self(Some(user)));
+        }
+
         let session = Session::from_request_parts(parts, state).await.map_err(|_err| ApiError::ServerConfiguration{message:"could not find tower sessions in request - ensure that tower sessions is in the middleware stack".to_owned()})?;
-        let user = session.get::<User>(USER_SESSION_KEY).await.unwrap();
+        let Some(user) = session.get::<User>(USER_SESSION_KEY).await.unwrap() else {
+            return Ok(Self(None));
+        };
+
+        Ok(Self(
+            revalidate(&session, &authentication_service, user).await,
+        ))
+    }
+}
+
+/// Re-validates the `user` of a web `session` with the IdP if the session has
+/// exceeded its maximum age.
+///
+/// Returns `None` (logging the user out) if the IdP rejected the user. If the
+/// IdP could not be reached, the user is kept as they are until the next
+/// request re-validates them.
+async fn revalidate(
+    session: &Session,
+    authentication_service: &AuthenticationService,
+    user: User,
+) -> Option<User> {
+    // Sessions that were never validated are treated as expired.
+    let validity = session
+        .get::<SessionValidity>(SESSION_VALIDITY_KEY)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(SessionValidity {
+            validated_at: OffsetDateTime::UNIX_EPOCH,
+            refresh_token: None,
+        });
+
+    match authentication_service.revalidate(&user, &validity).await {
+        Ok(None) => Some(user),
+        Ok(Some(callback_data)) => {
+            let user = callback_data.user.clone();
+            if let Err(e) = persist_authenticated_user(session, callback_data).await {
+                tracing::warn!("unable to persist the re-validated user in the session: {e}");
+            }
+
+            Some(user)
+        }
+        Err(
+            e @ (AuthenticationServiceError::Infrastructure { .. }
+            | AuthenticationServiceError::IdpExchange { .. }),
+        ) => {
+            tracing::warn!(
+                "keeping user ({}) for now, unable to re-validate: {e}",
+                user.id.as_str()
+            );
+
+            Some(user)
+        }
+        Err(
+            e @ (AuthenticationServiceError::Validation { .. }
+            | AuthenticationServiceError::UnknownProvider { .. }),
+        ) => {
+            tracing::info!(
+                "logging out user ({}), unable to re-validate: {e}",
+                user.id.as_str()
+            );
+            if let Err(e) = session.flush().await {
+                tracing::warn!("unable to clear the session: {e}");
+            }
+
+            None
+        }
+    }
+}
+
+/// Extracts the token from an `Authorization: Bearer` header, if present.
+fn bearer_token(parts: &Parts) -> Result<Option<BearerToken>, ApiError> {
+    let Some(authorization) = parts.headers.get(header::AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    let token = authorization
+        .to_str()
+        .ok()
+        .and_then(|value| value.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(BEARER_SCHEME))
+        .map(|(_, token)| token.trim())
+        .filter(|token| !token.is_empty())
+        .ok_or(ApiError::InvalidBearerToken {
+            message: "malformed `Authorization` header".to_owned(),
+        })?;
+
+    Ok(Some(BearerToken::new(token.to_owned())))
+}
+
+#[cfg(test)]
+mod extractors_tests {
+    use axum::http::Request;
+
+    use super::*;
+
+    fn parts(authorization: &str) -> Parts {
+        Request::builder()
+            .header(header::AUTHORIZATION, authorization)
+            .body(())
+            .unwrap()
+            .into_parts()
+            .0
+    }
+
+    mod bearer_token {
+        use super::*;
+
+        #[test_case::test_case("Bearer some-token"; "canonical scheme")]
+        #[test_case::test_case("bearer some-token"; "lowercase scheme")]
+        #[test_case::test_case("BEARER  some-token "; "uppercase scheme")]
+        fn should_return_the_token(authorization: &str) {
+            // Act
+            let token = bearer_token(&parts(authorization)).unwrap();
+
+            // Assert
+            assert_eq!(token, Some(BearerToken::new("some-token".to_owned())));
+        }
+
+        #[test_case::test_case("Basic dXNlcjpwYXNz"; "other scheme")]
+        #[test_case::test_case("Bearer "; "empty token")]
+        #[test_case::test_case("Bearersome-token"; "no separator")]
+        fn should_return_err_if_malformed(authorization: &str) {
+            // Act
+            let res = bearer_token(&parts(authorization));
 
-        Ok(Self(user))
+            // Assert
+            assert!(matches!(res, Err(ApiError::InvalidBearerToken { .. })));
+        }
     }
 }
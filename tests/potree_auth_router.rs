@@ -2,19 +2,37 @@
 
 mod test_utils;
 
+use std::net::SocketAddr;
 use std::sync::LazyLock;
 
 use axum::ServiceExt;
 use axum::extract::Request;
 use axum_test::TestServer;
 use axum_test::transport_layer::IntoTransportLayer;
+use http::HeaderName;
 use http::StatusCode;
 use http::header;
+use potree_auth::potree_auth::config::AuditConfiguration;
+use potree_auth::potree_auth::config::AuthorizationConfiguration;
+use potree_auth::potree_auth::config::ForwardAuthConfiguration;
 use potree_auth::potree_auth::config::PotreeAuthConfiguration;
+use potree_auth::potree_auth::config::ProjectStoreConfiguration;
+use potree_auth::potree_auth::config::ProjectsConfiguration;
+use potree_auth::potree_auth::config::SessionConfiguration;
+use potree_auth::potree_auth::config::SessionStoreConfiguration;
+use potree_auth::potree_auth::config::ShareLinkConfiguration;
+use potree_auth::potree_auth::config::TrustedProxyConfiguration;
 use potree_auth::potree_auth::init_application;
 use web_route::ParameterizedRoute;
 use web_route::WebRoute;
 
+use crate::test_utils::TEST_EXPIRED_PROJECT_DATA_PATH;
+use crate::test_utils::TEST_EXPIRED_PROJECT_DIR;
+use crate::test_utils::TEST_NESTED_PROJECT_DATA_PATH;
+use crate::test_utils::TEST_NESTED_PROJECT_DIR;
+use crate::test_utils::TEST_PATH_RULES_PROJECT_DATA_PATH;
+use crate::test_utils::TEST_PATH_RULES_PROJECT_DIR;
+use crate::test_utils::TEST_PATH_RULES_PROJECT_RAW_DATA_PATH;
 use crate::test_utils::TEST_PROJECT_1_DATA_CONTENT;
 use crate::test_utils::TEST_PROJECT_1_DATA_PATH;
 use crate::test_utils::TEST_PROJECT_1_DATA_TYPE;
@@ -22,6 +40,8 @@ use crate::test_utils::TEST_PROJECT_1_DIR;
 use crate::test_utils::TEST_PROJECT_2_DATA_PATH;
 use crate::test_utils::TEST_PROJECT_2_DIR;
 use crate::test_utils::TEST_PROJECT_PARENT;
+use crate::test_utils::TEST_PUBLIC_PROJECT_DATA_PATH;
+use crate::test_utils::TEST_PUBLIC_PROJECT_DIR;
 
 static HEALTH_CHECK: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/_health"));
 static POTREE_ASSETS: LazyLock<ParameterizedRoute> =
@@ -31,11 +51,27 @@ static PROJECT_ASSETS: LazyLock<ParameterizedRoute> =
 static POTREE_RENDER: LazyLock<ParameterizedRoute> =
     LazyLock::new(|| ParameterizedRoute::new("/potree/{project_id}"));
 static PROJECTS_DASHBOARD: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/projects"));
+static LOGOUT: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/auth/logout"));
+static SHARE_LINKS: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/share-links"));
+static FORWARD_AUTH_VERIFY: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/auth/verify"));
+static ACCESS_EXPLANATIONS: LazyLock<WebRoute> =
+    LazyLock::new(|| WebRoute::new("/access-explanations"));
+static VALIDATION_REPORT: LazyLock<WebRoute> =
+    LazyLock::new(|| WebRoute::new("/validation-report"));
+static ADMIN_PROJECTS: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/admin/projects"));
 
 fn test_configuration_no_idp() -> PotreeAuthConfiguration {
     PotreeAuthConfiguration {
         data_dir: TEST_PROJECT_PARENT.parse().unwrap(),
-        idp: None,
+        projects: ProjectsConfiguration::default(),
+        idps: Vec::new(),
+        local_users: None,
+        trusted_proxy: None,
+        session: SessionConfiguration::default(),
+        share_link: ShareLinkConfiguration::default(),
+        authorization: AuthorizationConfiguration::default(),
+        forward_auth: ForwardAuthConfiguration::default(),
+        audit: AuditConfiguration::default(),
     }
 }
 
@@ -45,6 +81,33 @@ async fn initialize_application() -> impl IntoTransportLayer {
     ServiceExt::<Request>::into_make_service(application)
 }
 
+/// Users are authenticated by the identity headers of requests from the
+/// `trusted_network`.
+fn test_configuration_trusted_proxy(trusted_network: &str) -> PotreeAuthConfiguration {
+    PotreeAuthConfiguration {
+        trusted_proxy: Some(TrustedProxyConfiguration {
+            trusted_networks: vec![trusted_network.parse().unwrap()],
+            user_id_header: HeaderName::from_static("x-forwarded-user"),
+            name_header: HeaderName::from_static("x-forwarded-preferred-username"),
+            email_header: HeaderName::from_static("x-forwarded-email"),
+            groups_header: HeaderName::from_static("x-forwarded-groups"),
+            groups_separator: ',',
+        }),
+        ..test_configuration_no_idp()
+    }
+}
+
+async fn initialize_application_with_trusted_proxy(
+    trusted_network: &str,
+) -> impl IntoTransportLayer {
+    let application = init_application(test_configuration_trusted_proxy(trusted_network))
+        .await
+        .unwrap();
+
+    // The peer address is needed to trust the proxy headers.
+    ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(application)
+}
+
 mod health_check {
 
     use super::*;
@@ -60,6 +123,21 @@ mod health_check {
         // Assert
         response.assert_status(StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn should_report_the_status_of_the_identity_providers() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server.get(&HEALTH_CHECK).await;
+
+        // Assert
+        response.assert_json(&serde_json::json!({
+            "status": "ok",
+            "identity_providers": [{ "name": "default", "status": "ready" }],
+        }));
+    }
 }
 
 mod potree_static_assets {
@@ -182,6 +260,29 @@ mod project_static_assets {
         response.assert_status(StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn should_return_a_401_with_www_authenticate_if_bearer_token_invalid() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!( {
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .authorization_bearer("not-a-valid-token")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        response.assert_header(header::WWW_AUTHENTICATE, r#"Bearer error="invalid_token""#);
+    }
+
     #[tokio::test]
     #[ignore = "the route handler seems to be getting project 2 as the project_id so authZ would be fine, this test should be done with proper auth mocking"]
     async fn should_return_a_404_if_parent_directory_reference_in_path() {
@@ -262,6 +363,1196 @@ mod projects_dashboard {
     }
 }
 
+mod forward_auth {
+    use super::*;
+
+    #[test_case::test_case("x-original-uri"; "nginx")]
+    #[test_case::test_case("x-forwarded-uri"; "traefik")]
+    #[tokio::test]
+    async fn should_return_a_200_if_allowed(original_uri_header: &'static str) {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(&FORWARD_AUTH_VERIFY)
+            .add_header(
+                original_uri_header,
+                format!("/project-assets/{TEST_PROJECT_1_DIR}/{TEST_PROJECT_1_DATA_PATH}"),
+            )
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+    }
+
+    #[test_case::test_case("/project-assets/not-exist/metadata.json"; "project not found")]
+    #[test_case::test_case("/potree-assets/build/potree/potree.js"; "outside of path prefix")]
+    #[test_case::test_case("/project-assets/project_1/%2e%2e/project_2/metadata.json"; "parent directory")]
+    #[tokio::test]
+    async fn should_return_a_403_if_not_a_readable_project_asset(original_uri: &'static str) {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(&FORWARD_AUTH_VERIFY)
+            .add_header("x-original-uri", original_uri)
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_400_if_no_original_uri() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server.get(&FORWARD_AUTH_VERIFY).await;
+
+        // Assert
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+mod public_projects {
+    use super::*;
+
+    #[test_case::test_case(TEST_PUBLIC_PROJECT_DIR, TEST_PUBLIC_PROJECT_DATA_PATH, StatusCode::OK; "public project")]
+    #[test_case::test_case(TEST_PROJECT_1_DIR, TEST_PROJECT_1_DATA_PATH, StatusCode::UNAUTHORIZED; "private project")]
+    #[tokio::test]
+    async fn should_only_serve_the_assets_of_public_projects_without_login(
+        project_id: &str,
+        path: &str,
+        expected_status: StatusCode,
+    ) {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": project_id,
+                        "path": path,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        response.assert_status(expected_status);
+    }
+
+    #[tokio::test]
+    async fn should_render_a_public_project_without_login() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &POTREE_RENDER
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PUBLIC_PROJECT_DIR,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_show_only_the_public_projects_on_the_dashboard_without_login() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server.get(&PROJECTS_DASHBOARD).await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+        assert!(response.text().contains("Public Project"));
+        assert!(!response.text().contains("Project 1"));
+        assert!(response.text().contains("/auth/login?next_path=/projects"));
+    }
+}
+
+mod access_windows {
+    use super::*;
+
+    fn expired_project_asset_route() -> String {
+        PROJECT_ASSETS
+            .to_web_route(&serde_json::json!({
+                "project_id": TEST_EXPIRED_PROJECT_DIR,
+                "path": TEST_EXPIRED_PROJECT_DATA_PATH,
+            }))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn should_show_that_access_expired_when_rendering_the_project() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &POTREE_RENDER
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_EXPIRED_PROJECT_DIR,
+                    }))
+                    .unwrap(),
+            )
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert!(response.text().contains("Access Expired"));
+        assert!(response.text().contains("2020-01-01"));
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_for_the_assets_once_access_expired() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&expired_project_asset_route())
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn should_not_list_the_project_once_access_expired() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&PROJECTS_DASHBOARD)
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+        assert!(!response.text().contains("Expired Project"));
+    }
+}
+
+mod path_rules {
+    use super::*;
+
+    fn path_rules_project_asset_route(path: &str) -> String {
+        PROJECT_ASSETS
+            .to_web_route(&serde_json::json!({
+                "project_id": TEST_PATH_RULES_PROJECT_DIR,
+                "path": path,
+            }))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_for_restricted_assets_if_not_in_the_rules_groups() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&path_rules_project_asset_route(
+                TEST_PATH_RULES_PROJECT_RAW_DATA_PATH,
+            ))
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn should_return_unrestricted_assets_if_not_in_the_rules_groups() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&path_rules_project_asset_route(
+                TEST_PATH_RULES_PROJECT_DATA_PATH,
+            ))
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn should_return_restricted_assets_if_in_the_rules_groups() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&path_rules_project_asset_route(
+                TEST_PATH_RULES_PROJECT_RAW_DATA_PATH,
+            ))
+            .add_header("x-forwarded-user", "surveyor-id")
+            .add_header("x-forwarded-email", "surveyor@example.com")
+            .add_header("x-forwarded-groups", "surveyors")
+            .await;
+
+        // Assert
+        response.assert_status_ok();
+    }
+}
+
+mod collections {
+    use super::*;
+
+    fn nested_project_asset_route() -> String {
+        PROJECT_ASSETS
+            .to_web_route(&serde_json::json!({
+                "project_id": TEST_NESTED_PROJECT_DIR,
+                "path": TEST_NESTED_PROJECT_DATA_PATH,
+            }))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn should_return_the_assets_of_a_nested_project_to_the_collections_groups() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&nested_project_asset_route())
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "client-a")
+            .await;
+
+        // Assert
+        response.assert_status_ok();
+        assert_eq!(response.text(), "x,y,z\n1,2,3\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_for_the_assets_of_a_nested_project_if_not_in_the_collections_groups()
+     {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&nested_project_asset_route())
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "client-b")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn should_render_a_nested_project_with_its_potree_config() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &POTREE_RENDER
+                    .to_web_route(&serde_json::json!({"project_id": TEST_NESTED_PROJECT_DIR}))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+        assert!(response.text().contains(&format!(
+            "/project-assets/{TEST_NESTED_PROJECT_DIR}/potree.json5"
+        )));
+    }
+}
+
+mod access_explanations {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_explain_which_check_denied_the_asset_to_an_admin() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .post(&ACCESS_EXPLANATIONS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "email": "client@example.com",
+                "groups": ["clients"],
+                "project_id": TEST_PATH_RULES_PROJECT_DIR,
+                "asset_path": TEST_PATH_RULES_PROJECT_RAW_DATA_PATH,
+            }))
+            .await;
+
+        // Assert
+        response.assert_status_ok();
+        let explanation = response.json::<serde_json::Value>();
+        assert_eq!(explanation["allowed"], false);
+        assert_eq!(explanation["project"]["allowed"], true);
+        let decisive_checks = explanation["project_asset"]["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|check| check["decisive"] == true)
+            .collect::<Vec<_>>();
+        assert_eq!(decisive_checks.len(), 1);
+        assert_eq!(decisive_checks[0]["kind"], "path-rule");
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_if_not_an_admin() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .post(&ACCESS_EXPLANATIONS)
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .json(&serde_json::json!({
+                "email": "client@example.com",
+                "groups": ["clients"],
+                "project_id": TEST_PATH_RULES_PROJECT_DIR,
+            }))
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
+
+mod validation_report {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_show_the_report_to_an_admin() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&VALIDATION_REPORT)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .await;
+
+        // Assert
+        response.assert_status_ok();
+        assert!(response.text().contains("Validation Report"));
+    }
+
+    #[tokio::test]
+    async fn should_redirect_to_404_if_not_an_admin() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&VALIDATION_REPORT)
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::SEE_OTHER);
+    }
+}
+
+mod project_admin {
+    use super::*;
+
+    /// The projects are stored in a new SQLite database (in the returned
+    /// directory), rather than defined by the manifests.
+    async fn initialize_application_with_sqlite_projects()
+    -> (impl IntoTransportLayer, tempfile::TempDir) {
+        let database_dir = tempfile::tempdir().unwrap();
+        let config = PotreeAuthConfiguration {
+            projects: ProjectsConfiguration {
+                store: ProjectStoreConfiguration::Sqlite {
+                    path: database_dir.path().join("projects.sqlite"),
+                },
+                ..ProjectsConfiguration::default()
+            },
+            ..test_configuration_trusted_proxy("127.0.0.0/8")
+        };
+        let application = init_application(config).await.unwrap();
+
+        (
+            ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(application),
+            database_dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn should_serve_the_assets_of_a_project_created_by_an_admin() {
+        // Arrange
+        let (application, _database_dir) = initialize_application_with_sqlite_projects().await;
+        let test_server = TestServer::new(application).unwrap();
+
+        // Act
+        let create_response = test_server
+            .post(&ADMIN_PROJECTS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "id": TEST_PROJECT_1_DIR,
+                "name": "Project 1",
+                "groups": ["clients"],
+            }))
+            .await;
+        let asset_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        create_response.assert_status(StatusCode::CREATED);
+        asset_response.assert_status_ok();
+        asset_response.assert_text(TEST_PROJECT_1_DATA_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn should_not_expose_a_nested_project_through_an_overlapping_public_project() {
+        // Arrange
+        let (application, _database_dir) = initialize_application_with_sqlite_projects().await;
+        let test_server = TestServer::new(application).unwrap();
+        let nested_project_id = format!("{TEST_PROJECT_1_DIR}/data");
+        test_server
+            .post(&ADMIN_PROJECTS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "id": nested_project_id,
+                "name": "Private Data",
+                "groups": ["surveyors"],
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        // Act
+        let create_response = test_server
+            .post(&ADMIN_PROJECTS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "id": TEST_PROJECT_1_DIR,
+                "name": "Project 1",
+                "groups": [],
+                "visibility": "public",
+            }))
+            .await;
+        let asset_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        create_response.assert_status(StatusCode::CONFLICT);
+        asset_response.assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_409_if_the_projects_are_defined_by_manifests() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .post(&ADMIN_PROJECTS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "id": "new-project",
+                "name": "New Project",
+                "groups": ["clients"],
+            }))
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_if_not_an_admin() {
+        // Arrange
+        let (application, _database_dir) = initialize_application_with_sqlite_projects().await;
+        let test_server = TestServer::new(application).unwrap();
+
+        // Act
+        let response = test_server
+            .get(&ADMIN_PROJECTS)
+            .add_header("x-forwarded-user", "client-id")
+            .add_header("x-forwarded-email", "client@example.com")
+            .add_header("x-forwarded-groups", "clients")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
+
+mod audit_log {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_record_one_event_per_project_session() {
+        // Arrange
+        let audit_dir = tempfile::tempdir().unwrap();
+        let audit_log_file = audit_dir.path().join("audit.jsonl");
+        let config = PotreeAuthConfiguration {
+            audit: AuditConfiguration {
+                log_file: Some(audit_log_file.clone()),
+                ..AuditConfiguration::default()
+            },
+            ..test_configuration_trusted_proxy("127.0.0.0/8")
+        };
+        let application = init_application(config).await.unwrap();
+        let test_server = TestServer::new(
+            ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(application),
+        )
+        .unwrap();
+        let asset_route = PROJECT_ASSETS
+            .to_web_route(&serde_json::json!({
+                "project_id": TEST_PATH_RULES_PROJECT_DIR,
+                "path": TEST_PATH_RULES_PROJECT_DATA_PATH,
+            }))
+            .unwrap();
+
+        // Act
+        for _ in 0..3 {
+            test_server
+                .get(&asset_route)
+                .add_header("x-forwarded-user", "client-id")
+                .add_header("x-forwarded-email", "client@example.com")
+                .add_header("x-forwarded-groups", "clients")
+                .await
+                .assert_status_ok();
+        }
+
+        // Assert
+        let events = std::fs::read_to_string(&audit_log_file)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        // The project is read before each of its assets.
+        let resource_types = events
+            .iter()
+            .map(|event| event["resource_type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(resource_types, vec!["project", "project-asset"]);
+        assert!(events.iter().all(|event| {
+            event["user_id"] == "client-id"
+                && event["project_id"] == TEST_PATH_RULES_PROJECT_DIR
+                && event["outcome"] == "allowed"
+        }));
+    }
+}
+
+mod trusted_proxy {
+    use super::*;
+
+    fn project_asset_route() -> String {
+        PROJECT_ASSETS
+            .to_web_route(&serde_json::json!( {
+                "project_id": TEST_PROJECT_1_DIR,
+                "path": TEST_PROJECT_1_DATA_PATH,
+            }))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_the_user_from_a_trusted_proxy() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("127.0.0.0/8").await)
+                .unwrap();
+
+        // Act
+        let response = test_server
+            .get(&project_asset_route())
+            .add_header("x-forwarded-user", "user-id")
+            .add_header("x-forwarded-email", "user@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+        assert_eq!(response.text(), TEST_PROJECT_1_DATA_CONTENT)
+    }
+
+    #[tokio::test]
+    async fn should_ignore_the_headers_from_an_untrusted_source() {
+        // Arrange
+        let test_server =
+            TestServer::new(initialize_application_with_trusted_proxy("10.0.0.0/8").await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(&project_asset_route())
+            .add_header("x-forwarded-user", "user-id")
+            .add_header("x-forwarded-email", "user@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod local_users {
+    use argon2::Algorithm;
+    use argon2::Argon2;
+    use argon2::Params;
+    use argon2::PasswordHasher;
+    use argon2::Version;
+    use argon2::password_hash::SaltString;
+    use argon2::password_hash::rand_core::OsRng;
+    use potree_auth::potree_auth::config::LocalUsersConfiguration;
+
+    use super::*;
+
+    static LOGIN: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/auth/login"));
+    static LOCAL_LOGIN: LazyLock<WebRoute> = LazyLock::new(|| WebRoute::new("/auth/login/local"));
+
+    async fn initialize_test_server_with_local_users() -> (TestServer, tempfile::NamedTempFile) {
+        // Cheap parameters keep the tests fast.
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            Params::new(1024, 1, 1, None).unwrap(),
+        )
+        .hash_password(b"correct horse", &salt)
+        .unwrap();
+        let users_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            users_file.path(),
+            format!(
+                "users:\n  - username: surveyor\n    password_hash: \"{password_hash}\"\n    \
+                 email: surveyor@example.com\n    groups: [admin]\n"
+            ),
+        )
+        .unwrap();
+
+        let config = PotreeAuthConfiguration {
+            local_users: Some(LocalUsersConfiguration {
+                users_file: users_file.path().to_owned(),
+                display_name: "Local Account".to_owned(),
+                max_failed_logins: 5,
+                max_backoff: std::time::Duration::from_secs(15 * 60),
+                trusted_proxy_networks: Vec::new(),
+            }),
+            ..test_configuration_no_idp()
+        };
+        let application = init_application(config).await.unwrap();
+        let mut test_server =
+            TestServer::new(ServiceExt::<Request>::into_make_service(application)).unwrap();
+        test_server.save_cookies();
+
+        (test_server, users_file)
+    }
+
+    /// Renders the login form, returning its CSRF token.
+    async fn csrf_token(test_server: &TestServer) -> String {
+        let login_form = test_server
+            .get(&LOGIN)
+            .add_query_param("next_path", "/projects")
+            .await
+            .text();
+
+        login_form
+            .split(r#"name="csrf_token" value=""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_owned()
+    }
+
+    #[tokio::test]
+    async fn should_log_the_user_in_with_the_correct_password() {
+        // Arrange
+        let (test_server, _users_file) = initialize_test_server_with_local_users().await;
+        let csrf_token = csrf_token(&test_server).await;
+
+        // Act
+        let response = test_server
+            .post(&LOCAL_LOGIN)
+            .form(&[
+                ("username", "surveyor"),
+                ("password", "correct horse"),
+                ("csrf_token", &csrf_token),
+                ("next_path", "/projects"),
+            ])
+            .await;
+        let asset_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::SEE_OTHER);
+        response.assert_header(header::LOCATION, "/projects");
+        asset_response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_show_the_form_again_if_password_incorrect() {
+        // Arrange
+        let (test_server, _users_file) = initialize_test_server_with_local_users().await;
+        let csrf_token = csrf_token(&test_server).await;
+
+        // Act
+        let response = test_server
+            .post(&LOCAL_LOGIN)
+            .form(&[
+                ("username", "surveyor"),
+                ("password", "wrong horse"),
+                ("csrf_token", &csrf_token),
+                ("next_path", "/projects"),
+            ])
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert!(response.text().contains("invalid username or password"));
+    }
+
+    #[tokio::test]
+    async fn should_reject_the_form_if_csrf_token_invalid() {
+        // Arrange
+        let (test_server, _users_file) = initialize_test_server_with_local_users().await;
+        let _ = csrf_token(&test_server).await;
+
+        // Act
+        let response = test_server
+            .post(&LOCAL_LOGIN)
+            .form(&[
+                ("username", "surveyor"),
+                ("password", "correct horse"),
+                ("csrf_token", "forged"),
+                ("next_path", "/projects"),
+            ])
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
+
+mod logout {
+    use super::*;
+
+    /// Renders the logout form, returning its CSRF token.
+    async fn csrf_token(test_server: &TestServer) -> String {
+        let logout_form = test_server.get(&LOGOUT).await.text();
+
+        logout_form
+            .split(r#"name="csrf_token" value=""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_owned()
+    }
+
+    #[tokio::test]
+    async fn should_redirect_to_the_root_if_no_idp_configured() {
+        // Arrange
+        let mut test_server = TestServer::new(initialize_application().await).unwrap();
+        test_server.save_cookies();
+        let csrf_token = csrf_token(&test_server).await;
+
+        // Act
+        let response = test_server
+            .post(&LOGOUT)
+            .form(&[("csrf_token", &csrf_token)])
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::SEE_OTHER);
+        response.assert_header(header::LOCATION, "/");
+    }
+
+    #[tokio::test]
+    async fn should_not_log_out_with_a_get_request() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server.get(&LOGOUT).await;
+
+        // Assert
+        response.assert_status_ok();
+        assert!(response.text().contains(r#"method="post""#));
+    }
+
+    #[tokio::test]
+    async fn should_reject_the_form_if_csrf_token_invalid() {
+        // Arrange
+        let mut test_server = TestServer::new(initialize_application().await).unwrap();
+        test_server.save_cookies();
+        let _ = csrf_token(&test_server).await;
+
+        // Act
+        let response = test_server
+            .post(&LOGOUT)
+            .form(&[("csrf_token", "forged")])
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
+
+mod session_store {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_create_the_sqlite_session_database_on_startup() {
+        // Arrange
+        let session_dir = tempfile::tempdir().unwrap();
+        let session_db_path = session_dir.path().join("sessions.sqlite");
+
+        let config = PotreeAuthConfiguration {
+            session: SessionConfiguration {
+                store: SessionStoreConfiguration::Sqlite {
+                    path: session_db_path.clone(),
+                },
+                ..SessionConfiguration::default()
+            },
+            ..test_configuration_no_idp()
+        };
+
+        // Act
+        let application = init_application(config).await.unwrap();
+        let test_server =
+            TestServer::new(ServiceExt::<Request>::into_make_service(application)).unwrap();
+        let response = test_server.get(&HEALTH_CHECK).await;
+
+        // Assert
+        response.assert_status(StatusCode::OK);
+        assert!(session_db_path.exists());
+    }
+}
+
+mod share_links {
+    use super::*;
+
+    async fn initialize_application_with_share_links() -> impl IntoTransportLayer {
+        let config = PotreeAuthConfiguration {
+            share_link: ShareLinkConfiguration {
+                signing_key: Some("a-very-secret-key-used-for-testing-purposes".to_owned()),
+                ..ShareLinkConfiguration::default()
+            },
+            ..test_configuration_no_idp()
+        };
+
+        ServiceExt::<Request>::into_make_service(init_application(config).await.unwrap())
+    }
+
+    /// Users are identified by the trusted proxy headers, requests without
+    /// them aren't logged in.
+    async fn initialize_application_with_share_links_behind_proxy() -> impl IntoTransportLayer {
+        let config = PotreeAuthConfiguration {
+            share_link: ShareLinkConfiguration {
+                signing_key: Some("a-very-secret-key-used-for-testing-purposes".to_owned()),
+                ..ShareLinkConfiguration::default()
+            },
+            ..test_configuration_trusted_proxy("127.0.0.0/8")
+        };
+
+        ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(
+            init_application(config).await.unwrap(),
+        )
+    }
+
+    /// Creates a share link to the project as an admin.
+    async fn create_share_link(test_server: &TestServer, project_id: &str) -> serde_json::Value {
+        test_server
+            .post(&SHARE_LINKS)
+            .add_header("x-forwarded-user", "admin-id")
+            .add_header("x-forwarded-email", "admin@example.com")
+            .add_header("x-forwarded-groups", "admin")
+            .json(&serde_json::json!({
+                "project_id": project_id,
+                "valid_for_minutes": 60,
+            }))
+            .await
+            .json::<serde_json::Value>()
+    }
+
+    #[tokio::test]
+    async fn should_render_and_serve_project_assets_with_a_created_share_link() {
+        // Arrange
+        let mut test_server =
+            TestServer::new(initialize_application_with_share_links().await).unwrap();
+        test_server.save_cookies();
+
+        let share_link = test_server
+            .post(&SHARE_LINKS)
+            .json(&serde_json::json!({
+                "project_id": TEST_PROJECT_1_DIR,
+                "valid_for_minutes": 60,
+            }))
+            .await
+            .json::<serde_json::Value>();
+        let potree_render_path = share_link["potree_render_path"].as_str().unwrap();
+
+        // Act
+        let render_response = test_server.get(potree_render_path).await;
+        // The `potree` viewer requests assets without the share token.
+        let asset_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        render_response.assert_status(StatusCode::OK);
+        assert!(potree_render_path.contains("share_token="));
+        asset_response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_for_assets_restricted_by_path_rules() {
+        // Arrange
+        // Visitors without the identity headers aren't logged in, so their
+        // requests are authorized with the share link.
+        let mut test_server =
+            TestServer::new(initialize_application_with_share_links_behind_proxy().await).unwrap();
+        test_server.save_cookies();
+
+        let share_link = create_share_link(&test_server, TEST_PATH_RULES_PROJECT_DIR).await;
+        test_server
+            .get(share_link["potree_render_path"].as_str().unwrap())
+            .await
+            .assert_status(StatusCode::OK);
+
+        // Act
+        let restricted_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PATH_RULES_PROJECT_DIR,
+                        "path": TEST_PATH_RULES_PROJECT_RAW_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+        let unrestricted_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PATH_RULES_PROJECT_DIR,
+                        "path": TEST_PATH_RULES_PROJECT_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .await;
+
+        // Assert
+        restricted_response.assert_status(StatusCode::FORBIDDEN);
+        unrestricted_response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_fall_back_to_the_share_link_if_the_user_is_not_authorized() {
+        // Arrange
+        let mut test_server =
+            TestServer::new(initialize_application_with_share_links_behind_proxy().await).unwrap();
+        test_server.save_cookies();
+
+        let share_link = create_share_link(&test_server, TEST_PROJECT_1_DIR).await;
+
+        // Act
+        // A colleague that is logged in, but isn't a member of the project's groups.
+        let render_response = test_server
+            .get(share_link["potree_render_path"].as_str().unwrap())
+            .add_header("x-forwarded-user", "colleague-id")
+            .add_header("x-forwarded-email", "colleague@example.com")
+            .add_header("x-forwarded-groups", "other")
+            .await;
+        let asset_response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .add_header("x-forwarded-user", "colleague-id")
+            .add_header("x-forwarded-email", "colleague@example.com")
+            .add_header("x-forwarded-groups", "other")
+            .await;
+
+        // Assert
+        render_response.assert_status(StatusCode::OK);
+        asset_response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_403_if_share_token_invalid() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application_with_share_links().await).unwrap();
+
+        // Act
+        let response = test_server
+            .get(
+                &PROJECT_ASSETS
+                    .to_web_route(&serde_json::json!({
+                        "project_id": TEST_PROJECT_1_DIR,
+                        "path": TEST_PROJECT_1_DATA_PATH,
+                    }))
+                    .unwrap(),
+            )
+            .add_query_param("share_token", "tampered.token")
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn should_return_a_400_if_validity_exceeds_the_maximum() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application_with_share_links().await).unwrap();
+
+        // Act
+        let response = test_server
+            .post(&SHARE_LINKS)
+            .json(&serde_json::json!({
+                "project_id": TEST_PROJECT_1_DIR,
+                "valid_for_minutes": u32::MAX,
+            }))
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn should_not_create_a_share_link_if_not_configured() {
+        // Arrange
+        let test_server = TestServer::new(initialize_application().await).unwrap();
+
+        // Act
+        let response = test_server
+            .post(&SHARE_LINKS)
+            .json(&serde_json::json!({
+                "project_id": TEST_PROJECT_1_DIR,
+                "valid_for_minutes": 60,
+            }))
+            .await;
+
+        // Assert
+        response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
+
 mod secure_headers {
     use super::*;
 
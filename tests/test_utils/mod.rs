@@ -6,6 +6,15 @@ pub const TEST_PROJECT_1_DIR: &str = "project_1";
 pub const TEST_PROJECT_1_DATA_PATH: &str = "data/some_data.txt";
 pub const TEST_PROJECT_2_DIR: &str = "project_2";
 pub const TEST_PROJECT_2_DATA_PATH: &str = "data/other_data.csv";
+pub const TEST_PUBLIC_PROJECT_DIR: &str = "project_3";
+pub const TEST_PUBLIC_PROJECT_DATA_PATH: &str = "data/demo.csv";
+pub const TEST_EXPIRED_PROJECT_DIR: &str = "project_4";
+pub const TEST_EXPIRED_PROJECT_DATA_PATH: &str = "data/survey.csv";
+pub const TEST_PATH_RULES_PROJECT_DIR: &str = "project_5";
+pub const TEST_PATH_RULES_PROJECT_DATA_PATH: &str = "data/processed.csv";
+pub const TEST_PATH_RULES_PROJECT_RAW_DATA_PATH: &str = "data/raw/scan.csv";
+pub const TEST_NESTED_PROJECT_DIR: &str = "client_a/survey";
+pub const TEST_NESTED_PROJECT_DATA_PATH: &str = "data/scan.csv";
 
 // The content of the project data
 pub static TEST_PROJECT_1_DATA_CONTENT: &str =